@@ -4,6 +4,11 @@ pub fn build(b: *std.Build) void {
     const target = b.standardTargetOptions(.{});
     const optimize = b.standardOptimizeOption(.{ .preferred_optimize_mode = .ReleaseFast });
 
+    // Fast DDS install to compile against; cross builds point it at one built
+    // for the target
+    const fastdds_prefix = b.option([]const u8, "fastdds-prefix", "Fast DDS install prefix (default: install)") orelse "install";
+    const fastdds_include = b.fmt("{s}/include", .{fastdds_prefix});
+
     // Build Cardinal FastDDS C++ wrapper library only
     // FastDDS and Fast-CDR are built with CMake separately
     const cardinal_lib = b.addStaticLibrary(.{
@@ -13,21 +18,22 @@ pub fn build(b: *std.Build) void {
     });
 
     // Add our wrapper source file
-    cardinal_lib.addCSourceFile(.{
+    const wrapper_source = std.Build.Module.CSourceFile{
         .file = b.path("lib/fastdds.cpp"),
         .flags = &[_][]const u8{
             "-std=c++17",
             "-fPIC",
             "-Ilib",
-            "-Iinstall/include",
+            b.fmt("-I{s}", .{fastdds_include}),
             "-IFast-DDS/include",
             "-IFast-CDR/include",
         },
-    });
+    };
+    cardinal_lib.addCSourceFile(wrapper_source);
 
     cardinal_lib.linkSystemLibrary("stdc++");
     cardinal_lib.addIncludePath(b.path("lib"));
-    cardinal_lib.addIncludePath(b.path("install/include"));
+    cardinal_lib.addIncludePath(.{ .cwd_relative = fastdds_include });
     cardinal_lib.addIncludePath(b.path("Fast-DDS/include"));
     cardinal_lib.addIncludePath(b.path("Fast-CDR/include"));
 
@@ -36,10 +42,32 @@ pub fn build(b: *std.Build) void {
         .dest_dir = .{ .override = .{ .custom = "build" } },
     });
 
+    // The wrapper as a shared library with Fast DDS linked in, for Go builds
+    // that load it at runtime (-tags fastdds,fastdds_dynamic)
+    const cardinal_shared = b.addSharedLibrary(.{
+        .name = "cardinal-fastdds",
+        .target = target,
+        .optimize = optimize,
+    });
+    cardinal_shared.addCSourceFile(wrapper_source);
+    cardinal_shared.addIncludePath(b.path("lib"));
+    cardinal_shared.addIncludePath(.{ .cwd_relative = fastdds_include });
+    cardinal_shared.addLibraryPath(.{ .cwd_relative = b.fmt("{s}/lib", .{fastdds_prefix}) });
+    cardinal_shared.linkSystemLibrary("fastdds");
+    cardinal_shared.linkSystemLibrary("fastcdr");
+    cardinal_shared.linkSystemLibrary("stdc++");
+
+    const install_shared = b.addInstallArtifact(cardinal_shared, .{
+        .dest_dir = .{ .override = .{ .custom = "build" } },
+    });
+
     // Build steps
     const lib_step = b.step("lib", "Build Cardinal FastDDS wrapper library");
     lib_step.dependOn(&install_cardinal.step);
 
+    const shared_step = b.step("shared", "Build Cardinal FastDDS wrapper as a shared library");
+    shared_step.dependOn(&install_shared.step);
+
     // Default step
     b.default_step = lib_step;
 }
@@ -0,0 +1,259 @@
+package main
+
+import (
+	"bufio"
+	"crypto/subtle"
+	"crypto/tls"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io/fs"
+	"log"
+	"net/http"
+	"os"
+	"path/filepath"
+	"strings"
+)
+
+// The HTTP API exposes the daemon control commands to remote clients.
+// Every request needs a bearer token from the tokens file; viewer tokens
+// can only read status, operator tokens can also change subscriptions,
+// record and publish.
+
+// Roles granted by API tokens
+type apiRole int
+
+const (
+	roleViewer apiRole = iota
+	roleOperator
+)
+
+var apiRoles = map[string]apiRole{
+	"viewer":   roleViewer,
+	"operator": roleOperator,
+}
+
+// An API token and the role it grants
+type apiToken struct {
+	token string
+	role  apiRole
+}
+
+// Load API tokens from a file with one "<role> <token>" pair per line.
+// Lines starting with # are comments; elsewhere # is part of the token.
+func loadAPITokens(path string) ([]apiToken, error) {
+	file, err := os.Open(path)
+	if err != nil {
+		return nil, err
+	}
+	defer file.Close()
+
+	var tokens []apiToken
+	scanner := bufio.NewScanner(file)
+	for lineNo := 1; scanner.Scan(); lineNo++ {
+		fields := strings.Fields(scanner.Text())
+		if len(fields) == 0 || strings.HasPrefix(fields[0], "#") {
+			continue
+		}
+		if len(fields) != 2 {
+			return nil, fmt.Errorf("%s:%d: expected <role> <token>", path, lineNo)
+		}
+		role, ok := apiRoles[fields[0]]
+		if !ok {
+			return nil, fmt.Errorf("%s:%d: unknown role %q", path, lineNo, fields[0])
+		}
+		tokens = append(tokens, apiToken{token: fields[1], role: role})
+	}
+	if err := scanner.Err(); err != nil {
+		return nil, err
+	}
+	if len(tokens) == 0 {
+		return nil, fmt.Errorf("%s: no tokens", path)
+	}
+	return tokens, nil
+}
+
+// Serves the control API over HTTP
+type apiServer struct {
+	session    *Session
+	profile    Profile
+	tokens     []apiToken
+	recordings string // directory API recordings are confined to
+}
+
+// Body of a publish request. On protected profiles, confirm must repeat
+// the topic name.
+type apiPublishRequest struct {
+	Topic   string `json:"topic"`
+	Content string `json:"content"`
+	Confirm string `json:"confirm,omitempty"`
+}
+
+// Role of the request's bearer token, if it has a valid one
+func (a *apiServer) role(r *http.Request) (apiRole, bool) {
+	token, ok := strings.CutPrefix(r.Header.Get("Authorization"), "Bearer ")
+	if !ok {
+		return 0, false
+	}
+
+	// Compare against every token so timing does not reveal which matched
+	role, found := apiRole(0), false
+	for _, t := range a.tokens {
+		if subtle.ConstantTimeCompare([]byte(token), []byte(t.token)) == 1 {
+			role, found = t.role, true
+		}
+	}
+	return role, found
+}
+
+// Wrap a handler so it only runs for tokens with at least the given role
+func (a *apiServer) require(required apiRole, handler func(*http.Request) (controlResponse, int)) http.HandlerFunc {
+	return func(w http.ResponseWriter, r *http.Request) {
+		var resp controlResponse
+		code := http.StatusOK
+
+		role, ok := a.role(r)
+		switch {
+		case !ok:
+			w.Header().Set("WWW-Authenticate", `Bearer realm="cardinal"`)
+			resp.Error, code = "missing or invalid token", http.StatusUnauthorized
+		case role < required:
+			resp.Error, code = "token is not allowed to do this", http.StatusForbidden
+		default:
+			resp, code = handler(r)
+		}
+
+		w.Header().Set("Content-Type", "application/json")
+		w.WriteHeader(code)
+		json.NewEncoder(w).Encode(resp)
+	}
+}
+
+// Run a control command and map its outcome to an HTTP status
+func (a *apiServer) control(req controlRequest) (controlResponse, int) {
+	resp := handleControl(req, a.session)
+	if !resp.OK {
+		return resp, http.StatusBadRequest
+	}
+	return resp, http.StatusOK
+}
+
+func (a *apiServer) status(r *http.Request) (controlResponse, int) {
+	return a.control(controlRequest{Command: "status"})
+}
+
+func (a *apiServer) addTopic(r *http.Request) (controlResponse, int) {
+	var req controlRequest
+	if err := json.NewDecoder(r.Body).Decode(&req); err != nil {
+		return controlResponse{Error: fmt.Sprintf("invalid request: %v", err)}, http.StatusBadRequest
+	}
+	return a.control(controlRequest{Command: "add-topic", Topic: req.Topic})
+}
+
+func (a *apiServer) startRecording(r *http.Request) (controlResponse, int) {
+	var req controlRequest
+	if err := json.NewDecoder(r.Body).Decode(&req); err != nil {
+		return controlResponse{Error: fmt.Sprintf("invalid request: %v", err)}, http.StatusBadRequest
+	}
+	path, err := a.recordingPath(req.Path)
+	if err != nil {
+		code := http.StatusBadRequest
+		if errors.Is(err, fs.ErrExist) {
+			code = http.StatusConflict
+		}
+		return controlResponse{Error: err.Error()}, code
+	}
+	return a.control(controlRequest{Command: "start-recording", Path: path})
+}
+
+// Resolve a client's recording path under the recordings directory. Only
+// relative paths that stay inside it are accepted, and existing captures,
+// the first chunk of a chunked one included, are never overwritten.
+func (a *apiServer) recordingPath(path string) (string, error) {
+	if a.recordings == "" {
+		return "", errors.New("recording over the API requires --api-recordings")
+	}
+	if path == "" {
+		return "", errors.New("start-recording requires a path")
+	}
+	if filepath.IsAbs(path) {
+		return "", fmt.Errorf("%s: recording paths must be relative to the recordings directory", path)
+	}
+	clean := filepath.Clean(path)
+	if clean == "." || clean == ".." || strings.HasPrefix(clean, ".."+string(filepath.Separator)) {
+		return "", fmt.Errorf("%s: recording paths must stay inside the recordings directory", path)
+	}
+	full := filepath.Join(a.recordings, clean)
+	for _, existing := range []string{full, chunkPath(full, 1)} {
+		if _, err := os.Lstat(existing); err == nil {
+			return "", fmt.Errorf("%s: %w", path, fs.ErrExist)
+		} else if !errors.Is(err, fs.ErrNotExist) {
+			return "", err
+		}
+	}
+	return full, nil
+}
+
+func (a *apiServer) stopRecording(r *http.Request) (controlResponse, int) {
+	return a.control(controlRequest{Command: "stop-recording"})
+}
+
+func (a *apiServer) publish(r *http.Request) (controlResponse, int) {
+	var req apiPublishRequest
+	if err := json.NewDecoder(r.Body).Decode(&req); err != nil {
+		return controlResponse{Error: fmt.Sprintf("invalid request: %v", err)}, http.StatusBadRequest
+	}
+	if req.Topic == "" {
+		return controlResponse{Error: "publish requires a topic"}, http.StatusBadRequest
+	}
+	if a.profile.Protected && req.Confirm != req.Topic {
+		return controlResponse{Error: errNotConfirmed.Error()}, http.StatusPreconditionFailed
+	}
+
+	if err := a.session.Publish(req.Topic, req.Content); err != nil {
+		code := http.StatusBadGateway
+		if errors.Is(err, errReadOnly) {
+			code = http.StatusForbidden
+		}
+		return controlResponse{Error: err.Error()}, code
+	}
+	return controlResponse{OK: true}, http.StatusOK
+}
+
+// Load the API's TLS certificate; both files must be given or neither
+func loadAPITLS(certFile, keyFile string) (*tls.Config, error) {
+	if certFile == "" && keyFile == "" {
+		return nil, nil
+	}
+	if certFile == "" || keyFile == "" {
+		return nil, fmt.Errorf("--tls-cert and --tls-key must be used together")
+	}
+	cert, err := tls.LoadX509KeyPair(certFile, keyFile)
+	if err != nil {
+		return nil, err
+	}
+	return &tls.Config{Certificates: []tls.Certificate{cert}, MinVersion: tls.VersionTLS12}, nil
+}
+
+// Serve the API on addr in the background, over TLS when tlsConfig is set
+func startAPIServer(addr string, a *apiServer, tlsConfig *tls.Config) {
+	mux := http.NewServeMux()
+	mux.Handle("GET /api/v1/status", a.require(roleViewer, a.status))
+	mux.Handle("POST /api/v1/topics", a.require(roleOperator, a.addTopic))
+	mux.Handle("POST /api/v1/recording", a.require(roleOperator, a.startRecording))
+	mux.Handle("DELETE /api/v1/recording", a.require(roleOperator, a.stopRecording))
+	mux.Handle("POST /api/v1/publish", a.require(roleOperator, a.publish))
+
+	server := &http.Server{Addr: addr, Handler: mux, TLSConfig: tlsConfig}
+	go func() {
+		var err error
+		if tlsConfig != nil {
+			log.Printf("API listening on https://%s/api/v1", addr)
+			err = server.ListenAndServeTLS("", "")
+		} else {
+			log.Printf("API listening on http://%s/api/v1", addr)
+			err = server.ListenAndServe()
+		}
+		log.Printf("API server stopped: %v", err)
+	}()
+}
@@ -0,0 +1,40 @@
+package main
+
+import (
+	"errors"
+	"io/fs"
+	"os"
+	"path/filepath"
+	"testing"
+)
+
+// API recordings stay inside the recordings directory and never replace
+// an existing capture
+func TestAPIRecordingPath(t *testing.T) {
+	dir := t.TempDir()
+	if err := os.WriteFile(filepath.Join(dir, "old.mcap"), nil, 0o600); err != nil {
+		t.Fatal(err)
+	}
+	if err := os.WriteFile(filepath.Join(dir, "chunked-0001.mcap"), nil, 0o600); err != nil {
+		t.Fatal(err)
+	}
+	api := &apiServer{recordings: dir}
+
+	path, err := api.recordingPath("runs/../run1.mcap")
+	if err != nil || path != filepath.Join(dir, "run1.mcap") {
+		t.Errorf("recordingPath(runs/../run1.mcap) = %q, %v", path, err)
+	}
+	for _, bad := range []string{"", ".", "..", "../run1.mcap", "runs/../../run1.mcap", "/tmp/run1.mcap"} {
+		if path, err := api.recordingPath(bad); err == nil {
+			t.Errorf("recordingPath(%q) = %q, want an error", bad, path)
+		}
+	}
+	for _, existing := range []string{"old.mcap", "chunked.mcap"} {
+		if _, err := api.recordingPath(existing); !errors.Is(err, fs.ErrExist) {
+			t.Errorf("recordingPath(%q) error = %v, want fs.ErrExist", existing, err)
+		}
+	}
+	if _, err := (&apiServer{}).recordingPath("run1.mcap"); err == nil {
+		t.Error("recorded over the API without a recordings directory")
+	}
+}
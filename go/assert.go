@@ -0,0 +1,309 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+	"os"
+	"os/signal"
+	"strconv"
+	"strings"
+	"sync"
+	"syscall"
+	"time"
+)
+
+// Traffic rules are a TOML file with one table per rule, in the config
+// file's subset:
+//
+//	[imu]
+//	topic = "/imu"
+//	min_rate = 95        # Hz over the whole run
+//
+//	[cmd]
+//	topic = "/cmd"
+//	max_gap = "200ms"    # also from the start and until the end
+//
+//	[battery]
+//	topic = "/battery"
+//	field = "battery"    # dotted path into a JSON payload
+//	above = 10
+
+// One rule on a topic; zero limits are not checked
+type trafficRule struct {
+	name    string
+	topic   string
+	minRate float64
+	maxRate float64
+	maxGap  time.Duration
+	field   []string
+	above   *float64
+	below   *float64
+}
+
+// Parse the rules file
+func loadRules(path string) ([]trafficRule, error) {
+	config, err := LoadConfig(path)
+	if err != nil {
+		return nil, err
+	}
+
+	var rules []trafficRule
+	for _, name := range sortedKeys(config) {
+		table := config[name]
+		if name == "" {
+			if len(table) > 0 {
+				return nil, fmt.Errorf("%s: keys must be inside a [rule] table", path)
+			}
+			continue
+		}
+
+		rule := trafficRule{name: name}
+		for key, value := range table {
+			var err error
+			switch key {
+			case "topic":
+				rule.topic = value
+			case "min_rate":
+				rule.minRate, err = strconv.ParseFloat(value, 64)
+			case "max_rate":
+				rule.maxRate, err = strconv.ParseFloat(value, 64)
+			case "max_gap":
+				rule.maxGap, err = time.ParseDuration(value)
+			case "field":
+				rule.field = strings.Split(value, ".")
+			case "above", "below":
+				var limit float64
+				limit, err = strconv.ParseFloat(value, 64)
+				if key == "above" {
+					rule.above = &limit
+				} else {
+					rule.below = &limit
+				}
+			default:
+				err = fmt.Errorf("unknown key")
+			}
+			if err != nil {
+				return nil, fmt.Errorf("%s: rule %s: %s: %w", path, name, key, err)
+			}
+		}
+		if rule.topic == "" {
+			return nil, fmt.Errorf("%s: rule %s has no topic", path, name)
+		}
+		if (rule.above != nil || rule.below != nil) != (rule.field != nil) {
+			return nil, fmt.Errorf("%s: rule %s: field needs above or below, and the other way round", path, name)
+		}
+		rules = append(rules, rule)
+	}
+	if len(rules) == 0 {
+		return nil, fmt.Errorf("%s: no rules", path)
+	}
+	return rules, nil
+}
+
+// Numeric value at a field path of a JSON payload
+func jsonNumber(content string, path []string) (float64, bool) {
+	var value any
+	if json.Unmarshal([]byte(content), &value) != nil {
+		return 0, false
+	}
+	for _, key := range path {
+		object, ok := value.(map[string]any)
+		if !ok {
+			return 0, false
+		}
+		value = object[key]
+	}
+	number, ok := value.(float64)
+	return number, ok
+}
+
+// What was observed on one topic during the run
+type topicObservation struct {
+	messages  int
+	last      time.Time
+	maxGap    time.Duration
+	fieldBad  map[string]int // by rule name: payloads outside the limits
+	fieldMiss map[string]int // by rule name: payloads without the field
+	firstBad  map[string]string
+}
+
+// Outcome of one rule, as reported in JSON
+type ruleReport struct {
+	Name       string   `json:"name"`
+	Topic      string   `json:"topic"`
+	Passed     bool     `json:"passed"`
+	Messages   int      `json:"messages"`
+	QoS        int      `json:"incompatible_qos,omitempty"`
+	RateHz     float64  `json:"rate_hz"`
+	MaxGap     string   `json:"max_gap"`
+	Violations []string `json:"violations,omitempty"`
+}
+
+// The JSON report written by `cardinal assert`
+type assertReport struct {
+	Duration string       `json:"duration"`
+	Passed   bool         `json:"passed"`
+	Rules    []ruleReport `json:"rules"`
+}
+
+// Check rules against what was observed over a run of the given length
+func evaluateRules(rules []trafficRule, observed map[string]*topicObservation, start, end time.Time) assertReport {
+	duration := end.Sub(start)
+	report := assertReport{Duration: duration.String(), Passed: true}
+
+	for _, rule := range rules {
+		obs := observed[rule.topic]
+		// The silence until the end counts as a gap too
+		gap := max(obs.maxGap, end.Sub(obs.last))
+		if obs.last.IsZero() {
+			gap = duration
+		}
+		rate := float64(obs.messages) / duration.Seconds()
+
+		r := ruleReport{Name: rule.name, Topic: rule.topic, Messages: obs.messages, RateHz: rate, MaxGap: gap.String()}
+		if rule.minRate > 0 && rate < rule.minRate {
+			r.Violations = append(r.Violations, fmt.Sprintf("rate %.2f Hz < %g Hz", rate, rule.minRate))
+		}
+		if rule.maxRate > 0 && rate > rule.maxRate {
+			r.Violations = append(r.Violations, fmt.Sprintf("rate %.2f Hz > %g Hz", rate, rule.maxRate))
+		}
+		if rule.maxGap > 0 && gap > rule.maxGap {
+			r.Violations = append(r.Violations, fmt.Sprintf("gap of %v > %v", gap, rule.maxGap))
+		}
+		if n := obs.fieldBad[rule.name]; n > 0 {
+			r.Violations = append(r.Violations, fmt.Sprintf("%s out of range in %d payloads (first: %s)", strings.Join(rule.field, "."), n, obs.firstBad[rule.name]))
+		}
+		if n := obs.fieldMiss[rule.name]; n > 0 {
+			r.Violations = append(r.Violations, fmt.Sprintf("%s missing or not a number in %d payloads", strings.Join(rule.field, "."), n))
+		}
+
+		r.Passed = len(r.Violations) == 0
+		report.Passed = report.Passed && r.Passed
+		report.Rules = append(report.Rules, r)
+	}
+	return report
+}
+
+// Run `cardinal assert`: watch the domain for a while, check the traffic
+// against the rules and print a JSON report, returning 1 on violations
+func runAssert(opts Options) int {
+	settings, err := loadSettings(opts)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal assert:", err)
+		return exitUsage
+	}
+	rules, err := loadRules(opts.RulesPath)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal assert:", err)
+		return exitUsage
+	}
+
+	transport, err := NewFastDDSTransport(settings.Profile.Domain, settings.QoS)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal assert:", err)
+		return exitFailure
+	}
+	transport = withPayloadCipher(readOnlyTransport{transport}, settings.Cipher)
+
+	var mu sync.Mutex
+	start := time.Now()
+	observed := map[string]*topicObservation{}
+	subs := map[string]DDSSubscriber{}
+	topicRules := map[string][]trafficRule{}
+	for _, rule := range rules {
+		topicRules[rule.topic] = append(topicRules[rule.topic], rule)
+	}
+	for topic := range topicRules {
+		observed[topic] = &topicObservation{fieldBad: map[string]int{}, fieldMiss: map[string]int{}, firstBad: map[string]string{}}
+		sub, err := transport.NewSubscriber(topic)
+		if err != nil {
+			fmt.Fprintln(os.Stderr, "cardinal assert:", err)
+			return exitFailure
+		}
+		defer sub.Cleanup()
+		subs[topic] = sub
+
+		go func(topic string, obs *topicObservation) {
+			for msg := range sub.Subscribe() {
+				now := time.Now()
+				mu.Lock()
+				obs.messages++
+				previous := obs.last
+				if previous.IsZero() {
+					previous = start
+				}
+				obs.maxGap = max(obs.maxGap, now.Sub(previous))
+				obs.last = now
+				for _, rule := range topicRules[topic] {
+					if rule.field == nil {
+						continue
+					}
+					value, ok := jsonNumber(msg.Content, rule.field)
+					switch {
+					case !ok:
+						obs.fieldMiss[rule.name]++
+					case (rule.above != nil && value <= *rule.above) || (rule.below != nil && value >= *rule.below):
+						if obs.fieldBad[rule.name] == 0 {
+							obs.firstBad[rule.name] = strconv.FormatFloat(value, 'g', -1, 64)
+						}
+						obs.fieldBad[rule.name]++
+					}
+				}
+				mu.Unlock()
+			}
+		}(topic, observed[topic])
+	}
+
+	ctx, cancel := signal.NotifyContext(context.Background(), os.Interrupt, syscall.SIGTERM)
+	defer cancel()
+	if !opts.Quiet {
+		fmt.Fprintf(os.Stderr, "🔎 Checking %d rules for %v\n", len(rules), opts.Duration)
+	}
+	select {
+	case <-ctx.Done():
+	case <-time.After(opts.Duration):
+	}
+
+	mu.Lock()
+	report := evaluateRules(rules, observed, start, time.Now())
+	mu.Unlock()
+
+	// A silent topic is often a QoS mismatch rather than a missing writer
+	received, mismatched := 0, false
+	for i := range report.Rules {
+		r := &report.Rules[i]
+		received += r.Messages
+		if r.Messages == 0 {
+			if r.QoS = incompatibleQoS(subs[r.Topic]); r.QoS > 0 {
+				mismatched = true
+				r.Violations = append(r.Violations, fmt.Sprintf("%d writers with incompatible QoS", r.QoS))
+				r.Passed = false
+				report.Passed = false
+			}
+		}
+	}
+
+	var cases []testCase
+	for _, rule := range report.Rules {
+		cases = append(cases, testCase{Name: rule.Name, Class: rule.Topic, Failure: strings.Join(rule.Violations, "; ")})
+	}
+	if err := writeReports(opts, "cardinal assert "+opts.RulesPath, opts.Duration, cases, report); err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal assert:", err)
+		return exitFailure
+	}
+
+	printResult(opts, report, func() {
+		out, _ := json.MarshalIndent(report, "", "  ")
+		fmt.Println(string(out))
+	})
+	switch {
+	case report.Passed:
+		return exitOK
+	case mismatched:
+		return exitQoSMismatch
+	case received == 0:
+		return exitNoData
+	}
+	return exitAssertFailed
+}
@@ -0,0 +1,124 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+	"net"
+	"os"
+	"time"
+)
+
+// Run `cardinal attach`: show a running daemon's streams in the TUI without
+// interrupting it, and detach cleanly on quit. Returns the exit code.
+func runAttach(opts Options) int {
+	config, err := LoadConfig(opts.ConfigPath)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal attach:", err)
+		return exitUsage
+	}
+	times, err := config.TimeFormats()
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal attach:", err)
+		return exitUsage
+	}
+	formats, err := config.DisplayFormats(times)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal attach:", err)
+		return exitUsage
+	}
+	// The shared socket keeps encrypted topics sealed
+	cipher, err := config.PayloadCipher()
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal attach:", err)
+		return exitUsage
+	}
+
+	conn, err := net.DialTimeout("unix", opts.ControlSocket, controlTimeout)
+	if err != nil {
+		fmt.Fprintf(os.Stderr, "cardinal attach: cannot reach daemon at %s: %v\n", opts.ControlSocket, err)
+		return exitFailure
+	}
+	defer conn.Close()
+
+	if err := json.NewEncoder(conn).Encode(controlRequest{Command: "attach"}); err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal attach:", err)
+		return exitFailure
+	}
+
+	decoder := json.NewDecoder(conn)
+	var resp controlResponse
+	if err := decoder.Decode(&resp); err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal attach: invalid response from daemon:", err)
+		return exitFailure
+	}
+	if !resp.OK || resp.Status == nil {
+		fmt.Fprintln(os.Stderr, "cardinal attach:", resp.Error)
+		return exitFailure
+	}
+
+	// Show the daemon's uptime rather than our own
+	startTime := time.Now().Add(-time.Duration(resp.Status.Uptime * float64(time.Second)))
+	stats := NewStatsTracker()
+	bursts := NewBurstDetector()
+	label := "Attached to daemon"
+	if resp.ReadOnly {
+		label += " (read-only)"
+	}
+	program, store := newProgram(stats, startTime, programConfig{
+		usingReal:    resp.Status.Transport == "fastdds",
+		sessionLabel: label,
+		bursts:       bursts,
+		formats:      formats,
+		times:        times,
+	})
+
+	streamErr := make(chan error, 1)
+	go runTask(context.Background(), "attach-stream", func(context.Context) {
+		for _, msg := range resp.History {
+			if opened, err := cipher.Open(msg); err == nil {
+				msg = opened
+			}
+			stats.Record(statsSample(msg))
+			bursts.Check(msg)
+			store.Append(msg)
+		}
+		for {
+			var msg DDSMessage
+			if err := decoder.Decode(&msg); err != nil {
+				streamErr <- err
+				program.Quit()
+				return
+			}
+			if opened, err := cipher.Open(msg); err == nil {
+				msg = opened
+			}
+			stats.Record(statsSample(msg))
+			bursts.Check(msg)
+			store.Append(msg)
+		}
+	})
+
+	_, err = program.Run()
+
+	// A stream error is only reported when it ended the session, not when
+	// closing the connection below ends the stream
+	var lostErr error
+	select {
+	case lostErr = <-streamErr:
+	default:
+	}
+
+	// Closing the connection detaches from the daemon
+	conn.Close()
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal attach:", err)
+		return exitFailure
+	}
+	if lostErr != nil {
+		fmt.Fprintln(os.Stderr, "cardinal attach: lost connection to daemon:", lostErr)
+		return exitFailure
+	}
+	fmt.Println("Detached from daemon.")
+	return exitOK
+}
@@ -0,0 +1,73 @@
+package main
+
+import (
+	"fmt"
+	"strings"
+	"time"
+
+	"github.com/charmbracelet/lipgloss"
+)
+
+// Render the bandwidth accounting card
+func (m model) renderBandwidth(headerStyle lipgloss.Style, textColor, dimColor lipgloss.Color) string {
+	window := leaderboardWindows[m.leaderboard.window]
+	now := time.Now()
+
+	rowStyle := lipgloss.NewStyle().Foreground(textColor)
+	dimStyle := lipgloss.NewStyle().Foreground(dimColor).Italic(true)
+
+	var b strings.Builder
+	b.WriteString(headerStyle.Render(fmt.Sprintf("📶 Bandwidth (rates over last %s)", formatDuration(window))))
+	b.WriteString("\n")
+
+	topics := m.stats.Topics()
+	if len(topics) == 0 {
+		b.WriteString(dimStyle.Render("No topics seen yet"))
+		return b.String()
+	}
+
+	b.WriteString(dimStyle.Render(fmt.Sprintf("%-24s %12s %12s %12s %11s %8s", "Topic", "Payload", "Wire (est.)", "Wire rate", "Frag (est.)", "Lost")))
+	b.WriteString("\n")
+
+	var totalPayload, totalWire, totalFragmented, totalLost int
+	var totalRate float64
+	var warnings []string
+	for _, stats := range topics {
+		ws := stats.Window(now, window)
+		totalPayload += stats.TotalBytes
+		totalWire += stats.TotalWireBytes
+		totalRate += ws.WireBandwidth
+		totalFragmented += stats.TotalFragmented
+		totalLost += stats.Lost
+		b.WriteString(rowStyle.Render(fmt.Sprintf("%-24s %12s %12s %10s/s %11d %8d",
+			truncate(stats.Topic, 24),
+			formatBytes(float64(stats.TotalBytes)),
+			formatBytes(float64(stats.TotalWireBytes)),
+			formatBytes(ws.WireBandwidth),
+			stats.TotalFragmented,
+			stats.Lost,
+		)))
+		b.WriteString("\n")
+		if stats.TotalFragmented > 0 && stats.Lost > 0 {
+			warnings = append(warnings, fmt.Sprintf("⚠ %s: samples up to %s are likely fragmented and %d were lost; incomplete fragments are a likely cause",
+				stats.Topic, formatBytes(float64(stats.LargestSample)), stats.Lost))
+		}
+	}
+
+	b.WriteString(rowStyle.Bold(true).Render(fmt.Sprintf("%-24s %12s %12s %10s/s %11d %8d",
+		"Total",
+		formatBytes(float64(totalPayload)),
+		formatBytes(float64(totalWire)),
+		formatBytes(totalRate),
+		totalFragmented,
+		totalLost,
+	)))
+	b.WriteString("\n")
+	for _, warning := range warnings {
+		b.WriteString(rowStyle.Render(warning))
+		b.WriteString("\n")
+	}
+	b.WriteString(dimStyle.Render("Wire estimate: payload + CDR encapsulation + RTPS/UDP headers; fragmented: estimated DATA_FRAG splits at the default 64 KB datagram limit"))
+
+	return b.String()
+}
@@ -0,0 +1,57 @@
+package main
+
+import (
+	"sync"
+	"time"
+)
+
+// Topics with a latency_budget in the [qos] tables have every sample's
+// end-to-end latency compared against it. Slower samples are flagged and
+// counted, and an alert is logged and the latency_budget hook fired at most
+// once per budgetAlertInterval and topic.
+
+// Least time between two alerts for one topic
+const budgetAlertInterval = time.Minute
+
+// Compares latencies against the configured budgets. A nil BudgetChecker
+// checks nothing.
+type BudgetChecker struct {
+	qos *QoSConfig
+
+	mu        sync.Mutex
+	budgets   map[string]time.Duration // by topic, 0 for none
+	lastAlert map[string]time.Time
+}
+
+// A checker for the budgets of a QoS config, or nil when it has none
+func NewBudgetChecker(qos *QoSConfig) *BudgetChecker {
+	if !qos.hasLatencyBudget() {
+		return nil
+	}
+	return &BudgetChecker{qos: qos, budgets: map[string]time.Duration{}, lastAlert: map[string]time.Time{}}
+}
+
+// Check the latency of a message against its topic's budget. Returns the
+// budget, whether the message exceeded it and whether to alert about it.
+func (b *BudgetChecker) Check(msg DDSMessage) (budget time.Duration, over, alert bool) {
+	if b == nil {
+		return 0, false, false
+	}
+
+	b.mu.Lock()
+	defer b.mu.Unlock()
+	budget, ok := b.budgets[msg.Topic]
+	if !ok {
+		budget = b.qos.For(msg.Topic).LatencyBudget
+		b.budgets[msg.Topic] = budget
+	}
+	if budget == 0 || msg.Latency() <= budget {
+		return budget, false, false
+	}
+
+	if msg.ReceivedAt.Sub(b.lastAlert[msg.Topic]) >= budgetAlertInterval {
+		b.lastAlert[msg.Topic] = msg.ReceivedAt
+		alert = true
+	}
+	return budget, true, alert
+}
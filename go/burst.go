@@ -0,0 +1,185 @@
+package main
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+	"sync"
+	"time"
+
+	"github.com/charmbracelet/lipgloss"
+)
+
+// A burst is a sliding window holding far more messages than the topic's
+// mean rate predicts. Consumers with small queues or slow callbacks tend to
+// stall right there, so bursts are kept with their size and duration.
+const (
+	burstWindow      = time.Second
+	burstMinMessages = 10               // never a burst below this many messages per window
+	burstFactor      = 5.0              // times the messages the mean rate predicts
+	burstWarmup      = 10 * time.Second // history needed before the mean rate is trusted
+)
+
+// Finished bursts kept for display
+const maxBurstHistory = 50
+
+// One detected burst on a topic
+type Burst struct {
+	Topic    string    `json:"topic"`
+	Start    time.Time `json:"start"`
+	End      time.Time `json:"end"`
+	Messages int       `json:"messages"`
+	Factor   float64   `json:"factor"` // peak window rate over the mean rate, 0 if the topic was idle
+}
+
+// Time from the first to the last message of the burst
+func (b Burst) Duration() time.Duration {
+	return b.End.Sub(b.Start)
+}
+
+func (b Burst) String() string {
+	factor := "from idle"
+	if b.Factor > 0 {
+		factor = fmt.Sprintf("%.0fx the mean rate", b.Factor)
+	}
+	return fmt.Sprintf("%d messages in %s (%s)", b.Messages, b.Duration().Round(time.Millisecond), factor)
+}
+
+// Arrival history of one topic
+type burstTopic struct {
+	first  time.Time
+	total  int
+	window []time.Time // arrivals within the last burstWindow
+	active *Burst
+}
+
+// Detects bursts per topic. Safe for concurrent use.
+type BurstDetector struct {
+	mu     sync.Mutex
+	topics map[string]*burstTopic
+	ended  []Burst
+}
+
+func NewBurstDetector() *BurstDetector {
+	return &BurstDetector{topics: map[string]*burstTopic{}}
+}
+
+// Record a message. Reports whether it is part of a burst, and returns the
+// bursts that ended with its arrival, on its topic or on topics that went
+// quiet since.
+func (d *BurstDetector) Check(msg DDSMessage) (bool, []Burst) {
+	if d == nil {
+		return false, nil
+	}
+	at := msg.ReceivedAt
+	if at.IsZero() {
+		at = time.Now()
+	}
+
+	d.mu.Lock()
+	defer d.mu.Unlock()
+
+	t, ok := d.topics[msg.Topic]
+	if !ok {
+		t = &burstTopic{first: at}
+		d.topics[msg.Topic] = t
+	}
+	t.total++
+	t.window = append(t.window, at)
+	cutoff := at.Add(-burstWindow)
+	i := 0
+	for i < len(t.window) && t.window[i].Before(cutoff) {
+		i++
+	}
+	t.window = t.window[i:]
+
+	// Mean rate of everything before the window
+	n := len(t.window)
+	history := t.window[0].Sub(t.first)
+	hot := false
+	if history >= burstWarmup && n >= burstMinMessages {
+		expected := float64(t.total-n) / history.Seconds() * burstWindow.Seconds()
+		if float64(n) > burstFactor*expected {
+			hot = true
+			if t.active == nil {
+				t.active = &Burst{Topic: msg.Topic, Start: t.window[0], Messages: n - 1}
+			}
+			t.active.Messages++
+			t.active.End = at
+			if expected > 0 {
+				t.active.Factor = max(t.active.Factor, float64(n)/expected)
+			}
+		}
+	}
+
+	var ended []Burst
+	for _, other := range d.topics {
+		if other.active != nil && ((other == t && !hot) || at.Sub(other.active.End) > burstWindow) {
+			ended = append(ended, *other.active)
+			other.active = nil
+		}
+	}
+	d.ended = append(d.ended, ended...)
+	if len(d.ended) > maxBurstHistory {
+		d.ended = d.ended[len(d.ended)-maxBurstHistory:]
+	}
+	return hot, ended
+}
+
+// Finished and ongoing bursts, oldest first
+func (d *BurstDetector) Recent() []Burst {
+	if d == nil {
+		return nil
+	}
+	d.mu.Lock()
+	defer d.mu.Unlock()
+
+	result := append([]Burst(nil), d.ended...)
+	for _, t := range d.topics {
+		if t.active != nil {
+			result = append(result, *t.active)
+		}
+	}
+	sort.Slice(result, func(i, j int) bool {
+		return result[i].Start.Before(result[j].Start)
+	})
+	return result
+}
+
+// Render the markers under the message rate timeline: one per second that
+// received messages belonging to a burst
+func renderBurstMarkers(hist []bool) string {
+	var b strings.Builder
+	for _, burst := range hist {
+		if burst {
+			b.WriteRune('▲')
+		} else {
+			b.WriteRune(' ')
+		}
+	}
+	return b.String()
+}
+
+// Render the list of recent bursts, newest first
+func (m model) renderBursts(headerStyle lipgloss.Style, textColor, dimColor lipgloss.Color) string {
+	rowStyle := lipgloss.NewStyle().Foreground(textColor)
+	dimStyle := lipgloss.NewStyle().Foreground(dimColor).Italic(true)
+
+	var b strings.Builder
+	b.WriteString(headerStyle.Render("💥 Bursts"))
+	b.WriteString("\n")
+
+	bursts := m.bursts.Recent()
+	if len(bursts) == 0 {
+		b.WriteString(dimStyle.Render("No bursts detected"))
+		return b.String()
+	}
+	const shown = 5
+	for i := len(bursts) - 1; i >= 0 && i >= len(bursts)-shown; i-- {
+		b.WriteString(rowStyle.Render(fmt.Sprintf("%s  %-24s %s",
+			m.times.Format("charts", bursts[i].Start, "s"), truncate(bursts[i].Topic, 24), bursts[i])))
+		b.WriteString("\n")
+	}
+	b.WriteString(dimStyle.Render(fmt.Sprintf("%d bursts recorded, ▲ marks them on the message rate timeline", len(bursts))))
+	return b.String()
+}
@@ -0,0 +1,8 @@
+//go:build capi
+
+#include "capi.h"
+
+void cardinal_call_message_callback(cardinal_message_callback callback, uintptr_t context, const char* topic,
+                                    const char* payload, int length, long long timestamp_ns) {
+    callback(context, topic, payload, length, timestamp_ns);
+}
@@ -0,0 +1,315 @@
+//go:build capi
+// +build capi
+
+package main
+
+/*
+#include <stdlib.h>
+#include "capi.h"
+*/
+import "C"
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"io"
+	"log"
+	"runtime/cgo"
+	"sync"
+	"sync/atomic"
+	"time"
+	"unsafe"
+)
+
+// Built with -tags capi -buildmode=c-shared, Cardinal is a library other
+// programs embed: they drive one headless session through the functions
+// below and receive its messages through a callback. Functions returning int
+// return 0 on success and -1 on failure, with cardinal_last_error telling
+// why; strings Cardinal returns are freed with cardinal_free.
+
+var (
+	errAlreadyOpen = errors.New("a session is already open")
+	errNotOpen     = errors.New("no session is open")
+)
+
+// A C callback and the context it is called with
+type messageCallback struct {
+	fn      C.cardinal_message_callback
+	context C.uintptr_t
+}
+
+// The embedded session
+var embedded struct {
+	mu       sync.Mutex
+	session  *Session
+	cancel   context.CancelFunc
+	wg       *sync.WaitGroup
+	uploader *Uploader
+	lastErr  *C.char
+
+	callback atomic.Pointer[messageCallback]
+}
+
+// Remember an error for cardinal_last_error; call with mu held
+func embedError(err error) C.int {
+	C.free(unsafe.Pointer(embedded.lastErr))
+	embedded.lastErr = C.CString(err.Error())
+	return -1
+}
+
+// Hand an ingested message to the C callback, if one is set
+func deliverMessage(msg DDSMessage) {
+	callback := embedded.callback.Load()
+	if callback == nil {
+		return
+	}
+	topic := C.CString(msg.Topic)
+	defer C.free(unsafe.Pointer(topic))
+	payload := C.CString(msg.Content)
+	defer C.free(unsafe.Pointer(payload))
+	C.cardinal_call_message_callback(callback.fn, callback.context, topic, payload, C.int(msg.Size()),
+		C.longlong(msg.Timestamp.UnixNano()))
+}
+
+// Start a session with the config file at path, or the default config when
+// path is NULL. Fast DDS is used where available, mock DDS otherwise.
+//
+//export cardinal_open
+func cardinal_open(path *C.char) C.int {
+	embedded.mu.Lock()
+	defer embedded.mu.Unlock()
+	if embedded.session != nil {
+		return embedError(errAlreadyOpen)
+	}
+
+	opts := Options{Domain: -1}
+	if path != nil {
+		opts.ConfigPath = C.GoString(path)
+	}
+	settings, err := loadSettings(opts)
+	if err != nil {
+		return embedError(err)
+	}
+	transport, err := NewFastDDSTransport(settings.Profile.Domain, settings.QoS)
+	if err != nil {
+		log.Printf("Real DDS failed, using mock DDS: %v", err)
+		transport = NewMockTransport()
+	}
+	transport = withPayloadCipher(transport, settings.Cipher)
+
+	ctx, cancel := context.WithCancel(context.Background())
+	wg := &sync.WaitGroup{}
+	startTime := time.Now()
+	uploader := StartUploader(settings.Upload)
+	session := NewSession(ctx, wg, transport, NewStatsTracker(), startTime, deliverMessage)
+	session.cipher = settings.Cipher
+	session.redactor = settings.Redactor
+	session.duplicates = NewDuplicateDetector(time.Second)
+	session.ordering = NewOrderChecker()
+	session.events.Subscribe(settings.Hooks.OnEvent)
+	session.budgets = NewBudgetChecker(settings.QoS)
+	session.ignore = settings.Ignore
+	session.recFilter = settings.Record
+	session.uploader = uploader
+	session.recOpts = recorderOptions{
+		decimation: settings.Decimate,
+		metadata:   sessionMetadata(settings, transport, startTime),
+		chunks:     settings.Chunks,
+		finished:   uploader.Enqueue,
+	}
+	session.trackReaders()
+	session.watchDiscovery()
+	for _, spec := range settings.Sinks {
+		sink, err := StartSink(spec)
+		if err != nil {
+			log.Printf("Error starting sink: %v", err)
+			continue
+		}
+		session.sinks = append(session.sinks, sink)
+	}
+
+	embedded.session, embedded.cancel, embedded.wg, embedded.uploader = session, cancel, wg, uploader
+	return 0
+}
+
+// The open session, or nil after recording why there is none; call with mu
+// held
+func openSession() *Session {
+	if embedded.session == nil {
+		embedError(errNotOpen)
+	}
+	return embedded.session
+}
+
+// Publish length bytes of payload to a topic
+//
+//export cardinal_publish
+func cardinal_publish(topic *C.char, payload *C.char, length C.int) C.int {
+	embedded.mu.Lock()
+	defer embedded.mu.Unlock()
+	session := openSession()
+	if session == nil {
+		return -1
+	}
+	if err := session.Publish(C.GoString(topic), C.GoStringN(payload, length)); err != nil {
+		return embedError(err)
+	}
+	return 0
+}
+
+// Subscribe to a topic
+//
+//export cardinal_subscribe
+func cardinal_subscribe(topic *C.char) C.int {
+	embedded.mu.Lock()
+	defer embedded.mu.Unlock()
+	session := openSession()
+	if session == nil {
+		return -1
+	}
+	if err := session.Subscribe(C.GoString(topic)); err != nil {
+		return embedError(err)
+	}
+	return 0
+}
+
+// Call callback with context for every message from now on; a NULL callback
+// stops the calls
+//
+//export cardinal_on_message
+func cardinal_on_message(callback C.cardinal_message_callback, context C.uintptr_t) {
+	if callback == nil {
+		embedded.callback.Store(nil)
+		return
+	}
+	embedded.callback.Store(&messageCallback{fn: callback, context: context})
+}
+
+// Start recording every message to a capture file
+//
+//export cardinal_start_recording
+func cardinal_start_recording(path *C.char) C.int {
+	embedded.mu.Lock()
+	defer embedded.mu.Unlock()
+	session := openSession()
+	if session == nil {
+		return -1
+	}
+	if _, err := session.StartRecording(C.GoString(path)); err != nil {
+		return embedError(err)
+	}
+	return 0
+}
+
+// Finish the recording
+//
+//export cardinal_stop_recording
+func cardinal_stop_recording() C.int {
+	embedded.mu.Lock()
+	defer embedded.mu.Unlock()
+	session := openSession()
+	if session == nil {
+		return -1
+	}
+	if _, err := session.StopRecording(); err != nil {
+		return embedError(err)
+	}
+	return 0
+}
+
+// Session status as JSON, as `cardinal ctl status` receives it, or NULL
+// without a session
+//
+//export cardinal_status_json
+func cardinal_status_json() *C.char {
+	embedded.mu.Lock()
+	defer embedded.mu.Unlock()
+	session := openSession()
+	if session == nil {
+		return nil
+	}
+	status, err := json.Marshal(session.Status())
+	if err != nil {
+		embedError(err)
+		return nil
+	}
+	return C.CString(string(status))
+}
+
+// Stop the session: finish its recording, close its sinks and endpoints,
+// and wait for queued uploads
+//
+//export cardinal_close
+func cardinal_close() {
+	embedded.mu.Lock()
+	defer embedded.mu.Unlock()
+	if embedded.session == nil {
+		return
+	}
+	embedded.session.Close()
+	embedded.cancel()
+	embedded.wg.Wait()
+	embedded.uploader.Close()
+	embedded.session = nil
+}
+
+// Open a capture file for reading, without needing a session. Returns a
+// handle for cardinal_capture_next, or 0 on failure.
+//
+//export cardinal_capture_open
+func cardinal_capture_open(path *C.char) C.uintptr_t {
+	capture, err := OpenCapture(C.GoString(path))
+	if err != nil {
+		embedded.mu.Lock()
+		defer embedded.mu.Unlock()
+		embedError(err)
+		return 0
+	}
+	return C.uintptr_t(cgo.NewHandle(capture))
+}
+
+// Read the next message of a capture into msg. Returns 1 for a message, 0
+// at the end of the capture and -1 on failure.
+//
+//export cardinal_capture_next
+func cardinal_capture_next(capture C.uintptr_t, msg *C.cardinal_message) C.int {
+	next, err := cgo.Handle(capture).Value().(*CaptureReader).Next()
+	if err == io.EOF {
+		return 0
+	}
+	if err != nil {
+		embedded.mu.Lock()
+		defer embedded.mu.Unlock()
+		return embedError(err)
+	}
+	msg.topic = C.CString(next.Topic)
+	msg.payload = (*C.char)(C.CBytes([]byte(next.Content)))
+	msg.length = C.int(next.Size())
+	msg.timestamp_ns = C.longlong(next.Timestamp.UnixNano())
+	return 1
+}
+
+// Close a capture opened with cardinal_capture_open
+//
+//export cardinal_capture_close
+func cardinal_capture_close(capture C.uintptr_t) {
+	handle := cgo.Handle(capture)
+	handle.Value().(*CaptureReader).Close()
+	handle.Delete()
+}
+
+// Why the last call failed; valid until the next failing call
+//
+//export cardinal_last_error
+func cardinal_last_error() *C.char {
+	embedded.mu.Lock()
+	defer embedded.mu.Unlock()
+	return embedded.lastErr
+}
+
+// Free a string returned by Cardinal
+//
+//export cardinal_free
+func cardinal_free(s *C.char) {
+	C.free(unsafe.Pointer(s))
+}
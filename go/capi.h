@@ -0,0 +1,24 @@
+#ifndef CARDINAL_CAPI_H
+#define CARDINAL_CAPI_H
+
+#include <stdint.h>
+
+// Called for every message Cardinal ingests, on one of its threads. topic and
+// payload are only valid during the call; payload holds length bytes.
+typedef void (*cardinal_message_callback)(uintptr_t context, const char* topic, const char* payload, int length,
+                                          long long timestamp_ns);
+
+// A message read from a capture; topic and payload are freed with
+// cardinal_free
+typedef struct {
+    char* topic;
+    char* payload;
+    int length;
+    long long timestamp_ns;
+} cardinal_message;
+
+// Call a message callback, which cgo cannot do itself
+void cardinal_call_message_callback(cardinal_message_callback callback, uintptr_t context, const char* topic,
+                                    const char* payload, int length, long long timestamp_ns);
+
+#endif // CARDINAL_CAPI_H
@@ -0,0 +1,324 @@
+package main
+
+import (
+	"bufio"
+	"bytes"
+	"context"
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"io"
+	"os"
+	"sort"
+	"time"
+
+	"cardinal/core"
+)
+
+// Captures are written as MCAP files (https://mcap.dev) so they can be
+// opened by standard tooling. Cardinal writes unchunked files without a
+// summary section, which keeps the writer simple and streamable.
+
+const captureMagic = core.Magic
+
+// MCAP record opcodes
+const (
+	mcapOpHeader   = core.OpHeader
+	mcapOpFooter   = core.OpFooter
+	mcapOpSchema   = core.OpSchema
+	mcapOpChannel  = core.OpChannel
+	mcapOpMessage  = core.OpMessage
+	mcapOpChunk    = core.OpChunk
+	mcapOpMetadata = core.OpMetadata
+	mcapOpDataEnd  = core.OpDataEnd
+)
+
+// MCAP profile and message encoding written by Cardinal
+const (
+	captureProfile  = "cardinal"
+	captureLibrary  = "cardinal"
+	captureEncoding = "cardinal-text"
+)
+
+// Builds the content of a single MCAP record
+type mcapRecord struct {
+	bytes.Buffer
+}
+
+func (r *mcapRecord) uint16(v uint16) {
+	binary.Write(r, binary.LittleEndian, v)
+}
+
+func (r *mcapRecord) uint32(v uint32) {
+	binary.Write(r, binary.LittleEndian, v)
+}
+
+func (r *mcapRecord) uint64(v uint64) {
+	binary.Write(r, binary.LittleEndian, v)
+}
+
+func (r *mcapRecord) string(s string) {
+	r.uint32(uint32(len(s)))
+	r.WriteString(s)
+}
+
+// Maps are prefixed with their total byte length
+func (r *mcapRecord) stringMap(m map[string]string) {
+	var entries mcapRecord
+	for _, key := range sortedKeys(m) {
+		entries.string(key)
+		entries.string(m[key])
+	}
+	r.uint32(uint32(entries.Len()))
+	r.Write(entries.Bytes())
+}
+
+// Keys of a map in sorted order, for deterministic output
+func sortedKeys[V any](m map[string]V) []string {
+	keys := make([]string, 0, len(m))
+	for key := range m {
+		keys = append(keys, key)
+	}
+	sort.Strings(keys)
+	return keys
+}
+
+// Writes DDS messages to an MCAP capture file
+type CaptureWriter struct {
+	file      *os.File
+	w         *bufio.Writer
+	channels  map[string]uint16
+	sequences map[uint16]uint32
+	rosTypes  map[string]string // topics holding raw CDR, with their ROS type
+	messages  int
+	bytes     int64
+}
+
+// Create a capture file and write its header
+func CreateCapture(path string) (*CaptureWriter, error) {
+	file, err := os.Create(path)
+	if err != nil {
+		return nil, err
+	}
+
+	c := &CaptureWriter{
+		file:      file,
+		w:         bufio.NewWriter(file),
+		channels:  map[string]uint16{},
+		sequences: map[uint16]uint32{},
+		rosTypes:  map[string]string{},
+	}
+
+	var header mcapRecord
+	header.string(captureProfile)
+	header.string(captureLibrary)
+
+	if _, err := c.w.WriteString(captureMagic); err != nil {
+		file.Close()
+		return nil, err
+	}
+	if err := c.writeRecord(mcapOpHeader, &header); err != nil {
+		file.Close()
+		return nil, err
+	}
+	return c, nil
+}
+
+func (c *CaptureWriter) writeRecord(opcode byte, record *mcapRecord) error {
+	var prefix [9]byte
+	prefix[0] = opcode
+	binary.LittleEndian.PutUint64(prefix[1:], uint64(record.Len()))
+	if _, err := c.w.Write(prefix[:]); err != nil {
+		return err
+	}
+	_, err := c.w.Write(record.Bytes())
+	c.bytes += int64(len(prefix) + record.Len())
+	return err
+}
+
+// Channel ID for a topic, writing the channel record on first use
+func (c *CaptureWriter) channel(topic string) (uint16, error) {
+	if id, ok := c.channels[topic]; ok {
+		return id, nil
+	}
+
+	id := uint16(len(c.channels) + 1)
+	encoding, metadata := captureEncoding, map[string]string(nil)
+	if rosType, ok := c.rosTypes[topic]; ok {
+		encoding, metadata = "cdr", map[string]string{"ros_type": rosType}
+	}
+	var record mcapRecord
+	record.uint16(id)
+	record.uint16(0) // no schema
+	record.string(topic)
+	record.string(encoding)
+	record.stringMap(metadata)
+	if err := c.writeRecord(mcapOpChannel, &record); err != nil {
+		return 0, err
+	}
+	c.channels[topic] = id
+	return id, nil
+}
+
+// Mark a topic's payloads as raw CDR of a ROS type, before its first message
+func (c *CaptureWriter) SetROSType(topic, rosType string) {
+	c.rosTypes[topic] = rosType
+}
+
+// Append a message to the capture
+func (c *CaptureWriter) Write(msg DDSMessage) error {
+	id, err := c.channel(msg.Topic)
+	if err != nil {
+		return err
+	}
+
+	c.sequences[id]++
+	var record mcapRecord
+	record.uint16(id)
+	record.uint32(c.sequences[id])
+	record.uint64(uint64(msg.ReceivedAt.UnixNano()))
+	record.uint64(uint64(msg.Timestamp.UnixNano()))
+	record.WriteString(msg.Content)
+	if err := c.writeRecord(mcapOpMessage, &record); err != nil {
+		return err
+	}
+	c.messages++
+	return nil
+}
+
+// Add a named metadata record to the capture
+func (c *CaptureWriter) WriteMetadata(name string, metadata map[string]string) error {
+	var record mcapRecord
+	record.string(name)
+	record.stringMap(metadata)
+	return c.writeRecord(mcapOpMetadata, &record)
+}
+
+// Write buffered records to the file, so readers following it see them
+func (c *CaptureWriter) Flush() error {
+	return c.w.Flush()
+}
+
+// Finish the capture: write the data end and footer records and close the file
+func (c *CaptureWriter) Close() error {
+	var dataEnd mcapRecord
+	dataEnd.uint32(0) // CRC not computed
+
+	var footer mcapRecord
+	footer.uint64(0) // no summary section
+	footer.uint64(0)
+	footer.uint32(0)
+
+	err := c.writeRecord(mcapOpDataEnd, &dataEnd)
+	if err == nil {
+		err = c.writeRecord(mcapOpFooter, &footer)
+	}
+	if err == nil {
+		_, err = c.w.WriteString(captureMagic)
+	}
+	if err == nil {
+		err = c.w.Flush()
+	}
+	if closeErr := c.file.Close(); err == nil {
+		err = closeErr
+	}
+	if err != nil {
+		return fmt.Errorf("failed to finish capture: %w", err)
+	}
+	return nil
+}
+
+// Largest record the reader accepts, guarding against corrupt lengths
+const maxCaptureRecord = core.MaxRecord
+
+// Reads messages back from an MCAP capture, one record at a time
+type CaptureReader struct {
+	file    *os.File
+	decoder *core.Decoder
+}
+
+// Open a capture file and check its magic
+func OpenCapture(path string) (*CaptureReader, error) {
+	file, err := os.Open(path)
+	if err != nil {
+		return nil, err
+	}
+	return newCaptureReader(path, file, file)
+}
+
+// How often a followed capture is checked for new data
+const captureFollowPoll = 200 * time.Millisecond
+
+// Open a capture that another process is still writing, waiting for the
+// file to appear. Reads wait for the writer at the end of the file, so Next
+// only returns io.EOF once the capture is finished, or an error once ctx
+// ends.
+func FollowCapture(ctx context.Context, path string) (*CaptureReader, error) {
+	for {
+		file, err := os.Open(path)
+		if err == nil {
+			return newCaptureReader(path, file, &tailReader{ctx: ctx, file: file})
+		}
+		if !errors.Is(err, os.ErrNotExist) {
+			return nil, err
+		}
+		select {
+		case <-ctx.Done():
+			return nil, ctx.Err()
+		case <-time.After(captureFollowPoll):
+		}
+	}
+}
+
+// Reads a file that is still growing, waiting at its end instead of
+// returning io.EOF until ctx ends
+type tailReader struct {
+	ctx  context.Context
+	file *os.File
+}
+
+func (t *tailReader) Read(p []byte) (int, error) {
+	for {
+		n, err := t.file.Read(p)
+		if n > 0 || err != io.EOF {
+			return n, err
+		}
+		select {
+		case <-t.ctx.Done():
+			return 0, t.ctx.Err()
+		case <-time.After(captureFollowPoll):
+		}
+	}
+}
+
+func newCaptureReader(path string, file *os.File, r io.Reader) (*CaptureReader, error) {
+	decoder, err := core.NewDecoder(r)
+	if err != nil {
+		file.Close()
+		return nil, fmt.Errorf("%s is not an MCAP capture", path)
+	}
+	return &CaptureReader{file: file, decoder: decoder}, nil
+}
+
+// Read the next message, returning io.EOF after the last one
+func (c *CaptureReader) Next() (DDSMessage, error) {
+	record, err := c.decoder.Next()
+	if err != nil {
+		return DDSMessage{}, err
+	}
+	return DDSMessage{
+		Topic:      record.Topic,
+		Content:    string(record.Data),
+		Timestamp:  record.PublishTime,
+		ReceivedAt: record.LogTime,
+	}, nil
+}
+
+// Metadata records read so far, by name
+func (c *CaptureReader) Metadata() map[string]map[string]string {
+	return c.decoder.Metadata()
+}
+
+func (c *CaptureReader) Close() error {
+	return c.file.Close()
+}
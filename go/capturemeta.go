@@ -0,0 +1,106 @@
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+	"os"
+	"runtime"
+	"runtime/debug"
+	"strconv"
+	"strings"
+	"time"
+)
+
+// Every recording starts with a metadata record describing the session that
+// wrote it: host, Cardinal and DDS versions, profile and a snapshot of the
+// config, so a capture can still be interpreted months later. The viewers
+// show it next to the capture's path.
+
+// Name of the session metadata record
+const captureSessionMetadata = "cardinal.session"
+
+// Config keys holding secrets, left out of snapshots
+var secretConfigKeys = map[string]bool{
+	"encryption.key": true,
+	"token":          true,
+	"password":       true,
+}
+
+// Version of this build: the module version for released builds, the VCS
+// revision for builds from a checkout
+func cardinalVersion() string {
+	info, ok := debug.ReadBuildInfo()
+	if !ok {
+		return "unknown"
+	}
+	version := info.Main.Version
+	for _, setting := range info.Settings {
+		if setting.Key == "vcs.revision" && (version == "" || version == "(devel)") {
+			version = setting.Value
+			if len(version) > 12 {
+				version = version[:12]
+			}
+		}
+	}
+	if version == "" {
+		return "unknown"
+	}
+	return version
+}
+
+// The config as JSON, secrets redacted
+func (c Config) Snapshot() string {
+	redacted := Config{}
+	for table, settings := range c {
+		if len(settings) == 0 {
+			continue
+		}
+		redacted[table] = map[string]string{}
+		for key, value := range settings {
+			if secretConfigKeys[key] || secretConfigKeys[table+"."+key] {
+				value = "<redacted>"
+			}
+			redacted[table][key] = value
+		}
+	}
+	snapshot, _ := json.Marshal(redacted)
+	return string(snapshot)
+}
+
+// Metadata describing the running session
+func sessionMetadata(settings Settings, transport Transport, startTime time.Time) map[string]string {
+	host, _ := os.Hostname()
+	dds := transport.Name()
+	if version := fastDDSVersion(); version != "" && dds == "fastdds" {
+		dds = "Fast DDS " + version
+	}
+	return map[string]string{
+		"host":     host,
+		"version":  cardinalVersion(),
+		"go":       runtime.Version(),
+		"dds":      dds,
+		"started":  startTime.Format(time.RFC3339Nano),
+		"profile":  settings.Profile.Name,
+		"domain":   strconv.Itoa(settings.Profile.Domain),
+		"config":   settings.Snapshot,
+		"platform": runtime.GOOS + "/" + runtime.GOARCH,
+	}
+}
+
+// One line summary of session metadata, or "" for captures without any
+func describeSession(metadata map[string]string) string {
+	if metadata == nil {
+		return ""
+	}
+	parts := []string{fmt.Sprintf("Cardinal %s on %s", metadata["version"], metadata["host"])}
+	if dds := metadata["dds"]; dds != "" {
+		parts = append(parts, dds)
+	}
+	if profile := metadata["profile"]; profile != "" {
+		parts = append(parts, fmt.Sprintf("profile %s, domain %s", profile, metadata["domain"]))
+	}
+	if started, err := time.Parse(time.RFC3339Nano, metadata["started"]); err == nil {
+		parts = append(parts, "started "+started.Format("2006-01-02 15:04:05 MST"))
+	}
+	return strings.Join(parts, " • ")
+}
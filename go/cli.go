@@ -0,0 +1,431 @@
+package main
+
+import (
+	"flag"
+	"fmt"
+	"os"
+	"path/filepath"
+	"slices"
+	"strconv"
+	"strings"
+	"time"
+)
+
+// Exit codes shared by every command, so scripts can branch on the outcome
+const (
+	exitOK           = 0
+	exitFailure      = 1 // anything went wrong that has no code of its own
+	exitUsage        = 2 // invalid arguments, config or input files
+	exitAssertFailed = 3 // the checks ran and found differences or violations
+	exitNoData       = 4 // nothing was received on the watched topics
+	exitQoSMismatch  = 5 // a remote endpoint was found with incompatible QoS
+	exitTimeout      = 6 // a wait or deadline expired
+)
+
+// Highest rate gen, scenario steps and the replay clock publish at. Ticker
+// periods are whole nanoseconds, and far below a microsecond they round to
+// nothing.
+const maxRateHz = 1e6
+
+// Command line options
+type Options struct {
+	Command       string
+	Args          []string
+	ConfigPath    string
+	Profile       string
+	Domain        int
+	Confirm       string
+	Expected      string
+	Actual        string
+	TimeTolerance time.Duration
+	Align         string
+	PprofAddr     string
+	OTLPEndpoint  string
+	Headless      bool
+	ReadOnly      bool
+	DemoPublisher bool
+	DemoInterval  time.Duration
+	HealthAddr    string
+	HealthMaxAge  time.Duration
+	APIAddr       string
+	APITokens     string
+	APIRecordings string
+	TLSCert       string
+	TLSKey        string
+	DupWindow     time.Duration
+	Heartbeat     string
+	BeatInterval  time.Duration
+	PersistStats  bool
+	StatsDB       string
+	MonoLatency   bool
+	HideOwn       bool
+	ControlSocket string
+	ShareSocket   string
+	Rate          float64
+	ClockTopic    string
+	ClockHz       float64
+	ExternalClock string
+	Output        string
+	Map           stringList
+	CSV           string
+	Topic         string
+	Topics        stringList
+	Hz            float64
+	TimeColumn    string
+	Duration      time.Duration
+	RulesPath     string
+	JUnitPath     string
+	JSONReport    string
+	Quiet         bool
+	JSON          bool
+	Timeout       time.Duration
+	Count         int
+	WaitMatched   time.Duration
+	Follow        bool
+	StatsOnly     bool
+	Domains       domainList
+	Key           string
+	Dispose       bool
+	Unregister    bool
+	IDL           string
+	Package       string
+}
+
+// Flag taking a comma-separated list of DDS domain IDs
+type domainList []int
+
+func (l *domainList) String() string {
+	ids := make([]string, len(*l))
+	for i, id := range *l {
+		ids[i] = strconv.Itoa(id)
+	}
+	return strings.Join(ids, ", ")
+}
+
+func (l *domainList) Set(value string) error {
+	for _, field := range strings.Split(value, ",") {
+		id, err := strconv.Atoi(strings.TrimSpace(field))
+		if err != nil || id < 0 || id > 232 {
+			return fmt.Errorf("invalid domain %q, expected ids between 0 and 232", field)
+		}
+		if slices.Contains(*l, id) {
+			return fmt.Errorf("domain %d given twice", id)
+		}
+		*l = append(*l, id)
+	}
+	return nil
+}
+
+// Flag collecting every occurrence of a repeated option
+type stringList []string
+
+func (l *stringList) String() string {
+	return strings.Join(*l, ", ")
+}
+
+func (l *stringList) Set(value string) error {
+	*l = append(*l, value)
+	return nil
+}
+
+// Subcommands and their one-line descriptions
+var commands = []struct {
+	name        string
+	description string
+}{
+	{"daemon", "run the pipelines in the background, controlled through a Unix socket"},
+	{"ctl", "send a command (status, add-topic, start-recording, stop-recording) to a running daemon"},
+	{"attach", "open the TUI on a running daemon's live streams; quitting detaches"},
+	{"echo", "print the messages received on a topic"},
+	{"pub", "publish one message to a topic"},
+	{"verify", "compare a capture against a golden capture topic by topic"},
+	{"diff", "inspect the differences between two captures side by side in the TUI"},
+	{"view", "show a capture in the TUI, or follow one that is still being recorded"},
+	{"gen", "publish synthetic waveforms (sine, square, ramp, noise) on topics, or generate Go types from IDL"},
+	{"scenario", "run a YAML scenario of timed publish and expect steps and report pass/fail"},
+	{"assert", "watch traffic headlessly and check it against rules (rates, gaps, field limits) for CI"},
+	{"import", "convert a ROS 2 bag (sqlite3 or MCAP) into a capture for replay"},
+	{"export", "convert a capture into a Parquet file with payload fields as columns"},
+	{"stats", "show the per-topic totals kept across restarts by --persist-stats"},
+	{"replay", "publish a capture's messages with their original timing, optionally against a simulation clock"},
+}
+
+// Default path of the daemon control socket
+func defaultControlSocket() string {
+	if dir := os.Getenv("XDG_RUNTIME_DIR"); dir != "" {
+		return filepath.Join(dir, "cardinal.sock")
+	}
+	return filepath.Join(os.TempDir(), fmt.Sprintf("cardinal-%d.sock", os.Getuid()))
+}
+
+// Register the options selecting the config file and profile
+func registerProfileFlags(fs *flag.FlagSet, opts *Options) {
+	fs.StringVar(&opts.ConfigPath, "config", defaultConfigPath(), "config file `path`")
+	fs.StringVar(&opts.Profile, "profile", "", "connection profile `name` from the config file")
+	fs.IntVar(&opts.Domain, "domain", -1, "DDS domain `id`, overriding the profile's")
+}
+
+// Register the options shared by every command that runs the pipelines
+func registerRunFlags(fs *flag.FlagSet, opts *Options) {
+	registerProfileFlags(fs, opts)
+	fs.StringVar(&opts.PprofAddr, "pprof", "", "serve pprof and execution traces on `addr` (e.g. localhost:6060)")
+	fs.StringVar(&opts.HealthAddr, "health-addr", "", "serve a /healthz endpoint on `addr` (e.g. :8080)")
+	fs.DurationVar(&opts.HealthMaxAge, "health-max-age", 30*time.Second, "report unhealthy when a topic has been silent this long (0 disables)")
+	fs.StringVar(&opts.APIAddr, "api-addr", "", "serve the HTTP control API on `addr` (requires --api-tokens)")
+	fs.StringVar(&opts.APITokens, "api-tokens", "", "API tokens `file` with one \"<viewer|operator> <token>\" per line")
+	fs.StringVar(&opts.APIRecordings, "api-recordings", "", "write recordings started over the API under `dir` (required to record over the API)")
+	fs.StringVar(&opts.TLSCert, "tls-cert", "", "serve the API over TLS with the PEM certificate `file`")
+	fs.StringVar(&opts.TLSKey, "tls-key", "", "PEM private key `file` for --tls-cert")
+	fs.DurationVar(&opts.DupWindow, "duplicate-window", time.Second, "flag identical payloads from different writers arriving within this window (0 disables)")
+	fs.StringVar(&opts.OTLPEndpoint, "otlp-endpoint", "", "export spans and metrics to the OTLP/HTTP collector at `url` (e.g. http://localhost:4318)")
+	fs.Var(&opts.Domains, "domains", "subscribe in all of these comma-separated DDS domain `ids` at once, labelling messages by domain; publishers use the first")
+	fs.Var(&opts.Topics, "topic", "`topic` to subscribe to on start, and of the demo publisher; repeat for several (default \""+defaultTopic+"\")")
+	fs.BoolVar(&opts.DemoPublisher, "demo-publisher", false, "publish \"Hello World\" messages on every --topic every --rate")
+	fs.DurationVar(&opts.DemoInterval, "rate", 2*time.Second, "time between demo publisher messages")
+	fs.StringVar(&opts.Heartbeat, "heartbeat-topic", "", "publish Cardinal's own status on `topic` so other monitoring can check it is alive")
+	fs.DurationVar(&opts.BeatInterval, "heartbeat-interval", time.Second, "time between heartbeats on --heartbeat-topic")
+	fs.BoolVar(&opts.PersistStats, "persist-stats", false, "add the per-topic totals and histograms to --stats-db, so they survive restarts")
+	fs.StringVar(&opts.StatsDB, "stats-db", defaultStatsDB(), "statistics `file` for --persist-stats")
+	fs.BoolVar(&opts.MonoLatency, "monotonic-latency", false, "measure latency on the senders' monotonic clocks, immune to NTP steps (falls back to wall clocks for senders without one)")
+	fs.BoolVar(&opts.HideOwn, "hide-own", false, "drop the messages of this instance's own publishers instead of just marking them")
+	fs.BoolVar(&opts.StatsOnly, "stats-only", false, "keep only per-topic counters and histograms; payloads are never stored, recorded, forwarded or logged (needs --headless or daemon)")
+	fs.BoolVar(&opts.ReadOnly, "read-only", false, "never publish on the domain (disables the demo publisher and every other publishing path)")
+}
+
+// Register the options selecting how a command prints its result
+func registerOutputFlags(fs *flag.FlagSet, opts *Options) {
+	fs.BoolVar(&opts.Quiet, "quiet", false, "print nothing but errors; only the exit code tells the result")
+	fs.BoolVar(&opts.JSON, "json", false, "print the result as JSON")
+}
+
+// Register the options writing the results of a checking command to files
+func registerReportFlags(fs *flag.FlagSet, opts *Options) {
+	fs.StringVar(&opts.JUnitPath, "junit", "", "also write the results as JUnit XML to `path`")
+	fs.StringVar(&opts.JSONReport, "json-report", "", "also write the results as JSON to `path`")
+}
+
+// Parse the command line arguments
+func parseOptions(args []string) (Options, error) {
+	opts := Options{Domain: -1}
+	if len(args) > 0 && !strings.HasPrefix(args[0], "-") {
+		opts.Command, args = args[0], args[1:]
+	}
+
+	name := "cardinal"
+	if opts.Command != "" {
+		name += " " + opts.Command
+	}
+	fs := flag.NewFlagSet(name, flag.ContinueOnError)
+
+	usage := "Usage: " + name + " [options]"
+	switch opts.Command {
+	case "":
+		registerRunFlags(fs, &opts)
+		fs.BoolVar(&opts.Headless, "headless", false, "run without the TUI, logging received messages until SIGINT/SIGTERM")
+		usage = "Usage: cardinal [command] [options]"
+	case "daemon":
+		registerRunFlags(fs, &opts)
+		fs.StringVar(&opts.ControlSocket, "socket", defaultControlSocket(), "control socket `path`")
+		fs.StringVar(&opts.ShareSocket, "share-socket", "", "also serve a read-only socket at `path` that the daemon's group can attach to")
+		opts.Headless = true
+	case "ctl":
+		fs.StringVar(&opts.ControlSocket, "socket", defaultControlSocket(), "control socket `path`")
+		usage = "Usage: cardinal ctl [options] <status|add-topic TOPIC|start-recording PATH|stop-recording>"
+	case "attach":
+		fs.StringVar(&opts.ControlSocket, "socket", defaultControlSocket(), "control socket `path`")
+		fs.StringVar(&opts.ConfigPath, "config", defaultConfigPath(), "config file `path` (for display formats)")
+	case "echo":
+		registerProfileFlags(fs, &opts)
+		fs.DurationVar(&opts.Timeout, "timeout", 0, "stop after this long (0 waits forever)")
+		fs.IntVar(&opts.Count, "count", 0, "stop after this many messages (0 for no limit)")
+		registerOutputFlags(fs, &opts)
+		usage = "Usage: cardinal echo [options] TOPIC"
+	case "pub":
+		registerProfileFlags(fs, &opts)
+		fs.StringVar(&opts.Confirm, "confirm", "", "confirm publishing on a protected profile by passing the `topic` name")
+		fs.DurationVar(&opts.WaitMatched, "wait-matched", 0, "wait up to this long for a reader to match before sending (0 sends right away)")
+		fs.StringVar(&opts.Key, "key", "", "instance `key` to publish to on a keyed topic")
+		fs.BoolVar(&opts.Dispose, "dispose", false, "dispose the instance of --key instead of publishing")
+		fs.BoolVar(&opts.Unregister, "unregister", false, "unregister from the instance of --key instead of publishing")
+		registerOutputFlags(fs, &opts)
+		usage = "Usage: cardinal pub [options] TOPIC MESSAGE\n       cardinal pub --key KEY --dispose|--unregister [options] TOPIC"
+	case "verify":
+		fs.StringVar(&opts.ConfigPath, "config", defaultConfigPath(), "config file `path` (for the encryption key)")
+		fs.StringVar(&opts.Expected, "expected", "", "golden capture `path`")
+		fs.StringVar(&opts.Actual, "actual", "", "capture `path` to check")
+		fs.DurationVar(&opts.TimeTolerance, "time-tolerance", 50*time.Millisecond, "allowed timing skew per message, relative to the capture start (0 disables)")
+		registerOutputFlags(fs, &opts)
+		usage = "Usage: cardinal verify --expected GOLDEN.mcap --actual RUN.mcap [options]"
+	case "diff":
+		fs.StringVar(&opts.ConfigPath, "config", defaultConfigPath(), "config file `path` (for the encryption key)")
+		fs.StringVar(&opts.Align, "align", "sequence", "initial alignment: sequence or time")
+		fs.DurationVar(&opts.TimeTolerance, "time-tolerance", 50*time.Millisecond, "largest offset difference paired up when aligning by time")
+		usage = "Usage: cardinal diff [options] EXPECTED.mcap ACTUAL.mcap"
+	case "view":
+		fs.StringVar(&opts.ConfigPath, "config", defaultConfigPath(), "config file `path` (for display formats and the encryption key)")
+		fs.BoolVar(&opts.Follow, "follow", false, "keep showing messages as they are written to the capture, and to the chunks after it")
+		usage = "Usage: cardinal view [options] CAPTURE.mcap"
+	case "gen":
+		registerProfileFlags(fs, &opts)
+		fs.StringVar(&opts.Confirm, "confirm", "", "confirm generating on a protected profile by passing the profile `name`")
+		fs.Float64Var(&opts.Hz, "hz", 50, "samples per second on every topic")
+		fs.DurationVar(&opts.Duration, "duration", 0, "stop after this long (0 runs until interrupted)")
+		fs.StringVar(&opts.IDL, "idl", "", "instead of publishing, generate Go types with CDR encoding from the IDL `file`")
+		fs.StringVar(&opts.Output, "o", "", "Go `path` to write the --idl types to (default stdout)")
+		fs.StringVar(&opts.Package, "package", "types", "Go package `name` of the --idl types")
+		usage = "Usage: cardinal gen [options] TOPIC=WAVE[:freq=HZ,amp=A,offset=B,phase=CYCLES] ...\n       cardinal gen --idl FILE [-o OUT.go] [--package NAME]"
+	case "scenario":
+		registerProfileFlags(fs, &opts)
+		fs.StringVar(&opts.Confirm, "confirm", "", "confirm running on a protected profile by passing the profile `name`")
+		registerReportFlags(fs, &opts)
+		registerOutputFlags(fs, &opts)
+		usage = "Usage: cardinal scenario [options] run SCENARIO.yaml"
+	case "assert":
+		registerProfileFlags(fs, &opts)
+		fs.StringVar(&opts.RulesPath, "rules", "", "rules `file` (TOML, one table per rule)")
+		fs.DurationVar(&opts.Duration, "duration", 60*time.Second, "how long to watch the traffic")
+		registerReportFlags(fs, &opts)
+		registerOutputFlags(fs, &opts)
+		usage = "Usage: cardinal assert --rules RULES.toml [options]"
+	case "import":
+		fs.StringVar(&opts.ConfigPath, "config", defaultConfigPath(), "config file `path` (for redaction and encryption)")
+		fs.StringVar(&opts.Output, "o", "", "capture `path` to write")
+		fs.Var(&opts.Map, "map", "rename a topic, as `from=to` (repeatable)")
+		registerOutputFlags(fs, &opts)
+		usage = "Usage: cardinal import [options] -o CAPTURE.mcap BAG"
+	case "export":
+		fs.StringVar(&opts.ConfigPath, "config", defaultConfigPath(), "config file `path` (for decryption)")
+		fs.StringVar(&opts.Output, "o", "", "Parquet `path` to write")
+		fs.StringVar(&opts.Topic, "topic", "", "export only this `topic`")
+		registerOutputFlags(fs, &opts)
+		usage = "Usage: cardinal export [options] -o OUT.parquet CAPTURE"
+	case "stats":
+		fs.StringVar(&opts.StatsDB, "stats-db", defaultStatsDB(), "statistics `file` written by --persist-stats")
+		fs.BoolVar(&opts.JSON, "json", false, "print the statistics as JSON")
+		usage = "Usage: cardinal stats [options] show"
+	case "replay":
+		registerProfileFlags(fs, &opts)
+		fs.StringVar(&opts.Confirm, "confirm", "", "confirm replaying on a protected profile by passing the profile `name`")
+		fs.Float64Var(&opts.Rate, "rate", 1, "initial playback rate (2 is twice as fast)")
+		fs.StringVar(&opts.ClockTopic, "clock-topic", "", "publish the replay position as simulation time on `topic` (e.g. /clock)")
+		fs.Float64Var(&opts.ClockHz, "clock-hz", 100, "rate of the messages on --clock-topic")
+		fs.StringVar(&opts.ExternalClock, "external-clock", "", "follow a simulator's clock on `topic` instead of wall time")
+		fs.StringVar(&opts.CSV, "csv", "", "replay the rows of a CSV `file` as JSON messages instead of a capture")
+		fs.StringVar(&opts.Topic, "topic", "", "topic for --csv rows")
+		fs.Var(&opts.Map, "map", "map a CSV column to a JSON field, as `column:field` with dotted fields for nesting (repeatable; default every column)")
+		fs.Float64Var(&opts.Hz, "hz", 10, "rows per second for --csv")
+		fs.StringVar(&opts.TimeColumn, "time-column", "", "space --csv rows by this `column` in seconds instead of --hz")
+		usage = "Usage: cardinal replay [options] CAPTURE.mcap\n       cardinal replay [options] --csv FILE --topic TOPIC"
+	default:
+		err := fmt.Errorf("unknown command: %s", opts.Command)
+		fmt.Fprintln(os.Stderr, err)
+		return opts, err
+	}
+
+	fs.Usage = func() {
+		fmt.Fprintln(fs.Output(), usage)
+		if opts.Command == "" {
+			fmt.Fprintln(fs.Output())
+			fmt.Fprintln(fs.Output(), "Commands:")
+			for _, c := range commands {
+				fmt.Fprintf(fs.Output(), "  %-8s %s\n", c.name, c.description)
+			}
+		}
+		fmt.Fprintln(fs.Output())
+		fmt.Fprintln(fs.Output(), "Options:")
+		fs.PrintDefaults()
+	}
+
+	if err := fs.Parse(args); err != nil {
+		return opts, err
+	}
+	opts.Args = fs.Args()
+	if (opts.Command == "" || opts.Command == "daemon") && len(opts.Topics) == 0 {
+		opts.Topics = stringList{defaultTopic}
+	}
+
+	var err error
+	switch {
+	case opts.Quiet && opts.JSON:
+		err = fmt.Errorf("--quiet and --json are exclusive")
+	case opts.Command == "echo" && len(opts.Args) != 1:
+		err = fmt.Errorf("echo takes a topic")
+	case opts.Command == "echo" && (opts.Count < 0 || opts.Timeout < 0):
+		err = fmt.Errorf("--count and --timeout must not be negative")
+	case opts.Command == "pub" && opts.Dispose && opts.Unregister:
+		err = fmt.Errorf("--dispose and --unregister are exclusive")
+	case opts.Command == "pub" && (opts.Dispose || opts.Unregister) && opts.Key == "":
+		err = fmt.Errorf("--dispose and --unregister need --key")
+	case opts.Command == "pub" && (opts.Dispose || opts.Unregister) && len(opts.Args) != 1:
+		err = fmt.Errorf("pub --dispose and --unregister take a topic")
+	case opts.Command == "pub" && !opts.Dispose && !opts.Unregister && len(opts.Args) != 2:
+		err = fmt.Errorf("pub takes a topic and a message")
+	case opts.Command == "verify" && (opts.Expected == "" || opts.Actual == ""):
+		err = fmt.Errorf("verify needs --expected and --actual")
+	case opts.Command == "diff" && len(opts.Args) != 2:
+		err = fmt.Errorf("diff takes two capture files")
+	case opts.Command == "diff" && opts.Align != "sequence" && opts.Align != "time":
+		err = fmt.Errorf("unknown alignment: %s", opts.Align)
+	case opts.Command == "view" && len(opts.Args) != 1:
+		err = fmt.Errorf("view takes one capture file")
+	case opts.Command == "gen" && opts.IDL != "" && len(opts.Args) > 0:
+		err = fmt.Errorf("gen --idl takes no signals")
+	case opts.Command == "gen" && opts.IDL != "" && !isIdent(opts.Package):
+		err = fmt.Errorf("invalid package name: %s", opts.Package)
+	case opts.Command == "gen" && opts.IDL == "" && len(opts.Args) == 0:
+		err = fmt.Errorf("gen takes at least one signal")
+	case opts.Command == "gen" && !(opts.Hz > 0 && opts.Hz <= maxRateHz):
+		err = fmt.Errorf("--hz must be positive and at most %g", maxRateHz)
+	case opts.Command == "scenario" && (len(opts.Args) != 2 || opts.Args[0] != "run"):
+		err = fmt.Errorf("scenario takes run and a scenario file")
+	case opts.Command == "assert" && (opts.RulesPath == "" || opts.Duration <= 0):
+		err = fmt.Errorf("assert needs --rules and a positive --duration")
+	case opts.Command == "import" && (len(opts.Args) != 1 || opts.Output == ""):
+		err = fmt.Errorf("import takes one bag and -o")
+	case opts.Command == "export" && (len(opts.Args) != 1 || opts.Output == ""):
+		err = fmt.Errorf("export takes one capture and -o")
+	case opts.Command == "stats" && (len(opts.Args) != 1 || opts.Args[0] != "show"):
+		err = fmt.Errorf("stats takes show")
+	case opts.Command == "replay" && opts.CSV == "" && len(opts.Args) != 1:
+		err = fmt.Errorf("replay takes one capture file")
+	case opts.Command == "replay" && opts.CSV != "" && (len(opts.Args) != 0 || opts.Topic == ""):
+		err = fmt.Errorf("--csv needs --topic and no capture file")
+	case opts.Command == "replay" && (opts.Rate <= 0 || opts.ClockHz <= 0 || opts.Hz <= 0):
+		err = fmt.Errorf("--rate, --clock-hz and --hz must be positive")
+	case opts.Command == "replay" && !(opts.ClockHz <= maxRateHz && opts.Hz <= maxRateHz):
+		err = fmt.Errorf("--clock-hz and --hz must be at most %g", maxRateHz)
+	case opts.Command == "replay" && opts.ExternalClock != "" && opts.ExternalClock == opts.ClockTopic:
+		err = fmt.Errorf("--clock-topic and --external-clock must differ")
+	case opts.Domain >= 0 && len(opts.Domains) > 0:
+		err = fmt.Errorf("--domain and --domains are exclusive")
+	case opts.Domain > 232:
+		err = fmt.Errorf("--domain must be between 0 and 232")
+	case (opts.Command == "" || opts.Command == "daemon") && (slices.Contains(opts.Topics, "") || opts.DemoInterval <= 0):
+		err = fmt.Errorf("--topic must not be empty and --rate must be positive")
+	case opts.StatsOnly && !opts.Headless:
+		err = fmt.Errorf("--stats-only needs --headless or the daemon")
+	case opts.Heartbeat != "" && opts.BeatInterval <= 0:
+		err = fmt.Errorf("--heartbeat-interval must be positive")
+	case opts.Command != "ctl" && opts.Command != "echo" && opts.Command != "pub" && opts.Command != "diff" && opts.Command != "view" && opts.Command != "gen" && opts.Command != "scenario" && opts.Command != "import" && opts.Command != "replay" && opts.Command != "stats" && opts.Command != "export" && len(opts.Args) > 0:
+		err = fmt.Errorf("unexpected argument: %s", opts.Args[0])
+	}
+	if err != nil {
+		fmt.Fprintln(fs.Output(), err)
+		fs.Usage()
+		return opts, err
+	}
+	return opts, nil
+}
+
+// Parse options or exit with the conventional status codes; the problem
+// has already been reported by then
+func mustParseOptions() Options {
+	opts, err := parseOptions(os.Args[1:])
+	if err == flag.ErrHelp {
+		os.Exit(exitOK)
+	}
+	if err != nil {
+		os.Exit(exitUsage)
+	}
+	return opts
+}
@@ -0,0 +1,257 @@
+package main
+
+import (
+	"bufio"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+	"strconv"
+	"strings"
+)
+
+// Cardinal reads an optional TOML config file. Only the subset needed for
+// flat tables is supported: [table] headers, key = value pairs (keys may be
+// quoted) with
+// strings, integers, booleans and single-line string arrays, and # comments.
+// Connection profiles live in [profiles.<name>] tables:
+//
+//	[profiles.production]
+//	domain = 7
+//	protected = true
+
+// Parsed config file: table name -> key -> raw value
+type Config map[string]map[string]string
+
+// Default path of the config file
+func defaultConfigPath() string {
+	dir, err := os.UserConfigDir()
+	if err != nil {
+		return "cardinal.toml"
+	}
+	return filepath.Join(dir, "cardinal", "config.toml")
+}
+
+// Load the config file at path. A missing file at the default path is an
+// empty config; an explicitly requested one must exist.
+func LoadConfig(path string) (Config, error) {
+	file, err := os.Open(path)
+	if os.IsNotExist(err) && path == defaultConfigPath() {
+		return Config{}, nil
+	}
+	if err != nil {
+		return nil, err
+	}
+	defer file.Close()
+
+	config, err := parseConfig(file)
+	if err != nil {
+		return nil, fmt.Errorf("%s: %w", path, err)
+	}
+	return config, nil
+}
+
+func parseConfig(r io.Reader) (Config, error) {
+	config := Config{"": {}}
+	table := ""
+
+	scanner := bufio.NewScanner(r)
+	for lineNo := 1; scanner.Scan(); lineNo++ {
+		line := strings.TrimSpace(stripComment(scanner.Text()))
+		if line == "" {
+			continue
+		}
+
+		if strings.HasPrefix(line, "[") {
+			if !strings.HasSuffix(line, "]") {
+				return nil, fmt.Errorf("line %d: unterminated table header", lineNo)
+			}
+			table = strings.TrimSpace(line[1 : len(line)-1])
+			if _, exists := config[table]; exists {
+				return nil, fmt.Errorf("line %d: duplicate table [%s]", lineNo, table)
+			}
+			config[table] = map[string]string{}
+			continue
+		}
+
+		key, value, ok := strings.Cut(line, "=")
+		if !ok {
+			return nil, fmt.Errorf("line %d: expected key = value", lineNo)
+		}
+		key = strings.TrimSpace(key)
+		value = strings.TrimSpace(value)
+		if strings.HasPrefix(key, `"`) {
+			unquoted, err := strconv.Unquote(key)
+			if err != nil {
+				return nil, fmt.Errorf("line %d: invalid quoted key %s", lineNo, key)
+			}
+			key = unquoted
+		}
+		if strings.HasPrefix(value, `"`) {
+			unquoted, err := strconv.Unquote(value)
+			if err != nil {
+				return nil, fmt.Errorf("line %d: invalid string for %s", lineNo, key)
+			}
+			value = unquoted
+		}
+		config[table][key] = value
+	}
+	return config, scanner.Err()
+}
+
+// Remove a trailing # comment that is not inside a string
+func stripComment(line string) string {
+	inString := false
+	for i := 0; i < len(line); i++ {
+		switch line[i] {
+		case '\\':
+			if inString {
+				i++
+			}
+		case '"':
+			inString = !inString
+		case '#':
+			if !inString {
+				return line[:i]
+			}
+		}
+	}
+	return line
+}
+
+// A named connection profile
+type Profile struct {
+	Name      string
+	Domain    int
+	Protected bool // publishes need the topic name typed as confirmation
+}
+
+// Look up a profile by name. The empty name is the built-in default
+// profile: domain 0, unprotected.
+func (c Config) Profile(name string) (Profile, error) {
+	profile := Profile{Name: name}
+	if name == "" {
+		profile.Name = "default"
+		return profile, nil
+	}
+
+	table, ok := c["profiles."+name]
+	if !ok {
+		return profile, fmt.Errorf("unknown profile: %s", name)
+	}
+	for key, value := range table {
+		var err error
+		switch key {
+		case "domain":
+			profile.Domain, err = strconv.Atoi(value)
+		case "protected":
+			profile.Protected, err = strconv.ParseBool(value)
+		default:
+			err = fmt.Errorf("unknown key")
+		}
+		if err != nil {
+			return profile, fmt.Errorf("profile %s: %s: %w", name, key, err)
+		}
+	}
+	return profile, nil
+}
+
+// Parse a ["a", "b"] string array value
+func parseStringList(value string) ([]string, error) {
+	if !strings.HasPrefix(value, "[") || !strings.HasSuffix(value, "]") {
+		return nil, fmt.Errorf("expected an array of strings")
+	}
+	var list []string
+	rest := strings.TrimSpace(value[1 : len(value)-1])
+	for rest != "" {
+		quoted, err := strconv.QuotedPrefix(rest)
+		if err != nil {
+			return nil, fmt.Errorf("invalid string at %s", rest)
+		}
+		unquoted, _ := strconv.Unquote(quoted)
+		list = append(list, unquoted)
+
+		rest = strings.TrimSpace(rest[len(quoted):])
+		if rest != "" {
+			if rest[0] != ',' {
+				return nil, fmt.Errorf("expected , after %s", quoted)
+			}
+			rest = strings.TrimSpace(rest[1:])
+		}
+	}
+	return list, nil
+}
+
+// Everything taken from the config file for one run
+type Settings struct {
+	Profile  Profile
+	Cipher   *payloadCipher
+	Redactor *Redactor
+	Sinks    []sinkSpec
+	Hooks    *Hooks
+	Formats  displayFormats
+	Times    timeFormats
+	QoS      *QoSConfig
+	Ignore   *IgnoreList
+	Decimate decimationRules
+	Record   *RecordFilter
+	Chunks   chunkPolicy
+	Upload   *uploadSpec
+	Snapshot string // the config as JSON, secrets redacted
+}
+
+// Load the config file and the profile selected on the command line
+func loadSettings(opts Options) (Settings, error) {
+	var settings Settings
+	config, err := LoadConfig(opts.ConfigPath)
+	if err != nil {
+		return settings, err
+	}
+	if settings.Profile, err = config.Profile(opts.Profile); err != nil {
+		return settings, err
+	}
+	if opts.Domain >= 0 {
+		settings.Profile.Domain = opts.Domain
+	}
+	if len(opts.Domains) > 0 {
+		settings.Profile.Domain = opts.Domains[0]
+	}
+	if settings.Cipher, err = config.PayloadCipher(); err != nil {
+		return settings, err
+	}
+	if settings.Redactor, err = config.Redactor(); err != nil {
+		return settings, err
+	}
+	if settings.Sinks, err = config.SinkSpecs(); err != nil {
+		return settings, err
+	}
+	if settings.Hooks, err = config.Hooks(settings.Profile.Name); err != nil {
+		return settings, err
+	}
+	if settings.Times, err = config.TimeFormats(); err != nil {
+		return settings, err
+	}
+	if settings.Formats, err = config.DisplayFormats(settings.Times); err != nil {
+		return settings, err
+	}
+	if settings.QoS, err = config.QoS(); err != nil {
+		return settings, err
+	}
+	if settings.Ignore, err = config.IgnoreList(); err != nil {
+		return settings, err
+	}
+	if settings.Decimate, err = config.DecimationRules(); err != nil {
+		return settings, err
+	}
+	if settings.Record, err = config.RecordFilter(); err != nil {
+		return settings, err
+	}
+	if settings.Chunks, err = config.ChunkPolicy(); err != nil {
+		return settings, err
+	}
+	if settings.Upload, err = config.UploadSpec(); err != nil {
+		return settings, err
+	}
+	settings.Snapshot = config.Snapshot()
+	return settings, nil
+}
@@ -0,0 +1,388 @@
+package main
+
+import (
+	"bufio"
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"log"
+	"net"
+	"os"
+	"strings"
+	"time"
+)
+
+// The daemon control protocol is newline-delimited JSON over a Unix domain
+// socket: each request line gets exactly one response line. The control
+// socket is private to the daemon's user; an optional shared socket is open
+// to the daemon's group but only serves read-only commands. Attached clients
+// get payloads redacted, and on the shared socket payloads of encrypted
+// topics stay sealed, so only viewers holding the key can read them.
+
+// Timeout for ctl round trips
+const controlTimeout = 5 * time.Second
+
+type controlRequest struct {
+	Command string `json:"command"`
+	Topic   string `json:"topic,omitempty"`
+	Path    string `json:"path,omitempty"`
+	GUID    string `json:"guid,omitempty"`
+	Name    string `json:"name,omitempty"`
+}
+
+type controlResponse struct {
+	OK        bool             `json:"ok"`
+	Error     string           `json:"error,omitempty"`
+	Status    *SessionStatus   `json:"status,omitempty"`
+	Recording *RecordingStatus `json:"recording,omitempty"`
+	History   []DDSMessage     `json:"history,omitempty"`
+	ReadOnly  bool             `json:"read_only,omitempty"`
+}
+
+// Socket permissions for the private control socket and the shared one
+const (
+	controlSocketMode os.FileMode = 0600
+	sharedSocketMode  os.FileMode = 0660
+)
+
+// Commands permitted on the shared read-only socket
+var readOnlyCommands = map[string]bool{
+	"status": true,
+	"attach": true,
+}
+
+// Listen on a control socket and serve requests until ctx is done
+func startControlServer(ctx context.Context, path string, session *Session, readOnly bool) error {
+	// Refuse to take over the socket of a daemon that is still running
+	if conn, err := net.Dial("unix", path); err == nil {
+		conn.Close()
+		return fmt.Errorf("another daemon is listening on %s", path)
+	}
+	os.Remove(path)
+
+	listener, err := net.Listen("unix", path)
+	if err != nil {
+		return err
+	}
+
+	mode := controlSocketMode
+	if readOnly {
+		mode = sharedSocketMode
+	}
+	if err := os.Chmod(path, mode); err != nil {
+		listener.Close()
+		return err
+	}
+
+	if readOnly {
+		log.Printf("Shared read-only socket listening on %s", path)
+	} else {
+		log.Printf("Control socket listening on %s", path)
+	}
+
+	go func() {
+		<-ctx.Done()
+		listener.Close()
+		os.Remove(path)
+	}()
+
+	go runTask(ctx, "control", func(ctx context.Context) {
+		for {
+			conn, err := listener.Accept()
+			if err != nil {
+				if !errors.Is(err, net.ErrClosed) {
+					log.Printf("Control socket stopped: %v", err)
+				}
+				return
+			}
+			go serveControl(conn, session, readOnly)
+		}
+	})
+	return nil
+}
+
+// Serve control requests from one client
+func serveControl(conn net.Conn, session *Session, readOnly bool) {
+	defer conn.Close()
+
+	scanner := bufio.NewScanner(conn)
+	encoder := json.NewEncoder(conn)
+	for scanner.Scan() {
+		var req controlRequest
+		var resp controlResponse
+		if err := json.Unmarshal(scanner.Bytes(), &req); err != nil {
+			resp.Error = fmt.Sprintf("invalid request: %v", err)
+		} else if readOnly && !readOnlyCommands[req.Command] {
+			resp.Error = fmt.Sprintf("%s is not permitted on the read-only socket", req.Command)
+		} else if req.Command == "attach" && session.statsOnly {
+			resp.Error = "the daemon keeps statistics only, there are no messages to attach to"
+		} else if req.Command == "attach" {
+			serveAttach(scanner, encoder, session, readOnly)
+			return
+		} else {
+			resp = handleControl(req, session)
+		}
+		if err := encoder.Encode(resp); err != nil {
+			return
+		}
+	}
+}
+
+// Stream messages to an attached client: one response line with the
+// session status and recent history, then one line per received message.
+// The client detaches by closing the connection.
+func serveAttach(scanner *bufio.Scanner, encoder *json.Encoder, session *Session, readOnly bool) {
+	history, listener := session.Attach()
+	defer session.Detach(listener)
+	for i, msg := range history {
+		history[i] = session.forViewer(msg, readOnly)
+	}
+
+	status := session.Status()
+	resp := controlResponse{OK: true, Status: &status, History: history, ReadOnly: readOnly}
+	if err := encoder.Encode(resp); err != nil {
+		return
+	}
+
+	// Attached clients send nothing more; EOF means they detached
+	closed := make(chan struct{})
+	go func() {
+		for scanner.Scan() {
+		}
+		close(closed)
+	}()
+
+	for {
+		select {
+		case <-closed:
+			return
+		case <-session.ctx.Done():
+			return
+		case msg := <-listener:
+			if err := encoder.Encode(session.forViewer(msg, readOnly)); err != nil {
+				return
+			}
+		}
+	}
+}
+
+// Execute a single control request against the session
+func handleControl(req controlRequest, session *Session) controlResponse {
+	var resp controlResponse
+	var err error
+
+	switch req.Command {
+	case "status":
+		status := session.Status()
+		resp.Status = &status
+	case "add-topic":
+		if req.Topic == "" {
+			err = fmt.Errorf("add-topic requires a topic")
+		} else {
+			err = session.Subscribe(req.Topic)
+		}
+	case "start-recording":
+		var recording RecordingStatus
+		if req.Path == "" {
+			err = fmt.Errorf("start-recording requires a path")
+		} else if recording, err = session.StartRecording(req.Path); err == nil {
+			resp.Recording = &recording
+		}
+	case "stop-recording":
+		var recording RecordingStatus
+		if recording, err = session.StopRecording(); err == nil {
+			resp.Recording = &recording
+		}
+	case "record-include", "record-exclude":
+		if req.Topic == "" {
+			err = fmt.Errorf("%s requires a topic pattern", req.Command)
+		} else {
+			err = session.FilterRecording(req.Command == "record-include", req.Topic)
+		}
+	case "record-clear":
+		session.ClearRecordingFilter()
+	case "ignore":
+		if req.GUID == "" {
+			err = fmt.Errorf("ignore requires a GUID")
+		} else {
+			err = session.Ignore(req.Name, req.GUID)
+		}
+	case "unignore":
+		if req.Name == "" {
+			err = fmt.Errorf("unignore requires a name or GUID")
+		} else {
+			err = session.Unignore(req.Name)
+		}
+	default:
+		err = fmt.Errorf("unknown command: %s", req.Command)
+	}
+
+	if err != nil {
+		resp.Error = err.Error()
+	} else {
+		resp.OK = true
+	}
+	return resp
+}
+
+// Send one request to the daemon and wait for its response
+func sendControl(path string, req controlRequest) (controlResponse, error) {
+	var resp controlResponse
+
+	conn, err := net.DialTimeout("unix", path, controlTimeout)
+	if err != nil {
+		return resp, fmt.Errorf("cannot reach daemon at %s: %w", path, err)
+	}
+	defer conn.Close()
+	conn.SetDeadline(time.Now().Add(controlTimeout))
+
+	if err := json.NewEncoder(conn).Encode(req); err != nil {
+		return resp, err
+	}
+	if err := json.NewDecoder(conn).Decode(&resp); err != nil {
+		return resp, fmt.Errorf("invalid response from daemon: %w", err)
+	}
+	return resp, nil
+}
+
+// Parse `cardinal ctl` arguments into a control request
+func parseControlRequest(args []string) (controlRequest, error) {
+	if len(args) == 0 {
+		return controlRequest{}, fmt.Errorf("missing command")
+	}
+
+	req := controlRequest{Command: args[0]}
+	wantArgs := 1
+	switch req.Command {
+	case "add-topic", "record-include", "record-exclude":
+		wantArgs = 2
+		if len(args) == wantArgs {
+			req.Topic = args[1]
+		}
+	case "start-recording":
+		wantArgs = 2
+		if len(args) == wantArgs {
+			req.Path = args[1]
+		}
+	case "ignore":
+		// An optional name follows the GUID
+		wantArgs = min(max(len(args), 2), 3)
+		if len(args) == wantArgs {
+			req.GUID = args[1]
+		}
+		if len(args) == 3 {
+			req.Name = args[2]
+		}
+	case "unignore":
+		wantArgs = 2
+		if len(args) == wantArgs {
+			req.Name = args[1]
+		}
+	}
+	if len(args) != wantArgs {
+		return req, fmt.Errorf("%s takes %d argument(s)", req.Command, wantArgs-1)
+	}
+	return req, nil
+}
+
+// Run `cardinal ctl` and return the process exit code
+func runCtl(opts Options) int {
+	req, err := parseControlRequest(opts.Args)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal ctl:", err)
+		return exitUsage
+	}
+
+	resp, err := sendControl(opts.ControlSocket, req)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal ctl:", err)
+		return exitFailure
+	}
+	if !resp.OK {
+		fmt.Fprintln(os.Stderr, "cardinal ctl:", resp.Error)
+		return exitFailure
+	}
+
+	switch {
+	case resp.Status != nil:
+		printStatus(*resp.Status)
+	case resp.Recording != nil:
+		fmt.Printf("✅ %s: %s (%d messages, %s)\n", req.Command, resp.Recording.Path,
+			resp.Recording.Messages, formatBytes(float64(resp.Recording.Bytes)))
+	default:
+		fmt.Printf("✅ %s\n", strings.Join(opts.Args, " "))
+	}
+	return exitOK
+}
+
+// Print a daemon status report
+func printStatus(status SessionStatus) {
+	fmt.Printf("Transport: %s\n", status.Transport)
+	fmt.Printf("Uptime:    %s\n", formatDuration(time.Duration(status.Uptime*float64(time.Second))))
+	fmt.Printf("Viewers:   %d attached\n", status.Viewers)
+	if status.StatsOnly {
+		fmt.Println("Mode:      statistics only")
+	}
+	if status.Recording != nil {
+		fmt.Printf("Recording: %s (%d messages, %s, %d decimated)\n", status.Recording.Path,
+			status.Recording.Messages, formatBytes(float64(status.Recording.Bytes)), status.Recording.Decimated)
+	} else {
+		fmt.Println("Recording: off")
+	}
+	if status.Recording != nil && status.Recording.Chunks > 0 {
+		fmt.Printf("Chunk:     %s (%d so far)\n", status.Recording.Chunk, status.Recording.Chunks)
+	}
+	if status.Uploads != nil {
+		fmt.Printf("Uploads:   %d pending, %d uploaded, %d failed\n", status.Uploads.Pending, status.Uploads.Uploaded, status.Uploads.Failed)
+	}
+	if include, exclude := status.Record.Include, status.Record.Exclude; len(include) > 0 || len(exclude) > 0 {
+		if len(include) == 0 {
+			include = []string{"*"}
+		}
+		fmt.Printf("Topics:    %s", strings.Join(include, " "))
+		if len(exclude) > 0 {
+			fmt.Printf(" except %s", strings.Join(exclude, " "))
+		}
+		fmt.Println()
+	}
+	fmt.Println()
+	fmt.Printf("%-32s %10s %12s %10s %10s %10s %10s %10s %11s %8s %11s %10s\n", "Topic", "Messages", "Bytes", "Rate", "Duplicates", "Reordered", "Repeated", "Jitter", "Frag (est.)", "Lost", "Over budget", "Deadlines")
+	for _, topic := range status.Topics {
+		jitter := time.Duration(topic.Jitter * float64(time.Second)).Round(time.Microsecond)
+		fmt.Printf("%-32s %10d %12s %8.1f/s %10d %10d %10d %10s %11d %8d %11d %10d\n", topic.Topic, topic.Messages,
+			formatBytes(float64(topic.Bytes)), topic.Rate, topic.Duplicates, topic.Reordered, topic.Repeated, formatInterval(jitter),
+			topic.Fragmented, topic.Lost, topic.OverBudget, topic.Deadlines)
+	}
+	if len(status.Writers) > 0 {
+		fmt.Println()
+		fmt.Printf("%-32s %12s %12s %12s %10s\n", "Writer", "Publish mode", "Heartbeat", "NACK delay", "Deadlines")
+		for _, writer := range status.Writers {
+			mode, heartbeat, nackDelay := "-", "-", "-"
+			if writer.PublishMode != "" {
+				mode = writer.PublishMode
+				heartbeat = time.Duration(writer.Heartbeat * float64(time.Second)).String()
+				nackDelay = time.Duration(writer.NackDelay * float64(time.Second)).String()
+			}
+			fmt.Printf("%-32s %12s %12s %12s %10d\n", writer.Topic, mode, heartbeat, nackDelay, writer.Deadlines)
+		}
+	}
+	if len(status.Remote) > 0 {
+		fmt.Println()
+		fmt.Printf("%-32s %-34s %6s\n", "Remote writer", "GUID", "Alive")
+		for _, writer := range status.Remote {
+			alive := "yes"
+			if !writer.Alive {
+				alive = "no"
+			}
+			fmt.Printf("%-32s %-34s %6s\n", writer.Topic, writer.GUID, alive)
+		}
+	}
+	if len(status.Ignored) > 0 {
+		fmt.Println()
+		fmt.Printf("%-32s %-34s %10s\n", "Ignored", "GUID", "Dropped")
+		for _, ignored := range status.Ignored {
+			fmt.Printf("%-32s %-34s %10d\n", ignored.Name, ignored.GUID, ignored.Messages)
+		}
+	}
+}
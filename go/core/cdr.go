@@ -0,0 +1,248 @@
+package core
+
+import (
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"math"
+	"reflect"
+)
+
+// Payloads of typed topics are plain CDR (XCDR1), the encoding of the types
+// `cardinal gen --idl` generates: primitives aligned to their size from the
+// end of the 4-byte encapsulation header, strings and sequences prefixed
+// with a 32-bit length, arrays and struct fields inline in order. Go values
+// map onto it by reflection:
+//
+//	bool, int8..int64, uint8..uint64    boolean, octet and the integers
+//	float32, float64                    float, double
+//	string                              string
+//	[]T, [N]T                           sequence<T>, T name[N]
+//	struct                              struct, exported fields in order
+//
+// int, uint, maps, interfaces and pointers inside values have no CDR
+// equivalent and fail to encode.
+
+// Encapsulation header of little-endian plain CDR
+var cdrHeader = []byte{0, 1, 0, 0}
+
+// Returned when a CDR payload ends before its value does
+var ErrCDRShort = errors.New("cdr: payload too short")
+
+// Encode a value, or a pointer to one, as a little-endian CDR payload
+func MarshalCDR(v any) ([]byte, error) {
+	rv := reflect.ValueOf(v)
+	if rv.Kind() == reflect.Pointer && !rv.IsNil() {
+		rv = rv.Elem()
+	}
+	if !rv.IsValid() {
+		return nil, errors.New("cdr: cannot encode nil")
+	}
+	e := cdrEncoder{buf: append([]byte(nil), cdrHeader...)}
+	if err := e.value(rv); err != nil {
+		return nil, err
+	}
+	return e.buf, nil
+}
+
+// Decode a CDR payload of either byte order into the value v points to
+func UnmarshalCDR(data []byte, v any) error {
+	rv := reflect.ValueOf(v)
+	if rv.Kind() != reflect.Pointer || rv.IsNil() {
+		return fmt.Errorf("cdr: cannot decode into %T, need a non-nil pointer", v)
+	}
+	r, err := NewCDRReader(data)
+	if err != nil {
+		return err
+	}
+	r.value(rv.Elem())
+	return r.Err
+}
+
+type cdrEncoder struct {
+	buf []byte
+}
+
+func (e *cdrEncoder) align(n int) {
+	for (len(e.buf)-len(cdrHeader))%n != 0 {
+		e.buf = append(e.buf, 0)
+	}
+}
+
+func (e *cdrEncoder) uint(v uint64, size int) {
+	e.align(size)
+	switch size {
+	case 1:
+		e.buf = append(e.buf, byte(v))
+	case 2:
+		e.buf = binary.LittleEndian.AppendUint16(e.buf, uint16(v))
+	case 4:
+		e.buf = binary.LittleEndian.AppendUint32(e.buf, uint32(v))
+	default:
+		e.buf = binary.LittleEndian.AppendUint64(e.buf, v)
+	}
+}
+
+func (e *cdrEncoder) value(v reflect.Value) error {
+	switch v.Kind() {
+	case reflect.Bool:
+		var b uint64
+		if v.Bool() {
+			b = 1
+		}
+		e.uint(b, 1)
+	case reflect.Int8, reflect.Int16, reflect.Int32, reflect.Int64:
+		e.uint(uint64(v.Int()), int(v.Type().Size()))
+	case reflect.Uint8, reflect.Uint16, reflect.Uint32, reflect.Uint64:
+		e.uint(v.Uint(), int(v.Type().Size()))
+	case reflect.Float32:
+		e.uint(uint64(math.Float32bits(float32(v.Float()))), 4)
+	case reflect.Float64:
+		e.uint(math.Float64bits(v.Float()), 8)
+	case reflect.String:
+		e.uint(uint64(v.Len()+1), 4)
+		e.buf = append(append(e.buf, v.String()...), 0)
+	case reflect.Slice:
+		e.uint(uint64(v.Len()), 4)
+		if v.Type().Elem().Kind() == reflect.Uint8 {
+			e.buf = append(e.buf, v.Bytes()...)
+			return nil
+		}
+		fallthrough
+	case reflect.Array:
+		for i := range v.Len() {
+			if err := e.value(v.Index(i)); err != nil {
+				return err
+			}
+		}
+	case reflect.Struct:
+		for i := range v.NumField() {
+			if !v.Type().Field(i).IsExported() {
+				continue
+			}
+			if err := e.value(v.Field(i)); err != nil {
+				return err
+			}
+		}
+	default:
+		return fmt.Errorf("cdr: cannot encode %s", v.Type())
+	}
+	return nil
+}
+
+// Reads the values of a CDR payload in order; after an error Err is set and
+// the rest read as zero, like Fields
+type CDRReader struct {
+	data  []byte
+	pos   int
+	order binary.ByteOrder
+	Err   error
+}
+
+// Start reading a plain CDR payload of either byte order
+func NewCDRReader(data []byte) (*CDRReader, error) {
+	if len(data) < len(cdrHeader) || data[0] != 0 || data[1] > 1 {
+		return nil, errors.New("cdr: not a plain CDR payload")
+	}
+	r := &CDRReader{data: data, pos: len(cdrHeader), order: binary.BigEndian}
+	if data[1] == 1 {
+		r.order = binary.LittleEndian
+	}
+	return r, nil
+}
+
+// Take the next n bytes, after padding to a multiple of align
+func (r *CDRReader) Take(n, align int) []byte {
+	if r.Err != nil {
+		return nil
+	}
+	if pad := (r.pos - len(cdrHeader)) % align; pad != 0 {
+		r.pos += align - pad
+	}
+	if n > len(r.data)-r.pos {
+		r.Err = ErrCDRShort
+		return nil
+	}
+	r.pos += n
+	return r.data[r.pos-n : r.pos]
+}
+
+// Read an unsigned integer of 1, 2, 4 or 8 bytes
+func (r *CDRReader) Uint(size int) uint64 {
+	b := r.Take(size, size)
+	switch {
+	case b == nil:
+		return 0
+	case size == 1:
+		return uint64(b[0])
+	case size == 2:
+		return uint64(r.order.Uint16(b))
+	case size == 4:
+		return uint64(r.order.Uint32(b))
+	}
+	return r.order.Uint64(b)
+}
+
+// Read a signed integer of 1, 2, 4 or 8 bytes
+func (r *CDRReader) Int(size int) int64 {
+	return int64(r.Uint(size)) << (64 - 8*size) >> (64 - 8*size)
+}
+
+// Read the length of a sequence, checked against the bytes left
+func (r *CDRReader) Count() int {
+	n := r.Uint(4)
+	if r.Err == nil && n > uint64(len(r.data)-r.pos) {
+		r.Err = ErrCDRShort
+	}
+	if r.Err != nil {
+		return 0
+	}
+	return int(n)
+}
+
+func (r *CDRReader) String() string {
+	b := r.Take(r.Count(), 1) // with its terminating NUL
+	if len(b) == 0 {
+		return ""
+	}
+	return string(b[:len(b)-1])
+}
+
+func (r *CDRReader) value(v reflect.Value) {
+	switch v.Kind() {
+	case reflect.Bool:
+		v.SetBool(r.Uint(1) != 0)
+	case reflect.Int8, reflect.Int16, reflect.Int32, reflect.Int64:
+		v.SetInt(r.Int(int(v.Type().Size())))
+	case reflect.Uint8, reflect.Uint16, reflect.Uint32, reflect.Uint64:
+		v.SetUint(r.Uint(int(v.Type().Size())))
+	case reflect.Float32:
+		v.SetFloat(float64(math.Float32frombits(uint32(r.Uint(4)))))
+	case reflect.Float64:
+		v.SetFloat(math.Float64frombits(r.Uint(8)))
+	case reflect.String:
+		v.SetString(r.String())
+	case reflect.Slice:
+		n := r.Count()
+		if v.Type().Elem().Kind() == reflect.Uint8 {
+			v.SetBytes(append([]byte(nil), r.Take(n, 1)...))
+			return
+		}
+		v.Set(reflect.MakeSlice(v.Type(), n, n))
+		fallthrough
+	case reflect.Array:
+		for i := range v.Len() {
+			r.value(v.Index(i))
+		}
+	case reflect.Struct:
+		for i := range v.NumField() {
+			if v.Type().Field(i).IsExported() {
+				r.value(v.Field(i))
+			}
+		}
+	default:
+		if r.Err == nil {
+			r.Err = fmt.Errorf("cdr: cannot decode %s", v.Type())
+		}
+	}
+}
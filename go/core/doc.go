@@ -0,0 +1,9 @@
+// Package core holds what Cardinal's views are computed from, free of DDS,
+// cgo and the terminal: decoding captures and CDR payloads, and the
+// per-topic statistics. The CLI and the browser viewer built with GOOS=js
+// GOARCH=wasm (see ../wasm) share it, so both decode captures and count gaps
+// and jitter the same way.
+//
+// Other Go programs can import it as cardinal/core, next to the DDS layer in
+// cardinal/dds.
+package core
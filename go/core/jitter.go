@@ -0,0 +1,113 @@
+package core
+
+import (
+	"encoding/json"
+	"fmt"
+	"math"
+	"time"
+)
+
+// Upper bounds of the inter-arrival histogram buckets; a last bucket holds
+// everything slower
+var IntervalBounds = [...]time.Duration{
+	time.Millisecond, 2 * time.Millisecond, 5 * time.Millisecond,
+	10 * time.Millisecond, 20 * time.Millisecond, 50 * time.Millisecond,
+	100 * time.Millisecond, 200 * time.Millisecond, 500 * time.Millisecond,
+	time.Second, 2 * time.Second, 5 * time.Second,
+}
+
+// Distribution of the time between consecutive messages on a topic. A
+// stable period matters as much as the average rate for control loops, so
+// besides the buckets this keeps a running mean and variance (Welford).
+type IntervalHistogram struct {
+	Buckets [len(IntervalBounds) + 1]int
+	Count   int
+	Min     time.Duration
+	Max     time.Duration
+	mean    float64 // seconds
+	m2      float64 // sum of squared deviations from the mean, seconds²
+}
+
+// Add one inter-arrival interval
+func (h *IntervalHistogram) Add(d time.Duration) {
+	i := 0
+	for i < len(IntervalBounds) && d > IntervalBounds[i] {
+		i++
+	}
+	h.Buckets[i]++
+
+	if h.Count == 0 || d < h.Min {
+		h.Min = d
+	}
+	h.Max = max(h.Max, d)
+
+	h.Count++
+	x := d.Seconds()
+	delta := x - h.mean
+	h.mean += delta / float64(h.Count)
+	h.m2 += delta * (x - h.mean)
+}
+
+// Mean interval
+func (h IntervalHistogram) Mean() time.Duration {
+	return time.Duration(h.mean * float64(time.Second))
+}
+
+// Standard deviation of the interval, the jitter
+func (h IntervalHistogram) StdDev() time.Duration {
+	if h.Count < 2 {
+		return 0
+	}
+	return time.Duration(math.Sqrt(h.m2/float64(h.Count-1)) * float64(time.Second))
+}
+
+// Combine two histograms, as if every interval of other had been added
+func (h *IntervalHistogram) Merge(other IntervalHistogram) {
+	if other.Count == 0 {
+		return
+	}
+	if h.Count == 0 {
+		*h = other
+		return
+	}
+	for i, n := range other.Buckets {
+		h.Buckets[i] += n
+	}
+	h.Min = min(h.Min, other.Min)
+	h.Max = max(h.Max, other.Max)
+
+	// Parallel variant of Welford's update
+	n := float64(h.Count + other.Count)
+	delta := other.mean - h.mean
+	h.m2 += other.m2 + delta*delta*float64(h.Count)*float64(other.Count)/n
+	h.mean += delta * float64(other.Count) / n
+	h.Count += other.Count
+}
+
+// Stored form of a histogram, keeping the running moments so that merged
+// histograms still report the right jitter
+type storedHistogram struct {
+	Buckets []int         `json:"buckets"`
+	Count   int           `json:"count"`
+	Min     time.Duration `json:"min_ns"`
+	Max     time.Duration `json:"max_ns"`
+	Mean    float64       `json:"mean_seconds"`
+	M2      float64       `json:"m2"`
+}
+
+func (h IntervalHistogram) MarshalJSON() ([]byte, error) {
+	return json.Marshal(storedHistogram{Buckets: h.Buckets[:], Count: h.Count, Min: h.Min, Max: h.Max, Mean: h.mean, M2: h.m2})
+}
+
+func (h *IntervalHistogram) UnmarshalJSON(data []byte) error {
+	var stored storedHistogram
+	if err := json.Unmarshal(data, &stored); err != nil {
+		return err
+	}
+	if len(stored.Buckets) != len(h.Buckets) {
+		return fmt.Errorf("histogram has %d buckets, expected %d", len(stored.Buckets), len(h.Buckets))
+	}
+	*h = IntervalHistogram{Count: stored.Count, Min: stored.Min, Max: stored.Max, mean: stored.Mean, m2: stored.M2}
+	copy(h.Buckets[:], stored.Buckets)
+	return nil
+}
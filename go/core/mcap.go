@@ -0,0 +1,174 @@
+package core
+
+import (
+	"bufio"
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"io"
+	"time"
+)
+
+// Captures are MCAP files (https://mcap.dev). The decoder here reads the
+// unchunked files Cardinal writes, for the CLI and the WASM viewer alike.
+
+const Magic = "\x89MCAP0\r\n"
+
+// MCAP record opcodes
+const (
+	OpHeader   = 0x01
+	OpFooter   = 0x02
+	OpSchema   = 0x03
+	OpChannel  = 0x04
+	OpMessage  = 0x05
+	OpChunk    = 0x06
+	OpMetadata = 0x0C
+	OpDataEnd  = 0x0F
+)
+
+// Largest record read, guarding against corrupt lengths
+const MaxRecord = 1 << 30
+
+// Returned by NewDecoder for data that is not MCAP
+var ErrNotMCAP = errors.New("not an MCAP capture")
+
+// Parses the fields of a single MCAP record; after a truncated read Err is
+// set and the fixed-size fields read as zero
+type Fields struct {
+	Data []byte // what is left of the record
+	Err  error
+}
+
+func (r *Fields) Take(n int) []byte {
+	if r.Err != nil || len(r.Data) < n {
+		r.Err = errors.New("truncated record")
+		return make([]byte, 8) // zeros for the fixed-size fields
+	}
+	b := r.Data[:n]
+	r.Data = r.Data[n:]
+	return b
+}
+
+func (r *Fields) Uint16() uint16 {
+	return binary.LittleEndian.Uint16(r.Take(2))
+}
+
+func (r *Fields) Uint32() uint32 {
+	return binary.LittleEndian.Uint32(r.Take(4))
+}
+
+func (r *Fields) Uint64() uint64 {
+	return binary.LittleEndian.Uint64(r.Take(8))
+}
+
+func (r *Fields) String() string {
+	return string(r.Take(int(r.Uint32())))
+}
+
+func (r *Fields) StringMap() map[string]string {
+	entries := Fields{Data: r.Take(int(r.Uint32()))}
+	m := map[string]string{}
+	for len(entries.Data) > 0 && entries.Err == nil {
+		key := entries.String()
+		m[key] = entries.String()
+	}
+	if r.Err == nil {
+		r.Err = entries.Err
+	}
+	return m
+}
+
+// A message read from a capture
+type Record struct {
+	Topic       string
+	Data        []byte
+	PublishTime time.Time
+	LogTime     time.Time // when Cardinal received it
+}
+
+// Reads the messages of a capture one record at a time
+type Decoder struct {
+	r        *bufio.Reader
+	channels map[uint16]string
+	metadata map[string]map[string]string
+}
+
+// Start decoding a capture, checking its magic
+func NewDecoder(r io.Reader) (*Decoder, error) {
+	d := &Decoder{
+		r:        bufio.NewReader(r),
+		channels: map[uint16]string{},
+		metadata: map[string]map[string]string{},
+	}
+	magic := make([]byte, len(Magic))
+	if _, err := io.ReadFull(d.r, magic); err != nil || string(magic) != Magic {
+		return nil, ErrNotMCAP
+	}
+	return d, nil
+}
+
+// Read the next message, returning io.EOF after the last one
+func (d *Decoder) Next() (Record, error) {
+	for {
+		var prefix [9]byte
+		if _, err := io.ReadFull(d.r, prefix[:]); err != nil {
+			if err == io.EOF {
+				return Record{}, io.EOF
+			}
+			return Record{}, fmt.Errorf("truncated capture: %w", err)
+		}
+		length := binary.LittleEndian.Uint64(prefix[1:])
+		if length > MaxRecord {
+			return Record{}, fmt.Errorf("corrupt capture: %d byte record", length)
+		}
+		record := make([]byte, length)
+		if _, err := io.ReadFull(d.r, record); err != nil {
+			return Record{}, fmt.Errorf("truncated capture: %w", err)
+		}
+		r := Fields{Data: record}
+
+		switch prefix[0] {
+		case OpChannel:
+			id := r.Uint16()
+			r.Uint16() // schema
+			topic := r.String()
+			if r.Err == nil {
+				d.channels[id] = topic
+			}
+		case OpMessage:
+			id := r.Uint16()
+			r.Uint32() // sequence
+			logTime := r.Uint64()
+			publishTime := r.Uint64()
+			if r.Err != nil {
+				return Record{}, fmt.Errorf("invalid message record: %w", r.Err)
+			}
+			topic, ok := d.channels[id]
+			if !ok {
+				return Record{}, fmt.Errorf("message on unknown channel %d", id)
+			}
+			return Record{
+				Topic:       topic,
+				Data:        r.Data,
+				PublishTime: time.Unix(0, int64(publishTime)),
+				LogTime:     time.Unix(0, int64(logTime)),
+			}, nil
+		case OpMetadata:
+			name := r.String()
+			metadata := r.StringMap()
+			if r.Err == nil {
+				d.metadata[name] = metadata
+			}
+		case OpChunk:
+			return Record{}, fmt.Errorf("chunked captures are not supported")
+		case OpDataEnd:
+			return Record{}, io.EOF
+		}
+		// Header and other records carry nothing Cardinal needs
+	}
+}
+
+// Metadata records read so far, by name
+func (d *Decoder) Metadata() map[string]map[string]string {
+	return d.metadata
+}
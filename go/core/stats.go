@@ -0,0 +1,231 @@
+package core
+
+import (
+	"fmt"
+	"log"
+	"sort"
+	"sync"
+	"time"
+)
+
+// Longest window the per-topic statistics keep samples for
+const maxStatsWindow = 5 * time.Minute
+
+// An arrival is counted as a gap when it comes this many mean periods late
+const gapFactor = 3.0
+
+// Samples needed before the mean period is trusted for gap detection
+const gapWarmupSamples = 5
+
+// A single received sample as seen by the statistics tracker
+type topicSample struct {
+	at        time.Time
+	bytes     int
+	wireBytes int
+	gap       bool
+}
+
+// Running statistics for one topic
+type TopicStats struct {
+	Topic           string
+	Total           int
+	TotalBytes      int
+	TotalWireBytes  int
+	TotalGaps       int
+	TotalDuplicates int
+	TotalReordered  int
+	TotalRepeated   int
+	TotalFragmented int // samples estimated to be split into DATA_FRAG submessages
+	TotalFragments  int // estimated DATA_FRAG submessages of those samples
+	TotalOverMTU    int // samples estimated to exceed the link MTU
+	LargestSample   int // payload bytes
+	Lost            int // samples the reader reported lost
+	TotalOverBudget int // samples slower than the topic's latency budget
+	LastSeen        time.Time
+	Intervals       IntervalHistogram
+	meanPeriod      time.Duration
+	samples         []topicSample
+}
+
+// Statistics of a topic restricted to a time window
+type TopicWindowStats struct {
+	Topic         string
+	Count         int
+	Rate          float64
+	Bandwidth     float64
+	WireBandwidth float64
+	Gaps          int
+}
+
+// Tracks per-topic statistics for every received message. Safe for
+// concurrent use, since exporters read it outside the UI goroutine.
+type StatsTracker struct {
+	mu     sync.Mutex
+	topics map[string]*TopicStats
+}
+
+func NewStatsTracker() *StatsTracker {
+	return &StatsTracker{topics: map[string]*TopicStats{}}
+}
+
+// What the statistics need of a received message
+type Sample struct {
+	Topic      string
+	Bytes      int       // payload size
+	ReceivedAt time.Time // now when zero
+	Duplicate  bool
+	Reordered  bool
+	Repeated   bool
+	OverBudget bool
+}
+
+// Record a received message
+func (t *StatsTracker) Record(msg Sample) {
+	t.mu.Lock()
+	defer t.mu.Unlock()
+
+	at := msg.ReceivedAt
+	if at.IsZero() {
+		at = time.Now()
+	}
+
+	stats, ok := t.topics[msg.Topic]
+	if !ok {
+		stats = &TopicStats{Topic: msg.Topic}
+		t.topics[msg.Topic] = stats
+	}
+
+	sample := topicSample{at: at, bytes: msg.Bytes, wireBytes: estimateWireBytes(msg.Bytes)}
+	if !stats.LastSeen.IsZero() {
+		period := at.Sub(stats.LastSeen)
+		stats.Intervals.Add(period)
+		if stats.Total >= gapWarmupSamples && float64(period) > gapFactor*float64(stats.meanPeriod) {
+			sample.gap = true
+			stats.TotalGaps++
+		}
+		// Exponentially weighted mean of the inter-arrival period
+		if stats.meanPeriod == 0 {
+			stats.meanPeriod = period
+		} else {
+			stats.meanPeriod = (stats.meanPeriod*7 + period) / 8
+		}
+	}
+
+	stats.Total++
+	if msg.Duplicate {
+		stats.TotalDuplicates++
+	}
+	if msg.Reordered {
+		stats.TotalReordered++
+	}
+	if msg.Repeated {
+		stats.TotalRepeated++
+	}
+	if msg.OverBudget {
+		stats.TotalOverBudget++
+	}
+	if fragments := rtpsFragments(msg.Bytes); fragments > 0 {
+		stats.TotalFragmented++
+		stats.TotalFragments += fragments
+		if stats.TotalFragmented == 1 {
+			log.Printf("Samples on %s are likely split into fragments (%s in an estimated %d at the default %d byte datagram limit); losing one fragment loses the whole sample",
+				msg.Topic, FormatBytes(float64(msg.Bytes)), fragments, maxDatagramBytes)
+		}
+	}
+	if exceedsMTU(msg.Bytes) {
+		stats.TotalOverMTU++
+		if stats.TotalOverMTU == 1 {
+			log.Printf("Samples on %s likely exceed an assumed %d byte MTU (%s) and are fragmented by IP", msg.Topic, linkMTU, FormatBytes(float64(msg.Bytes)))
+		}
+	}
+	stats.LargestSample = max(stats.LargestSample, msg.Bytes)
+	stats.TotalBytes += msg.Bytes
+	stats.TotalWireBytes += sample.wireBytes
+	stats.LastSeen = at
+	stats.samples = append(stats.samples, sample)
+	stats.prune(at)
+}
+
+// Drop samples older than the longest window
+func (s *TopicStats) prune(now time.Time) {
+	cutoff := now.Add(-maxStatsWindow)
+	i := 0
+	for i < len(s.samples) && s.samples[i].at.Before(cutoff) {
+		i++
+	}
+	s.samples = s.samples[i:]
+}
+
+// Statistics for the given topic over the last window
+func (s *TopicStats) Window(now time.Time, window time.Duration) TopicWindowStats {
+	ws := TopicWindowStats{Topic: s.Topic}
+	cutoff := now.Add(-window)
+	bytes, wireBytes := 0, 0
+	for i := len(s.samples) - 1; i >= 0 && !s.samples[i].at.Before(cutoff); i-- {
+		ws.Count++
+		bytes += s.samples[i].bytes
+		wireBytes += s.samples[i].wireBytes
+		if s.samples[i].gap {
+			ws.Gaps++
+		}
+	}
+	ws.Rate = float64(ws.Count) / window.Seconds()
+	ws.Bandwidth = float64(bytes) / window.Seconds()
+	ws.WireBandwidth = float64(wireBytes) / window.Seconds()
+	return ws
+}
+
+// Set the number of samples the reader of a topic reported lost
+func (t *StatsTracker) SetLost(topic string, lost int) {
+	t.mu.Lock()
+	defer t.mu.Unlock()
+
+	if stats, ok := t.topics[topic]; ok {
+		stats.Lost = lost
+	}
+}
+
+// Copies of all tracked topics, sorted by topic name
+func (t *StatsTracker) Topics() []TopicStats {
+	t.mu.Lock()
+	defer t.mu.Unlock()
+
+	result := make([]TopicStats, 0, len(t.topics))
+	for _, stats := range t.topics {
+		result = append(result, *stats)
+	}
+	sort.Slice(result, func(i, j int) bool {
+		return result[i].Topic < result[j].Topic
+	})
+	return result
+}
+
+// Window statistics of all topics, sorted by topic name
+func (t *StatsTracker) Window(now time.Time, window time.Duration) []TopicWindowStats {
+	t.mu.Lock()
+	defer t.mu.Unlock()
+
+	result := make([]TopicWindowStats, 0, len(t.topics))
+	for _, stats := range t.topics {
+		stats.prune(now)
+		result = append(result, stats.Window(now, window))
+	}
+	sort.Slice(result, func(i, j int) bool {
+		return result[i].Topic < result[j].Topic
+	})
+	return result
+}
+
+// Format a byte count with binary units
+func FormatBytes(bytes float64) string {
+	units := []string{"B", "KiB", "MiB", "GiB"}
+	i := 0
+	for bytes >= 1024 && i < len(units)-1 {
+		bytes /= 1024
+		i++
+	}
+	if i == 0 {
+		return fmt.Sprintf("%.0f %s", bytes, units[i])
+	}
+	return fmt.Sprintf("%.1f %s", bytes, units[i])
+}
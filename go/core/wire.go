@@ -0,0 +1,58 @@
+package core
+
+// Per-sample overhead heuristics for one RTPS DATA message over UDPv4.
+// These ignore heartbeats, acknacks and discovery traffic, so the result
+// is a lower bound on real network load, but a far better one than
+// payload bytes alone.
+//
+// The datagram limit and MTU are assumed defaults, not read from the
+// participant or the network: a transport configured with another
+// maxMessageSize, or a link with jumbo frames, fragments elsewhere. Every
+// fragment count derived from them is an estimate, and labelled as one.
+const (
+	udpIPv4HeaderBytes     = 28    // IPv4 (20) + UDP (8)
+	rtpsHeaderBytes        = 20    // "RTPS", version, vendor, GUID prefix
+	infoTimestampBytes     = 12    // INFO_TS submessage
+	dataSubmessageBytes    = 24    // DATA header, entity IDs, sequence number
+	dataFragExtraBytes     = 12    // DATA_FRAG fragment numbering fields
+	encapsulationBytes     = 4     // CDR encapsulation header
+	sampleFramingBytes     = 20    // SimpleMessage length prefix, timestamp and sender clock
+	maxDatagramBytes       = 65500 // assumed: the Fast DDS default max message size
+	linkMTU                = 1500  // assumed: Ethernet; larger datagrams are fragmented by IP
+	singleOverheadBudget   = udpIPv4HeaderBytes + rtpsHeaderBytes + infoTimestampBytes + dataSubmessageBytes
+	fragmentOverheadBudget = singleOverheadBudget + dataFragExtraBytes
+)
+
+// Serialized size of a sample with the given payload size
+func serializedBytes(payload int) int {
+	serialized := encapsulationBytes + sampleFramingBytes + payload
+	// Submessage bodies are padded to a 4 byte boundary
+	return (serialized + 3) &^ 3
+}
+
+// Estimated number of DATA_FRAG submessages, one per datagram, that a
+// sample with the given payload size is split into; 0 when it fits a
+// single DATA
+func rtpsFragments(payload int) int {
+	serialized := serializedBytes(payload)
+	if singleOverheadBudget+serialized <= maxDatagramBytes {
+		return 0
+	}
+	fragmentPayload := maxDatagramBytes - fragmentOverheadBudget
+	return (serialized + fragmentPayload - 1) / fragmentPayload
+}
+
+// Estimate the on-wire size of a sample with the given payload size
+func estimateWireBytes(payload int) int {
+	serialized := serializedBytes(payload)
+	if fragments := rtpsFragments(payload); fragments > 0 {
+		return serialized + fragments*fragmentOverheadBudget
+	}
+	return singleOverheadBudget + serialized
+}
+
+// Whether a sample with the given payload size likely travels in datagrams
+// larger than the assumed link MTU, which IP then fragments
+func exceedsMTU(payload int) bool {
+	return estimateWireBytes(payload) > linkMTU
+}
@@ -0,0 +1,152 @@
+package main
+
+import (
+	"encoding/csv"
+	"encoding/json"
+	"fmt"
+	"math"
+	"os"
+	"strconv"
+	"strings"
+	"time"
+)
+
+// A column of a CSV file and the JSON field it fills
+type csvColumn struct {
+	index int
+	path  []string // dotted field name, split
+}
+
+// Replays the rows of a CSV file as JSON messages on one topic. Rows are
+// spaced evenly at a fixed rate, or by a time column in seconds.
+type csvSource struct {
+	file       *os.File
+	r          *csv.Reader
+	topic      string
+	columns    []csvColumn
+	timeColumn int // -1 for a fixed rate
+	interval   time.Duration
+	start      time.Time
+	rows       int
+}
+
+// Open a CSV file with a header row. Mappings have the form column:field;
+// without any, every column maps to a field of the same name.
+func openCSVSource(path, topic string, mappings []string, timeColumn string, hz float64) (*csvSource, error) {
+	file, err := os.Open(path)
+	if err != nil {
+		return nil, err
+	}
+
+	s := &csvSource{
+		file:       file,
+		r:          csv.NewReader(file),
+		topic:      topic,
+		timeColumn: -1,
+		interval:   time.Duration(float64(time.Second) / hz),
+		start:      time.Now(),
+	}
+	s.r.TrimLeadingSpace = true
+
+	header, err := s.r.Read()
+	if err != nil {
+		file.Close()
+		return nil, fmt.Errorf("%s: missing header row: %w", path, err)
+	}
+	index := map[string]int{}
+	for i, name := range header {
+		index[name] = i
+	}
+
+	if len(mappings) == 0 {
+		for _, name := range header {
+			if name != timeColumn {
+				mappings = append(mappings, name+":"+name)
+			}
+		}
+	}
+	for _, mapping := range mappings {
+		column, field, ok := strings.Cut(mapping, ":")
+		if !ok || field == "" {
+			file.Close()
+			return nil, fmt.Errorf("invalid column mapping %q, expected column:field", mapping)
+		}
+		i, ok := index[column]
+		if !ok {
+			file.Close()
+			return nil, fmt.Errorf("%s has no column %q", path, column)
+		}
+		s.columns = append(s.columns, csvColumn{index: i, path: strings.Split(field, ".")})
+	}
+
+	if timeColumn != "" {
+		i, ok := index[timeColumn]
+		if !ok {
+			file.Close()
+			return nil, fmt.Errorf("%s has no column %q", path, timeColumn)
+		}
+		s.timeColumn = i
+	}
+	return s, nil
+}
+
+// Typed JSON value of a cell: numbers and booleans as such, NaN and
+// infinities as null, anything else as a string
+func csvValue(cell string) any {
+	if v, err := strconv.ParseInt(cell, 10, 64); err == nil {
+		return v
+	}
+	if v, err := strconv.ParseFloat(cell, 64); err == nil {
+		if math.IsNaN(v) || math.IsInf(v, 0) {
+			return nil // JSON has no NaN or infinity
+		}
+		return v
+	}
+	if v, err := strconv.ParseBool(cell); err == nil {
+		return v
+	}
+	return cell
+}
+
+// Convert the next row to a message, returning io.EOF after the last one
+func (s *csvSource) Next() (DDSMessage, error) {
+	row, err := s.r.Read()
+	if err != nil {
+		return DDSMessage{}, err
+	}
+	s.rows++
+
+	offset := time.Duration(s.rows-1) * s.interval
+	if s.timeColumn >= 0 {
+		seconds, err := strconv.ParseFloat(row[s.timeColumn], 64)
+		if err != nil || math.IsNaN(seconds) || math.IsInf(seconds, 0) {
+			return DDSMessage{}, fmt.Errorf("row %d: invalid time %q", s.rows, row[s.timeColumn])
+		}
+		offset = time.Duration(seconds * float64(time.Second))
+	}
+
+	payload := map[string]any{}
+	for _, column := range s.columns {
+		object := payload
+		for _, key := range column.path[:len(column.path)-1] {
+			child, ok := object[key].(map[string]any)
+			if !ok {
+				child = map[string]any{}
+				object[key] = child
+			}
+			object = child
+		}
+		object[column.path[len(column.path)-1]] = csvValue(row[column.index])
+	}
+	content, err := json.Marshal(payload)
+	if err != nil {
+		return DDSMessage{}, err
+	}
+
+	at := s.start.Add(offset)
+	return DDSMessage{Topic: s.topic, Content: string(content), Timestamp: at, ReceivedAt: at}, nil
+}
+
+func (s *csvSource) Close() error {
+	return s.file.Close()
+}
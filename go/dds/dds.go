@@ -0,0 +1,122 @@
+// Package dds is Cardinal's DDS layer as other Go programs can use it: the
+// message every transport delivers, the publisher, subscriber and transport
+// interfaces, and an in-process mock bus for running without Fast DDS.
+// Tracing, pipeline timing and the sender clock are reported through Hooks,
+// so the package carries none of the binary's global state.
+package dds
+
+import "time"
+
+// A message as it travels through a transport
+type Message struct {
+	Topic       string        `json:"topic"`
+	Content     string        `json:"content"`
+	Timestamp   time.Time     `json:"timestamp"`
+	ReceivedAt  time.Time     `json:"received_at"`
+	Hash        string        `json:"hash,omitempty"`         // XXH64 of the payload, set on ingest
+	Writer      string        `json:"writer,omitempty"`       // GUID of the sending DataWriter, if known
+	Duplicate   bool          `json:"duplicate,omitempty"`    // same payload seen from another writer
+	Sequence    int64         `json:"sequence,omitempty"`     // the writer's sequence number, if known
+	Reordered   bool          `json:"reordered,omitempty"`    // older than a sample already seen from the writer
+	Repeated    bool          `json:"repeated,omitempty"`     // sequence number already seen from the writer
+	Burst       bool          `json:"burst,omitempty"`        // arrived during a burst on its topic
+	OverBudget  bool          `json:"over_budget,omitempty"`  // slower than its topic's latency_budget
+	Own         bool          `json:"own,omitempty"`          // published by this Cardinal instance
+	SenderClock int64         `json:"sender_clock,omitempty"` // sender's monotonic clock in ns, 0 if it sent none
+	Domain      string        `json:"domain,omitempty"`       // DDS domain it arrived in, set when joining several
+	Key         string        `json:"key,omitempty"`          // instance key on keyed topics
+	Instance    string        `json:"instance,omitempty"`     // "disposed" or "unregistered" once the instance ended
+	Trace       SpanContext   `json:"-"`                      // span the message was published or received in
+	latency     time.Duration // measured on ingest, see SetLatency
+}
+
+// Size of the message payload in bytes
+func (msg Message) Size() int {
+	return len(msg.Content)
+}
+
+// Latency between publication and reception
+func (msg Message) Latency() time.Duration {
+	if msg.latency != 0 {
+		return msg.latency
+	}
+	return msg.ReceivedAt.Sub(msg.Timestamp)
+}
+
+// Set the latency measured for the message, instead of the difference of
+// its wall clock timestamps
+func (msg *Message) SetLatency(latency time.Duration) {
+	msg.latency = latency
+}
+
+// Identifies a span within a trace
+type SpanContext struct {
+	TraceID [16]byte
+	SpanID  [8]byte
+}
+
+// Whether the context belongs to a trace
+func (c SpanContext) Valid() bool {
+	return c.TraceID != [16]byte{}
+}
+
+// Interface for DDS publishers
+type Publisher interface {
+	Publish(msg Message) error
+	Cleanup()
+}
+
+// Interface for DDS subscribers
+type Subscriber interface {
+	Subscribe() <-chan Message
+	Cleanup()
+}
+
+// Interface for DDS transports, creating publishers and subscribers by topic
+type Transport interface {
+	Name() string
+	NewPublisher(topic string) (Publisher, error)
+	NewSubscriber(topic string) (Subscriber, error)
+}
+
+// Stages of the message pipeline a transport times
+type Stage int
+
+const (
+	StagePublish Stage = iota // handing messages to the DDS layer
+	StageReceive              // taking a sample from the DDS layer
+)
+
+// Hooks report what a transport does to the program using it. Each is
+// optional; a nil hook is skipped.
+type Hooks struct {
+	// Start timing a stage; the returned function is called when it is done
+	TimeStage func(stage Stage) (done func())
+	// Start a span of messages totalling bytes on a topic. The messages
+	// carry its context, and end is called when the operation is done.
+	StartSpan func(name, topic string, messages, bytes int) (ctx SpanContext, end func())
+	// The sender's monotonic clock in ns, stamped on published messages as
+	// SenderClock
+	Clock func() int64
+}
+
+func (h Hooks) timeStage(stage Stage) func() {
+	if h.TimeStage == nil {
+		return func() {}
+	}
+	return h.TimeStage(stage)
+}
+
+func (h Hooks) startSpan(name, topic string, messages, bytes int) (SpanContext, func()) {
+	if h.StartSpan == nil {
+		return SpanContext{}, func() {}
+	}
+	return h.StartSpan(name, topic, messages, bytes)
+}
+
+func (h Hooks) clock() int64 {
+	if h.Clock == nil {
+		return 0
+	}
+	return h.Clock()
+}
@@ -0,0 +1,48 @@
+package dds
+
+import (
+	"testing"
+	"time"
+)
+
+// The mock bus delivers published messages to the topic's subscribers,
+// with or without hooks
+func TestMockTransportHooks(t *testing.T) {
+	trace := SpanContext{TraceID: [16]byte{1}, SpanID: [8]byte{2}}
+	var stages []Stage
+	var spans []string
+	hooked := NewMockTransport()
+	hooked.Hooks = Hooks{
+		TimeStage: func(stage Stage) func() {
+			stages = append(stages, stage)
+			return func() {}
+		},
+		StartSpan: func(name, topic string, messages, bytes int) (SpanContext, func()) {
+			spans = append(spans, name+" "+topic)
+			return trace, func() {}
+		},
+		Clock: func() int64 { return 42 },
+	}
+
+	for _, transport := range []*MockTransport{NewMockTransport(), hooked} {
+		sub, _ := transport.NewSubscriber("/chatter")
+		pub, _ := transport.NewPublisher("/chatter")
+		if err := pub.Publish(Message{Content: "hello", Timestamp: time.Now()}); err != nil {
+			t.Fatal(err)
+		}
+		msg := <-sub.Subscribe()
+		if msg.Topic != "/chatter" || msg.Content != "hello" || msg.Sequence != 1 || msg.Writer == "" {
+			t.Errorf("received %+v", msg)
+		}
+		if transport == hooked && (msg.SenderClock != 42 || msg.Trace != trace) {
+			t.Errorf("hooked message: sender clock %d, trace %v", msg.SenderClock, msg.Trace)
+		}
+		if transport != hooked && (msg.SenderClock != 0 || msg.Trace.Valid()) {
+			t.Errorf("message without hooks: sender clock %d, trace %v", msg.SenderClock, msg.Trace)
+		}
+		sub.Cleanup()
+	}
+	if len(stages) != 1 || stages[0] != StagePublish || len(spans) != 1 || spans[0] != "publish /chatter" {
+		t.Errorf("hooks saw stages %v, spans %v", stages, spans)
+	}
+}
@@ -0,0 +1,134 @@
+package main
+
+import (
+	"fmt"
+	"path"
+	"strconv"
+	"strings"
+	"sync"
+	"time"
+)
+
+// Recordings can thin out high-rate topics of little value, so they do not
+// dominate the capture size while still being represented. The
+// [record.decimate] table maps topic patterns to a ratio or a rate:
+//
+//	[record.decimate]
+//	"/imu/*" = "1/10"   # keep every 10th sample
+//	"/camera/*" = "5Hz" # keep at most 5 samples a second
+//
+// An exact topic wins over patterns. Only recordings are decimated; views,
+// statistics and sinks still see every sample.
+
+// How to thin out one topic
+type decimation struct {
+	every    int           // keep one sample in every, 0 when limited by rate
+	interval time.Duration // least time between kept samples
+}
+
+// Parse "1/N" or "KHz"
+func parseDecimation(value string) (decimation, error) {
+	if ratio, ok := strings.CutPrefix(value, "1/"); ok {
+		n, err := strconv.Atoi(ratio)
+		if err != nil || n < 1 {
+			return decimation{}, fmt.Errorf("invalid ratio %q, expected 1/N", value)
+		}
+		return decimation{every: n}, nil
+	}
+	if rate, ok := strings.CutSuffix(value, "Hz"); ok {
+		hz, err := strconv.ParseFloat(strings.TrimSpace(rate), 64)
+		if err != nil || hz <= 0 {
+			return decimation{}, fmt.Errorf("invalid rate %q, expected a positive rate in Hz", value)
+		}
+		return decimation{interval: time.Duration(float64(time.Second) / hz)}, nil
+	}
+	return decimation{}, fmt.Errorf("invalid decimation %q, expected 1/N or KHz", value)
+}
+
+// Decimation settings by topic pattern
+type decimationRules map[string]decimation
+
+// Read the [record.decimate] table
+func (c Config) DecimationRules() (decimationRules, error) {
+	rules := decimationRules{}
+	for pattern, value := range c["record.decimate"] {
+		if _, err := path.Match(pattern, ""); err != nil {
+			return nil, fmt.Errorf("record.decimate: topic pattern %q: %w", pattern, err)
+		}
+		rule, err := parseDecimation(value)
+		if err != nil {
+			return nil, fmt.Errorf("record.decimate: %s: %w", pattern, err)
+		}
+		rules[pattern] = rule
+	}
+	return rules, nil
+}
+
+// The decimation of a topic; false when it keeps every sample
+func (r decimationRules) For(topic string) (decimation, bool) {
+	if rule, ok := r[topic]; ok {
+		return rule, true
+	}
+	for _, pattern := range sortedKeys(r) {
+		if ok, _ := path.Match(pattern, topic); ok {
+			return r[pattern], true
+		}
+	}
+	return decimation{}, false
+}
+
+// Picks the samples of one recording to keep. Safe for concurrent use; a
+// nil Decimator keeps everything.
+type Decimator struct {
+	rules decimationRules
+
+	mu      sync.Mutex
+	seen    map[string]int       // samples by topic
+	kept    map[string]time.Time // reception of the last kept sample by topic
+	dropped int
+}
+
+// A decimator for the rules, or nil when there are none
+func NewDecimator(rules decimationRules) *Decimator {
+	if len(rules) == 0 {
+		return nil
+	}
+	return &Decimator{rules: rules, seen: map[string]int{}, kept: map[string]time.Time{}}
+}
+
+// Whether to record a message
+func (d *Decimator) Keep(msg DDSMessage) bool {
+	if d == nil {
+		return true
+	}
+	rule, ok := d.rules.For(msg.Topic)
+	if !ok {
+		return true
+	}
+
+	d.mu.Lock()
+	defer d.mu.Unlock()
+	keep := true
+	if rule.every > 0 {
+		keep = d.seen[msg.Topic]%rule.every == 0
+		d.seen[msg.Topic]++
+	} else if last, ok := d.kept[msg.Topic]; ok {
+		keep = msg.ReceivedAt.Sub(last) >= rule.interval
+	}
+	if keep {
+		d.kept[msg.Topic] = msg.ReceivedAt
+	} else {
+		d.dropped++
+	}
+	return keep
+}
+
+// Samples left out so far
+func (d *Decimator) Dropped() int {
+	if d == nil {
+		return 0
+	}
+	d.mu.Lock()
+	defer d.mu.Unlock()
+	return d.dropped
+}
@@ -0,0 +1,27 @@
+#ifndef CARDINAL_DECODER_H
+#define CARDINAL_DECODER_H
+
+// The C ABI of payload decoder plugins. A plugin is a shared library that
+// exports cardinal_decoder_plugin; Cardinal loads it for the topics the
+// [decoders] table of its config assigns to it, and shows what it decodes
+// instead of the raw payload. Recordings keep the raw payload.
+
+// Version of this ABI; plugins built against another version are refused
+#define CARDINAL_DECODER_ABI 1
+
+typedef struct {
+    int abi_version;  // CARDINAL_DECODER_ABI
+    const char* name; // shown in logs, e.g. "acme-telemetry"
+    // Decode length bytes of payload received on topic into NUL-terminated
+    // text, preferably JSON so display formats can pick fields. Returns NULL
+    // for payloads the plugin does not understand. May be called from
+    // several threads at once.
+    char* (*decode)(const char* topic, const char* payload, int length);
+    void (*release)(char* text); // frees text returned by decode
+} cardinal_decoder;
+
+// Entry point every plugin exports; the decoder it returns must stay valid
+// while the library is loaded
+const cardinal_decoder* cardinal_decoder_plugin(void);
+
+#endif // CARDINAL_DECODER_H
@@ -0,0 +1,18 @@
+//go:build !cgo
+
+package main
+
+import "fmt"
+
+// Decoder plugins are loaded with dlopen, which static builds lack
+type decoderPlugin struct {
+	name string
+}
+
+func loadDecoderPlugin(path string) (*decoderPlugin, error) {
+	return nil, fmt.Errorf("%s: decoder plugins need a build with cgo", path)
+}
+
+func (p *decoderPlugin) Decode(topic, payload string) (string, bool) {
+	return "", false
+}
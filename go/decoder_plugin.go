@@ -0,0 +1,81 @@
+//go:build cgo
+
+package main
+
+/*
+#cgo LDFLAGS: -ldl
+#include <dlfcn.h>
+#include <stdlib.h>
+#include "decoder.h"
+
+typedef const cardinal_decoder* (*cardinal_decoder_entry)(void);
+
+static const cardinal_decoder* cardinal_open_decoder(const char* path, const char** reason) {
+    void* library = dlopen(path, RTLD_NOW | RTLD_LOCAL);
+    if (!library) {
+        *reason = dlerror();
+        return NULL;
+    }
+    cardinal_decoder_entry entry = (cardinal_decoder_entry)dlsym(library, "cardinal_decoder_plugin");
+    if (!entry) {
+        dlclose(library);
+        *reason = "no cardinal_decoder_plugin entry point";
+        return NULL;
+    }
+    return entry();
+}
+
+static char* cardinal_call_decode(const cardinal_decoder* decoder, const char* topic, const char* payload, int length) {
+    return decoder->decode(topic, payload, length);
+}
+
+static void cardinal_call_release(const cardinal_decoder* decoder, char* text) {
+    decoder->release(text);
+}
+*/
+import "C"
+import (
+	"fmt"
+	"unsafe"
+)
+
+// A loaded decoder plugin; libraries stay loaded until Cardinal exits
+type decoderPlugin struct {
+	name    string
+	decoder *C.cardinal_decoder
+}
+
+// Load the decoder plugin of a shared library
+func loadDecoderPlugin(path string) (*decoderPlugin, error) {
+	cPath := C.CString(path)
+	defer C.free(unsafe.Pointer(cPath))
+	var reason *C.char
+	decoder := C.cardinal_open_decoder(cPath, &reason)
+	if decoder == nil {
+		if reason == nil {
+			return nil, fmt.Errorf("%s: cardinal_decoder_plugin returned no decoder", path)
+		}
+		return nil, fmt.Errorf("%s: %s", path, C.GoString(reason))
+	}
+	if decoder.abi_version != C.CARDINAL_DECODER_ABI {
+		return nil, fmt.Errorf("%s: built for decoder ABI %d, Cardinal has %d", path, decoder.abi_version, C.CARDINAL_DECODER_ABI)
+	}
+	if decoder.decode == nil || decoder.release == nil {
+		return nil, fmt.Errorf("%s: decoder lacks decode or release", path)
+	}
+	return &decoderPlugin{name: C.GoString(decoder.name), decoder: decoder}, nil
+}
+
+// Decode a payload; false when the plugin does not understand it
+func (p *decoderPlugin) Decode(topic, payload string) (string, bool) {
+	cTopic := C.CString(topic)
+	defer C.free(unsafe.Pointer(cTopic))
+	cPayload := C.CString(payload)
+	defer C.free(unsafe.Pointer(cPayload))
+	text := C.cardinal_call_decode(p.decoder, cTopic, cPayload, C.int(len(payload)))
+	if text == nil {
+		return "", false
+	}
+	defer C.cardinal_call_release(p.decoder, text)
+	return C.GoString(text), true
+}
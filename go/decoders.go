@@ -0,0 +1,95 @@
+package main
+
+import (
+	"fmt"
+	"log"
+	"path"
+	"strings"
+)
+
+// Proprietary payload formats are decoded by plugins, shared libraries
+// implementing the C ABI of decoder.h, so their code need not live here.
+// The [decoders] table maps topic patterns to plugins:
+//
+//	[decoders]
+//	"/acme/*" = "/opt/acme/lib/libacme-decoder.so"
+//	"/robot/pose" = "/etc/cardinal/robot.idl:geometry::Pose"
+//
+// A value naming an IDL file and one of its structs decodes raw CDR payloads
+// of that type without a plugin (see idldecode.go).
+// What a plugin decodes is shown in place of the payload, and display
+// formats and the field tree work on it; recordings and sinks keep the raw
+// payload.
+
+// Decodes the payloads of a topic, reporting false for those it cannot
+type payloadDecoder interface {
+	Decode(topic, payload string) (string, bool)
+}
+
+// Decoders by topic pattern. A nil payloadDecoders decodes nothing.
+type payloadDecoders map[string]payloadDecoder
+
+// Read the [decoders] table, loading every plugin and IDL type once
+func (c Config) PayloadDecoders() (payloadDecoders, error) {
+	table := c["decoders"]
+	if len(table) == 0 {
+		return nil, nil
+	}
+	decoders := payloadDecoders{}
+	loaded := map[string]payloadDecoder{}
+	for _, pattern := range sortedKeys(table) {
+		if _, err := path.Match(pattern, ""); err != nil {
+			return nil, fmt.Errorf("decoders: topic pattern %q: %w", pattern, err)
+		}
+		spec := table[pattern]
+		decoder, ok := loaded[spec]
+		if !ok {
+			var err error
+			if decoder, err = loadPayloadDecoder(spec); err != nil {
+				return nil, fmt.Errorf("decoders: %w", err)
+			}
+			loaded[spec] = decoder
+		}
+		decoders[pattern] = decoder
+	}
+	return decoders, nil
+}
+
+// Load FILE.idl:TYPE as an IDL decoder, anything else as a plugin library
+func loadPayloadDecoder(spec string) (payloadDecoder, error) {
+	if file, name, ok := strings.Cut(spec, ".idl:"); ok {
+		decoder, err := loadIDLDecoder(file+".idl", name)
+		if err != nil {
+			return nil, err
+		}
+		log.Printf("Decoding %s from %s", name, file+".idl")
+		return decoder, nil
+	}
+	plugin, err := loadDecoderPlugin(spec)
+	if err != nil {
+		return nil, err
+	}
+	log.Printf("Loaded decoder %s from %s", plugin.name, spec)
+	return plugin, nil
+}
+
+// The message with its payload decoded by the plugin of its topic, when it
+// has one that understands it. An exact topic wins over patterns.
+func (d payloadDecoders) Decode(msg DDSMessage) DDSMessage {
+	decoder, ok := d[msg.Topic]
+	if !ok {
+		for _, pattern := range sortedKeys(d) {
+			if match, _ := path.Match(pattern, msg.Topic); match {
+				decoder, ok = d[pattern], true
+				break
+			}
+		}
+	}
+	if !ok {
+		return msg
+	}
+	if text, decoded := decoder.Decode(msg.Topic, msg.Content); decoded {
+		msg.Content = text
+	}
+	return msg
+}
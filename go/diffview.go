@@ -0,0 +1,366 @@
+package main
+
+import (
+	"fmt"
+	"io"
+	"os"
+	"sort"
+	"strings"
+	"time"
+
+	tea "github.com/charmbracelet/bubbletea"
+	"github.com/charmbracelet/lipgloss"
+)
+
+// How the two captures of a diff are paired up
+type alignMode int
+
+const (
+	alignBySequence alignMode = iota // n-th message of a topic with the n-th
+	alignByTime                      // messages at the same offset from the capture start
+	alignModeCount
+)
+
+var alignModeNames = [...]string{"Sequence", "Time"}
+
+// Classification of an aligned pair
+type diffKind int
+
+const (
+	diffSame      diffKind = iota
+	diffDifferent          // both sides present, payloads differ
+	diffMissing            // only in the expected capture
+	diffExtra              // only in the actual capture
+)
+
+// A message of a capture with its offset from the capture start
+type diffSample struct {
+	msg    DDSMessage
+	offset time.Duration
+	index  int // position within its topic, from 1
+}
+
+// One aligned row: either side may be missing
+type diffRow struct {
+	topic    string
+	expected *diffSample
+	actual   *diffSample
+	kind     diffKind
+}
+
+// Offset used to order a row in the combined view
+func (r diffRow) offset() time.Duration {
+	if r.expected != nil {
+		return r.expected.offset
+	}
+	return r.actual.offset
+}
+
+// Read every message of a capture, grouped by topic, and the metadata of
+// the session that recorded it
+func readSamples(path string, cipher *payloadCipher) (map[string][]diffSample, map[string]string, error) {
+	capture, err := OpenCapture(path)
+	if err != nil {
+		return nil, nil, err
+	}
+	defer capture.Close()
+
+	samples := map[string][]diffSample{}
+	var start time.Time
+	for {
+		msg, err := capture.Next()
+		if err == io.EOF {
+			return samples, capture.Metadata()[captureSessionMetadata], nil
+		}
+		if err != nil {
+			return nil, nil, fmt.Errorf("%s: %w", path, err)
+		}
+		if start.IsZero() {
+			start = msg.ReceivedAt
+		}
+		if opened, err := cipher.Open(msg); err == nil {
+			msg = opened
+		}
+		index := len(samples[msg.Topic]) + 1
+		samples[msg.Topic] = append(samples[msg.Topic], diffSample{msg: msg, offset: msg.ReceivedAt.Sub(start), index: index})
+	}
+}
+
+// Pair up the messages of two captures, topic by topic
+func alignCaptures(expected, actual map[string][]diffSample, mode alignMode, tolerance time.Duration) []diffRow {
+	topics := map[string]bool{}
+	for topic := range expected {
+		topics[topic] = true
+	}
+	for topic := range actual {
+		topics[topic] = true
+	}
+
+	var rows []diffRow
+	for _, topic := range sortedKeys(topics) {
+		left, right := expected[topic], actual[topic]
+		i, j := 0, 0
+		for i < len(left) || j < len(right) {
+			row := diffRow{topic: topic}
+			switch {
+			case j >= len(right):
+				row.expected = &left[i]
+				i++
+			case i >= len(left):
+				row.actual = &right[j]
+				j++
+			case mode == alignByTime && right[j].offset-left[i].offset > tolerance:
+				row.expected = &left[i]
+				i++
+			case mode == alignByTime && left[i].offset-right[j].offset > tolerance:
+				row.actual = &right[j]
+				j++
+			default:
+				row.expected, row.actual = &left[i], &right[j]
+				i++
+				j++
+			}
+
+			switch {
+			case row.actual == nil:
+				row.kind = diffMissing
+			case row.expected == nil:
+				row.kind = diffExtra
+			case row.expected.msg.Content != row.actual.msg.Content:
+				row.kind = diffDifferent
+			}
+			rows = append(rows, row)
+		}
+	}
+
+	sort.SliceStable(rows, func(a, b int) bool {
+		return rows[a].offset() < rows[b].offset()
+	})
+	return rows
+}
+
+// TUI model for comparing two captures side by side
+type diffModel struct {
+	expectedPath string
+	actualPath   string
+	expected     map[string][]diffSample
+	actual       map[string][]diffSample
+	expectedMeta map[string]string // session metadata of the captures
+	actualMeta   map[string]string
+	tolerance    time.Duration
+	align        alignMode
+	onlyDiffs    bool
+	rows         []diffRow
+	cursor       int
+	width        int
+	height       int
+}
+
+// Rows shown with the current filter
+func (m diffModel) visibleRows() []diffRow {
+	if !m.onlyDiffs {
+		return m.rows
+	}
+	var rows []diffRow
+	for _, row := range m.rows {
+		if row.kind != diffSame {
+			rows = append(rows, row)
+		}
+	}
+	return rows
+}
+
+func (m diffModel) Init() tea.Cmd {
+	return nil
+}
+
+func (m diffModel) Update(msg tea.Msg) (tea.Model, tea.Cmd) {
+	switch msg := msg.(type) {
+	case tea.WindowSizeMsg:
+		m.width = msg.Width
+		m.height = msg.Height
+
+	case tea.KeyMsg:
+		rows := m.visibleRows()
+		switch msg.String() {
+		case "ctrl+c", "q":
+			return m, tea.Quit
+		case "a":
+			// Switch between sequence and time alignment
+			m.align = (m.align + 1) % alignModeCount
+			m.rows = alignCaptures(m.expected, m.actual, m.align, m.tolerance)
+			m.cursor = 0
+		case "d":
+			// Show only differing rows
+			m.onlyDiffs = !m.onlyDiffs
+			m.cursor = 0
+		case "up", "k":
+			m.cursor = max(m.cursor-1, 0)
+		case "down", "j":
+			m.cursor = min(m.cursor+1, max(len(rows)-1, 0))
+		case "pgup":
+			m.cursor = max(m.cursor-m.pageSize(), 0)
+		case "pgdown":
+			m.cursor = min(m.cursor+m.pageSize(), max(len(rows)-1, 0))
+		case "n":
+			// Jump to the next difference
+			for i := m.cursor + 1; i < len(rows); i++ {
+				if rows[i].kind != diffSame {
+					m.cursor = i
+					break
+				}
+			}
+		case "N":
+			// Jump to the previous difference
+			for i := m.cursor - 1; i >= 0; i-- {
+				if rows[i].kind != diffSame {
+					m.cursor = i
+					break
+				}
+			}
+		}
+	}
+	return m, nil
+}
+
+// Rows that fit on screen below the header card
+func (m diffModel) pageSize() int {
+	return max(m.height-12, 5)
+}
+
+func (m diffModel) View() string {
+	if m.width == 0 {
+		return "Loading..."
+	}
+
+	var (
+		primaryColor = lipgloss.Color("#00D7FF") // Cyan
+		successColor = lipgloss.Color("#51CF66") // Green
+		warningColor = lipgloss.Color("#FFD93D") // Yellow
+		textColor    = lipgloss.Color("#F8F8F2") // Light
+		dimColor     = lipgloss.Color("#6E7681") // Dim
+		accentColor  = lipgloss.Color("#FF6B6B") // Coral
+	)
+
+	cardStyle := lipgloss.NewStyle().
+		Border(lipgloss.RoundedBorder()).
+		BorderForeground(primaryColor).
+		Padding(0, 1)
+
+	headerStyle := lipgloss.NewStyle().
+		Foreground(primaryColor).
+		Bold(true)
+
+	kindStyles := map[diffKind]lipgloss.Style{
+		diffSame:      lipgloss.NewStyle().Foreground(dimColor),
+		diffDifferent: lipgloss.NewStyle().Foreground(warningColor),
+		diffMissing:   lipgloss.NewStyle().Foreground(accentColor),
+		diffExtra:     lipgloss.NewStyle().Foreground(successColor),
+	}
+	kindMarkers := map[diffKind]string{diffSame: " ", diffDifferent: "≠", diffMissing: "-", diffExtra: "+"}
+
+	counts := map[diffKind]int{}
+	for _, row := range m.rows {
+		counts[row.kind]++
+	}
+
+	recordedBy := func(metadata map[string]string) string {
+		if session := describeSession(metadata); session != "" {
+			return lipgloss.NewStyle().Foreground(dimColor).Render(" (" + session + ")")
+		}
+		return ""
+	}
+
+	summary := lipgloss.JoinVertical(lipgloss.Left,
+		headerStyle.Render("🔍 Capture Diff"),
+		fmt.Sprintf("Expected: %s%s", m.expectedPath, recordedBy(m.expectedMeta)),
+		fmt.Sprintf("Actual:   %s%s", m.actualPath, recordedBy(m.actualMeta)),
+		fmt.Sprintf("Aligned by %s • %s • %s • %s • %s",
+			alignModeNames[m.align],
+			kindStyles[diffSame].Render(fmt.Sprintf("%d same", counts[diffSame])),
+			kindStyles[diffDifferent].Render(fmt.Sprintf("%d different", counts[diffDifferent])),
+			kindStyles[diffMissing].Render(fmt.Sprintf("%d missing", counts[diffMissing])),
+			kindStyles[diffExtra].Render(fmt.Sprintf("%d extra", counts[diffExtra])),
+		),
+	)
+
+	// Two columns of samples, each with its offset and payload
+	columnWidth := max((m.width-30)/2, 20)
+	side := func(sample *diffSample) string {
+		if sample == nil {
+			return fmt.Sprintf("%-*s", columnWidth, "")
+		}
+		text := fmt.Sprintf("#%-4d %9s %s", sample.index, formatOffset(sample.offset), payloadText(sample.msg.Content))
+		return fmt.Sprintf("%-*s", columnWidth, truncate(text, columnWidth))
+	}
+
+	rows := m.visibleRows()
+	cursor := min(m.cursor, max(len(rows)-1, 0))
+	start := 0
+	if cursor >= m.pageSize() {
+		start = cursor - m.pageSize() + 1
+	}
+	end := min(start+m.pageSize(), len(rows))
+
+	var b strings.Builder
+	b.WriteString(headerStyle.Render(fmt.Sprintf("  %-18s %-*s │ %s", "Topic", columnWidth, "Expected", "Actual")))
+	b.WriteString("\n")
+	if len(rows) == 0 {
+		b.WriteString(kindStyles[diffSame].Render("  No differences"))
+	}
+	for i := start; i < end; i++ {
+		row := rows[i]
+		marker := kindMarkers[row.kind]
+		if i == cursor {
+			marker = "›"
+		}
+		line := fmt.Sprintf("%s %-18s %s │ %s", marker, truncate(row.topic, 18), side(row.expected), side(row.actual))
+		style := kindStyles[row.kind]
+		if i == cursor {
+			style = style.Foreground(textColor).Bold(true)
+		}
+		b.WriteString(style.Render(line))
+		b.WriteString("\n")
+	}
+
+	footer := lipgloss.NewStyle().
+		Foreground(dimColor).
+		Italic(true).
+		Render("↑/↓ PgUp/PgDn scroll • n/N next/previous difference • a alignment • d only differences • q quit")
+
+	return lipgloss.JoinVertical(lipgloss.Left, cardStyle.Render(summary), b.String(), footer)
+}
+
+// Format an offset from the capture start as seconds
+func formatOffset(d time.Duration) string {
+	return fmt.Sprintf("+%.3fs", d.Seconds())
+}
+
+// Run `cardinal diff`: open two captures side by side in the TUI
+func runDiff(opts Options) int {
+	settings, err := loadSettings(opts)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal diff:", err)
+		return exitUsage
+	}
+
+	m := diffModel{expectedPath: opts.Args[0], actualPath: opts.Args[1], tolerance: opts.TimeTolerance}
+	if m.expected, m.expectedMeta, err = readSamples(m.expectedPath, settings.Cipher); err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal diff:", err)
+		return exitUsage
+	}
+	if m.actual, m.actualMeta, err = readSamples(m.actualPath, settings.Cipher); err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal diff:", err)
+		return exitUsage
+	}
+	if opts.Align == "time" {
+		m.align = alignByTime
+	}
+	m.rows = alignCaptures(m.expected, m.actual, m.align, m.tolerance)
+
+	if _, err := tea.NewProgram(m, tea.WithAltScreen()).Run(); err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal diff:", err)
+		return exitFailure
+	}
+	return exitOK
+}
@@ -0,0 +1,227 @@
+package main
+
+import (
+	"fmt"
+	"maps"
+	"strconv"
+	"sync"
+)
+
+// With --domains Cardinal joins several DDS domains at once: every topic is
+// subscribed in each of them, and messages carry the domain they arrived in
+// so the views can tell them apart. Publishers are created in the first
+// domain.
+
+// Open a transport per domain, or the single transport of the only domain
+func openDomains(domains []int, open func(domain int) (Transport, error)) (Transport, error) {
+	if len(domains) == 1 {
+		return open(domains[0])
+	}
+	t := multiDomainTransport{domains: domains}
+	for _, domain := range domains {
+		transport, err := open(domain)
+		if err != nil {
+			return nil, fmt.Errorf("domain %d: %w", domain, err)
+		}
+		t.transports = append(t.transports, transport)
+	}
+	return t, nil
+}
+
+// Transports of several domains, one per domain
+type multiDomainTransport struct {
+	domains    []int
+	transports []Transport
+}
+
+func (t multiDomainTransport) Name() string {
+	return t.transports[0].Name()
+}
+
+func (t multiDomainTransport) NewPublisher(topic string) (DDSPublisher, error) {
+	return t.transports[0].NewPublisher(topic)
+}
+
+func (t multiDomainTransport) NewSubscriber(topic string) (DDSSubscriber, error) {
+	return t.subscribe(func(transport Transport) (DDSSubscriber, error) {
+		return transport.NewSubscriber(topic)
+	})
+}
+
+// NewDynamicSubscriber reads the topic with its announced type in every
+// domain
+func (t multiDomainTransport) NewDynamicSubscriber(topic string) (DDSSubscriber, error) {
+	return t.subscribe(func(transport Transport) (DDSSubscriber, error) {
+		d, ok := transport.(dynamicSubscriber)
+		if !ok {
+			return nil, fmt.Errorf("%s transport cannot read topics of other types", transport.Name())
+		}
+		return d.NewDynamicSubscriber(topic)
+	})
+}
+
+// Open a subscriber in every domain, merged into one
+func (t multiDomainTransport) subscribe(open func(Transport) (DDSSubscriber, error)) (DDSSubscriber, error) {
+	sub := &multiDomainSubscriber{done: make(chan struct{})}
+	for i, transport := range t.transports {
+		s, err := open(transport)
+		if err != nil {
+			sub.Cleanup()
+			return nil, fmt.Errorf("domain %d: %w", t.domains[i], err)
+		}
+		sub.domains = append(sub.domains, strconv.Itoa(t.domains[i]))
+		sub.subs = append(sub.subs, s)
+	}
+	return sub, nil
+}
+
+// WatchDiscovery reports the participants and endpoints of every domain,
+// labelled with their domain
+func (t multiDomainTransport) WatchDiscovery(l discoveryListener) (func(), error) {
+	var stops []func()
+	stopAll := func() {
+		for _, stop := range stops {
+			stop()
+		}
+	}
+	for i, transport := range t.transports {
+		watcher, ok := transport.(discoveryWatcher)
+		if !ok {
+			continue
+		}
+		stop, err := watcher.WatchDiscovery(domainDiscovery{l, strconv.Itoa(t.domains[i])})
+		if err != nil {
+			stopAll()
+			return nil, fmt.Errorf("domain %d: %w", t.domains[i], err)
+		}
+		stops = append(stops, stop)
+	}
+	if len(stops) == 0 {
+		return nil, fmt.Errorf("%s transport does not report discovery", t.Name())
+	}
+	return stopAll, nil
+}
+
+// Passes on what the discovery of one domain reports, labelled with it
+type domainDiscovery struct {
+	discoveryListener
+	domain string
+}
+
+func (d domainDiscovery) participantChanged(p RemoteParticipant, present bool) {
+	p.Domain = d.domain
+	d.discoveryListener.participantChanged(p, present)
+}
+
+func (d domainDiscovery) endpointChanged(e RemoteEndpoint, present bool) {
+	e.Domain = d.domain
+	d.discoveryListener.endpointChanged(e, present)
+}
+
+// Subscribers of one topic in several domains, merged into one stream
+type multiDomainSubscriber struct {
+	domains []string
+	subs    []DDSSubscriber
+	done    chan struct{}
+	cleanup sync.Once
+}
+
+// Merge the domains' messages, labelled with their domain
+func (m *multiDomainSubscriber) Subscribe() <-chan DDSMessage {
+	out := make(chan DDSMessage, 100)
+	var wg sync.WaitGroup
+	for i, sub := range m.subs {
+		in, domain := sub.Subscribe(), m.domains[i]
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			for msg := range in {
+				msg.Domain = domain
+				select {
+				case out <- msg:
+				case <-m.done:
+					return
+				}
+			}
+		}()
+	}
+	go func() {
+		wg.Wait()
+		close(out)
+	}()
+	return out
+}
+
+func (m *multiDomainSubscriber) Cleanup() {
+	m.cleanup.Do(func() {
+		close(m.done)
+		for _, sub := range m.subs {
+			sub.Cleanup()
+		}
+	})
+}
+
+// IncompatibleQoS adds up the domains' counts
+func (m *multiDomainSubscriber) IncompatibleQoS() int {
+	total := 0
+	for _, sub := range m.subs {
+		total += incompatibleQoS(sub)
+	}
+	return total
+}
+
+// SamplesLost adds up the domains' counts
+func (m *multiDomainSubscriber) SamplesLost() int {
+	total := 0
+	for _, sub := range m.subs {
+		total += samplesLost(sub)
+	}
+	return total
+}
+
+// DeadlinesMissed adds up the domains' counts
+func (m *multiDomainSubscriber) DeadlinesMissed() int {
+	total := 0
+	for _, sub := range m.subs {
+		total += deadlinesMissed(sub)
+	}
+	return total
+}
+
+// WriterLiveliness merges the writers of every domain; GUIDs are unique
+// across domains
+func (m *multiDomainSubscriber) WriterLiveliness() map[string]bool {
+	var writers map[string]bool
+	for _, sub := range m.subs {
+		if liveliness := writerLiveliness(sub); liveliness != nil {
+			if writers == nil {
+				writers = map[string]bool{}
+			}
+			maps.Copy(writers, liveliness)
+		}
+	}
+	return writers
+}
+
+// OnWriterMatched reports the writers of every domain
+func (m *multiDomainSubscriber) OnWriterMatched(f func(writer string, matched bool)) {
+	for _, sub := range m.subs {
+		onWriterMatched(sub, f)
+	}
+}
+
+// OnIncompatibleQoS reports the writers of every domain
+func (m *multiDomainSubscriber) OnIncompatibleQoS(f func(policy string)) {
+	for _, sub := range m.subs {
+		onIncompatibleQoS(sub, f)
+	}
+}
+
+// Topic of a message as the views show it, prefixed with its domain when
+// Cardinal joined several
+func topicLabel(msg DDSMessage) string {
+	if msg.Domain == "" {
+		return msg.Topic
+	}
+	return "d" + msg.Domain + " " + msg.Topic
+}
@@ -0,0 +1,79 @@
+package main
+
+import (
+	"bytes"
+	"fmt"
+	"sync"
+	"time"
+)
+
+// Format a 16 byte DDS GUID as prefix.entity in hex, or "" when unknown
+func formatGUID(guid []byte) string {
+	if len(guid) != 16 || bytes.Equal(guid, make([]byte, 16)) {
+		return ""
+	}
+	return fmt.Sprintf("%x.%x", guid[:12], guid[12:])
+}
+
+// A payload seen from a writer, kept for the duplicate window
+type sighting struct {
+	key    string // topic and payload hash
+	writer string
+	at     time.Time
+}
+
+// Flags samples whose payload already arrived on the same topic from a
+// different DataWriter within the window, the signature of a publisher
+// that was doubled up after a failover. Safe for concurrent use.
+type DuplicateDetector struct {
+	window time.Duration
+
+	mu     sync.Mutex
+	queue  []sighting                // in arrival order, for expiry
+	recent map[string]map[string]int // key -> writer -> sightings in the window
+}
+
+// Create a detector, or nil when window is 0 (detection disabled)
+func NewDuplicateDetector(window time.Duration) *DuplicateDetector {
+	if window <= 0 {
+		return nil
+	}
+	return &DuplicateDetector{window: window, recent: map[string]map[string]int{}}
+}
+
+// Record a hashed message and report whether it duplicates another
+// writer's sample. Messages without a known writer are never flagged.
+func (d *DuplicateDetector) Check(msg DDSMessage) bool {
+	if d == nil || msg.Writer == "" {
+		return false
+	}
+
+	d.mu.Lock()
+	defer d.mu.Unlock()
+
+	// Forget sightings that left the window
+	cutoff := msg.ReceivedAt.Add(-d.window)
+	for len(d.queue) > 0 && d.queue[0].at.Before(cutoff) {
+		old := d.queue[0]
+		d.queue = d.queue[1:]
+		writers := d.recent[old.key]
+		if writers[old.writer]--; writers[old.writer] == 0 {
+			delete(writers, old.writer)
+		}
+		if len(writers) == 0 {
+			delete(d.recent, old.key)
+		}
+	}
+
+	key := msg.Topic + "\x00" + msg.Hash
+	writers, ok := d.recent[key]
+	if !ok {
+		writers = map[string]int{}
+		d.recent[key] = writers
+	}
+	duplicate := len(writers) > 1 || (len(writers) == 1 && writers[msg.Writer] == 0)
+
+	writers[msg.Writer]++
+	d.queue = append(d.queue, sighting{key: key, writer: msg.Writer, at: msg.ReceivedAt})
+	return duplicate
+}
@@ -0,0 +1,79 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+	"os"
+	"os/signal"
+	"syscall"
+	"time"
+)
+
+// Run `cardinal echo`: print the messages received on a topic until
+// interrupted, --count messages arrived or --timeout expired, and return
+// the exit code
+func runEcho(opts Options) int {
+	topic := opts.Args[0]
+
+	settings, err := loadSettings(opts)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal echo:", err)
+		return exitUsage
+	}
+	transport, err := NewFastDDSTransport(settings.Profile.Domain, settings.QoS)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal echo:", err)
+		return exitFailure
+	}
+	transport = withPayloadCipher(readOnlyTransport{transport}, settings.Cipher)
+
+	sub, err := transport.NewSubscriber(topic)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal echo:", err)
+		return exitFailure
+	}
+	defer sub.Cleanup()
+
+	ctx, cancel := signal.NotifyContext(context.Background(), os.Interrupt, syscall.SIGTERM)
+	defer cancel()
+	var timeout <-chan time.Time
+	if opts.Timeout > 0 {
+		timeout = time.After(opts.Timeout)
+	}
+
+	received := 0
+	messages := sub.Subscribe()
+	for opts.Count == 0 || received < opts.Count {
+		select {
+		case <-ctx.Done():
+			return exitOK
+		case <-timeout:
+			switch {
+			case received == 0 && incompatibleQoS(sub) > 0:
+				fmt.Fprintf(os.Stderr, "cardinal echo: writers on %s have incompatible QoS\n", topic)
+				return exitQoSMismatch
+			case received == 0:
+				return exitNoData
+			case opts.Count > 0:
+				// Fewer messages than asked for
+				return exitTimeout
+			}
+			return exitOK
+		case msg, ok := <-messages:
+			if !ok {
+				return exitFailure
+			}
+			received++
+			switch {
+			case opts.Quiet:
+			case opts.JSON:
+				line, _ := json.Marshal(msg)
+				fmt.Println(string(line))
+			default:
+				fmt.Printf("[%s] %s\n", settings.Times.Format("echo", msg.ReceivedAt, "ms"), settings.Formats.Render(msg))
+			}
+		}
+	}
+	return exitOK
+}
@@ -0,0 +1,206 @@
+package main
+
+import (
+	"crypto/aes"
+	"crypto/cipher"
+	"crypto/rand"
+	"encoding/base64"
+	"errors"
+	"fmt"
+	"path"
+	"strings"
+	"sync"
+)
+
+// Application-level payload encryption for selected topics, as an interim
+// measure where DDS-Security cannot be deployed. Payloads are sealed with
+// AES-256-GCM under a shared key from the [encryption] config table:
+//
+//	[encryption]
+//	key = "<base64 encoded 32 byte key>"
+//	topics = ["secure/*", "sensor/cmd"]
+//
+// Sealed payloads stay printable so they fit the string-based transport:
+// the prefix below followed by base64(nonce || ciphertext).
+const sealedPrefix = "enc:v1:"
+
+// Shown instead of payloads that cannot be decrypted
+const undecryptablePayload = "🔒 <undecryptable payload>"
+
+var errUndecryptable = errors.New("payload cannot be decrypted")
+
+// Seals and opens payloads on the configured topics. A nil cipher leaves
+// every message unchanged.
+type payloadCipher struct {
+	aead   cipher.AEAD
+	topics []string // path.Match patterns
+}
+
+// Build the payload cipher from the [encryption] table, or nil when the
+// config has none
+func (c Config) PayloadCipher() (*payloadCipher, error) {
+	table, ok := c["encryption"]
+	if !ok {
+		return nil, nil
+	}
+
+	key, err := base64.StdEncoding.DecodeString(table["key"])
+	if err != nil || len(key) != 32 {
+		return nil, fmt.Errorf("encryption: key must be 32 bytes, base64 encoded")
+	}
+	block, err := aes.NewCipher(key)
+	if err != nil {
+		return nil, fmt.Errorf("encryption: %w", err)
+	}
+	aead, err := cipher.NewGCM(block)
+	if err != nil {
+		return nil, fmt.Errorf("encryption: %w", err)
+	}
+
+	topics, err := parseStringList(table["topics"])
+	if err != nil {
+		return nil, fmt.Errorf("encryption: topics: %w", err)
+	}
+	for _, pattern := range topics {
+		if _, err := path.Match(pattern, ""); err != nil {
+			return nil, fmt.Errorf("encryption: topic pattern %q: %w", pattern, err)
+		}
+	}
+	return &payloadCipher{aead: aead, topics: topics}, nil
+}
+
+// Whether payloads on topic are encrypted
+func (c *payloadCipher) matches(topic string) bool {
+	if c == nil {
+		return false
+	}
+	for _, pattern := range c.topics {
+		if ok, _ := path.Match(pattern, topic); ok {
+			return true
+		}
+	}
+	return false
+}
+
+// Encrypt the payload of a message on an encrypted topic
+func (c *payloadCipher) Seal(msg DDSMessage) DDSMessage {
+	if !c.matches(msg.Topic) {
+		return msg
+	}
+
+	nonce := make([]byte, c.aead.NonceSize())
+	rand.Read(nonce)
+	// The topic is authenticated so a payload cannot be replayed on another
+	sealed := c.aead.Seal(nonce, nonce, []byte(msg.Content), []byte(msg.Topic))
+	msg.Content = sealedPrefix + base64.StdEncoding.EncodeToString(sealed)
+	return msg
+}
+
+// Decrypt the payload of a message on an encrypted topic
+func (c *payloadCipher) Open(msg DDSMessage) (DDSMessage, error) {
+	if !c.matches(msg.Topic) {
+		return msg, nil
+	}
+
+	encoded, ok := strings.CutPrefix(msg.Content, sealedPrefix)
+	if !ok {
+		return msg, errUndecryptable
+	}
+	sealed, err := base64.StdEncoding.DecodeString(encoded)
+	if err != nil || len(sealed) < c.aead.NonceSize() {
+		return msg, errUndecryptable
+	}
+	nonce, ciphertext := sealed[:c.aead.NonceSize()], sealed[c.aead.NonceSize():]
+	plaintext, err := c.aead.Open(nil, nonce, ciphertext, []byte(msg.Topic))
+	if err != nil {
+		return msg, errUndecryptable
+	}
+	msg.Content = string(plaintext)
+	return msg, nil
+}
+
+// Apply the payload cipher to a transport, if one is configured
+func withPayloadCipher(transport Transport, c *payloadCipher) Transport {
+	if c == nil {
+		return transport
+	}
+	return encryptedTransport{transport, c}
+}
+
+// Wraps a transport so publishers seal and subscribers open payloads on
+// the encrypted topics
+type encryptedTransport struct {
+	Transport
+	cipher *payloadCipher
+}
+
+type encryptedPublisher struct {
+	DDSPublisher
+	cipher *payloadCipher
+}
+
+type encryptedSubscriber struct {
+	DDSSubscriber
+	cipher  *payloadCipher
+	done    chan struct{}
+	cleanup sync.Once
+}
+
+func (t encryptedTransport) NewPublisher(topic string) (DDSPublisher, error) {
+	pub, err := t.Transport.NewPublisher(topic)
+	if err != nil || !t.cipher.matches(topic) {
+		return pub, err
+	}
+	return encryptedPublisher{pub, t.cipher}, nil
+}
+
+func (t encryptedTransport) NewSubscriber(topic string) (DDSSubscriber, error) {
+	sub, err := t.Transport.NewSubscriber(topic)
+	if err != nil || !t.cipher.matches(topic) {
+		return sub, err
+	}
+	return &encryptedSubscriber{DDSSubscriber: sub, cipher: t.cipher, done: make(chan struct{})}, nil
+}
+
+func (p encryptedPublisher) Publish(msg DDSMessage) error {
+	return p.DDSPublisher.Publish(p.cipher.Seal(msg))
+}
+
+func (p encryptedPublisher) PublishBatch(batch []DDSMessage) error {
+	sealed := make([]DDSMessage, len(batch))
+	for i, msg := range batch {
+		sealed[i] = p.cipher.Seal(msg)
+	}
+	return publishBatch(p.DDSPublisher, sealed)
+}
+
+// Decrypt received messages; payloads that fail authentication are
+// replaced rather than shown as ciphertext
+func (s *encryptedSubscriber) Subscribe() <-chan DDSMessage {
+	in := s.DDSSubscriber.Subscribe()
+	out := make(chan DDSMessage, cap(in))
+	go func() {
+		defer close(out)
+		for msg := range in {
+			opened, err := s.cipher.Open(msg)
+			if err != nil {
+				opened.Content = undecryptablePayload
+			}
+			select {
+			case out <- opened:
+			case <-s.done:
+				return
+			}
+		}
+	}()
+	return out
+}
+
+// Stop decrypting, even when nobody reads the output any more, and clean up
+// the transport's subscriber
+func (s *encryptedSubscriber) Cleanup() {
+	s.cleanup.Do(func() {
+		close(s.done)
+		s.DDSSubscriber.Cleanup()
+	})
+}
@@ -0,0 +1,106 @@
+package main
+
+import (
+	"strconv"
+	"sync"
+)
+
+// Events a session publishes on its bus. The subsystems reacting to them
+// (history, recorder, sinks, hooks, the TUI) subscribe to the bus instead of
+// being called from the session one by one. The set is closed: every event
+// names the hook it fires, if any, with the hook's CARDINAL_* variables.
+type Event interface {
+	hook() (event string, vars map[string]string)
+}
+
+// A message passed the session's checks and statistics
+type MessageReceived struct {
+	Message DDSMessage
+}
+
+// A remote writer matched a subscription, or went away
+type EndpointMatched struct {
+	Topic   string
+	Writer  string
+	Matched bool
+}
+
+// Something needs attention: a latency budget was exceeded, a burst ended,
+// a writer lost liveliness, or a topic went stale or recovered
+type AlertFired struct {
+	Kind  string // hook event, e.g. latency_budget
+	Topic string
+	Text  string            // what happened, as logged and shown
+	Vars  map[string]string // hook variables besides TOPIC
+}
+
+// A recording started or stopped
+type RecordingStateChanged struct {
+	Recording bool
+	Status    RecordingStatus
+}
+
+func (MessageReceived) hook() (string, map[string]string) {
+	return "", nil
+}
+
+func (e EndpointMatched) hook() (string, map[string]string) {
+	event := "writer_matched"
+	if !e.Matched {
+		event = "writer_unmatched"
+	}
+	return event, map[string]string{"TOPIC": e.Topic, "WRITER": e.Writer}
+}
+
+func (e AlertFired) hook() (string, map[string]string) {
+	vars := map[string]string{"TOPIC": e.Topic}
+	for name, value := range e.Vars {
+		vars[name] = value
+	}
+	return e.Kind, vars
+}
+
+func (e RecordingStateChanged) hook() (string, map[string]string) {
+	if e.Recording {
+		return "recording_started", map[string]string{"PATH": e.Status.Path}
+	}
+	return "recording_stopped", map[string]string{
+		"PATH":     e.Status.Path,
+		"MESSAGES": strconv.Itoa(e.Status.Messages),
+		"BYTES":    strconv.FormatInt(e.Status.Bytes, 10),
+	}
+}
+
+// Delivers every published event to the subscribers, synchronously and in
+// the order they subscribed. Subscribers run on the publishing goroutine,
+// often a transport thread, so they must not block or call back into the
+// session while it holds its lock.
+type EventBus struct {
+	mu       sync.RWMutex
+	handlers []func(Event)
+}
+
+// Add a subscriber receiving every event from now on
+func (b *EventBus) Subscribe(handler func(Event)) {
+	b.mu.Lock()
+	defer b.mu.Unlock()
+	b.handlers = append(b.handlers, handler)
+}
+
+// Add a subscriber receiving only the messages
+func (b *EventBus) OnMessage(handler func(DDSMessage)) {
+	b.Subscribe(func(ev Event) {
+		if received, ok := ev.(MessageReceived); ok {
+			handler(received.Message)
+		}
+	})
+}
+
+func (b *EventBus) Publish(ev Event) {
+	b.mu.RLock()
+	handlers := b.handlers
+	b.mu.RUnlock()
+	for _, handler := range handlers {
+		handler(ev)
+	}
+}
@@ -0,0 +1,180 @@
+//go:build fastdds
+// +build fastdds
+
+package main
+
+/*
+#include "../lib/fastdds.h"
+
+extern void cardinalDataAvailable(uintptr_t context);
+extern void cardinalWriterMatched(uintptr_t context, unsigned char* guid, int matched);
+extern void cardinalRequestedIncompatibleQoS(uintptr_t context, char* policy);
+extern void cardinalOfferedIncompatibleQoS(uintptr_t context, char* policy);
+extern void cardinalParticipantChanged(uintptr_t context, SimpleParticipantInfo* info);
+extern void cardinalEndpointChanged(uintptr_t context, SimpleEndpointInfo* info);
+*/
+import "C"
+import (
+	"runtime/cgo"
+	"sync"
+	"time"
+	"unsafe"
+)
+
+// Events of a Fast DDS reader, which its listener passes to Go through the
+// exported callbacks below
+type readerEvents struct {
+	data chan struct{} // signalled when unread samples arrived
+
+	mu           sync.Mutex
+	matched      func(writer string, matched bool)
+	incompatible func(policy string)
+}
+
+// Register a reader's callbacks. The returned handle stays valid until
+// releaseReaderEvents.
+func watchReaderEvents(sub C.SimpleDDSSubscriber) (*readerEvents, cgo.Handle) {
+	events := &readerEvents{data: make(chan struct{}, 1)}
+	handle := cgo.NewHandle(events)
+	callbacks := C.SimpleReaderCallbacks{
+		data_available:   (*[0]byte)(C.cardinalDataAvailable),
+		writer_matched:   (*[0]byte)(C.cardinalWriterMatched),
+		incompatible_qos: (*[0]byte)(C.cardinalRequestedIncompatibleQoS),
+		context:          C.uintptr_t(handle),
+	}
+	C.simple_subscriber_set_callbacks(sub, &callbacks)
+	return events, handle
+}
+
+// Remove a reader's callbacks, waiting for running ones, before it is
+// destroyed
+func releaseReaderEvents(sub C.SimpleDDSSubscriber, handle cgo.Handle) {
+	C.simple_subscriber_set_callbacks(sub, nil)
+	handle.Delete()
+}
+
+//export cardinalDataAvailable
+func cardinalDataAvailable(context C.uintptr_t) {
+	events := cgo.Handle(context).Value().(*readerEvents)
+	// One pending signal is enough: the receive loop drains the reader
+	select {
+	case events.data <- struct{}{}:
+	default:
+	}
+}
+
+//export cardinalWriterMatched
+func cardinalWriterMatched(context C.uintptr_t, guid *C.uchar, matched C.int) {
+	events := cgo.Handle(context).Value().(*readerEvents)
+	events.mu.Lock()
+	f := events.matched
+	events.mu.Unlock()
+	if f != nil {
+		f(formatGUID(C.GoBytes(unsafe.Pointer(guid), 16)), matched != 0)
+	}
+}
+
+//export cardinalRequestedIncompatibleQoS
+func cardinalRequestedIncompatibleQoS(context C.uintptr_t, policy *C.char) {
+	events := cgo.Handle(context).Value().(*readerEvents)
+	events.mu.Lock()
+	f := events.incompatible
+	events.mu.Unlock()
+	if f != nil {
+		f(C.GoString(policy))
+	}
+}
+
+// Events of a Fast DDS writer, which its listener passes to Go through the
+// exported callbacks below
+type writerEvents struct {
+	mu           sync.Mutex
+	incompatible func(policy string)
+}
+
+// Register a writer's callbacks. The returned handle stays valid until
+// releaseWriterEvents.
+func watchWriterEvents(pub C.SimpleDDSPublisher) (*writerEvents, cgo.Handle) {
+	events := &writerEvents{}
+	handle := cgo.NewHandle(events)
+	callbacks := C.SimpleWriterCallbacks{
+		incompatible_qos: (*[0]byte)(C.cardinalOfferedIncompatibleQoS),
+		context:          C.uintptr_t(handle),
+	}
+	C.simple_publisher_set_callbacks(pub, &callbacks)
+	return events, handle
+}
+
+// Remove a writer's callbacks, waiting for running ones, before it is
+// destroyed
+func releaseWriterEvents(pub C.SimpleDDSPublisher, handle cgo.Handle) {
+	C.simple_publisher_set_callbacks(pub, nil)
+	handle.Delete()
+}
+
+//export cardinalOfferedIncompatibleQoS
+func cardinalOfferedIncompatibleQoS(context C.uintptr_t, policy *C.char) {
+	events := cgo.Handle(context).Value().(*writerEvents)
+	events.mu.Lock()
+	f := events.incompatible
+	events.mu.Unlock()
+	if f != nil {
+		f(C.GoString(policy))
+	}
+}
+
+// Start a discovery watcher reporting to l. The returned handle stays valid
+// until the watcher is destroyed; nil when the watcher could not be created,
+// the handle already released.
+func watchDiscovery(domainID int, l discoveryListener) (C.SimpleDDSDiscoveryWatcher, cgo.Handle) {
+	handle := cgo.NewHandle(l)
+	callbacks := C.SimpleDiscoveryCallbacks{
+		participant: (*[0]byte)(C.cardinalParticipantChanged),
+		endpoint:    (*[0]byte)(C.cardinalEndpointChanged),
+		context:     C.uintptr_t(handle),
+	}
+	watcher := C.create_discovery_watcher(C.int(domainID), &callbacks)
+	if watcher == nil {
+		handle.Delete()
+	}
+	return watcher, handle
+}
+
+//export cardinalParticipantChanged
+func cardinalParticipantChanged(context C.uintptr_t, info *C.SimpleParticipantInfo) {
+	l := cgo.Handle(context).Value().(discoveryListener)
+	l.participantChanged(RemoteParticipant{
+		GUID:    formatGUID(C.GoBytes(unsafe.Pointer(&info.guid[0]), 16)),
+		Name:    C.GoString(info.name),
+		Host:    C.GoString(info.hostname),
+		Process: C.GoString(info.process),
+	}, info.discovered != 0)
+}
+
+//export cardinalEndpointChanged
+func cardinalEndpointChanged(context C.uintptr_t, info *C.SimpleEndpointInfo) {
+	l := cgo.Handle(context).Value().(discoveryListener)
+	var partitions []string
+	for _, name := range unsafe.Slice(info.partitions, int(info.partition_count)) {
+		partitions = append(partitions, C.GoString(name))
+	}
+	l.endpointChanged(RemoteEndpoint{
+		GUID:        formatGUID(C.GoBytes(unsafe.Pointer(&info.guid[0]), 16)),
+		Participant: formatGUID(C.GoBytes(unsafe.Pointer(&info.participant_guid[0]), 16)),
+		Topic:       C.GoString(info.topic_name),
+		Type:        C.GoString(info.type_name),
+		Writer:      info.writer != 0,
+		QoS: QoS{
+			Reliability:   announcedReliability[info.reliability],
+			Durability:    announcedDurability[info.durability],
+			LatencyBudget: time.Duration(info.latency_budget_ns),
+			Deadline:      time.Duration(info.deadline_period_ns),
+			Liveliness:    announcedLiveliness[info.liveliness.kind],
+			Lease:         time.Duration(info.liveliness.lease_duration_ns),
+			Partitions:    partitions,
+			Keyed:         info.keyed != 0,
+		},
+		Exclusive: info.exclusive != 0,
+		Strength:  int(info.ownership_strength),
+	}, info.discovered != 0)
+}
@@ -0,0 +1,97 @@
+//go:build fastdds && fastdds_dynamic
+
+// The wrapper API for builds that load libcardinal-fastdds.so at runtime:
+// every function calls through to the library's, which cardinal_load_fastdds
+// resolves before the first call.
+
+#include <dlfcn.h>
+#include <stddef.h>
+
+#include "../lib/fastdds.h"
+
+#define FASTDDS_FUNCTIONS(F, V)                                                                               \
+    F(const char*, simple_fastdds_version, (void), ())                                                        \
+    F(SimpleDDSPublisher, create_simple_publisher, (const char* topic_name), (topic_name))                    \
+    F(SimpleDDSPublisher, create_simple_publisher_qos,                                                        \
+      (int domain_id, const char* topic_name, const SimpleWriterQos* qos), (domain_id, topic_name, qos))      \
+    F(int, publish_simple_message,                                                                            \
+      (SimpleDDSPublisher pub, const char* data, int length, long long timestamp, long long sender_clock),    \
+      (pub, data, length, timestamp, sender_clock))                                                           \
+    F(int, publish_simple_borrowed,                                                                           \
+      (SimpleDDSPublisher pub, const char* data, int length, long long timestamp, long long sender_clock),    \
+      (pub, data, length, timestamp, sender_clock))                                                           \
+    F(int, publish_simple_batch,                                                                              \
+      (SimpleDDSPublisher pub, const char* const* messages, const int* lengths, const long long* timestamps, \
+       const long long* sender_clocks, int count),                                                            \
+      (pub, messages, lengths, timestamps, sender_clocks, count))                                             \
+    F(int, publish_simple_keyed,                                                                              \
+      (SimpleDDSPublisher pub, const char* key, int key_length, const char* data, int length,                \
+       long long timestamp, long long sender_clock),                                                          \
+      (pub, key, key_length, data, length, timestamp, sender_clock))                                          \
+    F(int, dispose_simple_instance, (SimpleDDSPublisher pub, const char* key, int key_length),                \
+      (pub, key, key_length))                                                                                 \
+    F(int, unregister_simple_instance, (SimpleDDSPublisher pub, const char* key, int key_length),             \
+      (pub, key, key_length))                                                                                 \
+    V(simple_publisher_set_callbacks, (SimpleDDSPublisher pub, const SimpleWriterCallbacks* callbacks),       \
+      (pub, callbacks))                                                                                       \
+    V(destroy_simple_publisher, (SimpleDDSPublisher pub), (pub))                                              \
+    F(int, simple_publisher_incompatible_qos, (SimpleDDSPublisher pub), (pub))                                \
+    F(int, simple_publisher_matched_readers, (SimpleDDSPublisher pub), (pub))                                 \
+    F(int, simple_publisher_publish_mode, (SimpleDDSPublisher pub), (pub))                                    \
+    V(simple_publisher_reliable_times,                                                                        \
+      (SimpleDDSPublisher pub, long long* heartbeat_period_ns, long long* nack_response_delay_ns),            \
+      (pub, heartbeat_period_ns, nack_response_delay_ns))                                                     \
+    V(simple_publisher_guid, (SimpleDDSPublisher pub, unsigned char guid[16]), (pub, guid))                   \
+    F(int, simple_publisher_deadlines_missed, (SimpleDDSPublisher pub), (pub))                                \
+    F(SimpleDDSSubscriber, create_simple_subscriber, (const char* topic_name), (topic_name))                  \
+    F(SimpleDDSSubscriber, create_simple_subscriber_qos,                                                      \
+      (int domain_id, const char* topic_name, const SimpleReaderQos* qos), (domain_id, topic_name, qos))      \
+    F(SimpleDDSSubscriber, create_dynamic_subscriber,                                                         \
+      (int domain_id, const char* topic_name, const SimpleReaderQos* qos, int timeout_ms),                    \
+      (domain_id, topic_name, qos, timeout_ms))                                                               \
+    F(const char*, simple_subscriber_type_name, (SimpleDDSSubscriber sub), (sub))                             \
+    F(int, receive_simple_message, (SimpleDDSSubscriber sub, SimpleMessage* msg), (sub, msg))                 \
+    V(simple_subscriber_set_callbacks, (SimpleDDSSubscriber sub, const SimpleReaderCallbacks* callbacks),     \
+      (sub, callbacks))                                                                                       \
+    V(destroy_simple_subscriber, (SimpleDDSSubscriber sub), (sub))                                            \
+    F(int, simple_subscriber_incompatible_qos, (SimpleDDSSubscriber sub), (sub))                              \
+    F(int, simple_subscriber_samples_lost, (SimpleDDSSubscriber sub), (sub))                                  \
+    F(int, simple_subscriber_deadlines_missed, (SimpleDDSSubscriber sub), (sub))                              \
+    F(int, simple_subscriber_writer_liveliness, (SimpleDDSSubscriber sub, SimpleWriterLiveliness* writers,    \
+      int max), (sub, writers, max))                                                                          \
+    F(SimpleDDSDiscoveryWatcher, create_discovery_watcher,                                                    \
+      (int domain_id, const SimpleDiscoveryCallbacks* callbacks), (domain_id, callbacks))                     \
+    V(destroy_discovery_watcher, (SimpleDDSDiscoveryWatcher watcher), (watcher))
+
+// A pointer to each library function, and the function calling it
+#define DEFINE(ret, name, params, args) \
+    static ret(*name##_fn) params;      \
+    ret name params { return name##_fn args; }
+#define DEFINE_VOID(name, params, args) \
+    static void(*name##_fn) params;     \
+    void name params { name##_fn args; }
+FASTDDS_FUNCTIONS(DEFINE, DEFINE_VOID)
+
+// Symbol name and pointer of each function
+#define SYMBOL(ret, name, params, args) {#name, (void**)&name##_fn},
+#define SYMBOL_VOID(name, params, args) SYMBOL(void, name, params, args)
+static const struct {
+    const char* name;
+    void** fn;
+} symbols[] = {FASTDDS_FUNCTIONS(SYMBOL, SYMBOL_VOID)};
+
+// Load the wrapper library and resolve its functions; NULL on success, the
+// reason otherwise
+const char* cardinal_load_fastdds(const char* path) {
+    void* library = dlopen(path, RTLD_NOW | RTLD_LOCAL);
+    if (!library) {
+        return dlerror();
+    }
+    for (size_t i = 0; i < sizeof(symbols) / sizeof(symbols[0]); i++) {
+        *symbols[i].fn = dlsym(library, symbols[i].name);
+        if (!*symbols[i].fn) {
+            return dlerror();
+        }
+    }
+    return NULL;
+}
@@ -0,0 +1,35 @@
+//go:build fastdds && fastdds_dynamic
+// +build fastdds,fastdds_dynamic
+
+package main
+
+/*
+#cgo LDFLAGS: -ldl
+#include <stdlib.h>
+
+const char* cardinal_load_fastdds(const char* path);
+*/
+import "C"
+import (
+	"cmp"
+	"fmt"
+	"os"
+	"sync"
+	"unsafe"
+)
+
+// Wrapper library loaded unless CARDINAL_FASTDDS_LIBRARY names another;
+// found through the usual library search path
+const fastDDSLibrary = "libcardinal-fastdds.so"
+
+// Load the Fast DDS wrapper on first use. Without it Cardinal falls back to
+// the mock transport like a build without Fast DDS.
+var loadFastDDS = sync.OnceValue(func() error {
+	path := cmp.Or(os.Getenv("CARDINAL_FASTDDS_LIBRARY"), fastDDSLibrary)
+	cPath := C.CString(path)
+	defer C.free(unsafe.Pointer(cPath))
+	if reason := C.cardinal_load_fastdds(cPath); reason != nil {
+		return fmt.Errorf("FastDDS wrapper not loaded: %s", C.GoString(reason))
+	}
+	return nil
+})
@@ -7,14 +7,17 @@ package main
 #cgo CFLAGS: -I../install/include -I../lib
 #cgo CPPFLAGS: -I../install/include -I../lib
 #cgo CXXFLAGS: -std=c++17
-#cgo LDFLAGS: -L../install/lib -L../build -lcardinal-fastdds -lfastdds -lfastcdr -lstdc++
 #include "../lib/fastdds.h"
 
 #include <stdlib.h>
 */
 import "C"
 import (
+	"context"
 	"fmt"
+	"log"
+	"runtime/cgo"
+	"sync"
 	"time"
 	"unsafe"
 )
@@ -23,53 +26,315 @@ import (
 type RealDDSPublisher struct {
 	publisher C.SimpleDDSPublisher
 	topic     string
+	keyed     bool
+	events    *writerEvents
+	handle    cgo.Handle
 }
 
 // RealDDSSubscriber wraps the simplified Fast DDS subscriber
 type RealDDSSubscriber struct {
 	subscriber C.SimpleDDSSubscriber
 	topic      string
+	events     *readerEvents
+	handle     cgo.Handle
+	done       chan struct{}
+	stopped    chan struct{}
+	cleanup    sync.Once
 }
 
-// NewRealDDSSystem creates a real Fast DDS publisher and subscriber
-func NewRealDDSSystem(domainID int, topic string) (*RealDDSPublisher, *RealDDSSubscriber, error) {
-	// Create publisher
+// FastDDSTransport creates Fast DDS publishers and subscribers in one domain
+type FastDDSTransport struct {
+	domainID int
+	qos      *QoSConfig
+}
+
+// NewFastDDSTransport creates a transport backed by the Fast DDS wrapper,
+// creating endpoints with the QoS configured for their topics
+func NewFastDDSTransport(domainID int, qos *QoSConfig) (Transport, error) {
+	if err := loadFastDDS(); err != nil {
+		return nil, err
+	}
+	return &FastDDSTransport{domainID: domainID, qos: qos}, nil
+}
+
+// Instance states of received samples, "" for alive instances
+var instanceStates = map[C.int]string{
+	C.SIMPLE_INSTANCE_ALIVE:        "",
+	C.SIMPLE_INSTANCE_DISPOSED:     "disposed",
+	C.SIMPLE_INSTANCE_UNREGISTERED: "unregistered",
+}
+
+// QoS kinds other participants' endpoints announce, by their C value
+var announcedReliability = map[C.int]string{
+	C.SIMPLE_RELIABILITY_BEST_EFFORT: "best_effort",
+	C.SIMPLE_RELIABILITY_RELIABLE:    "reliable",
+}
+
+var announcedDurability = map[C.int]string{
+	C.SIMPLE_DURABILITY_VOLATILE:        "volatile",
+	C.SIMPLE_DURABILITY_TRANSIENT_LOCAL: "transient_local",
+	C.SIMPLE_DURABILITY_TRANSIENT:       "transient",
+	C.SIMPLE_DURABILITY_PERSISTENT:      "persistent",
+}
+
+var announcedLiveliness = map[C.int]string{
+	C.SIMPLE_LIVELINESS_AUTOMATIC:             "automatic",
+	C.SIMPLE_LIVELINESS_MANUAL_BY_PARTICIPANT: "manual_by_participant",
+	C.SIMPLE_LIVELINESS_MANUAL_BY_TOPIC:       "manual_by_topic",
+}
+
+// C publish modes by name
+var publishModes = map[string]C.int{
+	"":      C.SIMPLE_PUBLISH_DEFAULT,
+	"sync":  C.SIMPLE_PUBLISH_SYNC,
+	"async": C.SIMPLE_PUBLISH_ASYNC,
+}
+
+var reliabilityKinds = map[string]C.int{
+	"":            C.SIMPLE_RELIABILITY_DEFAULT,
+	"best_effort": C.SIMPLE_RELIABILITY_BEST_EFFORT,
+	"reliable":    C.SIMPLE_RELIABILITY_RELIABLE,
+}
+
+var durabilityKinds = map[string]C.int{
+	"":                C.SIMPLE_DURABILITY_DEFAULT,
+	"volatile":        C.SIMPLE_DURABILITY_VOLATILE,
+	"transient_local": C.SIMPLE_DURABILITY_TRANSIENT_LOCAL,
+}
+
+var historyKinds = map[string]C.int{
+	"":          C.SIMPLE_HISTORY_DEFAULT,
+	"keep_last": C.SIMPLE_HISTORY_KEEP_LAST,
+	"keep_all":  C.SIMPLE_HISTORY_KEEP_ALL,
+}
+
+var livelinessKinds = map[string]C.int{
+	"":                      C.SIMPLE_LIVELINESS_DEFAULT,
+	"automatic":             C.SIMPLE_LIVELINESS_AUTOMATIC,
+	"manual_by_participant": C.SIMPLE_LIVELINESS_MANUAL_BY_PARTICIPANT,
+	"manual_by_topic":       C.SIMPLE_LIVELINESS_MANUAL_BY_TOPIC,
+}
+
+// The C form of a topic's liveliness
+func livelinessQoS(qos QoS) C.SimpleLivelinessQos {
+	return C.SimpleLivelinessQos{
+		kind:              livelinessKinds[qos.Liveliness],
+		lease_duration_ns: C.longlong(qos.Lease),
+	}
+}
+
+// The C form of a topic's history and resource limits
+func historyQoS(qos QoS) C.SimpleHistoryQos {
+	return C.SimpleHistoryQos{
+		kind:        historyKinds[qos.History],
+		depth:       C.int(qos.HistoryDepth),
+		max_samples: C.int(qos.MaxSamples),
+	}
+}
+
+// Copy strings into a C array, so C may keep pointers into it during a call
+func cStringArray(values []string) []*C.char {
+	if len(values) == 0 {
+		return nil
+	}
+	array := unsafe.Slice((**C.char)(C.malloc(C.size_t(len(values))*C.size_t(unsafe.Sizeof((*C.char)(nil))))), len(values))
+	for i, value := range values {
+		array[i] = C.CString(value)
+	}
+	return array
+}
+
+func freeCStringArray(array []*C.char) {
+	if len(array) == 0 {
+		return
+	}
+	for _, cstr := range array {
+		C.free(unsafe.Pointer(cstr))
+	}
+	C.free(unsafe.Pointer(&array[0]))
+}
+
+// The C form of a topic's writer QoS
+func writerQoS(qos QoS) C.SimpleWriterQos {
+	return C.SimpleWriterQos{
+		flow_max_bytes: C.longlong(qos.FlowLimit),
+		flow_period_ms: C.int(qos.FlowPeriod.Milliseconds()),
+		publish_mode:   publishModes[qos.PublishMode],
+		reliability:    reliabilityKinds[qos.Reliability],
+		durability:     durabilityKinds[qos.Durability],
+		history:        historyQoS(qos),
+		liveliness:     livelinessQoS(qos),
+
+		heartbeat_period_ns:    C.longlong(qos.Heartbeat),
+		nack_response_delay_ns: C.longlong(qos.NackDelay),
+		latency_budget_ns:      C.longlong(qos.LatencyBudget),
+		deadline_period_ns:     C.longlong(qos.Deadline),
+		keyed:                  cBool(qos.Keyed),
+	}
+}
+
+// The C form of a topic's reader QoS
+func readerQoS(qos QoS) C.SimpleReaderQos {
+	return C.SimpleReaderQos{
+		latency_budget_ns:  C.longlong(qos.LatencyBudget),
+		deadline_period_ns: C.longlong(qos.Deadline),
+		reliability:        reliabilityKinds[qos.Reliability],
+		durability:         durabilityKinds[qos.Durability],
+		history:            historyQoS(qos),
+		liveliness:         livelinessQoS(qos),
+		keyed:              cBool(qos.Keyed),
+	}
+}
+
+// C truth value of a bool
+func cBool(b bool) C.int {
+	if b {
+		return 1
+	}
+	return 0
+}
+
+// Version of the linked Fast DDS library
+func fastDDSVersion() string {
+	if loadFastDDS() != nil {
+		return ""
+	}
+	return C.GoString(C.simple_fastdds_version())
+}
+
+func (t *FastDDSTransport) Name() string {
+	return "fastdds"
+}
+
+// WatchDiscovery reports the other participants of the transport's domain
+// and their endpoints through a participant of its own
+func (t *FastDDSTransport) WatchDiscovery(l discoveryListener) (func(), error) {
+	watcher, handle := watchDiscovery(t.domainID, l)
+	if watcher == nil {
+		return nil, fmt.Errorf("failed to create discovery participant")
+	}
+	return func() {
+		C.destroy_discovery_watcher(watcher)
+		handle.Delete()
+	}, nil
+}
+
+// NewPublisher creates a real Fast DDS publisher
+func (t *FastDDSTransport) NewPublisher(topic string) (DDSPublisher, error) {
 	topicCStr := C.CString(topic)
 	defer C.free(unsafe.Pointer(topicCStr))
 
-	publisher := C.create_simple_publisher(topicCStr)
-	if publisher == nil {
-		return nil, nil, fmt.Errorf("failed to create DDS publisher")
+	qos := t.qos.For(topic)
+	cQoS := writerQoS(qos)
+	partitions := cStringArray(qos.Partitions)
+	defer freeCStringArray(partitions)
+	if len(partitions) > 0 {
+		cQoS.partitions, cQoS.partition_count = &partitions[0], C.int(len(partitions))
 	}
-
-	// Create subscriber
-	subscriber := C.create_simple_subscriber(topicCStr)
-	if subscriber == nil {
-		C.destroy_simple_publisher(publisher)
-		return nil, nil, fmt.Errorf("failed to create DDS subscriber")
+	publisher := C.create_simple_publisher_qos(C.int(t.domainID), topicCStr, &cQoS)
+	if publisher == nil {
+		return nil, fmt.Errorf("failed to create DDS publisher")
 	}
 
-	pub := &RealDDSPublisher{
+	events, handle := watchWriterEvents(publisher)
+	return &RealDDSPublisher{
 		publisher: publisher,
 		topic:     topic,
+		keyed:     qos.Keyed,
+		events:    events,
+		handle:    handle,
+	}, nil
+}
+
+// NewSubscriber creates a real Fast DDS subscriber
+func (t *FastDDSTransport) NewSubscriber(topic string) (DDSSubscriber, error) {
+	return t.newSubscriber(topic, false)
+}
+
+// NewDynamicSubscriber creates a subscriber decoding the type the topic's
+// writers announce, whatever its QoS configuration says
+func (t *FastDDSTransport) NewDynamicSubscriber(topic string) (DDSSubscriber, error) {
+	return t.newSubscriber(topic, true)
+}
+
+func (t *FastDDSTransport) newSubscriber(topic string, dynamic bool) (DDSSubscriber, error) {
+	topicCStr := C.CString(topic)
+	defer C.free(unsafe.Pointer(topicCStr))
+
+	qos := t.qos.For(topic)
+	cQoS := readerQoS(qos)
+	partitions := cStringArray(qos.Partitions)
+	defer freeCStringArray(partitions)
+	if len(partitions) > 0 {
+		cQoS.partitions, cQoS.partition_count = &partitions[0], C.int(len(partitions))
+	}
+	if qos.Dynamic || dynamic {
+		subscriber := C.create_dynamic_subscriber(C.int(t.domainID), topicCStr, &cQoS, C.int(typeDiscoveryTimeout.Milliseconds()))
+		if subscriber == nil {
+			return nil, fmt.Errorf("no type for %s: no writer announced one within %s", topic, typeDiscoveryTimeout)
+		}
+		log.Printf("Decoding %s as %s", topic, C.GoString(C.simple_subscriber_type_name(subscriber)))
+		return newRealDDSSubscriber(subscriber, topic), nil
+	}
+	subscriber := C.create_simple_subscriber_qos(C.int(t.domainID), topicCStr, &cQoS)
+	if subscriber == nil {
+		return nil, fmt.Errorf("failed to create DDS subscriber")
 	}
+	return newRealDDSSubscriber(subscriber, topic), nil
+}
+
+// How long a dynamic subscriber waits for a writer to announce its type
+const typeDiscoveryTimeout = 10 * time.Second
 
-	sub := &RealDDSSubscriber{
+// Wrap a created subscriber, watching its reader events
+func newRealDDSSubscriber(subscriber C.SimpleDDSSubscriber, topic string) *RealDDSSubscriber {
+	events, handle := watchReaderEvents(subscriber)
+	return &RealDDSSubscriber{
 		subscriber: subscriber,
 		topic:      topic,
+		events:     events,
+		handle:     handle,
+		done:       make(chan struct{}),
 	}
-
-	return pub, sub, nil
 }
 
-// Publish sends a message via Fast DDS
-func (p *RealDDSPublisher) Publish(msg DDSMessage) error {
-	contentCStr := C.CString(msg.Content)
-	defer C.free(unsafe.Pointer(contentCStr))
+// Payloads from this size on are published from the Go string itself
+// rather than a C copy of it
+const borrowThreshold = 16 << 10
 
-	timestamp := C.long(msg.Timestamp.Unix())
+// Publish sends a message via Fast DDS, on keyed topics to the instance of
+// its key
+func (p *RealDDSPublisher) Publish(msg DDSMessage) error {
+	if msg.Key != "" && !p.keyed {
+		return errNotKeyed(p.topic)
+	}
+	timestamp := C.longlong(msg.Timestamp.UnixNano())
+	clock := C.longlong(monotonicNow())
 
-	result := C.publish_simple_message(p.publisher, contentCStr, timestamp)
+	span := startSpan("publish", spanContext{})
+	span.SetString("topic", p.topic)
+	span.SetInt("messages", 1)
+	span.SetInt("bytes", int64(msg.Size()))
+	done := timeStage(stagePublish)
+	var result C.int
+	switch {
+	case p.keyed:
+		keyCStr, contentCStr := C.CString(msg.Key), C.CString(msg.Content)
+		defer C.free(unsafe.Pointer(keyCStr))
+		defer C.free(unsafe.Pointer(contentCStr))
+		result = C.publish_simple_keyed(p.publisher, keyCStr, C.int(len(msg.Key)), contentCStr, C.int(msg.Size()), timestamp, clock)
+	case msg.Size() >= borrowThreshold:
+		// The string's bytes hold no pointers, so cgo lets C read them
+		// in place for the duration of the call
+		data := (*C.char)(unsafe.Pointer(unsafe.StringData(msg.Content)))
+		result = C.publish_simple_borrowed(p.publisher, data, C.int(msg.Size()), timestamp, clock)
+	default:
+		contentCStr := C.CString(msg.Content)
+		defer C.free(unsafe.Pointer(contentCStr))
+		result = C.publish_simple_message(p.publisher, contentCStr, C.int(msg.Size()), timestamp, clock)
+	}
+	done()
+	span.End()
 	if result != 0 {
 		return fmt.Errorf("failed to publish message")
 	}
@@ -77,42 +342,269 @@ func (p *RealDDSPublisher) Publish(msg DDSMessage) error {
 	return nil
 }
 
-// Subscribe receives messages from Fast DDS
+// PublishBatch sends several messages in one call into the library
+func (p *RealDDSPublisher) PublishBatch(batch []DDSMessage) error {
+	if len(batch) == 0 {
+		return nil
+	}
+	if p.keyed {
+		// Batches carry no keys; keyed samples go one by one
+		for _, msg := range batch {
+			if err := p.Publish(msg); err != nil {
+				return err
+			}
+		}
+		return nil
+	}
+	messages := make([]*C.char, len(batch))
+	lengths := make([]C.int, len(batch))
+	timestamps := make([]C.longlong, len(batch))
+	clocks := make([]C.longlong, len(batch))
+	defer func() {
+		for _, cstr := range messages {
+			C.free(unsafe.Pointer(cstr))
+		}
+	}()
+	bytes := 0
+	for i, msg := range batch {
+		messages[i] = C.CString(msg.Content)
+		lengths[i] = C.int(msg.Size())
+		timestamps[i] = C.longlong(msg.Timestamp.UnixNano())
+		clocks[i] = C.longlong(monotonicNow())
+		bytes += msg.Size()
+	}
+
+	span := startSpan("publish", spanContext{})
+	span.SetString("topic", p.topic)
+	span.SetInt("messages", int64(len(batch)))
+	span.SetInt("bytes", int64(bytes))
+	done := timeStage(stagePublish)
+	written := C.publish_simple_batch(p.publisher, &messages[0], &lengths[0], &timestamps[0], &clocks[0], C.int(len(batch)))
+	done()
+	span.End()
+	if int(written) != len(batch) {
+		return fmt.Errorf("failed to publish message %d of %d", max(int(written), 0)+1, len(batch))
+	}
+
+	return nil
+}
+
+// Subscribe receives messages from Fast DDS until Cleanup is called
 func (s *RealDDSSubscriber) Subscribe() <-chan DDSMessage {
 	msgChan := make(chan DDSMessage, 100)
+	s.stopped = make(chan struct{})
 
-	go func() {
+	go runTask(context.Background(), "ffi-receive", func(context.Context) {
+		defer close(s.stopped)
 		defer close(msgChan)
 
 		for {
+			select {
+			case <-s.done:
+				return
+			default:
+			}
+
 			var cMsg C.SimpleMessage
+			start := time.Now()
 			result := C.receive_simple_message(s.subscriber, &cMsg)
 
 			if result == 0 {
+				// Polls that find nothing get no span
+				pipeline[stageReceive].observe(time.Since(start))
+				receiveSpan := startSpanAt("receive", spanContext{}, start)
+				receiveSpan.SetString("topic", s.topic)
+				receiveSpan.SetInt("messages", 1)
+				receiveSpan.End()
+
+				decodeSpan := startSpan("decode", receiveSpan.Context())
+				done := timeStage(stageDecode)
 				msg := DDSMessage{
-					Content:   C.GoString(&cMsg.message[0]),
-					Timestamp: time.Unix(int64(cMsg.timestamp), 0),
+					Topic:       s.topic,
+					Content:     C.GoStringN(cMsg.data, cMsg.length),
+					Timestamp:   time.Unix(0, int64(cMsg.timestamp)),
+					ReceivedAt:  time.Now(),
+					Writer:      formatGUID(C.GoBytes(unsafe.Pointer(&cMsg.writer_guid[0]), 16)),
+					Sequence:    int64(cMsg.sequence),
+					SenderClock: int64(cMsg.sender_clock),
+					Instance:    instanceStates[cMsg.instance_state],
+					Trace:       receiveSpan.Context(),
 				}
-				msgChan <- msg
+				if cMsg.key != nil {
+					msg.Key = C.GoStringN(cMsg.key, cMsg.key_length)
+				}
+				done()
+				decodeSpan.SetInt("bytes", int64(msg.Size()))
+				decodeSpan.End()
+
+				select {
+				case msgChan <- msg:
+				case <-s.done:
+					return
+				}
+				continue
 			}
 
-			// Small sleep to prevent busy waiting
-			time.Sleep(10 * time.Millisecond)
+			// Nothing to read: wait for the reader's listener to report
+			// new samples
+			select {
+			case <-s.events.data:
+			case <-s.done:
+				return
+			}
 		}
-	}()
+	})
 
 	return msgChan
 }
 
-// Cleanup cleans up Fast DDS resources
+// Returned for keys published on a topic not configured as keyed
+func errNotKeyed(topic string) error {
+	return fmt.Errorf("%s is not keyed; set keyed = true in its [qos]", topic)
+}
+
+// Dispose tells the readers that the instance of a key is gone
+func (p *RealDDSPublisher) Dispose(key string) error {
+	return p.endInstance(key, true)
+}
+
+// Unregister tells the readers that this writer no longer updates the
+// instance of a key
+func (p *RealDDSPublisher) Unregister(key string) error {
+	return p.endInstance(key, false)
+}
+
+func (p *RealDDSPublisher) endInstance(key string, dispose bool) error {
+	if !p.keyed {
+		return errNotKeyed(p.topic)
+	}
+	keyCStr := C.CString(key)
+	defer C.free(unsafe.Pointer(keyCStr))
+	var result C.int
+	if dispose {
+		result = C.dispose_simple_instance(p.publisher, keyCStr, C.int(len(key)))
+	} else {
+		result = C.unregister_simple_instance(p.publisher, keyCStr, C.int(len(key)))
+	}
+	if result != 0 {
+		return fmt.Errorf("failed to end instance %q", key)
+	}
+	return nil
+}
+
+// IncompatibleQoS counts the readers offered an incompatible QoS
+func (p *RealDDSPublisher) IncompatibleQoS() int {
+	return int(C.simple_publisher_incompatible_qos(p.publisher))
+}
+
+// MatchedReaders counts the readers currently matched with the writer
+func (p *RealDDSPublisher) MatchedReaders() int {
+	return int(C.simple_publisher_matched_readers(p.publisher))
+}
+
+// ActiveQoS is the writer QoS in effect: publish mode and reliable timing
+func (p *RealDDSPublisher) ActiveQoS() QoS {
+	qos := QoS{PublishMode: "sync"}
+	if C.simple_publisher_publish_mode(p.publisher) == C.SIMPLE_PUBLISH_ASYNC {
+		qos.PublishMode = "async"
+	}
+	var heartbeat, nackDelay C.longlong
+	C.simple_publisher_reliable_times(p.publisher, &heartbeat, &nackDelay)
+	qos.Heartbeat = time.Duration(heartbeat)
+	qos.NackDelay = time.Duration(nackDelay)
+	return qos
+}
+
+// WriterGUID is the GUID of the DataWriter, as receivers see it
+func (p *RealDDSPublisher) WriterGUID() string {
+	var guid [16]C.uchar
+	C.simple_publisher_guid(p.publisher, &guid[0])
+	return formatGUID(C.GoBytes(unsafe.Pointer(&guid[0]), 16))
+}
+
+// DeadlinesMissed counts the deadline periods the writer wrote nothing in
+func (p *RealDDSPublisher) DeadlinesMissed() int {
+	return int(C.simple_publisher_deadlines_missed(p.publisher))
+}
+
+// IncompatibleQoS counts the writers requested with an incompatible QoS
+func (s *RealDDSSubscriber) IncompatibleQoS() int {
+	return int(C.simple_subscriber_incompatible_qos(s.subscriber))
+}
+
+// SamplesLost counts the samples the reader knows it missed, including
+// fragmented samples it could not reassemble
+func (s *RealDDSSubscriber) SamplesLost() int {
+	return int(C.simple_subscriber_samples_lost(s.subscriber))
+}
+
+// DeadlinesMissed counts the deadline periods the reader received nothing in
+func (s *RealDDSSubscriber) DeadlinesMissed() int {
+	return int(C.simple_subscriber_deadlines_missed(s.subscriber))
+}
+
+// WriterLiveliness tells for the GUID of every matched writer whether it is
+// alive
+func (s *RealDDSSubscriber) WriterLiveliness() map[string]bool {
+	writers := make([]C.SimpleWriterLiveliness, 64)
+	n := int(C.simple_subscriber_writer_liveliness(s.subscriber, &writers[0], C.int(len(writers))))
+	if n > len(writers) {
+		writers = make([]C.SimpleWriterLiveliness, n)
+		n = int(C.simple_subscriber_writer_liveliness(s.subscriber, &writers[0], C.int(len(writers))))
+	}
+	n = min(n, len(writers))
+
+	liveliness := make(map[string]bool, n)
+	for i := range writers[:n] {
+		guid := C.GoBytes(unsafe.Pointer(&writers[i].guid[0]), 16)
+		liveliness[formatGUID(guid)] = writers[i].alive != 0
+	}
+	return liveliness
+}
+
+// OnIncompatibleQoS calls f with the policy of every reader the writer
+// cannot match because of QoS
+func (p *RealDDSPublisher) OnIncompatibleQoS(f func(policy string)) {
+	p.events.mu.Lock()
+	p.events.incompatible = f
+	p.events.mu.Unlock()
+}
+
+// Cleanup removes the callbacks before destroying the writer they report on
 func (p *RealDDSPublisher) Cleanup() {
 	if p.publisher != nil {
+		releaseWriterEvents(p.publisher, p.handle)
 		C.destroy_simple_publisher(p.publisher)
 	}
 }
 
+// OnWriterMatched calls f with the GUID of every writer that matches or
+// unmatches the reader
+func (s *RealDDSSubscriber) OnWriterMatched(f func(writer string, matched bool)) {
+	s.events.mu.Lock()
+	s.events.matched = f
+	s.events.mu.Unlock()
+}
+
+// OnIncompatibleQoS calls f with the policy of every writer the reader
+// cannot match because of QoS
+func (s *RealDDSSubscriber) OnIncompatibleQoS(f func(policy string)) {
+	s.events.mu.Lock()
+	s.events.incompatible = f
+	s.events.mu.Unlock()
+}
+
+// Cleanup stops the receive loop and the callbacks before destroying the
+// reader they read from. Calls after the first do nothing.
 func (s *RealDDSSubscriber) Cleanup() {
-	if s.subscriber != nil {
-		C.destroy_simple_subscriber(s.subscriber)
-	}
+	s.cleanup.Do(func() {
+		close(s.done)
+		if s.stopped != nil {
+			<-s.stopped
+		}
+		if s.subscriber != nil {
+			releaseReaderEvents(s.subscriber, s.handle)
+			C.destroy_simple_subscriber(s.subscriber)
+		}
+	})
 }
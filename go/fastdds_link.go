@@ -0,0 +1,12 @@
+//go:build fastdds && !fastdds_dynamic
+// +build fastdds,!fastdds_dynamic
+
+package main
+
+// #cgo LDFLAGS: -L../install/lib -L../build -lcardinal-fastdds -lfastdds -lfastcdr -lstdc++
+import "C"
+
+// The wrapper is linked into the binary, so there is nothing to load
+func loadFastDDS() error {
+	return nil
+}
@@ -0,0 +1,104 @@
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+	"strconv"
+	"strings"
+
+	"github.com/charmbracelet/lipgloss"
+)
+
+// The fields card of the messages tab shows the newest structured payload,
+// after its decoder plugin, as a tree, one field per line. Topics read with `dynamic = true` arrive as
+// JSON of whatever type their writers announced, so this is how their
+// samples are inspected without a display format written for them.
+
+// Most lines of a field tree shown
+const maxFieldTreeLines = 40
+
+// Render a JSON object or array payload as a field tree; false for other
+// payloads
+func fieldTree(content string) ([]string, bool) {
+	decoder := json.NewDecoder(strings.NewReader(content))
+	decoder.UseNumber()
+	var value any
+	if err := decoder.Decode(&value); err != nil {
+		return nil, false
+	}
+	switch value.(type) {
+	case map[string]any, []any:
+	default:
+		return nil, false
+	}
+	var lines []string
+	appendFields(&lines, value, "")
+	return lines, true
+}
+
+// Append the children of an object or array, indented below prefix
+func appendFields(lines *[]string, value any, prefix string) {
+	var names []string
+	var children []any
+	switch v := value.(type) {
+	case map[string]any:
+		names = sortedKeys(v)
+		for _, name := range names {
+			children = append(children, v[name])
+		}
+	case []any:
+		for i, child := range v {
+			names = append(names, "["+strconv.Itoa(i)+"]")
+			children = append(children, child)
+		}
+	}
+
+	for i, child := range children {
+		branch, indent := "├─ ", "│  "
+		if i == len(children)-1 {
+			branch, indent = "└─ ", "   "
+		}
+		switch child.(type) {
+		case map[string]any, []any:
+			*lines = append(*lines, prefix+branch+names[i])
+			appendFields(lines, child, prefix+indent)
+		default:
+			*lines = append(*lines, prefix+branch+names[i]+": "+leafText(child))
+		}
+	}
+}
+
+// Text of a scalar field
+func leafText(value any) string {
+	switch v := value.(type) {
+	case nil:
+		return "null"
+	case string:
+		return strconv.Quote(v)
+	case json.Number:
+		return v.String()
+	}
+	return fmt.Sprint(value)
+}
+
+// Render the fields card for the newest message with a structured payload
+func (m model) renderFields(headerStyle lipgloss.Style, textColor, dimColor lipgloss.Color) string {
+	dimStyle := lipgloss.NewStyle().Foreground(dimColor).Italic(true)
+	for i := len(m.messages) - 1; i >= 0; i-- {
+		lines, ok := fieldTree(m.formats.decoders.Decode(m.messages[i]).Content)
+		if !ok {
+			continue
+		}
+		if len(lines) > maxFieldTreeLines {
+			lines = append(lines[:maxFieldTreeLines], fmt.Sprintf("… %d more fields", len(lines)-maxFieldTreeLines))
+		}
+		return lipgloss.JoinVertical(lipgloss.Left,
+			headerStyle.Render("🌳 Fields of "+topicLabel(m.messages[i])),
+			lipgloss.NewStyle().Foreground(textColor).Render(strings.Join(lines, "\n")),
+		)
+	}
+	return lipgloss.JoinVertical(lipgloss.Left,
+		headerStyle.Render("🌳 Fields"),
+		dimStyle.Render("No structured payload yet"),
+	)
+}
@@ -0,0 +1,86 @@
+package main
+
+import (
+	"maps"
+	"slices"
+	"strings"
+)
+
+// Topics can be muted or soloed from the topics tab, to focus on a few
+// during an incident. The messages tab hides muted topics and, once any
+// topic is soloed, shows only soloed ones. Only the view changes: the
+// session still counts, records and raises alerts for every message.
+
+// Topics muted and soloed. The maps are never changed once set, so the
+// model's copies may share them.
+type topicFocus struct {
+	muted  map[string]bool
+	soloed map[string]bool
+}
+
+// Mute or unmute a topic; muting it ends its solo
+func (f topicFocus) toggleMute(topic string) topicFocus {
+	f.muted = toggled(f.muted, topic)
+	if f.muted[topic] && f.soloed[topic] {
+		f.soloed = toggled(f.soloed, topic)
+	}
+	return f
+}
+
+// Solo a topic or end its solo; soloing it unmutes it
+func (f topicFocus) toggleSolo(topic string) topicFocus {
+	f.soloed = toggled(f.soloed, topic)
+	if f.soloed[topic] && f.muted[topic] {
+		f.muted = toggled(f.muted, topic)
+	}
+	return f
+}
+
+// A copy of the set with the topic added or removed
+func toggled(set map[string]bool, topic string) map[string]bool {
+	set = maps.Clone(set)
+	if set == nil {
+		set = map[string]bool{}
+	}
+	if set[topic] {
+		delete(set, topic)
+	} else {
+		set[topic] = true
+	}
+	return set
+}
+
+// Whether the messages tab shows the topic
+func (f topicFocus) shows(topic string) bool {
+	if len(f.soloed) > 0 {
+		return f.soloed[topic]
+	}
+	return !f.muted[topic]
+}
+
+// The messages of topics shown, the same slice when all are
+func (f topicFocus) apply(messages []DDSMessage) []DDSMessage {
+	if len(f.muted) == 0 && len(f.soloed) == 0 {
+		return messages
+	}
+	var shown []DDSMessage
+	for _, msg := range messages {
+		if f.shows(msg.Topic) {
+			shown = append(shown, msg)
+		}
+	}
+	return shown
+}
+
+// What the messages tab hides, e.g. "solo imu • muted tf, clock"; empty when
+// nothing
+func (f topicFocus) String() string {
+	var parts []string
+	if len(f.soloed) > 0 {
+		parts = append(parts, "solo "+strings.Join(slices.Sorted(maps.Keys(f.soloed)), ", "))
+	}
+	if len(f.muted) > 0 {
+		parts = append(parts, "muted "+strings.Join(slices.Sorted(maps.Keys(f.muted)), ", "))
+	}
+	return strings.Join(parts, " • ")
+}
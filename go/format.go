@@ -0,0 +1,193 @@
+package main
+
+import (
+	"encoding/hex"
+	"encoding/json"
+	"fmt"
+	"path"
+	"strconv"
+	"strings"
+	"unicode"
+	"unicode/utf8"
+)
+
+// Display formats turn JSON payloads into one-liners for the message list,
+// the table and `cardinal echo`. The [formats] table maps topic patterns to
+// templates:
+//
+//	[formats]
+//	"odom" = "{ts} pos=({pose.x:.2},{pose.y:.2}) v={vel:.1}"
+//	"sensor/*" = "{topic}: {value}"
+//
+// Placeholders name a field path of the payload, or ts, topic or payload;
+// :.N rounds numbers to N decimals. {{ and }} stand for literal braces, and
+// fields a payload lacks show as ?. Payloads that are not printable text,
+// binary ones or those with control characters, show as hex.
+
+// A placeholder or a literal part of a template
+type templatePart struct {
+	literal   string
+	path      []string // nil for literals
+	precision int      // decimals for numbers, -1 for as many as needed
+}
+
+// A parsed display template
+type displayTemplate struct {
+	parts []templatePart
+	ts    timeFormat // how {ts} is rendered
+}
+
+// Parse a display template
+func parseTemplate(text string) (*displayTemplate, error) {
+	t := &displayTemplate{}
+	var literal strings.Builder
+	for i := 0; i < len(text); i++ {
+		switch {
+		case strings.HasPrefix(text[i:], "{{"), strings.HasPrefix(text[i:], "}}"):
+			literal.WriteByte(text[i])
+			i++
+		case text[i] == '}':
+			return nil, fmt.Errorf("unmatched } at %d", i)
+		case text[i] == '{':
+			end := strings.IndexByte(text[i:], '}')
+			if end < 0 {
+				return nil, fmt.Errorf("unterminated placeholder at %d", i)
+			}
+			name, spec, _ := strings.Cut(text[i+1:i+end], ":")
+			if name == "" {
+				return nil, fmt.Errorf("empty placeholder at %d", i)
+			}
+			part := templatePart{path: strings.Split(name, "."), precision: -1}
+			if spec != "" {
+				digits, ok := strings.CutPrefix(strings.TrimSuffix(spec, "f"), ".")
+				n, err := strconv.Atoi(digits)
+				if !ok || err != nil || n < 0 {
+					return nil, fmt.Errorf("unsupported format %q for %s, expected .N", spec, name)
+				}
+				part.precision = n
+			}
+			if literal.Len() > 0 {
+				t.parts = append(t.parts, templatePart{literal: literal.String()})
+				literal.Reset()
+			}
+			t.parts = append(t.parts, part)
+			i += end
+		default:
+			literal.WriteByte(text[i])
+		}
+	}
+	if literal.Len() > 0 {
+		t.parts = append(t.parts, templatePart{literal: literal.String()})
+	}
+	return t, nil
+}
+
+// Render a message with the template
+func (t *displayTemplate) Render(msg DDSMessage) string {
+	var payload any
+	parsed := false
+
+	var b strings.Builder
+	for _, part := range t.parts {
+		if part.path == nil {
+			b.WriteString(part.literal)
+			continue
+		}
+		if len(part.path) == 1 {
+			switch part.path[0] {
+			case "ts":
+				b.WriteString(t.ts.Format(msg.ReceivedAt, "ms"))
+				continue
+			case "topic":
+				b.WriteString(msg.Topic)
+				continue
+			case "payload":
+				b.WriteString(payloadText(msg.Content))
+				continue
+			}
+		}
+
+		if !parsed {
+			json.Unmarshal([]byte(msg.Content), &payload)
+			parsed = true
+		}
+		value := payload
+		for _, key := range part.path {
+			object, _ := value.(map[string]any)
+			value = object[key]
+		}
+		b.WriteString(formatTemplateValue(value, part.precision))
+	}
+	return b.String()
+}
+
+// Text of a payload value in a template
+func formatTemplateValue(value any, precision int) string {
+	switch v := value.(type) {
+	case nil:
+		return "?"
+	case float64:
+		return strconv.FormatFloat(v, 'f', precision, 64)
+	case string:
+		return v
+	case bool:
+		return strconv.FormatBool(v)
+	}
+	encoded, _ := json.Marshal(value)
+	return string(encoded)
+}
+
+// Display templates by topic pattern, applied to payloads after their
+// decoder plugins
+type displayFormats struct {
+	templates map[string]*displayTemplate
+	decoders  payloadDecoders
+}
+
+// Read the [formats] and [decoders] tables; {ts} follows the timestamp
+// format of the formats view
+func (c Config) DisplayFormats(times timeFormats) (displayFormats, error) {
+	decoders, err := c.PayloadDecoders()
+	if err != nil {
+		return displayFormats{}, err
+	}
+	formats := displayFormats{templates: map[string]*displayTemplate{}, decoders: decoders}
+	for pattern, text := range c["formats"] {
+		if _, err := path.Match(pattern, ""); err != nil {
+			return displayFormats{}, fmt.Errorf("formats: topic pattern %q: %w", pattern, err)
+		}
+		template, err := parseTemplate(text)
+		if err != nil {
+			return displayFormats{}, fmt.Errorf("formats: %s: %w", pattern, err)
+		}
+		template.ts = times["formats"]
+		formats.templates[pattern] = template
+	}
+	return formats, nil
+}
+
+// The message as shown in lists: its topic's template, or the raw payload
+// when it has none. An exact topic wins over patterns.
+func (f displayFormats) Render(msg DDSMessage) string {
+	msg = f.decoders.Decode(msg)
+	if template, ok := f.templates[msg.Topic]; ok {
+		return template.Render(msg)
+	}
+	for _, pattern := range sortedKeys(f.templates) {
+		if ok, _ := path.Match(pattern, msg.Topic); ok {
+			return f.templates[pattern].Render(msg)
+		}
+	}
+	return payloadText(msg.Content)
+}
+
+// A payload as it is shown: as is when it is printable UTF-8, otherwise as
+// 0x and its bytes in hex, which keeps binary from garbling the terminal
+func payloadText(content string) string {
+	if utf8.ValidString(content) && !strings.ContainsFunc(content, func(r rune) bool {
+		return unicode.IsControl(r) && !unicode.IsSpace(r)
+	}) {
+		return content
+	}
+	return "0x" + hex.EncodeToString([]byte(content))
+}
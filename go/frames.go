@@ -0,0 +1,77 @@
+package main
+
+import (
+	"fmt"
+	"time"
+
+	"github.com/charmbracelet/lipgloss"
+)
+
+// The frame profiler times every Update and View of the TUI against the
+// frame budget of Bubble Tea's renderer, which draws at most 60 frames a
+// second. F or :profile shows the numbers in an overlay above the footer,
+// to find what makes the views slow on slow terminals. The Settings tab's
+// pipeline breakdown has the totals; the overlay shows the recent frames.
+
+// Time the renderer leaves for one frame
+const frameBudget = time.Second / 60
+
+// Frames the overlay's averages and maxima cover
+const frameWindow = 120
+
+// Recent update and draw times. The model holds it by pointer, so its
+// value copies share it; only the TUI goroutine touches it.
+type frameProfiler struct {
+	draws   [frameWindow]time.Duration
+	updates [frameWindow]time.Duration
+	drawn   int // frames drawn, indexing draws
+	handled int // events handled, indexing updates
+	dropped int // frames the renderer missed because drawing or handling overran the budget
+}
+
+// Take in the time of one View
+func (p *frameProfiler) observeDraw(d time.Duration) {
+	p.draws[p.drawn%frameWindow] = d
+	p.drawn++
+	p.dropped += int(d / frameBudget)
+}
+
+// Take in the time of one Update
+func (p *frameProfiler) observeUpdate(d time.Duration) {
+	p.updates[p.handled%frameWindow] = d
+	p.handled++
+	p.dropped += int(d / frameBudget)
+}
+
+// Average and maximum of the recent times, the n first of which are in use
+func recentTimes(times []time.Duration, n int) (avg, longest time.Duration) {
+	n = min(n, len(times))
+	if n == 0 {
+		return 0, 0
+	}
+	var total time.Duration
+	for _, d := range times[:n] {
+		total += d
+		longest = max(longest, d)
+	}
+	return total / time.Duration(n), longest
+}
+
+// Render the overlay, warning when recent frames overran the budget
+func (p *frameProfiler) render(textColor, dimColor, warningColor lipgloss.Color) string {
+	drawAvg, drawMax := recentTimes(p.draws[:], p.drawn)
+	updateAvg, updateMax := recentTimes(p.updates[:], p.handled)
+	last := p.draws[(p.drawn+frameWindow-1)%frameWindow]
+
+	style := lipgloss.NewStyle().Foreground(textColor)
+	if drawMax+updateMax > frameBudget {
+		style = style.Foreground(warningColor)
+	}
+	text := fmt.Sprintf("⏱  draw %s (avg %s, max %s)  •  events avg %s, max %s  •  %d dropped  •  budget %s",
+		last.Round(time.Microsecond), drawAvg.Round(time.Microsecond), drawMax.Round(time.Microsecond),
+		updateAvg.Round(time.Microsecond), updateMax.Round(time.Microsecond), p.dropped, frameBudget.Round(time.Microsecond))
+	return lipgloss.NewStyle().
+		Border(lipgloss.NormalBorder(), true, false, false, false).
+		BorderForeground(dimColor).
+		Render(style.Render(text))
+}
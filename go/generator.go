@@ -0,0 +1,188 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+	"math"
+	"math/rand"
+	"os"
+	"os/signal"
+	"strconv"
+	"strings"
+	"syscall"
+	"time"
+)
+
+// Waveforms the generator can produce, as a function of the phase in cycles
+var waveforms = map[string]func(phase float64) float64{
+	"sine": func(phase float64) float64 { return math.Sin(2 * math.Pi * phase) },
+	"square": func(phase float64) float64 {
+		if phase-math.Floor(phase) < 0.5 {
+			return 1
+		}
+		return -1
+	},
+	"ramp":  func(phase float64) float64 { return 2*(phase-math.Floor(phase)) - 1 },
+	"noise": func(float64) float64 { return rand.NormFloat64() },
+}
+
+// A synthetic signal on one topic: offset + amp * waveform(freq * t + phase)
+type signalSpec struct {
+	topic  string
+	wave   string
+	freq   float64 // Hz
+	amp    float64 // for noise, the standard deviation
+	offset float64
+	phase  float64 // in cycles
+}
+
+// Parse a signal expression of the form TOPIC=WAVE[:key=value,...], e.g.
+// /wave=sine:freq=0.5,amp=2,offset=1
+func parseSignal(expr string) (signalSpec, error) {
+	topic, rest, ok := strings.Cut(expr, "=")
+	if !ok || topic == "" {
+		return signalSpec{}, fmt.Errorf("invalid signal %q, expected TOPIC=WAVE[:key=value,...]", expr)
+	}
+	wave, params, _ := strings.Cut(rest, ":")
+	if _, ok := waveforms[wave]; !ok {
+		return signalSpec{}, fmt.Errorf("unknown waveform %q (sine, square, ramp, noise)", wave)
+	}
+
+	spec := signalSpec{topic: topic, wave: wave, freq: 1, amp: 1}
+	for _, param := range strings.Split(params, ",") {
+		if param == "" {
+			continue
+		}
+		key, value, _ := strings.Cut(param, "=")
+		v, err := strconv.ParseFloat(value, 64)
+		if err != nil || math.IsNaN(v) || math.IsInf(v, 0) {
+			return signalSpec{}, fmt.Errorf("invalid %s in %q", key, expr)
+		}
+		switch key {
+		case "freq":
+			spec.freq = v
+		case "amp":
+			spec.amp = v
+		case "offset":
+			spec.offset = v
+		case "phase":
+			spec.phase = v
+		default:
+			return signalSpec{}, fmt.Errorf("unknown parameter %q in %q (freq, amp, offset, phase)", key, expr)
+		}
+	}
+	return spec, nil
+}
+
+// Value of the signal t after the start
+func (s signalSpec) value(t time.Duration) float64 {
+	return s.offset + s.amp*waveforms[s.wave](s.freq*t.Seconds()+s.phase)
+}
+
+// Payload of one generated sample
+type signalSample struct {
+	T     float64 `json:"t"`
+	Value float64 `json:"value"`
+}
+
+// Run `cardinal gen`: publish synthetic signals until interrupted or the
+// duration is over, and return the exit code
+func runGen(opts Options) int {
+	if opts.IDL != "" {
+		return runGenIDL(opts)
+	}
+	settings, err := loadSettings(opts)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal gen:", err)
+		return exitUsage
+	}
+	var signals []signalSpec
+	for _, arg := range opts.Args {
+		spec, err := parseSignal(arg)
+		if err != nil {
+			fmt.Fprintln(os.Stderr, "cardinal gen:", err)
+			return exitUsage
+		}
+		signals = append(signals, spec)
+	}
+
+	if err := confirmCommand(opts, settings.Profile, "the profile name", settings.Profile.Name, os.Stdin); err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal gen:", err)
+		return exitFailure
+	}
+
+	transport, err := NewFastDDSTransport(settings.Profile.Domain, settings.QoS)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal gen:", err)
+		return exitFailure
+	}
+	transport = withPayloadCipher(transport, settings.Cipher)
+
+	// One publisher per topic; signals sharing a topic go out in one batch
+	publishers := map[string]DDSPublisher{}
+	for _, spec := range signals {
+		if _, ok := publishers[spec.topic]; ok {
+			continue
+		}
+		pub, err := transport.NewPublisher(spec.topic)
+		if err != nil {
+			fmt.Fprintln(os.Stderr, "cardinal gen:", err)
+			return exitFailure
+		}
+		defer pub.Cleanup()
+		publishers[spec.topic] = pub
+	}
+
+	ctx, cancel := signal.NotifyContext(context.Background(), os.Interrupt, syscall.SIGTERM)
+	defer cancel()
+	if opts.Duration > 0 {
+		ctx, cancel = context.WithTimeout(ctx, opts.Duration)
+		defer cancel()
+	}
+
+	fmt.Printf("〰️  Generating %d signals at %g Hz\n", len(signals), opts.Hz)
+	for _, topic := range sortedKeys(publishers) {
+		if qos, ok := activeQoS(publishers[topic]); ok {
+			fmt.Printf("   %s: %s\n", topic, qos.writerSummary())
+		}
+	}
+	ticker := time.NewTicker(time.Duration(float64(time.Second) / opts.Hz))
+	defer ticker.Stop()
+
+	// At high rates the ticker drops ticks; every tick due since the last
+	// one is generated and published in one batch per topic instead
+	start := time.Now()
+	ticks := 0
+	samples := 0
+	batches := map[string][]DDSMessage{}
+	for {
+		select {
+		case <-ctx.Done():
+			fmt.Printf("✅ Published %d samples\n", samples)
+			return exitOK
+		case now := <-ticker.C:
+			due := int(now.Sub(start).Seconds() * opts.Hz)
+			for ; ticks < due; ticks++ {
+				t := time.Duration(float64(ticks+1) / opts.Hz * float64(time.Second))
+				for _, spec := range signals {
+					content, err := json.Marshal(signalSample{T: t.Seconds(), Value: spec.value(t)})
+					if err != nil {
+						fmt.Fprintf(os.Stderr, "cardinal gen: %s: %v\n", spec.topic, err)
+						return exitFailure
+					}
+					msg := DDSMessage{Topic: spec.topic, Content: string(content), Timestamp: start.Add(t)}
+					batches[spec.topic] = append(batches[spec.topic], msg)
+				}
+			}
+			for topic, batch := range batches {
+				if err := publishBatch(publishers[topic], batch); err != nil {
+					fmt.Fprintln(os.Stderr, "cardinal gen:", err)
+					return exitFailure
+				}
+				samples += len(batch)
+				batches[topic] = batch[:0]
+			}
+		}
+	}
+}
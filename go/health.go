@@ -0,0 +1,91 @@
+package main
+
+import (
+	"encoding/json"
+	"log"
+	"net/http"
+	"time"
+)
+
+// Health of a single subscribed topic
+type topicHealth struct {
+	Topic          string   `json:"topic"`
+	Messages       int      `json:"messages"`
+	LastMessageAge *float64 `json:"last_message_age_seconds"`
+	Stale          bool     `json:"stale"`
+}
+
+// Body of the /healthz response
+type healthReport struct {
+	Status    string        `json:"status"`
+	Transport string        `json:"transport"`
+	Connected bool          `json:"connected"`
+	Uptime    float64       `json:"uptime_seconds"`
+	Topics    []topicHealth `json:"topics"`
+}
+
+// Serves /healthz for container liveness probes
+type healthServer struct {
+	session *Session
+	maxAge  time.Duration
+}
+
+// Build the current health report. A topic is stale when its last message
+// (or startup, if it never received one) is older than maxAge. Connected
+// means a DDS participant is up: the real transport, not the mock fallback,
+// with at least one reader, as every reader has a participant of its own.
+func (h *healthServer) report(now time.Time) healthReport {
+	topics := h.session.Topics()
+	report := healthReport{
+		Status:    "ok",
+		Transport: h.session.transport.Name(),
+		Uptime:    now.Sub(h.session.startTime).Seconds(),
+	}
+	report.Connected = report.Transport != "mock" && len(topics) > 0
+
+	seen := map[string]TopicStats{}
+	for _, stats := range h.session.stats.Topics() {
+		seen[stats.Topic] = stats
+	}
+
+	for _, topic := range topics {
+		th := topicHealth{Topic: topic}
+		last := h.session.startTime
+		if stats, ok := seen[topic]; ok {
+			th.Messages = stats.Total
+			last = stats.LastSeen
+			age := now.Sub(last).Seconds()
+			th.LastMessageAge = &age
+		}
+		if h.maxAge > 0 && now.Sub(last) > h.maxAge {
+			th.Stale = true
+			report.Status = "stale"
+		}
+		report.Topics = append(report.Topics, th)
+	}
+
+	return report
+}
+
+func (h *healthServer) ServeHTTP(w http.ResponseWriter, r *http.Request) {
+	report := h.report(time.Now())
+
+	w.Header().Set("Content-Type", "application/json")
+	if report.Status != "ok" {
+		w.WriteHeader(http.StatusServiceUnavailable)
+	}
+	json.NewEncoder(w).Encode(report)
+}
+
+// Serve the health endpoint on addr in the background
+func startHealthServer(addr string, h *healthServer) {
+	mux := http.NewServeMux()
+	mux.Handle("/healthz", h)
+
+	go func() {
+		log.Printf("Health check listening on http://%s/healthz", addr)
+		if err := http.ListenAndServe(addr, mux); err != nil {
+			log.Printf("Health server stopped: %v", err)
+		}
+	}()
+}
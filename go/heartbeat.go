@@ -0,0 +1,47 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"log"
+	"os"
+	"sync"
+	"time"
+)
+
+// Payload of Cardinal's own heartbeat topic, so that other monitoring can
+// tell the monitor is alive and what it is watching
+type heartbeat struct {
+	Time    time.Time `json:"time"`
+	Host    string    `json:"host"`
+	PID     int       `json:"pid"`
+	Profile string    `json:"profile"`
+	SessionStatus
+}
+
+// Publish the session status on the heartbeat topic every interval until
+// the context is done
+func publishHeartbeat(ctx context.Context, pub DDSPublisher, session *Session, profile string, interval time.Duration, wg *sync.WaitGroup) {
+	defer wg.Done()
+
+	host, _ := os.Hostname()
+	ticker := time.NewTicker(interval)
+	defer ticker.Stop()
+
+	for {
+		select {
+		case <-ctx.Done():
+			return
+		case now := <-ticker.C:
+			beat := heartbeat{Time: now, Host: host, PID: os.Getpid(), Profile: profile, SessionStatus: session.Status()}
+			content, err := json.Marshal(beat)
+			if err != nil {
+				log.Printf("Error encoding heartbeat: %v", err)
+				continue
+			}
+			if err := pub.Publish(DDSMessage{Content: string(content), Timestamp: now}); err != nil {
+				log.Printf("Error publishing heartbeat: %v", err)
+			}
+		}
+	}
+}
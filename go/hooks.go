@@ -0,0 +1,139 @@
+package main
+
+import (
+	"context"
+	"fmt"
+	"log"
+	"os"
+	"os/exec"
+	"strconv"
+	"strings"
+	"sync"
+	"time"
+)
+
+// Hooks run shell commands on events, so Cardinal can page through
+// existing scripts. They are configured in the [hooks] table, one command
+// per event:
+//
+//	[hooks]
+//	stale = "/usr/local/bin/page-oncall"
+//	recording_started = "logger -t cardinal recording to $CARDINAL_PATH"
+//
+// Commands run through sh in the background with the event in CARDINAL_*
+// environment variables; a command still running after hookTimeout is
+// killed.
+
+// Events that can have a hook, with what they mean
+var hookEvents = map[string]string{
+	"recording_started": "a recording started (CARDINAL_PATH)",
+	"recording_stopped": "a recording stopped (CARDINAL_PATH, CARDINAL_MESSAGES, CARDINAL_BYTES)",
+	"burst":             "a message burst ended (CARDINAL_TOPIC, CARDINAL_MESSAGES, CARDINAL_DURATION)",
+	"stale":             "a topic went silent for --health-max-age (CARDINAL_TOPIC, CARDINAL_LAST_MESSAGE)",
+	"recovered":         "a stale topic received messages again (CARDINAL_TOPIC)",
+	"liveliness_lost":   "a remote writer let its liveliness lease expire (CARDINAL_TOPIC, CARDINAL_WRITER)",
+	"writer_matched":    "a writer matched a subscription (CARDINAL_TOPIC, CARDINAL_WRITER)",
+	"writer_unmatched":  "a matched writer went away (CARDINAL_TOPIC, CARDINAL_WRITER)",
+	"latency_budget":    "a sample exceeded its topic's latency budget, at most once a minute per topic (CARDINAL_TOPIC, CARDINAL_LATENCY, CARDINAL_BUDGET)",
+	"incompatible_qos":  "a remote endpoint cannot match because of a QoS policy (CARDINAL_TOPIC, CARDINAL_POLICY, CARDINAL_ENDPOINT)",
+}
+
+// Longest a hook command may run
+const hookTimeout = 30 * time.Second
+
+// Commands by event. A nil Hooks runs nothing.
+type Hooks struct {
+	commands map[string]string
+	profile  string
+}
+
+// Read the [hooks] table, or nil when the config has none
+func (c Config) Hooks(profile string) (*Hooks, error) {
+	table, ok := c["hooks"]
+	if !ok {
+		return nil, nil
+	}
+	for event := range table {
+		if _, ok := hookEvents[event]; !ok {
+			return nil, fmt.Errorf("hooks: unknown event %q (%s)", event, strings.Join(sortedKeys(hookEvents), ", "))
+		}
+	}
+	return &Hooks{commands: table, profile: profile}, nil
+}
+
+// Whether an event has a hook
+func (h *Hooks) Has(event string) bool {
+	return h != nil && h.commands[event] != ""
+}
+
+// Run the hook of an event, if any, with the given CARDINAL_* variables
+func (h *Hooks) Fire(event string, vars map[string]string) {
+	if !h.Has(event) {
+		return
+	}
+	env := append(os.Environ(),
+		"CARDINAL_EVENT="+event,
+		"CARDINAL_TIME="+time.Now().Format(time.RFC3339),
+		"CARDINAL_PROFILE="+h.profile,
+	)
+	for name, value := range vars {
+		env = append(env, "CARDINAL_"+name+"="+value)
+	}
+
+	go runTask(context.Background(), "hook", func(context.Context) {
+		ctx, cancel := context.WithTimeout(context.Background(), hookTimeout)
+		defer cancel()
+		cmd := exec.CommandContext(ctx, "sh", "-c", h.commands[event])
+		cmd.Env = env
+		if out, err := cmd.CombinedOutput(); err != nil {
+			log.Printf("Hook for %s failed: %v: %s", event, err, strings.TrimSpace(string(out)))
+		}
+	})
+}
+
+// Run the hook of a session event, if any
+func (h *Hooks) OnEvent(ev Event) {
+	if event, vars := ev.hook(); event != "" {
+		h.Fire(event, vars)
+	}
+}
+
+// Raise stale and recovered alerts as topics go silent for longer than
+// maxAge and come back
+func startStaleAlerts(ctx context.Context, wg *sync.WaitGroup, session *Session, maxAge time.Duration) {
+	health := &healthServer{session: session, maxAge: maxAge}
+	stale := map[string]bool{}
+
+	wg.Add(1)
+	go runTask(ctx, "stale-hooks", func(ctx context.Context) {
+		defer wg.Done()
+		ticker := time.NewTicker(time.Second)
+		defer ticker.Stop()
+
+		for {
+			select {
+			case <-ctx.Done():
+				return
+			case now := <-ticker.C:
+				for _, topic := range health.report(now).Topics {
+					switch {
+					case topic.Stale && !stale[topic.Topic]:
+						last := "never"
+						if topic.LastMessageAge != nil {
+							last = now.Add(-time.Duration(*topic.LastMessageAge * float64(time.Second))).Format(time.RFC3339)
+						}
+						session.alert(AlertFired{
+							Kind:  "stale",
+							Topic: topic.Topic,
+							Text:  fmt.Sprintf("%s went silent, last message %s", topic.Topic, last),
+							Vars:  map[string]string{"LAST_MESSAGE": last, "MESSAGES": strconv.Itoa(topic.Messages)},
+						})
+					case !topic.Stale && stale[topic.Topic]:
+						session.alert(AlertFired{Kind: "recovered", Topic: topic.Topic, Text: topic.Topic + " receives messages again"})
+					}
+					stale[topic.Topic] = topic.Stale
+				}
+			}
+		}
+	})
+}
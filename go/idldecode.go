@@ -0,0 +1,134 @@
+package main
+
+import (
+	"bytes"
+	"encoding/json"
+	"fmt"
+	"math"
+	"os"
+	"strconv"
+	"strings"
+
+	"cardinal/core"
+)
+
+// Raw CDR payloads of types nobody wrote a plugin for are decoded from
+// their IDL at runtime: a [decoders] value of the form FILE.idl:TYPE reads
+// the topic's payloads as TYPE from the IDL file and shows them as JSON with
+// the IDL's field names, in declaration order.
+
+// Decodes the CDR payloads of one IDL struct into JSON
+type idlDecoder struct {
+	decl *idlDecl
+}
+
+// Sizes of the IDL primitives in CDR, by Go type
+var idlSizes = map[string]int{
+	"bool": 1, "byte": 1, "int8": 1, "uint8": 1,
+	"int16": 2, "uint16": 2, "int32": 4, "uint32": 4, "float32": 4,
+	"int64": 8, "uint64": 8, "float64": 8,
+}
+
+// Load the struct named name, e.g. geometry::Pose, from an IDL file
+func loadIDLDecoder(file, name string) (*idlDecoder, error) {
+	src, err := os.ReadFile(file)
+	if err != nil {
+		return nil, err
+	}
+	decls, err := parseIDL(string(src))
+	if err != nil {
+		return nil, fmt.Errorf("%s: %w", file, err)
+	}
+	name = strings.TrimPrefix(name, "::")
+	for _, decl := range decls {
+		if decl.idlName() == name && decl.kind == "struct" {
+			return &idlDecoder{decl: decl}, nil
+		}
+	}
+	return nil, fmt.Errorf("%s: no struct %s", file, name)
+}
+
+func (d *idlDecoder) Decode(topic, payload string) (string, bool) {
+	r, err := core.NewCDRReader([]byte(payload))
+	if err != nil {
+		return "", false
+	}
+	var out bytes.Buffer
+	readIDLValue(r, &out, idlType{decl: d.decl}, nil)
+	if r.Err != nil {
+		return "", false
+	}
+	return out.String(), true
+}
+
+// Read one value of an IDL type and write it as JSON
+func readIDLValue(r *core.CDRReader, out *bytes.Buffer, t idlType, dims []int) {
+	if len(dims) > 0 {
+		out.WriteByte('[')
+		for i := range dims[0] {
+			if i > 0 {
+				out.WriteByte(',')
+			}
+			readIDLValue(r, out, t, dims[1:])
+		}
+		out.WriteByte(']')
+		return
+	}
+
+	switch {
+	case t.decl != nil && t.decl.kind == "typedef":
+		readIDLValue(r, out, t.decl.alias, t.decl.dims)
+	case t.decl != nil && t.decl.kind == "enum":
+		// Enumerators by name, unknown values as numbers
+		n := r.Uint(4)
+		if n < uint64(len(t.decl.enumerators)) {
+			out.WriteString(strconv.Quote(t.decl.enumerators[n]))
+		} else {
+			out.WriteString(strconv.FormatUint(n, 10))
+		}
+	case t.decl != nil:
+		out.WriteByte('{')
+		for i, field := range t.decl.fields {
+			if i > 0 {
+				out.WriteByte(',')
+			}
+			out.WriteString(strconv.Quote(field.name) + ":")
+			readIDLValue(r, out, field.typ, field.dims)
+		}
+		out.WriteByte('}')
+	case t.name == "sequence":
+		n := r.Count()
+		if t.bound > 0 && n > t.bound && r.Err == nil {
+			r.Err = fmt.Errorf("cdr: sequence of %d elements, bound %d", n, t.bound)
+		}
+		out.WriteByte('[')
+		for i := 0; i < n && r.Err == nil; i++ {
+			if i > 0 {
+				out.WriteByte(',')
+			}
+			readIDLValue(r, out, *t.elem, nil)
+		}
+		out.WriteByte(']')
+	case t.name == "string":
+		text, _ := json.Marshal(r.String())
+		out.Write(text)
+	case t.name == "bool":
+		out.WriteString(strconv.FormatBool(r.Uint(1) != 0))
+	case t.name == "float32" || t.name == "float64":
+		var v float64
+		if t.name == "float32" {
+			v = float64(math.Float32frombits(uint32(r.Uint(4))))
+		} else {
+			v = math.Float64frombits(r.Uint(8))
+		}
+		if math.IsNaN(v) || math.IsInf(v, 0) {
+			out.WriteString("null") // JSON has no NaN or infinity
+		} else {
+			out.WriteString(strconv.FormatFloat(v, 'g', -1, 64))
+		}
+	case strings.HasPrefix(t.name, "int"):
+		out.WriteString(strconv.FormatInt(r.Int(idlSizes[t.name]), 10))
+	default:
+		out.WriteString(strconv.FormatUint(r.Uint(idlSizes[t.name]), 10))
+	}
+}
@@ -0,0 +1,887 @@
+package main
+
+import (
+	"bytes"
+	"fmt"
+	"go/format"
+	"os"
+	"strconv"
+	"strings"
+)
+
+// One token of an IDL file, with the line it starts on
+type idlToken struct {
+	text string
+	line int
+}
+
+// Split IDL source into tokens, dropping comments and preprocessor lines
+func idlTokens(src string) ([]idlToken, error) {
+	var toks []idlToken
+	line := 1
+	for i := 0; i < len(src); {
+		c := src[i]
+		switch {
+		case c == '\n':
+			line++
+			i++
+		case c == ' ' || c == '\t' || c == '\r':
+			i++
+		case c == '#' || strings.HasPrefix(src[i:], "//"):
+			for i < len(src) && src[i] != '\n' {
+				i++
+			}
+		case strings.HasPrefix(src[i:], "/*"):
+			end := strings.Index(src[i+2:], "*/")
+			if end < 0 {
+				return nil, fmt.Errorf("line %d: unterminated comment", line)
+			}
+			line += strings.Count(src[i:i+2+end], "\n")
+			i += end + 4
+		case isIdentByte(c):
+			start := i
+			for i < len(src) && (isIdentByte(src[i]) || src[i] == '.') {
+				i++
+			}
+			toks = append(toks, idlToken{src[start:i], line})
+		case c == '"':
+			start := i
+			for i++; i < len(src) && src[i] != '"'; i++ {
+				if src[i] == '\\' {
+					i++
+				}
+			}
+			if i >= len(src) {
+				return nil, fmt.Errorf("line %d: unterminated string", line)
+			}
+			i++
+			toks = append(toks, idlToken{src[start:i], line})
+		case strings.HasPrefix(src[i:], "::"):
+			toks = append(toks, idlToken{"::", line})
+			i += 2
+		case strings.IndexByte("{}()<>[],;:=@-", c) >= 0:
+			toks = append(toks, idlToken{string(c), line})
+			i++
+		default:
+			return nil, fmt.Errorf("line %d: unexpected character %q", line, c)
+		}
+	}
+	return toks, nil
+}
+
+func isIdentByte(c byte) bool {
+	return c == '_' || c >= 'a' && c <= 'z' || c >= 'A' && c <= 'Z' || c >= '0' && c <= '9'
+}
+
+func isIdent(s string) bool {
+	if s == "" || s[0] >= '0' && s[0] <= '9' {
+		return false
+	}
+	for i := range len(s) {
+		if !isIdentByte(s[i]) {
+			return false
+		}
+	}
+	return true
+}
+
+// Reference to an IDL type: a primitive, a string, a sequence, or something
+// declared in the file
+type idlType struct {
+	name  string   // Go type of primitives, "string" or "sequence"; empty for declared types
+	elem  *idlType // element type of sequences
+	bound int      // maximum length of bounded strings and sequences, 0 if unbounded
+	decl  *idlDecl
+}
+
+// Member of an IDL struct
+type idlField struct {
+	name string
+	typ  idlType
+	dims []int // array dimensions, outermost first
+	key  bool
+}
+
+// A struct, enum, typedef or constant declared in an IDL file
+type idlDecl struct {
+	kind        string   // "struct", "enum", "typedef" or "const"
+	scoped      []string // modules and name
+	defined     bool     // false for structs only forward-declared so far
+	fields      []idlField
+	enumerators []string
+	alias       idlType // aliased type of typedefs, and type of constants
+	dims        []int   // array dimensions of typedefs
+	value       string  // Go literal of constants
+}
+
+// Name of the declaration in IDL, e.g. geometry::Point
+func (d *idlDecl) idlName() string {
+	return strings.Join(d.scoped, "::")
+}
+
+// Name of the declaration in Go, its scoped name in camel case, e.g.
+// GeometryPoint
+func (d *idlDecl) goName() string {
+	var name strings.Builder
+	for _, part := range d.scoped {
+		name.WriteString(exportName(part))
+	}
+	return name.String()
+}
+
+// Exported Go name of an IDL identifier, e.g. Velocity for velocity and
+// LinearVelocity for linear_velocity
+func exportName(s string) string {
+	var name strings.Builder
+	for _, word := range strings.Split(s, "_") {
+		if word != "" {
+			name.WriteString(strings.ToUpper(word[:1]) + word[1:])
+		}
+	}
+	return name.String()
+}
+
+// Recursive descent parser for the part of OMG IDL that describes data
+// types. Like core.Fields, it keeps the first error and does nothing once it
+// has one, so callers check it once at the end.
+type idlParser struct {
+	toks  []idlToken
+	pos   int
+	scope []string
+	decls map[string]*idlDecl // by scoped name
+	order []*idlDecl
+	ints  map[string]int // integer constants by scoped name, for bounds
+	err   error
+}
+
+func (p *idlParser) fail(format string, args ...any) {
+	if p.err != nil {
+		return
+	}
+	line := 0
+	if len(p.toks) > 0 {
+		line = p.toks[min(p.pos, len(p.toks)-1)].line
+	}
+	p.err = fmt.Errorf("line %d: %s", line, fmt.Sprintf(format, args...))
+}
+
+// Next token without consuming it, empty at the end or after an error
+func (p *idlParser) peek() string {
+	if p.err != nil || p.pos >= len(p.toks) {
+		return ""
+	}
+	return p.toks[p.pos].text
+}
+
+func (p *idlParser) next() string {
+	t := p.peek()
+	if t == "" {
+		p.fail("unexpected end of file")
+		return ""
+	}
+	p.pos++
+	return t
+}
+
+func (p *idlParser) expect(want string) {
+	if got := p.next(); got != want {
+		p.fail("expected %q, found %q", want, got)
+	}
+}
+
+func (p *idlParser) ident() string {
+	t := p.next()
+	if !isIdent(t) {
+		p.fail("expected a name, found %q", t)
+	}
+	return t
+}
+
+// Parse definitions up to the end token, "}" in modules and "" for the file
+func (p *idlParser) definitions(end string) {
+	for p.err == nil && p.peek() != end {
+		p.definition()
+	}
+}
+
+func (p *idlParser) definition() {
+	annotations := p.annotations()
+	switch kw := p.next(); kw {
+	case "module":
+		name := p.ident()
+		p.expect("{")
+		p.scope = append(p.scope, name)
+		p.definitions("}")
+		p.scope = p.scope[:len(p.scope)-1]
+		p.expect("}")
+	case "struct":
+		p.structDecl(annotations)
+	case "enum":
+		p.enumDecl()
+	case "typedef":
+		p.typedef()
+	case "const":
+		p.constDecl()
+	case "union", "bitset", "bitmask", "interface", "valuetype", "exception":
+		p.fail("%s declarations are not supported", kw)
+	default:
+		p.fail("unexpected %q", kw)
+	}
+	p.expect(";")
+}
+
+// Parse annotations like @key or @extensibility(FINAL), returning them in
+// lower case, e.g. key and extensibility(final)
+func (p *idlParser) annotations() []string {
+	var names []string
+	for p.err == nil && p.peek() == "@" {
+		p.next()
+		name := p.ident()
+		for depth := 0; p.err == nil && (depth > 0 || p.peek() == "("); {
+			t := p.next()
+			switch t {
+			case "(":
+				depth++
+			case ")":
+				depth--
+			}
+			name += t
+		}
+		names = append(names, strings.ToLower(name))
+	}
+	return names
+}
+
+func (p *idlParser) structDecl(annotations []string) {
+	for _, a := range annotations {
+		if a == "mutable" || a == "extensibility(mutable)" {
+			p.fail("only final and appendable structs are supported")
+		}
+	}
+	name := p.ident()
+	decl := p.decls[strings.Join(p.scoped(name), "::")]
+	if decl == nil || decl.kind != "struct" || decl.defined {
+		decl = &idlDecl{kind: "struct", scoped: p.scoped(name)}
+		p.declare(decl)
+	}
+	if p.peek() == ";" {
+		return // forward declaration
+	}
+	decl.defined = true
+
+	if p.peek() == ":" {
+		p.next()
+		base := p.resolve(p.scopedName(p.next()))
+		if base != nil && (base.kind != "struct" || !base.defined) {
+			p.fail("%s is not a defined struct", base.idlName())
+		}
+		if base != nil {
+			decl.fields = append(decl.fields, base.fields...)
+		}
+	}
+	p.expect("{")
+	for p.err == nil && p.peek() != "}" {
+		annotations := p.annotations()
+		typ := p.typeSpec()
+		for p.err == nil {
+			field := idlField{name: p.ident(), typ: typ, dims: p.dims()}
+			for _, a := range annotations {
+				field.key = field.key || a == "key"
+			}
+			decl.fields = append(decl.fields, field)
+			if p.peek() != "," {
+				break
+			}
+			p.next()
+		}
+		p.expect(";")
+	}
+	p.expect("}")
+}
+
+func (p *idlParser) enumDecl() {
+	decl := &idlDecl{kind: "enum", scoped: p.scoped(p.ident()), defined: true}
+	p.expect("{")
+	for p.err == nil {
+		for _, a := range p.annotations() {
+			if a == "value" {
+				p.fail("enumerators with explicit values are not supported")
+			}
+		}
+		decl.enumerators = append(decl.enumerators, p.ident())
+		if p.peek() != "," {
+			break
+		}
+		p.next()
+	}
+	p.expect("}")
+	p.declare(decl)
+}
+
+func (p *idlParser) typedef() {
+	typ := p.typeSpec()
+	for p.err == nil {
+		name := p.ident()
+		p.declare(&idlDecl{kind: "typedef", scoped: p.scoped(name), defined: true, alias: typ, dims: p.dims()})
+		if p.peek() != "," {
+			break
+		}
+		p.next()
+	}
+}
+
+// Parse a constant; the value must be a literal or another constant
+func (p *idlParser) constDecl() {
+	typ := p.typeSpec()
+	decl := &idlDecl{kind: "const", scoped: p.scoped(p.ident()), defined: true, alias: typ}
+	p.expect("=")
+	sign := ""
+	if p.peek() == "-" {
+		sign = p.next()
+	}
+	switch t := p.next(); {
+	case t == "TRUE" || t == "FALSE":
+		decl.value = strings.ToLower(t)
+	case strings.HasPrefix(t, `"`) || t != "" && t[0] >= '0' && t[0] <= '9':
+		decl.value = sign + t
+	case isIdent(t) || t == "::":
+		ref := p.resolve(p.scopedName(t))
+		if ref != nil && ref.kind != "const" {
+			p.fail("%s is not a constant", ref.idlName())
+		}
+		if ref != nil {
+			decl.value = sign + ref.goName()
+			if n, ok := p.ints[ref.idlName()]; ok && sign == "" {
+				p.ints[decl.idlName()] = n
+			} else if ok {
+				p.ints[decl.idlName()] = -n
+			}
+		}
+	default:
+		p.fail("unsupported constant expression %q", t)
+	}
+	if n, err := strconv.ParseInt(decl.value, 0, 64); err == nil {
+		p.ints[decl.idlName()] = int(n)
+	}
+	p.declare(decl)
+}
+
+// Parse a type specification
+func (p *idlParser) typeSpec() idlType {
+	switch t := p.next(); t {
+	case "boolean":
+		return idlType{name: "bool"}
+	case "char", "octet":
+		return idlType{name: "byte"}
+	case "int8", "uint8", "int16", "uint16", "int32", "uint32", "int64", "uint64":
+		return idlType{name: t}
+	case "short":
+		return idlType{name: "int16"}
+	case "float":
+		return idlType{name: "float32"}
+	case "double":
+		return idlType{name: "float64"}
+	case "long":
+		if p.peek() == "double" {
+			p.fail("long double is not supported")
+		}
+		if p.peek() == "long" {
+			p.next()
+			return idlType{name: "int64"}
+		}
+		return idlType{name: "int32"}
+	case "unsigned":
+		switch p.next() {
+		case "short":
+			return idlType{name: "uint16"}
+		case "long":
+			if p.peek() == "long" {
+				p.next()
+				return idlType{name: "uint64"}
+			}
+			return idlType{name: "uint32"}
+		}
+		p.fail("expected short or long after unsigned")
+	case "string":
+		typ := idlType{name: "string"}
+		if p.peek() == "<" {
+			p.next()
+			typ.bound = p.bound()
+			p.expect(">")
+		}
+		return typ
+	case "sequence":
+		p.expect("<")
+		elem := p.typeSpec()
+		typ := idlType{name: "sequence", elem: &elem}
+		if p.peek() == "," {
+			p.next()
+			typ.bound = p.bound()
+		}
+		p.expect(">")
+		return typ
+	case "wchar", "wstring", "fixed", "any", "map":
+		p.fail("%s is not supported", t)
+	default:
+		if decl := p.resolve(p.scopedName(t)); decl != nil {
+			if decl.kind == "const" {
+				p.fail("%s is a constant, not a type", decl.idlName())
+			}
+			return idlType{decl: decl}
+		}
+	}
+	return idlType{}
+}
+
+// Parse array dimensions after a declarator, e.g. [3][4]
+func (p *idlParser) dims() []int {
+	var dims []int
+	for p.err == nil && p.peek() == "[" {
+		p.next()
+		dims = append(dims, p.bound())
+		p.expect("]")
+	}
+	return dims
+}
+
+// Parse a positive integer, given literally or as a constant
+func (p *idlParser) bound() int {
+	t := p.next()
+	n, err := strconv.ParseInt(t, 0, 64)
+	if err != nil && (isIdent(t) || t == "::") {
+		name := p.scopedName(t)
+		if decl := p.resolve(name); decl != nil {
+			v, ok := p.ints[decl.idlName()]
+			n, err = int64(v), nil
+			if !ok {
+				err = fmt.Errorf("%s is not an integer constant", name)
+			}
+		}
+	}
+	if err == nil && n <= 0 {
+		err = fmt.Errorf("bound %s must be positive", t)
+	}
+	if err != nil {
+		p.fail("invalid bound %q", t)
+		return 0
+	}
+	return int(n)
+}
+
+// Parse a scoped name starting with the token first, e.g. geometry::Point or
+// ::geometry::Point
+func (p *idlParser) scopedName(first string) string {
+	name := first
+	switch {
+	case first == "::":
+		name += p.ident()
+	case !isIdent(first):
+		p.fail("unexpected %q", first)
+	}
+	for p.err == nil && p.peek() == "::" {
+		p.next()
+		name += "::" + p.ident()
+	}
+	return name
+}
+
+// Modules of the current scope followed by name
+func (p *idlParser) scoped(name string) []string {
+	return append(p.scope[:len(p.scope):len(p.scope)], name)
+}
+
+// Look a name up from the current scope outwards
+func (p *idlParser) resolve(name string) *idlDecl {
+	if p.err != nil {
+		return nil
+	}
+	if absolute, ok := strings.CutPrefix(name, "::"); ok {
+		if decl := p.decls[absolute]; decl != nil {
+			return decl
+		}
+	} else {
+		for i := len(p.scope); i >= 0; i-- {
+			if decl := p.decls[strings.Join(append(p.scope[:i:i], name), "::")]; decl != nil {
+				return decl
+			}
+		}
+	}
+	p.fail("%s is not declared", name)
+	return nil
+}
+
+func (p *idlParser) declare(decl *idlDecl) {
+	if p.err != nil {
+		return
+	}
+	name := decl.idlName()
+	if _, ok := p.decls[name]; ok {
+		p.fail("%s is declared twice", name)
+		return
+	}
+	p.decls[name] = decl
+	p.order = append(p.order, decl)
+}
+
+// Go code generator for parsed IDL declarations
+type idlGen struct {
+	buf   bytes.Buffer
+	depth int // of nested loops, naming their index variables
+}
+
+func (g *idlGen) printf(format string, args ...any) {
+	fmt.Fprintf(&g.buf, format, args...)
+}
+
+// Go type of an IDL type with array dimensions
+func goType(t idlType, dims []int) string {
+	var prefix strings.Builder
+	for _, n := range dims {
+		fmt.Fprintf(&prefix, "[%d]", n)
+	}
+	switch {
+	case t.decl != nil:
+		return prefix.String() + t.decl.goName()
+	case t.name == "sequence":
+		return prefix.String() + "[]" + goType(*t.elem, nil)
+	}
+	return prefix.String() + t.name
+}
+
+// Whether a type is a sequence of bytes, encoded in one piece
+func isByteSequence(t idlType) bool {
+	return t.name == "sequence" && t.elem.decl == nil && (t.elem.name == "byte" || t.elem.name == "uint8")
+}
+
+// Names of the encoder and decoder methods of each primitive, and the
+// unsigned type they work on
+var cdrPrimitives = map[string]struct{ method, unsigned string }{
+	"bool":    {"boolean", "bool"},
+	"byte":    {"u8", "byte"},
+	"int8":    {"u8", "uint8"},
+	"uint8":   {"u8", "uint8"},
+	"int16":   {"u16", "uint16"},
+	"uint16":  {"u16", "uint16"},
+	"int32":   {"u32", "uint32"},
+	"uint32":  {"u32", "uint32"},
+	"int64":   {"u64", "uint64"},
+	"uint64":  {"u64", "uint64"},
+	"float32": {"f32", "float32"},
+	"float64": {"f64", "float64"},
+}
+
+// Emit the statements encoding expr
+func (g *idlGen) encode(t idlType, dims []int, expr string) {
+	if len(dims) > 0 {
+		g.loop(expr, func(elem string) { g.encode(t, dims[1:], elem) })
+		return
+	}
+	switch {
+	case t.decl != nil && t.decl.kind == "typedef":
+		g.encode(t.decl.alias, t.decl.dims, expr)
+	case t.decl != nil && t.decl.kind == "enum":
+		g.printf("e.u32(uint32(%s))\n", expr)
+	case t.decl != nil:
+		g.printf("%s.encodeCDR(e)\n", expr)
+	case isByteSequence(t):
+		g.printf("e.bytes(%s)\n", expr)
+	case t.name == "sequence":
+		g.printf("e.u32(uint32(len(%s)))\n", expr)
+		g.loop(expr, func(elem string) { g.encode(*t.elem, nil, elem) })
+	case t.name == "string":
+		g.printf("e.str(%s)\n", expr)
+	default:
+		p := cdrPrimitives[t.name]
+		if p.unsigned != t.name {
+			expr = p.unsigned + "(" + expr + ")"
+		}
+		g.printf("e.%s(%s)\n", p.method, expr)
+	}
+}
+
+// Emit the statements decoding into expr
+func (g *idlGen) decode(t idlType, dims []int, expr string) {
+	if len(dims) > 0 {
+		g.loop(expr, func(elem string) { g.decode(t, dims[1:], elem) })
+		return
+	}
+	switch {
+	case t.decl != nil && t.decl.kind == "typedef":
+		g.decode(t.decl.alias, t.decl.dims, expr)
+	case t.decl != nil && t.decl.kind == "enum":
+		g.printf("%s = %s(d.u32())\n", expr, t.decl.goName())
+	case t.decl != nil:
+		g.printf("%s.decodeCDR(d)\n", expr)
+	case isByteSequence(t):
+		g.printf("%s = d.bytes(%d)\n", expr, t.bound)
+	case t.name == "sequence":
+		g.printf("%s = make(%s, d.count(%d))\n", expr, goType(t, nil), t.bound)
+		g.loop(expr, func(elem string) { g.decode(*t.elem, nil, elem) })
+	case t.name == "string":
+		g.printf("%s = d.str(%d)\n", expr, t.bound)
+	default:
+		p := cdrPrimitives[t.name]
+		if p.unsigned != t.name {
+			g.printf("%s = %s(d.%s())\n", expr, t.name, p.method)
+		} else {
+			g.printf("%s = d.%s()\n", expr, p.method)
+		}
+	}
+}
+
+// Emit a loop over the elements of an array or slice
+func (g *idlGen) loop(expr string, body func(elem string)) {
+	i := fmt.Sprintf("i%d", g.depth)
+	g.depth++
+	g.printf("for %s := range %s {\n", i, expr)
+	body(expr + "[" + i + "]")
+	g.printf("}\n")
+	g.depth--
+}
+
+func (g *idlGen) declaration(decl *idlDecl) {
+	name := decl.goName()
+	switch decl.kind {
+	case "const":
+		g.printf("// %s\nconst %s %s = %s\n\n", decl.idlName(), name, goType(decl.alias, nil), decl.value)
+	case "typedef":
+		g.printf("// %s\ntype %s = %s\n\n", decl.idlName(), name, goType(decl.alias, decl.dims))
+	case "enum":
+		g.printf("// %s\ntype %s uint32\n\nconst (\n", decl.idlName(), name)
+		for i, enumerator := range decl.enumerators {
+			if i == 0 {
+				g.printf("%s%s %s = iota\n", name, exportName(enumerator), name)
+			} else {
+				g.printf("%s%s\n", name, exportName(enumerator))
+			}
+		}
+		g.printf(")\n\n")
+	case "struct":
+		g.printf("// %s\ntype %s struct {\n", decl.idlName(), name)
+		for _, f := range decl.fields {
+			g.printf("%s %s", exportName(f.name), goType(f.typ, f.dims))
+			if f.key {
+				g.printf(" // key")
+			}
+			g.printf("\n")
+		}
+		g.printf("}\n\n")
+		g.printf("// TypeName returns the IDL name of the type, as registered with DDS\n")
+		g.printf("func (*%s) TypeName() string { return %q }\n\n", name, decl.idlName())
+		g.printf("// MarshalCDR encodes the value as a little-endian CDR payload\n")
+		g.printf("func (v *%s) MarshalCDR() []byte {\ne := &cdrEncoder{buf: []byte{0, 1, 0, 0}}\nv.encodeCDR(e)\nreturn e.buf\n}\n\n", name)
+		g.printf("// UnmarshalCDR decodes a CDR payload of either byte order into the value\n")
+		g.printf("func (v *%s) UnmarshalCDR(data []byte) error {\nd, err := newCDRDecoder(data)\nif err != nil {\nreturn err\n}\nv.decodeCDR(d)\nreturn d.err\n}\n\n", name)
+		g.printf("func (v *%s) encodeCDR(e *cdrEncoder) {\n", name)
+		for _, f := range decl.fields {
+			g.encode(f.typ, f.dims, "v."+exportName(f.name))
+		}
+		g.printf("}\n\nfunc (v *%s) decodeCDR(d *cdrDecoder) {\n", name)
+		for _, f := range decl.fields {
+			g.decode(f.typ, f.dims, "v."+exportName(f.name))
+		}
+		g.printf("}\n\n")
+	}
+}
+
+// Plain CDR (XCDR1) encoder and decoder shared by the generated types.
+// Primitives are aligned to their size, counted from the end of the 4-byte
+// encapsulation header.
+const cdrRuntime = `
+type cdrEncoder struct{ buf []byte }
+
+func (e *cdrEncoder) align(n int) {
+	for (len(e.buf)-4)%n != 0 {
+		e.buf = append(e.buf, 0)
+	}
+}
+
+func (e *cdrEncoder) boolean(v bool) {
+	if v {
+		e.u8(1)
+	} else {
+		e.u8(0)
+	}
+}
+
+func (e *cdrEncoder) u8(v uint8)    { e.buf = append(e.buf, v) }
+func (e *cdrEncoder) u16(v uint16)  { e.align(2); e.buf = binary.LittleEndian.AppendUint16(e.buf, v) }
+func (e *cdrEncoder) u32(v uint32)  { e.align(4); e.buf = binary.LittleEndian.AppendUint32(e.buf, v) }
+func (e *cdrEncoder) u64(v uint64)  { e.align(8); e.buf = binary.LittleEndian.AppendUint64(e.buf, v) }
+func (e *cdrEncoder) f32(v float32) { e.u32(math.Float32bits(v)) }
+func (e *cdrEncoder) f64(v float64) { e.u64(math.Float64bits(v)) }
+
+func (e *cdrEncoder) bytes(v []byte) {
+	e.u32(uint32(len(v)))
+	e.buf = append(e.buf, v...)
+}
+
+func (e *cdrEncoder) str(v string) {
+	e.u32(uint32(len(v) + 1))
+	e.buf = append(append(e.buf, v...), 0)
+}
+
+var (
+	errCDRShort = errors.New("cdr: payload too short")
+	errCDRBound = errors.New("cdr: string or sequence longer than its bound")
+)
+
+type cdrDecoder struct {
+	data  []byte
+	pos   int
+	order binary.ByteOrder
+	err   error
+}
+
+func newCDRDecoder(data []byte) (*cdrDecoder, error) {
+	if len(data) < 4 || data[0] != 0 || data[1] > 1 {
+		return nil, errors.New("cdr: not a plain CDR payload")
+	}
+	d := &cdrDecoder{data: data, pos: 4, order: binary.BigEndian}
+	if data[1] == 1 {
+		d.order = binary.LittleEndian
+	}
+	return d, nil
+}
+
+func (d *cdrDecoder) take(n, align int) []byte {
+	if d.err != nil {
+		return nil
+	}
+	if pad := (d.pos - 4) % align; pad != 0 {
+		d.pos += align - pad
+	}
+	if n > len(d.data)-d.pos {
+		d.err = errCDRShort
+		return nil
+	}
+	d.pos += n
+	return d.data[d.pos-n : d.pos]
+}
+
+func (d *cdrDecoder) u8() uint8 {
+	if b := d.take(1, 1); b != nil {
+		return b[0]
+	}
+	return 0
+}
+
+func (d *cdrDecoder) u16() uint16 {
+	if b := d.take(2, 2); b != nil {
+		return d.order.Uint16(b)
+	}
+	return 0
+}
+
+func (d *cdrDecoder) u32() uint32 {
+	if b := d.take(4, 4); b != nil {
+		return d.order.Uint32(b)
+	}
+	return 0
+}
+
+func (d *cdrDecoder) u64() uint64 {
+	if b := d.take(8, 8); b != nil {
+		return d.order.Uint64(b)
+	}
+	return 0
+}
+
+func (d *cdrDecoder) boolean() bool { return d.u8() != 0 }
+func (d *cdrDecoder) f32() float32  { return math.Float32frombits(d.u32()) }
+func (d *cdrDecoder) f64() float64  { return math.Float64frombits(d.u64()) }
+
+// Length of a sequence, checked against its bound and the bytes left
+func (d *cdrDecoder) count(bound int) int {
+	n := d.u32()
+	switch {
+	case d.err != nil:
+		return 0
+	case bound > 0 && uint64(n) > uint64(bound):
+		d.err = errCDRBound
+		return 0
+	case uint64(n) > uint64(len(d.data)-d.pos):
+		d.err = errCDRShort
+		return 0
+	}
+	return int(n)
+}
+
+func (d *cdrDecoder) bytes(bound int) []byte {
+	return append([]byte(nil), d.take(d.count(bound), 1)...)
+}
+
+func (d *cdrDecoder) str(bound int) string {
+	b := d.take(d.count(0), 1) // with its terminating NUL
+	if len(b) == 0 {
+		return ""
+	}
+	if bound > 0 && len(b)-1 > bound {
+		d.err = errCDRBound
+	}
+	return string(b[:len(b)-1])
+}
+`
+
+// Parse IDL source, returning its declarations in order
+func parseIDL(src string) ([]*idlDecl, error) {
+	toks, err := idlTokens(src)
+	if err != nil {
+		return nil, err
+	}
+	p := &idlParser{toks: toks, decls: map[string]*idlDecl{}, ints: map[string]int{}}
+	p.definitions("")
+	if p.err != nil {
+		return nil, p.err
+	}
+	for _, decl := range p.order {
+		if !decl.defined {
+			return nil, fmt.Errorf("struct %s is declared but never defined", decl.idlName())
+		}
+	}
+	return p.order, nil
+}
+
+// Generate a Go file in package pkg with a type for every declaration in the
+// IDL source, and CDR encoding methods for the structs
+func generateIDL(src, source, pkg string) ([]byte, error) {
+	decls, err := parseIDL(src)
+	if err != nil {
+		return nil, err
+	}
+
+	g := &idlGen{}
+	g.printf("// Code generated by cardinal gen --idl %s; DO NOT EDIT.\n\n", source)
+	g.printf("package %s\n\nimport (\n\"encoding/binary\"\n\"errors\"\n\"math\"\n)\n\n", pkg)
+	for _, decl := range decls {
+		g.declaration(decl)
+	}
+	g.buf.WriteString(cdrRuntime)
+	return format.Source(g.buf.Bytes())
+}
+
+// Run `cardinal gen --idl`: write the Go types of an IDL file, and return
+// the exit code
+func runGenIDL(opts Options) int {
+	src, err := os.ReadFile(opts.IDL)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal gen:", err)
+		return exitUsage
+	}
+	code, err := generateIDL(string(src), opts.IDL, opts.Package)
+	if err != nil {
+		fmt.Fprintf(os.Stderr, "cardinal gen: %s: %v\n", opts.IDL, err)
+		return exitUsage
+	}
+
+	if opts.Output == "" {
+		_, err = os.Stdout.Write(code)
+	} else {
+		err = os.WriteFile(opts.Output, code, 0o644)
+	}
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal gen:", err)
+		return exitFailure
+	}
+	return exitOK
+}
@@ -0,0 +1,114 @@
+package main
+
+import (
+	"fmt"
+	"regexp"
+	"sort"
+	"strings"
+	"sync"
+)
+
+// Traffic from known-noisy tools, other Cardinal instances included, can be
+// ignored by GUID: a whole participant by its GUID prefix, or a single
+// DataWriter by its full GUID. Ignored samples are dropped on ingest, so
+// they reach no view, statistic, recording or sink. The [ignore] table names
+// the entries, and `cardinal ctl ignore` changes them at runtime:
+//
+//	[ignore]
+//	rviz = "010f4a1bd3c2000001000000"                 # participant
+//	bridge = "010f4a1bd3c2000001000000.000003c2"      # one writer
+
+// GUIDs as formatGUID prints them: a 12 byte prefix, optionally followed by
+// the 4 byte entity id
+var guidPattern = regexp.MustCompile(`^[0-9a-f]{24}(\.[0-9a-f]{8})?$`)
+
+// An ignored participant or writer
+type IgnoredStatus struct {
+	Name     string `json:"name"`
+	GUID     string `json:"guid"`
+	Messages int    `json:"messages"` // samples dropped so far
+}
+
+// Named GUIDs whose samples are dropped. Safe for concurrent use; a nil
+// IgnoreList ignores nothing.
+type IgnoreList struct {
+	mu      sync.Mutex
+	entries map[string]*IgnoredStatus // by name
+}
+
+func NewIgnoreList() *IgnoreList {
+	return &IgnoreList{entries: map[string]*IgnoredStatus{}}
+}
+
+// Read the [ignore] table
+func (c Config) IgnoreList() (*IgnoreList, error) {
+	list := NewIgnoreList()
+	for name, guid := range c["ignore"] {
+		if err := list.Add(name, guid); err != nil {
+			return nil, fmt.Errorf("ignore: %w", err)
+		}
+	}
+	return list, nil
+}
+
+// Ignore a participant or writer under a name; an empty name uses the GUID
+func (l *IgnoreList) Add(name, guid string) error {
+	guid = strings.ToLower(strings.TrimSpace(guid))
+	if !guidPattern.MatchString(guid) {
+		return fmt.Errorf("invalid GUID %q, expected a 24 digit hex prefix, optionally with .ENTITY", guid)
+	}
+	if name == "" {
+		name = guid
+	}
+
+	l.mu.Lock()
+	defer l.mu.Unlock()
+	l.entries[name] = &IgnoredStatus{Name: name, GUID: guid}
+	return nil
+}
+
+// Stop ignoring the entry with the given name or GUID
+func (l *IgnoreList) Remove(name string) error {
+	l.mu.Lock()
+	defer l.mu.Unlock()
+	for key, entry := range l.entries {
+		if key == name || entry.GUID == strings.ToLower(name) {
+			delete(l.entries, key)
+			return nil
+		}
+	}
+	return fmt.Errorf("%s is not ignored", name)
+}
+
+// Whether samples of a writer are ignored, counting them if so
+func (l *IgnoreList) Drop(writer string) bool {
+	if l == nil || writer == "" {
+		return false
+	}
+
+	l.mu.Lock()
+	defer l.mu.Unlock()
+	for _, entry := range l.entries {
+		if writer == entry.GUID || strings.HasPrefix(writer, entry.GUID+".") {
+			entry.Messages++
+			return true
+		}
+	}
+	return false
+}
+
+// Copies of the entries, sorted by name
+func (l *IgnoreList) Entries() []IgnoredStatus {
+	if l == nil {
+		return nil
+	}
+
+	l.mu.Lock()
+	defer l.mu.Unlock()
+	entries := make([]IgnoredStatus, 0, len(l.entries))
+	for _, entry := range l.entries {
+		entries = append(entries, *entry)
+	}
+	sort.Slice(entries, func(i, j int) bool { return entries[i].Name < entries[j].Name })
+	return entries
+}
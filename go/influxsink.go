@@ -0,0 +1,120 @@
+package main
+
+import (
+	"bytes"
+	"fmt"
+	"net/http"
+	"strconv"
+	"strings"
+	"time"
+)
+
+// The InfluxDB sink pushes selected numeric fields of JSON payloads as line
+// protocol, one point per message with the topic as a tag:
+//
+//	[sinks.dashboards]
+//	type = "influx"
+//	url = "http://influx:8086/api/v2/write?org=lab&bucket=robots&precision=ns"
+//	token = "..."
+//	measurement = "cardinal"
+//	fields = ["odom:pose.x", "odom:pose.y", "battery:voltage"]
+//
+// Anything that accepts line protocol over HTTP works the same way, e.g.
+// Telegraf's http_listener_v2 in front of TimescaleDB.
+
+// Time allowed for one write request
+const influxTimeout = 10 * time.Second
+
+// A field to extract from the payloads of a topic
+type influxField struct {
+	topic string
+	path  []string
+	key   string // field key, the dotted path
+}
+
+type influxSink struct {
+	url         string
+	token       string
+	measurement string
+	fields      map[string][]influxField // by topic
+	client      *http.Client
+}
+
+func newInfluxSink(spec sinkSpec) (sinkBackend, error) {
+	s := &influxSink{
+		url:         spec.settings["url"],
+		token:       spec.settings["token"],
+		measurement: spec.settings["measurement"],
+		fields:      map[string][]influxField{},
+		client:      &http.Client{Timeout: influxTimeout},
+	}
+	if s.url == "" {
+		return nil, fmt.Errorf("needs a url")
+	}
+	if s.measurement == "" {
+		s.measurement = "cardinal"
+	}
+	fields, err := parseStringList(spec.settings["fields"])
+	if err != nil || len(fields) == 0 {
+		return nil, fmt.Errorf("needs fields as [\"topic:field.path\", ...]")
+	}
+	for _, field := range fields {
+		topic, key, ok := strings.Cut(field, ":")
+		if !ok || topic == "" || key == "" {
+			return nil, fmt.Errorf("invalid field %q, expected topic:field.path", field)
+		}
+		s.fields[topic] = append(s.fields[topic], influxField{topic: topic, path: strings.Split(key, "."), key: key})
+	}
+	return s, nil
+}
+
+// Escape a measurement name, tag value or field key for line protocol
+var influxEscaper = strings.NewReplacer(",", `\,`, " ", `\ `, "=", `\=`)
+
+// Line protocol for a message, or "" when it has none of the fields
+func (s *influxSink) line(msg DDSMessage) string {
+	var values []string
+	for _, field := range s.fields[msg.Topic] {
+		if v, ok := jsonNumber(msg.Content, field.path); ok {
+			values = append(values, influxEscaper.Replace(field.key)+"="+strconv.FormatFloat(v, 'g', -1, 64))
+		}
+	}
+	if len(values) == 0 {
+		return ""
+	}
+	return fmt.Sprintf("%s,topic=%s %s %d\n", influxEscaper.Replace(s.measurement), influxEscaper.Replace(msg.Topic),
+		strings.Join(values, ","), msg.ReceivedAt.UnixNano())
+}
+
+// Write the points of a batch in one request
+func (s *influxSink) WriteBatch(batch []DDSMessage) error {
+	var body bytes.Buffer
+	for _, msg := range batch {
+		body.WriteString(s.line(msg))
+	}
+	if body.Len() == 0 {
+		return nil
+	}
+
+	req, err := http.NewRequest(http.MethodPost, s.url, &body)
+	if err != nil {
+		return err
+	}
+	req.Header.Set("Content-Type", "text/plain; charset=utf-8")
+	if s.token != "" {
+		req.Header.Set("Authorization", "Token "+s.token)
+	}
+	resp, err := s.client.Do(req)
+	if err != nil {
+		return err
+	}
+	defer resp.Body.Close()
+	if resp.StatusCode/100 != 2 {
+		return fmt.Errorf("influx write failed: %s", resp.Status)
+	}
+	return nil
+}
+
+func (s *influxSink) Close() error {
+	return nil
+}
@@ -0,0 +1,75 @@
+package main
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+	"time"
+
+	"github.com/charmbracelet/lipgloss"
+)
+
+// Keyed topics carry one instance per key, e.g. one per robot of a fleet
+// publishing on /fleet/pose. The dashboard shows the latest value of every
+// instance seen, and whether it was disposed or unregistered since.
+
+// Most instances shown on the dashboard
+const maxInstancesShown = 12
+
+// Instance of a keyed topic
+type instanceID struct {
+	topic string
+	key   string
+}
+
+// Latest sample of an instance
+type instanceValue struct {
+	content string
+	state   string // "" while alive, otherwise as in DDSMessage.Instance
+	updated time.Time
+}
+
+// Latest values of the instances seen, by topic and key
+type instanceTable map[instanceID]instanceValue
+
+// Take in a received message; unkeyed messages are ignored. Samples that
+// end an instance carry no data, so the last value is kept.
+func (t instanceTable) update(msg DDSMessage) {
+	if msg.Key == "" {
+		return
+	}
+	id := instanceID{topic: topicLabel(msg), key: msg.Key}
+	value := t[id]
+	if msg.Instance == "" {
+		value.content = msg.Content
+	}
+	value.state = msg.Instance
+	value.updated = msg.Timestamp
+	t[id] = value
+}
+
+// Render the instances card, most recently updated first
+func (t instanceTable) render(headerStyle lipgloss.Style, textColor, dimColor, warningColor lipgloss.Color) string {
+	ids := make([]instanceID, 0, len(t))
+	for id := range t {
+		ids = append(ids, id)
+	}
+	sort.Slice(ids, func(i, j int) bool {
+		return t[ids[i]].updated.After(t[ids[j]].updated)
+	})
+
+	lines := []string{headerStyle.Render(fmt.Sprintf("🔑 Instances (%d)", len(ids)))}
+	nameStyle := lipgloss.NewStyle().Foreground(dimColor).Width(32)
+	for _, id := range ids[:min(len(ids), maxInstancesShown)] {
+		value := t[id]
+		text := lipgloss.NewStyle().Foreground(textColor).Render(truncate(strings.ReplaceAll(payloadText(value.content), "\n", " "), 48))
+		if value.state != "" {
+			text = lipgloss.NewStyle().Foreground(warningColor).Render(value.state)
+		}
+		lines = append(lines, lipgloss.JoinHorizontal(lipgloss.Left, nameStyle.Render(truncate(id.topic+" "+id.key, 30)), text))
+	}
+	if len(ids) > maxInstancesShown {
+		lines = append(lines, lipgloss.NewStyle().Foreground(dimColor).Render(fmt.Sprintf("… %d more", len(ids)-maxInstancesShown)))
+	}
+	return lipgloss.JoinVertical(lipgloss.Left, lines...)
+}
@@ -0,0 +1,76 @@
+package main
+
+import (
+	"fmt"
+	"strings"
+	"time"
+
+	"cardinal/core"
+
+	"github.com/charmbracelet/lipgloss"
+)
+
+// Inter-arrival distribution of a topic, kept by package core
+type IntervalHistogram = core.IntervalHistogram
+
+// Label of a bucket by its upper bound
+func intervalBucketLabel(i int) string {
+	if i == len(core.IntervalBounds) {
+		return ">" + formatInterval(core.IntervalBounds[i-1])
+	}
+	return "≤" + formatInterval(core.IntervalBounds[i])
+}
+
+// Short form of an interval for the UI
+func formatInterval(d time.Duration) string {
+	switch {
+	case d >= time.Second:
+		return fmt.Sprintf("%gs", d.Seconds())
+	case d >= time.Millisecond:
+		return fmt.Sprintf("%gms", float64(d)/float64(time.Millisecond))
+	}
+	return fmt.Sprintf("%gµs", float64(d)/float64(time.Microsecond))
+}
+
+// Render the inter-arrival card: per topic the mean period, jitter and the
+// shape of the distribution over the buckets
+func (m model) renderJitter(headerStyle lipgloss.Style, textColor, dimColor lipgloss.Color) string {
+	rowStyle := lipgloss.NewStyle().Foreground(textColor)
+	dimStyle := lipgloss.NewStyle().Foreground(dimColor).Italic(true)
+
+	var b strings.Builder
+	b.WriteString(headerStyle.Render("⏱️  Inter-arrival Time"))
+	b.WriteString("\n")
+
+	topics := m.stats.Topics()
+	if len(topics) == 0 {
+		b.WriteString(dimStyle.Render("No topics seen yet"))
+		return b.String()
+	}
+
+	b.WriteString(dimStyle.Render(fmt.Sprintf("%-24s %9s %9s %9s %9s  %s", "Topic", "Mean", "Jitter", "Min", "Max",
+		intervalBucketLabel(0)+" … "+intervalBucketLabel(len(core.IntervalBounds)))))
+	b.WriteString("\n")
+	for _, stats := range topics {
+		h := stats.Intervals
+		shape := "-"
+		if h.Count > 0 {
+			buckets := make([]float64, len(h.Buckets))
+			for i, n := range h.Buckets {
+				buckets[i] = float64(n)
+			}
+			shape = renderSparkline(buckets)
+		}
+		b.WriteString(rowStyle.Render(fmt.Sprintf("%-24s %9s %9s %9s %9s  %s",
+			truncate(stats.Topic, 24),
+			formatInterval(h.Mean().Round(time.Microsecond)),
+			formatInterval(h.StdDev().Round(time.Microsecond)),
+			formatInterval(h.Min.Round(time.Microsecond)),
+			formatInterval(h.Max.Round(time.Microsecond)),
+			shape,
+		)))
+		b.WriteString("\n")
+	}
+	b.WriteString(dimStyle.Render("Jitter: standard deviation of the time between messages"))
+	return b.String()
+}
@@ -0,0 +1,591 @@
+package main
+
+import (
+	"bufio"
+	"encoding/binary"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"hash/crc32"
+	"io"
+	"net"
+	"slices"
+	"sort"
+	"strings"
+	"time"
+)
+
+// The Kafka sink produces every message to a Kafka topic, speaking the Kafka
+// protocol to the brokers itself:
+//
+//	[sinks.lake]
+//	type = "kafka"
+//	brokers = "kafka1:9092,kafka2:9092"
+//	topic = "dds.{topic}"
+//	map = ["odom=robot.odometry"]
+//	key = "robot_id"
+//
+// {topic} stands for the DDS topic with slashes turned into dots; map
+// entries override single topics. The record key is the value of the key
+// field of JSON payloads, and records without one have no key. Keyed records
+// go to the partition Kafka's own producers pick for the key, the others to
+// one partition per batch in turn.
+//
+// The client covers the small part of the protocol this needs: metadata to
+// find the partition leaders, and uncompressed record batches in Produce
+// requests acknowledged by the leader. Payloads are length-prefixed, so
+// binary and sealed ones pass unchanged.
+
+// Time allowed to connect to a broker and for one request
+const kafkaTimeout = 10 * time.Second
+
+// Kafka API keys, and the versions of them used
+const (
+	kafkaProduce         = 0
+	kafkaMetadata        = 3
+	kafkaProduceVersion  = 3 // the first with v2 record batches
+	kafkaMetadataVersion = 4 // the first with allow_auto_topic_creation
+)
+
+// Kafka error codes worth naming
+var kafkaErrors = map[int16]string{
+	3:  "unknown topic or partition",
+	5:  "leader not available",
+	6:  "not leader for the partition",
+	7:  "request timed out",
+	10: "message too large",
+	29: "topic authorization failed",
+}
+
+func kafkaError(code int16) error {
+	if name, ok := kafkaErrors[code]; ok {
+		return errors.New(name)
+	}
+	return fmt.Errorf("error code %d", code)
+}
+
+var errKafkaShortResponse = errors.New("short response from the broker")
+
+// Checksum of record batches
+var kafkaCRC = crc32.MakeTable(crc32.Castagnoli)
+
+// A connection to one broker, carrying one request at a time
+type kafkaConn struct {
+	conn        net.Conn
+	r           *bufio.Reader
+	correlation int32
+}
+
+// One record on its way to a partition
+type kafkaRecord struct {
+	key   string
+	value string
+	at    time.Time
+}
+
+// A partition of a Kafka topic
+type kafkaPartition struct {
+	topic string
+	index int32
+}
+
+type kafkaSink struct {
+	brokers  []string              // bootstrap addresses
+	template string                // Kafka topic, {topic} standing for the DDS topic
+	topics   map[string]string     // DDS topic -> Kafka topic
+	key      []string              // JSON path of the record key
+	addrs    map[int32]string      // broker id -> address, from the metadata
+	leaders  map[string][]int32    // Kafka topic -> leader of each partition
+	conns    map[string]*kafkaConn // by broker address
+	next     map[string]int32      // Kafka topic -> partition of the next unkeyed batch
+}
+
+func newKafkaSink(spec sinkSpec) (sinkBackend, error) {
+	s := &kafkaSink{
+		template: spec.settings["topic"],
+		topics:   map[string]string{},
+		addrs:    map[int32]string{},
+		leaders:  map[string][]int32{},
+		conns:    map[string]*kafkaConn{},
+		next:     map[string]int32{},
+	}
+	for _, broker := range strings.Split(spec.settings["brokers"], ",") {
+		if broker = strings.TrimSpace(broker); broker != "" {
+			s.brokers = append(s.brokers, broker)
+		}
+	}
+	if len(s.brokers) == 0 {
+		return nil, fmt.Errorf("needs brokers")
+	}
+	if s.template == "" {
+		s.template = "{topic}"
+	}
+	if spec.settings["map"] != "" {
+		mappings, err := parseStringList(spec.settings["map"])
+		if err != nil {
+			return nil, fmt.Errorf("map: %w", err)
+		}
+		if s.topics, err = parseTopicMap(mappings); err != nil {
+			return nil, err
+		}
+	}
+	if key := spec.settings["key"]; key != "" {
+		s.key = strings.Split(key, ".")
+	}
+	return s, nil
+}
+
+// Kafka topic for a DDS topic
+func (s *kafkaSink) kafkaTopic(topic string) string {
+	if mapped, ok := s.topics[topic]; ok {
+		return mapped
+	}
+	name := strings.ReplaceAll(strings.Trim(topic, "/"), "/", ".")
+	return strings.ReplaceAll(s.template, "{topic}", name)
+}
+
+// Record key of a message
+func (s *kafkaSink) recordKey(msg DDSMessage) string {
+	if s.key == nil {
+		return ""
+	}
+	var value any
+	if json.Unmarshal([]byte(msg.Content), &value) != nil {
+		return ""
+	}
+	for _, key := range s.key {
+		object, ok := value.(map[string]any)
+		if !ok {
+			return ""
+		}
+		value = object[key]
+	}
+	switch v := value.(type) {
+	case nil, map[string]any, []any:
+		return ""
+	case string:
+		return v
+	default:
+		return fmt.Sprint(v)
+	}
+}
+
+func (s *kafkaSink) WriteBatch(batch []DDSMessage) error {
+	var missing []string
+	for _, msg := range batch {
+		topic := s.kafkaTopic(msg.Topic)
+		if _, ok := s.leaders[topic]; !ok && !slices.Contains(missing, topic) {
+			missing = append(missing, topic)
+		}
+	}
+
+	// Topics without usable leaders are skipped and reported, the records
+	// of the others still go out
+	var errs []error
+	if len(missing) > 0 {
+		if err := s.metadata(missing); err != nil {
+			errs = append(errs, err)
+		}
+	}
+	usable := map[string][]int32{}
+
+	// Records by leader and partition, unkeyed ones of a topic all in the
+	// partition whose turn it is
+	byLeader := map[int32]map[kafkaPartition][]kafkaRecord{}
+	unkeyed := map[string]int32{}
+	for _, msg := range batch {
+		topic := s.kafkaTopic(msg.Topic)
+		leaders, seen := usable[topic]
+		if !seen {
+			var err error
+			if leaders, err = s.topicLeaders(topic); err != nil {
+				errs = append(errs, err)
+			}
+			usable[topic] = leaders
+		}
+		if leaders == nil {
+			continue
+		}
+		record := kafkaRecord{key: s.recordKey(msg), value: msg.Content, at: msg.ReceivedAt}
+		var index int32
+		if record.key != "" {
+			index = int32(murmur2([]byte(record.key)) & 0x7fffffff % uint32(len(leaders)))
+		} else if turn, ok := unkeyed[topic]; ok {
+			index = turn
+		} else {
+			index = s.next[topic] % int32(len(leaders))
+			s.next[topic] = index + 1
+			unkeyed[topic] = index
+		}
+		leader := leaders[index]
+		if byLeader[leader] == nil {
+			byLeader[leader] = map[kafkaPartition][]kafkaRecord{}
+		}
+		partition := kafkaPartition{topic, index}
+		byLeader[leader][partition] = append(byLeader[leader][partition], record)
+	}
+
+	for leader, partitions := range byLeader {
+		if err := s.produce(s.addrs[leader], partitions); err != nil {
+			errs = append(errs, err)
+		}
+	}
+	return errors.Join(errs...)
+}
+
+// The partition leaders of a topic, nil when the metadata lookup already
+// reported the topic, and an error when a leader is not a known broker
+func (s *kafkaSink) topicLeaders(topic string) ([]int32, error) {
+	leaders, ok := s.leaders[topic]
+	if !ok {
+		return nil, nil
+	}
+	for index, leader := range leaders {
+		if _, ok := s.addrs[leader]; !ok {
+			delete(s.leaders, topic)
+			return nil, fmt.Errorf("partition %d of %s: %w", index, topic, kafkaError(5))
+		}
+	}
+	return leaders, nil
+}
+
+// Send the records of the partitions one broker leads
+func (s *kafkaSink) produce(addr string, partitions map[kafkaPartition][]kafkaRecord) error {
+	order := make([]kafkaPartition, 0, len(partitions))
+	for partition := range partitions {
+		order = append(order, partition)
+	}
+	sort.Slice(order, func(i, j int) bool {
+		if order[i].topic != order[j].topic {
+			return order[i].topic < order[j].topic
+		}
+		return order[i].index < order[j].index
+	})
+
+	var req kafkaEncoder
+	req.i16(-1) // no transactional id
+	req.i16(1)  // acks from the leader
+	req.i32(int32(kafkaTimeout / time.Millisecond))
+	var topics int32
+	for i, partition := range order {
+		if i == 0 || partition.topic != order[i-1].topic {
+			topics++
+		}
+	}
+	req.i32(topics)
+	for i := 0; i < len(order); {
+		topic := order[i].topic
+		j := i
+		for j < len(order) && order[j].topic == topic {
+			j++
+		}
+		req.str(topic)
+		req.i32(int32(j - i))
+		for _, partition := range order[i:j] {
+			req.i32(partition.index)
+			req.bytes(kafkaRecordBatch(partitions[partition]))
+		}
+		i = j
+	}
+
+	resp, err := s.request(addr, kafkaProduce, kafkaProduceVersion, req.buf)
+	if err != nil {
+		return err
+	}
+	var firstErr error
+	for range resp.count() {
+		topic := resp.str()
+		for range resp.count() {
+			index := resp.i32()
+			code := resp.i16()
+			resp.i64() // base offset
+			resp.i64() // log append time
+			if code != 0 && resp.err == nil {
+				// Stale leaders show up as errors; look them up again
+				delete(s.leaders, topic)
+				if firstErr == nil {
+					firstErr = fmt.Errorf("partition %d of %s: %w", index, topic, kafkaError(code))
+				}
+			}
+		}
+	}
+	if resp.err != nil {
+		return resp.err
+	}
+	return firstErr
+}
+
+// Look up the partition leaders of topics, creating them where the brokers
+// allow it
+func (s *kafkaSink) metadata(topics []string) error {
+	var req kafkaEncoder
+	req.i32(int32(len(topics)))
+	for _, topic := range topics {
+		req.str(topic)
+	}
+	req.i8(1) // allow auto topic creation
+
+	var resp *kafkaDecoder
+	var err error
+	for _, addr := range s.brokers {
+		if resp, err = s.request(addr, kafkaMetadata, kafkaMetadataVersion, req.buf); err == nil {
+			break
+		}
+	}
+	if err != nil {
+		return err
+	}
+
+	resp.i32() // throttle time
+	for range resp.count() {
+		id := resp.i32()
+		host := resp.str()
+		port := resp.i32()
+		resp.str() // rack
+		s.addrs[id] = net.JoinHostPort(host, fmt.Sprint(port))
+	}
+	resp.str() // cluster id
+	resp.i32() // controller id
+	var errs []error
+	answered := map[string]bool{}
+	for range resp.count() {
+		code := resp.i16()
+		topic := resp.str()
+		resp.i8() // internal
+		answered[topic] = true
+		var leaders []int32
+		for range resp.count() {
+			resp.i16() // partition error, e.g. no leader right now
+			index := resp.i32()
+			leader := resp.i32()
+			for range resp.count() {
+				resp.i32() // replica
+			}
+			for range resp.count() {
+				resp.i32() // in-sync replica
+			}
+			if int(index) >= len(leaders) {
+				leaders = append(leaders, make([]int32, int(index)+1-len(leaders))...)
+			}
+			leaders[index] = leader
+		}
+		if code != 0 || len(leaders) == 0 {
+			if code == 0 {
+				code = 5
+			}
+			errs = append(errs, fmt.Errorf("topic %s: %w", topic, kafkaError(code)))
+			continue
+		}
+		s.leaders[topic] = leaders
+	}
+	if resp.err != nil {
+		return resp.err
+	}
+	for _, topic := range topics {
+		if !answered[topic] {
+			errs = append(errs, fmt.Errorf("topic %s: missing from the metadata response", topic))
+		}
+	}
+	return errors.Join(errs...)
+}
+
+// Send one request to a broker and read its response, dropping the
+// connection and the cached leaders when the broker cannot be reached
+func (s *kafkaSink) request(addr string, apiKey, version int16, body []byte) (*kafkaDecoder, error) {
+	c, err := s.conn(addr)
+	if err == nil {
+		var resp []byte
+		if resp, err = c.roundTrip(apiKey, version, body); err == nil {
+			return &kafkaDecoder{buf: resp}, nil
+		}
+		c.conn.Close()
+		delete(s.conns, addr)
+	}
+	s.leaders = map[string][]int32{}
+	return nil, fmt.Errorf("broker %s: %w", addr, err)
+}
+
+func (s *kafkaSink) conn(addr string) (*kafkaConn, error) {
+	if c, ok := s.conns[addr]; ok {
+		return c, nil
+	}
+	conn, err := net.DialTimeout("tcp", addr, kafkaTimeout)
+	if err != nil {
+		return nil, err
+	}
+	c := &kafkaConn{conn: conn, r: bufio.NewReader(conn)}
+	s.conns[addr] = c
+	return c, nil
+}
+
+func (c *kafkaConn) roundTrip(apiKey, version int16, body []byte) ([]byte, error) {
+	c.correlation++
+	var req kafkaEncoder
+	req.i32(0) // size, set below
+	req.i16(apiKey)
+	req.i16(version)
+	req.i32(c.correlation)
+	req.str("cardinal")
+	req.buf = append(req.buf, body...)
+	binary.BigEndian.PutUint32(req.buf, uint32(len(req.buf)-4))
+
+	c.conn.SetDeadline(time.Now().Add(kafkaTimeout))
+	if _, err := c.conn.Write(req.buf); err != nil {
+		return nil, err
+	}
+	var size [4]byte
+	if _, err := io.ReadFull(c.r, size[:]); err != nil {
+		return nil, err
+	}
+	resp := make([]byte, binary.BigEndian.Uint32(size[:]))
+	if _, err := io.ReadFull(c.r, resp); err != nil {
+		return nil, err
+	}
+	if len(resp) < 4 || int32(binary.BigEndian.Uint32(resp)) != c.correlation {
+		return nil, fmt.Errorf("response out of order")
+	}
+	return resp[4:], nil
+}
+
+func (s *kafkaSink) Close() error {
+	for addr, c := range s.conns {
+		c.conn.Close()
+		delete(s.conns, addr)
+	}
+	return nil
+}
+
+// Encode records as one uncompressed v2 record batch
+func kafkaRecordBatch(records []kafkaRecord) []byte {
+	first, last := records[0].at.UnixMilli(), records[0].at.UnixMilli()
+	for _, record := range records {
+		first = min(first, record.at.UnixMilli())
+		last = max(last, record.at.UnixMilli())
+	}
+
+	var body kafkaEncoder
+	body.i16(0) // attributes: no compression
+	body.i32(int32(len(records) - 1))
+	body.i64(first)
+	body.i64(last)
+	body.i64(-1) // no producer id
+	body.i16(-1) // producer epoch
+	body.i32(-1) // base sequence
+	body.i32(int32(len(records)))
+	for i, record := range records {
+		var r []byte
+		r = append(r, 0) // attributes
+		r = binary.AppendVarint(r, record.at.UnixMilli()-first)
+		r = binary.AppendVarint(r, int64(i))
+		if record.key == "" {
+			r = binary.AppendVarint(r, -1)
+		} else {
+			r = binary.AppendVarint(r, int64(len(record.key)))
+			r = append(r, record.key...)
+		}
+		r = binary.AppendVarint(r, int64(len(record.value)))
+		r = append(r, record.value...)
+		r = binary.AppendVarint(r, 0) // no headers
+		body.buf = binary.AppendVarint(body.buf, int64(len(r)))
+		body.buf = append(body.buf, r...)
+	}
+
+	var batch kafkaEncoder
+	batch.i64(0) // base offset, assigned by the broker
+	batch.i32(int32(4 + 1 + 4 + len(body.buf)))
+	batch.i32(-1) // partition leader epoch
+	batch.i8(2)   // magic
+	batch.i32(int32(crc32.Checksum(body.buf, kafkaCRC)))
+	batch.buf = append(batch.buf, body.buf...)
+	return batch.buf
+}
+
+// Kafka's default partitioner hash, so keys land where other producers put
+// them
+func murmur2(data []byte) uint32 {
+	const m = 0x5bd1e995
+	h := uint32(0x9747b28c) ^ uint32(len(data))
+	n := len(data) &^ 3
+	for i := 0; i < n; i += 4 {
+		k := binary.LittleEndian.Uint32(data[i:])
+		k *= m
+		k ^= k >> 24
+		k *= m
+		h *= m
+		h ^= k
+	}
+	switch len(data) & 3 {
+	case 3:
+		h ^= uint32(data[n+2]) << 16
+		fallthrough
+	case 2:
+		h ^= uint32(data[n+1]) << 8
+		fallthrough
+	case 1:
+		h ^= uint32(data[n])
+		h *= m
+	}
+	h ^= h >> 13
+	h *= m
+	h ^= h >> 15
+	return h
+}
+
+// Builds Kafka requests, big-endian
+type kafkaEncoder struct {
+	buf []byte
+}
+
+func (e *kafkaEncoder) i8(v int8)   { e.buf = append(e.buf, byte(v)) }
+func (e *kafkaEncoder) i16(v int16) { e.buf = binary.BigEndian.AppendUint16(e.buf, uint16(v)) }
+func (e *kafkaEncoder) i32(v int32) { e.buf = binary.BigEndian.AppendUint32(e.buf, uint32(v)) }
+func (e *kafkaEncoder) i64(v int64) { e.buf = binary.BigEndian.AppendUint64(e.buf, uint64(v)) }
+
+func (e *kafkaEncoder) str(s string) {
+	e.i16(int16(len(s)))
+	e.buf = append(e.buf, s...)
+}
+
+func (e *kafkaEncoder) bytes(b []byte) {
+	e.i32(int32(len(b)))
+	e.buf = append(e.buf, b...)
+}
+
+// Reads Kafka responses; past the end every value is zero and err is set
+type kafkaDecoder struct {
+	buf []byte
+	err error
+}
+
+func (d *kafkaDecoder) next(n int) []byte {
+	if d.err != nil || n > len(d.buf) {
+		d.err = errKafkaShortResponse
+		return make([]byte, n)
+	}
+	b := d.buf[:n]
+	d.buf = d.buf[n:]
+	return b
+}
+
+func (d *kafkaDecoder) i8() int8   { return int8(d.next(1)[0]) }
+func (d *kafkaDecoder) i16() int16 { return int16(binary.BigEndian.Uint16(d.next(2))) }
+func (d *kafkaDecoder) i32() int32 { return int32(binary.BigEndian.Uint32(d.next(4))) }
+func (d *kafkaDecoder) i64() int64 { return int64(binary.BigEndian.Uint64(d.next(8))) }
+
+// A nullable string, empty when null
+func (d *kafkaDecoder) str() string {
+	n := d.i16()
+	if n < 0 {
+		return ""
+	}
+	return string(d.next(int(n)))
+}
+
+// Length of an array, 0 when null or longer than the response could hold
+func (d *kafkaDecoder) count() int {
+	n := int(d.i32())
+	if n < 0 || n > len(d.buf) {
+		return 0
+	}
+	return n
+}
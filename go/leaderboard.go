@@ -0,0 +1,105 @@
+package main
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+	"time"
+
+	"cardinal/core"
+
+	"github.com/charmbracelet/lipgloss"
+)
+
+// Metric used to rank topics in the leaderboard
+type rankMetric int
+
+const (
+	rankByRate rankMetric = iota
+	rankByBandwidth
+	rankByGaps
+	rankMetricCount
+)
+
+var rankMetricNames = [...]string{"Rate", "Bandwidth", "Gaps"}
+
+// Selectable leaderboard windows
+var leaderboardWindows = []time.Duration{10 * time.Second, time.Minute, 5 * time.Minute}
+
+// Number of topics shown in the leaderboard
+const leaderboardSize = 5
+
+// View settings for the top topics pane
+type leaderboardState struct {
+	metric rankMetric
+	window int
+}
+
+// Handle a key press while the dashboard is active
+func (l leaderboardState) handleKey(key string) leaderboardState {
+	switch key {
+	case "o":
+		l.metric = (l.metric + 1) % rankMetricCount
+	case "w":
+		l.window = (l.window + 1) % len(leaderboardWindows)
+	}
+	return l
+}
+
+// Rank topics by the selected metric, highest first
+func (l leaderboardState) rank(stats []TopicWindowStats) []TopicWindowStats {
+	ranked := make([]TopicWindowStats, len(stats))
+	copy(ranked, stats)
+	sort.SliceStable(ranked, func(i, j int) bool {
+		switch l.metric {
+		case rankByBandwidth:
+			return ranked[i].WireBandwidth > ranked[j].WireBandwidth
+		case rankByGaps:
+			return ranked[i].Gaps > ranked[j].Gaps
+		}
+		return ranked[i].Rate > ranked[j].Rate
+	})
+	if len(ranked) > leaderboardSize {
+		ranked = ranked[:leaderboardSize]
+	}
+	return ranked
+}
+
+// Render the top topics pane
+func (m model) renderLeaderboard(headerStyle lipgloss.Style, textColor, dimColor, accentColor lipgloss.Color) string {
+	window := leaderboardWindows[m.leaderboard.window]
+	ranked := m.leaderboard.rank(m.stats.Window(time.Now(), window))
+
+	rowStyle := lipgloss.NewStyle().Foreground(textColor)
+	leaderStyle := lipgloss.NewStyle().Foreground(accentColor).Bold(true)
+	dimStyle := lipgloss.NewStyle().Foreground(dimColor).Italic(true)
+
+	var b strings.Builder
+	b.WriteString(headerStyle.Render(fmt.Sprintf("🏆 Top Topics by %s (last %s)",
+		rankMetricNames[m.leaderboard.metric], formatDuration(window))))
+	b.WriteString("\n")
+
+	if len(ranked) == 0 {
+		b.WriteString(dimStyle.Render("No topics seen yet"))
+		return b.String()
+	}
+
+	for i, ws := range ranked {
+		line := fmt.Sprintf("%d. %-24s %8.1f msg/s %10s/s %4d gaps",
+			i+1, truncate(ws.Topic, 24), ws.Rate, formatBytes(ws.WireBandwidth), ws.Gaps)
+		if i == 0 {
+			b.WriteString(leaderStyle.Render(line))
+		} else {
+			b.WriteString(rowStyle.Render(line))
+		}
+		b.WriteString("\n")
+	}
+	b.WriteString(dimStyle.Render("o: change ranking • w: change window"))
+
+	return b.String()
+}
+
+// Helper function to format a byte count with binary units
+func formatBytes(bytes float64) string {
+	return core.FormatBytes(bytes)
+}
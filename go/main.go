@@ -4,22 +4,64 @@ import (
 	"context"
 	"fmt"
 	"log"
+	"os"
+	"os/signal"
 	"strings"
 	"sync"
+	"syscall"
 	"time"
 
+	"cardinal/dds"
+
 	"github.com/charmbracelet/bubbles/progress"
 	"github.com/charmbracelet/bubbles/spinner"
 	tea "github.com/charmbracelet/bubbletea"
 	"github.com/charmbracelet/lipgloss"
 )
 
-// DDS Message structure
-type DDSMessage struct {
-	Content   string
-	Timestamp time.Time
+// The DDS layer lives in cardinal/dds; the binary keeps its own names for it
+type (
+	DDSMessage    = dds.Message
+	DDSPublisher  = dds.Publisher
+	DDSSubscriber = dds.Subscriber
+	Transport     = dds.Transport
+	MockTransport = dds.MockTransport
+)
+
+// Pipeline stages the DDS layer times, by its own stage
+var ddsStages = [...]pipelineStage{dds.StagePublish: stagePublish, dds.StageReceive: stageReceive}
+
+// Report the DDS layer's publishes into the pipeline timing and the spans
+var transportHooks = dds.Hooks{
+	TimeStage: func(stage dds.Stage) func() {
+		return timeStage(ddsStages[stage])
+	},
+	StartSpan: func(name, topic string, messages, bytes int) (spanContext, func()) {
+		span := startSpan(name, spanContext{})
+		span.SetString("topic", topic)
+		span.SetInt("messages", int64(messages))
+		span.SetInt("bytes", int64(bytes))
+		return span.Context(), span.End
+	},
+	Clock: monotonicNow,
+}
+
+// Create the mock bus, reporting into the binary's timing and tracing
+func NewMockTransport() *MockTransport {
+	t := dds.NewMockTransport()
+	t.Hooks = transportHooks
+	return t
 }
 
+// Topic the subscriber and demo publisher use unless --topic says otherwise
+const defaultTopic = "hello_topic"
+
+// History limits for received messages
+const (
+	maxMessageHistory = 500
+	streamLength      = 15
+)
+
 // System metrics for the TUI
 type SystemMetrics struct {
 	MessagesReceived   int
@@ -37,42 +79,204 @@ type tickMsg struct{}
 // Metrics update message
 type metricsMsg SystemMetrics
 
-// Interface for DDS publishers
-type DDSPublisher interface {
-	Publish(msg DDSMessage) error
+// Implemented by endpoints that count the remote endpoints they discovered
+// but could not match because of incompatible QoS
+type qosReporter interface {
+	IncompatibleQoS() int
+}
+
+// Implemented by subscribers that count the samples they lost
+type lossReporter interface {
+	SamplesLost() int
+}
+
+// Implemented by endpoints that count the deadline periods without a sample
+type deadlineReporter interface {
+	DeadlinesMissed() int
+}
+
+// Implemented by subscribers that track the liveliness of the writers they
+// matched
+type livelinessReporter interface {
+	WriterLiveliness() map[string]bool
+}
+
+// Implemented by subscribers that report writers matching and unmatching as
+// it happens
+type matchNotifier interface {
+	OnWriterMatched(f func(writer string, matched bool))
+}
+
+// Implemented by endpoints that report the remote endpoints they cannot
+// match because of QoS as they are discovered, naming the policy
+type qosNotifier interface {
+	OnIncompatibleQoS(f func(policy string))
+}
+
+// Implemented by transports that report the other participants of their
+// domain and their endpoints coming and going; stop ends the reports
+type discoveryWatcher interface {
+	WatchDiscovery(l discoveryListener) (stop func(), err error)
+}
+
+// Receives what discovery reports, on transport threads
+type discoveryListener interface {
+	participantChanged(p RemoteParticipant, present bool)
+	endpointChanged(e RemoteEndpoint, present bool)
+}
+
+// Implemented by transports that can read a topic of any type, decoding
+// samples with the type its writers announce
+type dynamicSubscriber interface {
+	NewDynamicSubscriber(topic string) (DDSSubscriber, error)
+}
+
+// Implemented by publishers of keyed topics, which can end instances
+type instancePublisher interface {
+	Dispose(key string) error
+	Unregister(key string) error
+}
+
+// Implemented by publishers that know how many readers they matched
+type matchReporter interface {
+	MatchedReaders() int
+}
+
+// Implemented by publishers that know their active writer QoS
+type writerQoSReporter interface {
+	ActiveQoS() QoS
 }
 
-// Interface for DDS subscribers
-type DDSSubscriber interface {
-	Subscribe() <-chan DDSMessage
+// Implemented by publishers that know the GUID their samples arrive with
+type writerIdentity interface {
+	WriterGUID() string
 }
 
-// Simple DDS-like message channel (simulating Fast DDS for fallback)
-type MockDDSPublisher struct {
-	topic   string
-	channel chan DDSMessage
+// Implemented by publishers that can send several messages in one call
+type batchPublisher interface {
+	PublishBatch(batch []DDSMessage) error
 }
 
-type MockDDSSubscriber struct {
-	topic   string
-	channel chan DDSMessage
+// Publish messages in one call where the publisher supports it, one by one
+// otherwise
+func publishBatch(pub DDSPublisher, batch []DDSMessage) error {
+	if b, ok := pub.(batchPublisher); ok {
+		return b.PublishBatch(batch)
+	}
+	for _, msg := range batch {
+		if err := pub.Publish(msg); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// The transport's own publisher or subscriber under the payload cipher
+func baseEndpoint(endpoint any) any {
+	switch e := endpoint.(type) {
+	case encryptedPublisher:
+		return e.DDSPublisher
+	case *encryptedSubscriber:
+		return e.DDSSubscriber
+	}
+	return endpoint
+}
+
+// The transport under the payload cipher and the read-only guard, for the
+// optional capabilities the wrappers do not forward. Those only read, so
+// reaching them past the read-only guard is safe.
+func baseTransport(transport Transport) Transport {
+	for {
+		switch t := transport.(type) {
+		case encryptedTransport:
+			transport = t.Transport
+		case readOnlyTransport:
+			transport = t.Transport
+		default:
+			return transport
+		}
+	}
+}
+
+// Incompatible QoS count of a publisher or subscriber; 0 when the transport
+// cannot tell
+func incompatibleQoS(endpoint any) int {
+	if r, ok := baseEndpoint(endpoint).(qosReporter); ok {
+		return r.IncompatibleQoS()
+	}
+	return 0
 }
 
-// Create a simple DDS-like system
-func NewDDSSystem() (*MockDDSPublisher, *MockDDSSubscriber) {
-	channel := make(chan DDSMessage, 100)
-	pub := &MockDDSPublisher{topic: "hello_topic", channel: channel}
-	sub := &MockDDSSubscriber{topic: "hello_topic", channel: channel}
-	return pub, sub
+// Writer QoS in effect for a publisher; false when the transport cannot
+// tell
+func activeQoS(endpoint any) (QoS, bool) {
+	if r, ok := baseEndpoint(endpoint).(writerQoSReporter); ok {
+		return r.ActiveQoS(), true
+	}
+	return QoS{}, false
+}
+
+// GUID of a publisher's writer; empty when the transport cannot tell
+func writerGUID(endpoint any) string {
+	if r, ok := baseEndpoint(endpoint).(writerIdentity); ok {
+		return r.WriterGUID()
+	}
+	return ""
 }
 
-func (p *MockDDSPublisher) Publish(msg DDSMessage) error {
-	p.channel <- msg
+// Samples a subscriber lost; 0 when the transport cannot tell
+func samplesLost(endpoint any) int {
+	if r, ok := baseEndpoint(endpoint).(lossReporter); ok {
+		return r.SamplesLost()
+	}
+	return 0
+}
+
+// Deadlines a publisher or subscriber missed; 0 when the transport cannot
+// tell
+func deadlinesMissed(endpoint any) int {
+	if r, ok := baseEndpoint(endpoint).(deadlineReporter); ok {
+		return r.DeadlinesMissed()
+	}
+	return 0
+}
+
+// Whether the writers a subscriber matched are alive, by GUID; nil when the
+// transport cannot tell
+func writerLiveliness(endpoint any) map[string]bool {
+	if r, ok := baseEndpoint(endpoint).(livelinessReporter); ok {
+		return r.WriterLiveliness()
+	}
 	return nil
 }
 
-func (s *MockDDSSubscriber) Subscribe() <-chan DDSMessage {
-	return s.channel
+// Dispose the instance of a key, or only unregister the publisher from it
+func endInstance(pub DDSPublisher, key string, dispose bool) error {
+	p, ok := baseEndpoint(pub).(instancePublisher)
+	if !ok {
+		return fmt.Errorf("ending instances needs the Fast DDS transport")
+	}
+	if dispose {
+		return p.Dispose(key)
+	}
+	return p.Unregister(key)
+}
+
+// Call f whenever a writer matches or unmatches a subscriber, where the
+// transport reports it. f runs on a transport thread and must not block.
+func onWriterMatched(endpoint any, f func(writer string, matched bool)) {
+	if n, ok := baseEndpoint(endpoint).(matchNotifier); ok {
+		n.OnWriterMatched(f)
+	}
+}
+
+// Call f whenever a publisher or subscriber finds a remote endpoint with an
+// incompatible QoS, where the transport reports it. f runs on a transport
+// thread and must not block.
+func onIncompatibleQoS(endpoint any, f func(policy string)) {
+	if n, ok := baseEndpoint(endpoint).(qosNotifier); ok {
+		n.OnIncompatibleQoS(f)
+	}
 }
 
 // Tab represents a tab in the TUI
@@ -85,35 +289,87 @@ type Tab struct {
 var tabs = []Tab{
 	{name: "📊 Dashboard", key: "dashboard"},
 	{name: "💬 Messages", key: "messages"},
+	{name: "🧭 Topics", key: "topics"},
 	{name: "📈 Charts", key: "charts"},
+	{name: "🛰️ Participants", key: "participants"},
 	{name: "⚙️ Settings", key: "settings"},
 }
 
 // TUI Model using Bubble Tea
 type model struct {
-	messages        []DDSMessage
+	store           *messageStore
+	messages        []DDSMessage // of the store's snapshot as of the last update
+	seen            uint64       // messages of the store handled so far
+	rows            *rowCache    // the message table's rows
 	metrics         SystemMetrics
 	startTime       time.Time
 	spinner         spinner.Model
 	progressBar     progress.Model
 	connectionBar   progress.Model
 	messageRateHist []float64
+	burstHist       []bool // seconds of messageRateHist that saw a burst
+	inBurst         bool   // a burst message arrived since the last tick
 	width           int
 	height          int
 	usingRealDDS    bool
 	activeTab       int
+	table           tableState
+	stats           *StatsTracker
+	leaderboard     leaderboardState
+	sessionLabel    string
+	profile         Profile
+	publish         publishFunc
+	prompt          publishPrompt
+	bursts          *BurstDetector
+	formats         displayFormats
+	times           timeFormats
+	deadlines       func() map[string]int // nil when the session cannot tell
+	deadlineCounts  map[string]int        // deadlines missed by topic at the last tick
+	deadlineAlert   string                // topics that missed a deadline recently
+	deadlineAlertAt time.Time
+	remoteWriters   func() []RemoteWriter // nil when the session cannot tell
+	writers         []RemoteWriter        // liveliness of the matched writers at the last tick
+
+	remoteParticipants func() []RemoteParticipant // nil when the transport cannot tell
+	participants       []RemoteParticipant        // as discovered at the last tick
+	discoveredTopics   func() []DiscoveredTopic   // nil when the transport cannot tell
+	topics             []DiscoveredTopic          // as discovered at the last tick
+	topicCursor        int                        // selected row of the topics tab
+	subscribe          func(topic string) error   // subscribes to a discovered topic
+	instances       instanceTable         // latest values of keyed topics
+	showFields      bool                  // field tree card on the messages tab
+	panes           []Pane                // registered custom panes, see RegisterPane
+	alert           AlertFired            // latest alert raised by the session
+	alertAt         time.Time
+	mode            inputMode             // what keys do, see handleKey
+	input           string                // line typed in filter and command mode
+	filter          string                // messages tab shows only messages containing it
+	search          searchState           // messages matching the filter
+	focus           topicFocus            // topics muted and soloed from the topics tab
+	detail          DDSMessage            // message shown in detail mode
+	status          string                // outcome of the last command
+	undo            []viewSettings        // view settings before each change, newest last
+	frames          *frameProfiler        // times of the recent updates and draws
+	showFrames      bool                  // frame times overlay above the footer
 }
 
+// How long a missed deadline stays highlighted on the dashboard, and an
+// alert in the footer
+const deadlineAlertTime = 10 * time.Second
+
 func (m model) Init() tea.Cmd {
 	return tea.Batch(
 		m.spinner.Tick,
 		tea.Tick(time.Second, func(time.Time) tea.Msg {
 			return tickMsg{}
 		}),
+		m.store.Wait(),
 	)
 }
 
 func (m model) Update(msg tea.Msg) (tea.Model, tea.Cmd) {
+	start := time.Now()
+	defer func() { m.frames.observeUpdate(time.Since(start)) }()
 	var cmds []tea.Cmd
 
 	switch msg := msg.(type) {
@@ -124,27 +380,9 @@ func (m model) Update(msg tea.Msg) (tea.Model, tea.Cmd) {
 		m.connectionBar.Width = msg.Width - 20
 
 	case tea.KeyMsg:
-		switch msg.String() {
-		case "ctrl+c", "q":
-			return m, tea.Quit
-		case "1", "2", "3", "4":
-			// Switch tabs with number keys
-			if tabIndex := int(msg.String()[0]) - '1'; tabIndex >= 0 && tabIndex < len(tabs) {
-				m.activeTab = tabIndex
-			}
-		case "tab":
-			// Cycle through tabs with Tab key
-			m.activeTab = (m.activeTab + 1) % len(tabs)
-		case "shift+tab":
-			// Cycle backwards through tabs with Shift+Tab
-			m.activeTab = (m.activeTab - 1 + len(tabs)) % len(tabs)
-		case "left", "h":
-			// Navigate left through tabs
-			m.activeTab = (m.activeTab - 1 + len(tabs)) % len(tabs)
-		case "right", "l":
-			// Navigate right through tabs
-			m.activeTab = (m.activeTab + 1) % len(tabs)
-		}
+		var cmd tea.Cmd
+		m, cmd = m.handleKey(msg)
+		return m, cmd
 
 	case tickMsg:
 		// Update metrics
@@ -152,32 +390,86 @@ func (m model) Update(msg tea.Msg) (tea.Model, tea.Cmd) {
 
 		// Update message rate history (last 10 seconds)
 		m.messageRateHist = append(m.messageRateHist, m.metrics.MessageRate)
+		m.burstHist = append(m.burstHist, m.inBurst)
+		m.inBurst = false
 		if len(m.messageRateHist) > 10 {
 			m.messageRateHist = m.messageRateHist[1:]
+			m.burstHist = m.burstHist[1:]
+		}
+		m.refreshDeadlines()
+		if m.remoteWriters != nil {
+			m.writers = m.remoteWriters()
+		}
+		if m.remoteParticipants != nil {
+			m.participants = m.remoteParticipants()
+		}
+		if m.discoveredTopics != nil {
+			m.topics = m.discoveredTopics()
+			m.topicCursor = max(min(m.topicCursor, len(m.topics)-1), 0)
 		}
 
 		cmds = append(cmds, tea.Tick(time.Second, func(time.Time) tea.Msg {
 			return tickMsg{}
 		}))
 
-	case DDSMessage:
-		m.messages = append(m.messages, msg)
-		m.metrics.MessagesReceived++
-		m.metrics.LastMessageLatency = time.Since(msg.Timestamp)
+	case AlertFired:
+		m.alert, m.alertAt = msg, time.Now()
+
+	case searchResultMsg:
+		// The store may have moved on while the search ran
+		m.search = m.search.found(msg).caughtUp(m.store.Snapshot())
+
+	case subscribeResultMsg:
+		if msg.err != nil {
+			m.status = "❌ " + msg.err.Error()
+		} else {
+			m.status = "Subscribed to " + msg.topic
+		}
+
+	case storeUpdatedMsg:
+		// Catch up with everything received since the last update, which
+		// may be several messages
+		snap := m.store.Snapshot()
+		for _, msg := range snap.Since(m.seen) {
+			for _, pane := range m.panes {
+				pane.Update(msg)
+			}
+			if msg.Key != "" {
+				if m.instances == nil {
+					m.instances = instanceTable{}
+				}
+				m.instances.update(msg)
+			}
+			m.metrics.LastMessageLatency = msg.Latency()
+			if msg.Burst {
+				m.inBurst = true
+			}
+		}
+		m.metrics.MessagesReceived += int(snap.Total - m.seen)
+		m.messages, m.seen = snap.Messages, snap.Total
+		m.search = m.search.caughtUp(snap)
+		if m.table.enabled && m.table.cursor > 0 {
+			m.table = m.table.follow(m.tableRows())
+		}
 
 		// Calculate message rate (messages per second over last 10 seconds)
 		if len(m.messageRateHist) > 0 {
 			m.metrics.MessageRate = float64(m.metrics.MessagesReceived) / float64(len(m.messageRateHist))
 		}
-
-		// Keep only last 15 messages
-		if len(m.messages) > 15 {
-			m.messages = m.messages[1:]
-		}
+		cmds = append(cmds, m.store.Wait())
 
 	case metricsMsg:
 		m.metrics = SystemMetrics(msg)
 
+	case publishResultMsg:
+		if msg.err != nil {
+			m.prompt.status = "❌ " + msg.err.Error()
+			m.metrics.ErrorCount++
+		} else {
+			m.prompt.status = "✅ Published to " + msg.topic
+			m.metrics.MessagesPublished++
+		}
+
 	case spinner.TickMsg:
 		var cmd tea.Cmd
 		m.spinner, cmd = m.spinner.Update(msg)
@@ -188,6 +480,13 @@ func (m model) Update(msg tea.Msg) (tea.Model, tea.Cmd) {
 }
 
 func (m model) View() string {
+	defer timeStage(stageRender)()
+	start := time.Now()
+	defer func() { m.frames.observeDraw(time.Since(start)) }()
+	span := startSpan("render", spanContext{})
+	span.SetInt("messages", int64(len(m.messages)))
+	defer span.End()
+
 	if m.width == 0 {
 		return "Loading..."
 	}
@@ -209,19 +508,34 @@ func (m model) View() string {
 	var content string
 	switch tabs[m.activeTab].key {
 	case "dashboard":
-		content = m.renderDashboard(primaryColor, successColor, warningColor, textColor, dimColor)
+		content = m.renderDashboard(primaryColor, successColor, warningColor, textColor, dimColor, accentColor)
 	case "messages":
+		if m.mode == modeDetail {
+			content = m.renderDetail(primaryColor, textColor, dimColor)
+			break
+		}
 		content = m.renderMessages(primaryColor, textColor, dimColor)
 	case "charts":
 		content = m.renderCharts(primaryColor, textColor, dimColor, accentColor)
+	case "topics":
+		content = m.renderTopics(primaryColor, textColor, dimColor)
+	case "participants":
+		content = m.renderParticipants(primaryColor, textColor, dimColor)
 	case "settings":
 		content = m.renderSettings(primaryColor, textColor, dimColor)
 	default:
-		content = m.renderDashboard(primaryColor, successColor, warningColor, textColor, dimColor)
+		if pane := m.activePane(); pane != nil {
+			content = m.renderPane(pane, primaryColor)
+		} else {
+			content = m.renderDashboard(primaryColor, successColor, warningColor, textColor, dimColor, accentColor)
+		}
 	}
 
 	// Footer with navigation hints
-	footer := m.renderFooter(dimColor)
+	footer := m.renderFooter(primaryColor, textColor, dimColor, warningColor)
+	if m.showFrames {
+		footer = lipgloss.JoinVertical(lipgloss.Left, m.frames.render(textColor, dimColor, warningColor), footer)
+	}
 
 	return lipgloss.JoinVertical(lipgloss.Left, tabBar, content, footer)
 }
@@ -264,7 +578,7 @@ func (m model) renderTabBar(primaryColor, textColor, dimColor, accentColor lipgl
 }
 
 // Render the dashboard tab
-func (m model) renderDashboard(primaryColor, successColor, warningColor, textColor, dimColor lipgloss.Color) string {
+func (m model) renderDashboard(primaryColor, successColor, warningColor, textColor, dimColor, accentColor lipgloss.Color) string {
 	cardStyle := lipgloss.NewStyle().
 		Border(lipgloss.RoundedBorder()).
 		BorderForeground(primaryColor).
@@ -298,6 +612,9 @@ func (m model) renderDashboard(primaryColor, successColor, warningColor, textCol
 	if m.usingRealDDS {
 		connectionStatus = "✅ Real DDS"
 	}
+	if m.sessionLabel != "" {
+		connectionStatus += " • " + m.sessionLabel
+	}
 
 	// Calculate connection progress
 	connectionProgress := 0.8
@@ -314,7 +631,7 @@ func (m model) renderDashboard(primaryColor, successColor, warningColor, textCol
 		rateProgress = 1.0
 	}
 
-	metricsContent := lipgloss.JoinVertical(lipgloss.Left,
+	overview := []string{
 		headerStyle.Render("📊 System Overview"),
 		lipgloss.JoinHorizontal(lipgloss.Left,
 			metricLabelStyle.Render("Status:"),
@@ -332,6 +649,12 @@ func (m model) renderDashboard(primaryColor, successColor, warningColor, textCol
 			metricLabelStyle.Render("Message Rate:"),
 			metricValueStyle.Render(fmt.Sprintf("%.1f/sec", m.metrics.MessageRate)),
 		),
+		m.renderDeadlines(metricLabelStyle, metricValueStyle, warningColor),
+	}
+	if len(m.writers) > 0 {
+		overview = append(overview, m.renderLiveliness(metricLabelStyle, metricValueStyle, warningColor))
+	}
+	overview = append(overview,
 		"",
 		lipgloss.JoinHorizontal(lipgloss.Left,
 			metricLabelStyle.Render("Connection Health:"),
@@ -342,6 +665,7 @@ func (m model) renderDashboard(primaryColor, successColor, warningColor, textCol
 			m.progressBar.ViewAs(rateProgress),
 		),
 	)
+	metricsContent := lipgloss.JoinVertical(lipgloss.Left, overview...)
 	sections = append(sections, cardStyle.Render(metricsContent))
 
 	// Quick charts section
@@ -353,9 +677,71 @@ func (m model) renderDashboard(primaryColor, successColor, warningColor, textCol
 		sections = append(sections, cardStyle.Render(chartContent))
 	}
 
+	// Top topics section
+	sections = append(sections, cardStyle.Render(m.renderLeaderboard(headerStyle, textColor, dimColor, accentColor)))
+
+	// Keyed topics section
+	if len(m.instances) > 0 {
+		sections = append(sections, cardStyle.Render(m.instances.render(headerStyle, textColor, dimColor, warningColor)))
+	}
+
 	return lipgloss.JoinVertical(lipgloss.Left, sections...)
 }
 
+// Render the missed deadline counter, highlighted while a topic recently
+// missed one
+func (m model) renderDeadlines(labelStyle, valueStyle lipgloss.Style, warningColor lipgloss.Color) string {
+	total := 0
+	for _, n := range m.deadlineCounts {
+		total += n
+	}
+	value := valueStyle.Render(fmt.Sprintf("%d", total))
+	if m.deadlineAlert != "" && time.Since(m.deadlineAlertAt) < deadlineAlertTime {
+		alertStyle := lipgloss.NewStyle().Foreground(warningColor).Bold(true)
+		value = alertStyle.Render(fmt.Sprintf("%d  ⚠️  missed on %s", total, m.deadlineAlert))
+	}
+	return lipgloss.JoinHorizontal(lipgloss.Left, labelStyle.Render("Deadlines Missed:"), value)
+}
+
+// Render how many matched writers are alive, naming the dead ones
+func (m model) renderLiveliness(labelStyle, valueStyle lipgloss.Style, warningColor lipgloss.Color) string {
+	alive := 0
+	var dead []string
+	for _, writer := range m.writers {
+		if writer.Alive {
+			alive++
+		} else {
+			dead = append(dead, fmt.Sprintf("%s on %s", writer.GUID, writer.Topic))
+		}
+	}
+	value := valueStyle.Render(fmt.Sprintf("%d alive", alive))
+	if len(dead) > 0 {
+		deadStyle := lipgloss.NewStyle().Foreground(warningColor).Bold(true)
+		value = deadStyle.Render(fmt.Sprintf("%d alive, %d dead  ⚠️  %s", alive, len(dead), strings.Join(dead, ", ")))
+	}
+	return lipgloss.JoinHorizontal(lipgloss.Left, labelStyle.Render("Remote Writers:"), value)
+}
+
+// Pick up the deadlines missed since the last tick, raising an alert for
+// the topics that missed one
+func (m *model) refreshDeadlines() {
+	if m.deadlines == nil {
+		return
+	}
+	counts := m.deadlines()
+	var missed []string
+	for _, topic := range sortedKeys(counts) {
+		if counts[topic] > m.deadlineCounts[topic] {
+			missed = append(missed, topic)
+		}
+	}
+	if len(missed) > 0 {
+		m.deadlineAlert = strings.Join(missed, ", ")
+		m.deadlineAlertAt = time.Now()
+	}
+	m.deadlineCounts = counts
+}
+
 // Render the messages tab
 func (m model) renderMessages(primaryColor, textColor, dimColor lipgloss.Color) string {
 	cardStyle := lipgloss.NewStyle().
@@ -377,17 +763,32 @@ func (m model) renderMessages(primaryColor, textColor, dimColor lipgloss.Color)
 		Foreground(dimColor).
 		Italic(true)
 
+	topicStyle := lipgloss.NewStyle().
+		Foreground(primaryColor)
+
 	var sections []string
 
 	// Messages section
 	messageContent := headerStyle.Render("💬 DDS Message Stream") + "\n"
-	if len(m.messages) == 0 {
+	stream := m.visibleMessages()
+	if len(stream) > streamLength {
+		stream = stream[len(stream)-streamLength:]
+	}
+	if m.table.enabled {
+		messageContent = headerStyle.Render("📋 DDS Message Table") + "\n" +
+			m.renderTable(primaryColor, textColor, dimColor)
+	} else if len(stream) == 0 && m.query() != "" {
+		messageContent += lipgloss.NewStyle().
+			Foreground(dimColor).
+			Italic(true).
+			Render(fmt.Sprintf("No messages match %q", m.query()))
+	} else if len(m.messages) == 0 {
 		messageContent += lipgloss.NewStyle().
 			Foreground(dimColor).
 			Italic(true).
 			Render("Waiting for messages... " + m.spinner.View())
 	} else {
-		for i, msg := range m.messages {
+		for i, msg := range stream {
 			var indicator string
 			age := time.Since(msg.Timestamp)
 			if age < 5*time.Second {
@@ -398,14 +799,15 @@ func (m model) renderMessages(primaryColor, textColor, dimColor lipgloss.Color)
 				indicator = "⚪"
 			}
 
-			messageContent += fmt.Sprintf("%s %s %s\n",
+			messageContent += fmt.Sprintf("%s %s%s %s\n",
 				indicator,
-				messageStyle.Render(msg.Content),
-				timestampStyle.Render(msg.Timestamp.Format("15:04:05")),
+				topicStyle.Render("["+topicLabel(msg)+"]"),
+				messageStyle.Render(m.formats.Render(msg)),
+				timestampStyle.Render(m.times.Format("messages", msg.Timestamp, "s")),
 			)
 
 			// Add separator for readability
-			if i < len(m.messages)-1 {
+			if i < len(stream)-1 {
 				messageContent += lipgloss.NewStyle().
 					Foreground(dimColor).
 					Render("  ├─────────────────────────") + "\n"
@@ -420,8 +822,18 @@ func (m model) renderMessages(primaryColor, textColor, dimColor lipgloss.Color)
 		fmt.Sprintf("Current Rate: %.1f msg/sec", m.metrics.MessageRate),
 		fmt.Sprintf("Average Latency: %v", m.metrics.LastMessageLatency),
 	)
+	if focus := m.focus.String(); focus != "" {
+		statsContent = lipgloss.JoinVertical(lipgloss.Left, statsContent,
+			lipgloss.NewStyle().Foreground(dimColor).Render("Hidden from view: "+focus))
+	}
 
+	if m.prompt.active || m.prompt.status != "" {
+		sections = append(sections, cardStyle.Render(m.prompt.render(primaryColor, textColor, dimColor, m.profile)))
+	}
 	sections = append(sections, cardStyle.Render(messageContent))
+	if m.showFields {
+		sections = append(sections, cardStyle.Render(m.renderFields(headerStyle, textColor, dimColor)))
+	}
 	sections = append(sections, cardStyle.Render(statsContent))
 
 	return lipgloss.JoinVertical(lipgloss.Left, sections...)
@@ -447,7 +859,7 @@ func (m model) renderCharts(primaryColor, textColor, dimColor, accentColor lipgl
 		chartContent := lipgloss.JoinVertical(lipgloss.Left,
 			headerStyle.Render("📈 Message Rate Over Time"),
 			renderSparkline(m.messageRateHist),
-			"",
+			lipgloss.NewStyle().Foreground(accentColor).Render(renderBurstMarkers(m.burstHist)),
 			lipgloss.NewStyle().Foreground(dimColor).Render("Legend: ▁▂▃▄▅▆▇█ (Low to High)"),
 		)
 		sections = append(sections, cardStyle.Render(chartContent))
@@ -481,6 +893,18 @@ func (m model) renderCharts(primaryColor, textColor, dimColor, accentColor lipgl
 	)
 	sections = append(sections, cardStyle.Render(perfContent))
 
+	// Bandwidth accounting
+	sections = append(sections, cardStyle.Render(m.renderBandwidth(headerStyle, textColor, dimColor)))
+
+	// Recent bursts
+	sections = append(sections, cardStyle.Render(m.renderBursts(headerStyle, textColor, dimColor)))
+
+	// Inter-arrival time distribution and jitter
+	sections = append(sections, cardStyle.Render(m.renderJitter(headerStyle, textColor, dimColor)))
+
+	// Time spent per pipeline stage
+	sections = append(sections, cardStyle.Render(renderPipeline(headerStyle, textColor, dimColor, accentColor)))
+
 	return lipgloss.JoinVertical(lipgloss.Left, sections...)
 }
 
@@ -509,25 +933,55 @@ func (m model) renderSettings(primaryColor, textColor, dimColor lipgloss.Color)
 			return "Mock DDS (Development)"
 		}()),
 		fmt.Sprintf("Update Interval: 1 second"),
-		fmt.Sprintf("Max Message History: 15 messages"),
+		fmt.Sprintf("Max Message History: %d messages", maxMessageHistory),
 		fmt.Sprintf("Chart History: %d data points", len(m.messageRateHist)),
 	)
 	sections = append(sections, cardStyle.Render(configContent))
 
 	// Controls section
-	controlsContent := lipgloss.JoinVertical(lipgloss.Left,
+	controls := []string{
 		headerStyle.Render("🎮 Controls"),
 		"Tab Navigation:",
-		"  • [1-4] or Tab/Shift+Tab: Switch tabs",
+		"  • [1-6] or Tab/Shift+Tab: Switch tabs",
 		"  • ←/→ or h/l: Navigate tabs",
 		"  • q or Ctrl+C: Quit",
+		"  • :: Type a command (see below)",
+		"  • u: Undo the last change to the view",
+		"  • F: Toggle the frame times overlay",
+		"",
+		"Dashboard Tab:",
+		"  • o/w: Cycle top topics ranking / window",
+		"",
+		"Messages Tab:",
+		"  • v: Toggle stream/table view",
+		"  • f: Toggle the field tree of the newest structured payload",
+		"  • p: Publish a message (protected profiles ask for the topic name)",
+		"  • s/r: Cycle sort column / reverse order",
+		"  • g: Cycle grouping (none/topic/key)",
+		"  • /: Filter messages by topic or payload",
+		"  • ↑/↓ or j/k: Move cursor, Enter: Open message or collapse group",
+		"  • PgUp/PgDn, Home/End: Scroll the table by a page, to the top or bottom",
+		"",
+		"Topics Tab:",
+		"  • ↑/↓ or j/k: Move cursor, Enter: Subscribe and show its messages",
+		"  • m/s: Mute or solo the topic on the messages tab, c: Show all again",
 		"",
 		"Tabs Available:",
 		"  • [1] Dashboard: System overview",
 		"  • [2] Messages: DDS message stream",
-		"  • [3] Charts: Performance visualizations",
-		"  • [4] Settings: Configuration & help",
-	)
+		"  • [3] Topics: Topics discovered in the domain",
+		"  • [4] Charts: Performance visualizations",
+		"  • [5] Participants: Other participants of the domain",
+		"  • [6] Settings: Configuration & help",
+	}
+	for i, pane := range m.panes {
+		controls = append(controls, fmt.Sprintf("  • [%d] %s: Custom pane", i+7, pane.Title()))
+	}
+	controls = append(controls, "", "Commands:")
+	for _, command := range tuiCommands {
+		controls = append(controls, fmt.Sprintf("  • :%s: %s", command[0], command[1]))
+	}
+	controlsContent := lipgloss.JoinVertical(lipgloss.Left, controls...)
 	sections = append(sections, cardStyle.Render(controlsContent))
 
 	// About section
@@ -549,13 +1003,25 @@ func (m model) renderSettings(primaryColor, textColor, dimColor lipgloss.Color)
 }
 
 // Render the footer
-func (m model) renderFooter(dimColor lipgloss.Color) string {
+func (m model) renderFooter(primaryColor, textColor, dimColor, warningColor lipgloss.Color) string {
+	if line := m.renderInputLine(primaryColor, textColor, dimColor); line != "" {
+		return lipgloss.NewStyle().Width(m.width - 4).Render(line)
+	}
+	if m.alert.Text != "" && time.Since(m.alertAt) < deadlineAlertTime {
+		return lipgloss.NewStyle().
+			Foreground(warningColor).
+			Bold(true).
+			Width(m.width - 4).
+			Align(lipgloss.Center).
+			Render("⚠️  " + m.alert.Text)
+	}
+
 	footer := lipgloss.NewStyle().
 		Foreground(dimColor).
 		Italic(true).
 		Width(m.width - 4).
 		Align(lipgloss.Center).
-		Render("Cardinal v1.0 • Use Tab/1-4 to navigate • q to quit")
+		Render(fmt.Sprintf("Cardinal v1.0 • Use Tab/1-%d to navigate • q to quit", min(len(tabs), 9)))
 
 	return footer
 }
@@ -638,11 +1104,11 @@ func renderSparkline(data []float64) string {
 }
 
 // Hello World Publisher Thread
-func helloWorldPublisher(ctx context.Context, pub DDSPublisher, wg *sync.WaitGroup) {
+func helloWorldPublisher(ctx context.Context, pub DDSPublisher, interval time.Duration, wg *sync.WaitGroup) {
 	defer wg.Done()
 
 	counter := 0
-	ticker := time.NewTicker(2 * time.Second)
+	ticker := time.NewTicker(interval)
 	defer ticker.Stop()
 
 	for {
@@ -665,46 +1131,348 @@ func helloWorldPublisher(ctx context.Context, pub DDSPublisher, wg *sync.WaitGro
 	}
 }
 
-// TUI Subscriber Thread
-func tuiSubscriber(ctx context.Context, sub DDSSubscriber, program *tea.Program, wg *sync.WaitGroup) {
-	defer wg.Done()
-
-	msgChan := sub.Subscribe()
+func main() {
+	opts := mustParseOptions()
+
+	switch opts.Command {
+	case "ctl":
+		os.Exit(runCtl(opts))
+	case "attach":
+		os.Exit(runAttach(opts))
+	case "echo":
+		os.Exit(runEcho(opts))
+	case "pub":
+		os.Exit(runPub(opts))
+	case "verify":
+		os.Exit(runVerify(opts))
+	case "diff":
+		os.Exit(runDiff(opts))
+	case "view":
+		os.Exit(runView(opts))
+	case "gen":
+		os.Exit(runGen(opts))
+	case "scenario":
+		os.Exit(runScenarioCommand(opts))
+	case "assert":
+		os.Exit(runAssert(opts))
+	case "import":
+		os.Exit(runImport(opts))
+	case "replay":
+		os.Exit(runReplay(opts))
+	case "stats":
+		os.Exit(runStats(opts))
+	case "export":
+		os.Exit(runExport(opts))
+	}
 
-	for {
-		select {
-		case <-ctx.Done():
-			log.Println("TUI Subscriber: Shutting down...")
-			return
-		case msg := <-msgChan:
-			// Send the DDS message to the TUI
-			program.Send(msg)
-		}
+	settings, err := loadSettings(opts)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal:", err)
+		os.Exit(exitUsage)
 	}
-}
 
-func main() {
 	fmt.Println("🚀 Starting Cardinal - Enhanced TUI with Tabs")
 
+	if opts.PprofAddr != "" {
+		startProfiling(opts.PprofAddr)
+	}
+
+	domains := opts.Domains
+	if len(domains) == 0 {
+		domains = []int{settings.Profile.Domain}
+	}
+
 	// Try real Fast DDS first, fallback to mock
-	realPub, realSub, err := NewRealDDSSystem(0, "hello_topic")
+	transport, err := openDomains(domains, func(domain int) (Transport, error) {
+		return NewFastDDSTransport(domain, settings.QoS)
+	})
+	var sub DDSSubscriber
+	if err == nil {
+		transport = withPayloadCipher(transport, settings.Cipher)
+		sub, err = transport.NewSubscriber(opts.Topics[0])
+	}
 	if err != nil {
 		// Fallback to mock DDS
 		fmt.Println("⚠️  Real DDS failed, using mock DDS:", err)
-		pub, sub := NewDDSSystem()
-		runApplication(pub, sub, false)
+		transport, _ = openDomains(domains, func(int) (Transport, error) {
+			return NewMockTransport(), nil
+		})
+		transport = withPayloadCipher(transport, settings.Cipher)
+		sub, _ = transport.NewSubscriber(opts.Topics[0])
+		runApplication(opts, settings, transport, sub, false)
 	} else {
 		fmt.Println("✅ Using real Fast DDS!")
+		runApplication(opts, settings, transport, sub, true)
+	}
+}
+
+func runApplication(opts Options, settings Settings, transport Transport, sub DDSSubscriber, usingReal bool) {
+	startTime := time.Now()
+	if opts.ReadOnly {
+		transport = readOnlyTransport{transport}
+	}
+	stats := NewStatsTracker()
+	bursts := NewBurstDetector()
+
+	// Create context for graceful shutdown. Without a TUI there is no quit
+	// key, so headless runs stop on SIGINT/SIGTERM instead.
+	var ctx context.Context
+	var cancel context.CancelFunc
+	if opts.Headless {
+		ctx, cancel = signal.NotifyContext(context.Background(), os.Interrupt, syscall.SIGTERM)
+	} else {
+		ctx, cancel = context.WithCancel(context.Background())
+	}
+	defer cancel()
+	var wg sync.WaitGroup
+
+	// Received messages go to the TUI, or to the log when headless. The
+	// session is created below, before the TUI can publish through it.
+	var session *Session
+	handler := logMessage
+	var program *tea.Program
+	if !opts.Headless {
+		config := programConfig{usingReal: usingReal, profile: settings.Profile, bursts: bursts, formats: settings.Formats, times: settings.Times}
+		var labels []string
+		if opts.ReadOnly {
+			labels = append(labels, "Read-only")
+		} else {
+			config.publish = func(topic, content string) error {
+				return session.Publish(topic, content)
+			}
+		}
+		config.deadlines = func() map[string]int {
+			return session.DeadlinesMissed()
+		}
+		config.writers = func() []RemoteWriter {
+			return session.RemoteWriters()
+		}
+		if _, ok := baseTransport(transport).(discoveryWatcher); ok {
+			config.participants = func() []RemoteParticipant {
+				return session.Participants()
+			}
+			config.topics = func() []DiscoveredTopic {
+				return session.DiscoveredTopics()
+			}
+			config.subscribe = func(topic string) error {
+				return session.SubscribeDiscovered(topic)
+			}
+		}
+		if settings.Profile.Name != "default" {
+			labels = append(labels, "Profile "+settings.Profile.Name)
+		}
+		if len(opts.Domains) > 1 {
+			labels = append(labels, "Domains "+opts.Domains.String())
+		}
+		config.sessionLabel = strings.Join(labels, " • ")
+		var store *messageStore
+		program, store = newProgram(stats, startTime, config)
+		handler = func(msg DDSMessage) {
+			// Hand the DDS message to the TUI without waiting for it
+			span := startSpan("enqueue", msg.Trace)
+			span.SetInt("messages", 1)
+			store.Append(msg)
+			span.End()
+		}
+	}
+
+	// Upload finished recordings; the deferred Close runs after the
+	// session's, so it waits for the last recording too
+	uploader := StartUploader(settings.Upload)
+	defer uploader.Close()
+
+	session = NewSession(ctx, &wg, transport, stats, startTime, handler)
+	session.cipher = settings.Cipher
+	session.redactor = settings.Redactor
+	if !opts.StatsOnly {
+		// Duplicates are found by payload hash, which stats-only skips
+		session.duplicates = NewDuplicateDetector(opts.DupWindow)
+	}
+	session.ordering = NewOrderChecker()
+	session.bursts = bursts
+	session.events.Subscribe(settings.Hooks.OnEvent)
+	if program != nil {
+		// Show alerts in the TUI's footer
+		session.events.Subscribe(func(ev Event) {
+			if alert, ok := ev.(AlertFired); ok {
+				program.Send(alert)
+			}
+		})
+	}
+	session.budgets = NewBudgetChecker(settings.QoS)
+	session.ignore = settings.Ignore
+	session.hideOwn = opts.HideOwn
+	session.recFilter = settings.Record
+	session.uploader = uploader
+	session.statsOnly = opts.StatsOnly
+	session.recOpts = recorderOptions{
+		decimation: settings.Decimate,
+		metadata:   sessionMetadata(settings, transport, startTime),
+		chunks:     settings.Chunks,
+		finished:   uploader.Enqueue,
+	}
+	session.trackReaders()
+	session.watchDiscovery()
+	if opts.MonoLatency {
+		session.clock = NewLatencyClock()
+	}
+	if opts.StatsOnly && len(settings.Sinks) > 0 {
+		log.Printf("Statistics only: not starting the %d configured sinks", len(settings.Sinks))
+		settings.Sinks = nil
+	}
+	for _, spec := range settings.Sinks {
+		sink, err := StartSink(spec)
+		if err != nil {
+			log.Printf("Error starting sink: %v", err)
+			continue
+		}
+		session.sinks = append(session.sinks, sink)
+	}
+	defer session.Close()
+
+	// Export pipeline spans and metrics when a collector is configured
+	if opts.OTLPEndpoint != "" {
+		exporter := newOTLPExporter(opts.OTLPEndpoint)
+		startTracing(ctx, exporter)
+		startMetricsExport(ctx, exporter, stats, startTime)
+	}
+
+	// Keep cumulative statistics across restarts
+	if opts.PersistStats {
+		if err := startStatsPersistence(ctx, &wg, opts.StatsDB, stats, startTime); err != nil {
+			log.Printf("Error loading statistics: %v", err)
+		}
+	}
+
+	// Raise alerts, and run their hooks, as topics go silent
+	if opts.HealthMaxAge > 0 {
+		startStaleAlerts(ctx, &wg, session, opts.HealthMaxAge)
+	}
+
+	// Serve health checks for container orchestration
+	if opts.HealthAddr != "" {
+		startHealthServer(opts.HealthAddr, &healthServer{session: session, maxAge: opts.HealthMaxAge})
+	}
+
+	// Serve the control API to remote clients holding a token
+	if opts.APIAddr != "" {
+		if opts.APITokens == "" {
+			log.Printf("Error starting API: --api-addr requires --api-tokens")
+			sub.Cleanup()
+			return
+		}
+		tokens, err := loadAPITokens(opts.APITokens)
+		if err != nil {
+			log.Printf("Error starting API: %v", err)
+			sub.Cleanup()
+			return
+		}
+		tlsConfig, err := loadAPITLS(opts.TLSCert, opts.TLSKey)
+		if err != nil {
+			log.Printf("Error starting API: %v", err)
+			sub.Cleanup()
+			return
+		}
+		startAPIServer(opts.APIAddr, &apiServer{session: session, profile: settings.Profile, tokens: tokens, recordings: opts.APIRecordings}, tlsConfig)
+	}
 
-		// Cleanup real DDS on exit
-		defer realPub.Cleanup()
-		defer realSub.Cleanup()
+	// Accept control commands from `cardinal ctl`, and read-only viewers
+	// on the shared socket
+	if opts.ControlSocket != "" {
+		if err := startControlServer(ctx, opts.ControlSocket, session, false); err != nil {
+			log.Printf("Error starting control socket: %v", err)
+			sub.Cleanup()
+			return
+		}
+	}
+	if opts.ShareSocket != "" {
+		if err := startControlServer(ctx, opts.ShareSocket, session, true); err != nil {
+			log.Printf("Error starting shared socket: %v", err)
+			sub.Cleanup()
+			return
+		}
+	}
 
-		runApplication(realPub, realSub, true)
+	// Every topic gets its own subscriber and queue; the first one was
+	// created to probe the transport
+	session.AddSubscriber(opts.Topics[0], sub)
+	for _, topic := range opts.Topics[1:] {
+		if err := session.Subscribe(topic); err != nil {
+			log.Printf("Error: %v", err)
+		}
 	}
+
+	// Start a hello world publisher thread per topic. It is opt-in so that
+	// Cardinal never creates a DataWriter on a real domain unless asked to.
+	if opts.DemoPublisher && opts.ReadOnly {
+		log.Printf("Read-only mode: demo publisher disabled")
+	} else if opts.DemoPublisher {
+		for _, topic := range opts.Topics {
+			pub, err := transport.NewPublisher(topic)
+			if err != nil {
+				log.Printf("Error creating publisher: %v", err)
+				continue
+			}
+			defer pub.Cleanup()
+			session.AddOwnPublisher(pub)
+			wg.Add(1)
+			go runTask(ctx, "publisher", func(ctx context.Context) {
+				helloWorldPublisher(ctx, pub, opts.DemoInterval, &wg)
+			})
+		}
+	}
+
+	// Publish Cardinal's own heartbeat for external monitoring
+	if opts.Heartbeat != "" {
+		if opts.ReadOnly {
+			log.Printf("Read-only mode: heartbeat disabled")
+		} else if pub, err := transport.NewPublisher(opts.Heartbeat); err != nil {
+			log.Printf("Error creating heartbeat publisher: %v", err)
+		} else {
+			defer pub.Cleanup()
+			session.AddOwnPublisher(pub)
+			wg.Add(1)
+			go runTask(ctx, "heartbeat", func(ctx context.Context) {
+				publishHeartbeat(ctx, pub, session, settings.Profile.Name, opts.BeatInterval, &wg)
+			})
+		}
+	}
+
+	// Handle program termination
+	if program != nil {
+		go runTask(ctx, "ui", func(context.Context) {
+			if _, err := program.Run(); err != nil {
+				log.Printf("Error running program: %v", err)
+			}
+			cancel() // Signal all goroutines to stop
+		})
+	}
+
+	// Wait for all goroutines to finish
+	wg.Wait()
+
+	fmt.Println("Cardinal application terminated.")
 }
 
-func runApplication(pub DDSPublisher, sub DDSSubscriber, usingReal bool) {
+// How the TUI presents its session
+type programConfig struct {
+	usingReal    bool
+	sessionLabel string // where the data comes from, e.g. an attached daemon
+	profile      Profile
+	publish      publishFunc // nil when the TUI cannot publish
+	bursts       *BurstDetector
+	formats      displayFormats
+	times        timeFormats
+	deadlines    func() map[string]int      // nil when the session cannot tell
+	writers      func() []RemoteWriter      // nil when the session cannot tell
+	participants func() []RemoteParticipant // nil when the transport cannot tell
+	topics       func() []DiscoveredTopic   // nil when the transport cannot tell
+	subscribe    func(topic string) error
+}
+
+// Create the Bubble Tea program for the TUI, and the store received messages
+// are handed to it through
+func newProgram(stats *StatsTracker, startTime time.Time, config programConfig) (*tea.Program, *messageStore) {
 	// Initialize spinner
 	s := spinner.New()
 	s.Spinner = spinner.Dot
@@ -715,43 +1483,37 @@ func runApplication(pub DDSPublisher, sub DDSSubscriber, usingReal bool) {
 	connBar := progress.New(progress.WithDefaultGradient())
 
 	// Create TUI model
+	store := newMessageStore()
 	m := model{
+		store:           store,
 		messages:        []DDSMessage{},
 		metrics:         SystemMetrics{ConnectionStatus: "Initializing..."},
-		startTime:       time.Now(),
+		startTime:       startTime,
 		spinner:         s,
 		progressBar:     prog,
 		connectionBar:   connBar,
 		messageRateHist: []float64{},
-		usingRealDDS:    usingReal,
+		usingRealDDS:    config.usingReal,
 		activeTab:       0, // Start with Dashboard tab
+		table:           newTableState(),
+		rows:            &rowCache{},
+		stats:           stats,
+		sessionLabel:    config.sessionLabel,
+		profile:         config.profile,
+		publish:         config.publish,
+		bursts:          config.bursts,
+		formats:         config.formats,
+		times:           config.times,
+		deadlines:       config.deadlines,
+		remoteWriters:   config.writers,
+		panes:           newPanes(),
+		frames:          &frameProfiler{},
+
+		remoteParticipants: config.participants,
+		discoveredTopics:   config.topics,
+		subscribe:          config.subscribe,
 	}
 
 	// Create Bubble Tea program
-	program := tea.NewProgram(m, tea.WithAltScreen())
-
-	// Create context for graceful shutdown
-	ctx, cancel := context.WithCancel(context.Background())
-	var wg sync.WaitGroup
-
-	// Start the hello world publisher thread
-	wg.Add(1)
-	go helloWorldPublisher(ctx, pub, &wg)
-
-	// Start the TUI subscriber thread
-	wg.Add(1)
-	go tuiSubscriber(ctx, sub, program, &wg)
-
-	// Handle program termination
-	go func() {
-		if _, err := program.Run(); err != nil {
-			log.Printf("Error running program: %v", err)
-		}
-		cancel() // Signal all goroutines to stop
-	}()
-
-	// Wait for all goroutines to finish
-	wg.Wait()
-
-	fmt.Println("Cardinal application terminated.")
+	return tea.NewProgram(m, tea.WithAltScreen()), store
 }
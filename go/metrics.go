@@ -0,0 +1,227 @@
+package main
+
+import (
+	"context"
+	"log"
+	"strconv"
+	"time"
+
+	"cardinal/core"
+)
+
+// How often metrics are pushed to the OTLP endpoint
+const metricsExportInterval = 10 * time.Second
+
+// OTLP aggregation temporality for running totals
+const otlpTemporalityCumulative = 2
+
+type otlpNumberDataPoint struct {
+	Attributes        []otlpKeyValue `json:"attributes,omitempty"`
+	StartTimeUnixNano string         `json:"startTimeUnixNano,omitempty"`
+	TimeUnixNano      string         `json:"timeUnixNano"`
+	AsInt             *string        `json:"asInt,omitempty"`
+	AsDouble          *float64       `json:"asDouble,omitempty"`
+}
+
+type otlpSum struct {
+	AggregationTemporality int                   `json:"aggregationTemporality"`
+	IsMonotonic            bool                  `json:"isMonotonic"`
+	DataPoints             []otlpNumberDataPoint `json:"dataPoints"`
+}
+
+type otlpGauge struct {
+	DataPoints []otlpNumberDataPoint `json:"dataPoints"`
+}
+
+type otlpHistogramDataPoint struct {
+	Attributes        []otlpKeyValue `json:"attributes,omitempty"`
+	StartTimeUnixNano string         `json:"startTimeUnixNano,omitempty"`
+	TimeUnixNano      string         `json:"timeUnixNano"`
+	Count             string         `json:"count"`
+	Sum               float64        `json:"sum"`
+	Min               float64        `json:"min"`
+	Max               float64        `json:"max"`
+	BucketCounts      []string       `json:"bucketCounts"`
+	ExplicitBounds    []float64      `json:"explicitBounds"`
+}
+
+type otlpHistogram struct {
+	AggregationTemporality int                      `json:"aggregationTemporality"`
+	DataPoints             []otlpHistogramDataPoint `json:"dataPoints"`
+}
+
+type otlpMetric struct {
+	Name        string         `json:"name"`
+	Description string         `json:"description,omitempty"`
+	Unit        string         `json:"unit,omitempty"`
+	Sum         *otlpSum       `json:"sum,omitempty"`
+	Gauge       *otlpGauge     `json:"gauge,omitempty"`
+	Histogram   *otlpHistogram `json:"histogram,omitempty"`
+}
+
+type otlpScopeMetrics struct {
+	Scope   otlpScope    `json:"scope"`
+	Metrics []otlpMetric `json:"metrics"`
+}
+
+type otlpResourceMetrics struct {
+	Resource     otlpResource       `json:"resource"`
+	ScopeMetrics []otlpScopeMetrics `json:"scopeMetrics"`
+}
+
+type otlpMetricsRequest struct {
+	ResourceMetrics []otlpResourceMetrics `json:"resourceMetrics"`
+}
+
+// Builds the data points of one metrics export
+type metricsBatch struct {
+	start   string
+	now     string
+	metrics []otlpMetric
+}
+
+func (b *metricsBatch) intPoint(value int64, attrs ...otlpKeyValue) otlpNumberDataPoint {
+	s := strconv.FormatInt(value, 10)
+	return otlpNumberDataPoint{Attributes: attrs, StartTimeUnixNano: b.start, TimeUnixNano: b.now, AsInt: &s}
+}
+
+func (b *metricsBatch) doublePoint(value float64, attrs ...otlpKeyValue) otlpNumberDataPoint {
+	return otlpNumberDataPoint{Attributes: attrs, StartTimeUnixNano: b.start, TimeUnixNano: b.now, AsDouble: &value}
+}
+
+func (b *metricsBatch) counter(name, unit, description string, points []otlpNumberDataPoint) {
+	b.metrics = append(b.metrics, otlpMetric{
+		Name:        name,
+		Unit:        unit,
+		Description: description,
+		Sum: &otlpSum{
+			AggregationTemporality: otlpTemporalityCumulative,
+			IsMonotonic:            true,
+			DataPoints:             points,
+		},
+	})
+}
+
+func (b *metricsBatch) gauge(name, unit, description string, points []otlpNumberDataPoint) {
+	b.metrics = append(b.metrics, otlpMetric{
+		Name:        name,
+		Unit:        unit,
+		Description: description,
+		Gauge:       &otlpGauge{DataPoints: points},
+	})
+}
+
+// Data point of an inter-arrival histogram, in seconds
+func (b *metricsBatch) intervalPoint(h IntervalHistogram, attrs ...otlpKeyValue) otlpHistogramDataPoint {
+	point := otlpHistogramDataPoint{
+		Attributes:        attrs,
+		StartTimeUnixNano: b.start,
+		TimeUnixNano:      b.now,
+		Count:             strconv.Itoa(h.Count),
+		Sum:               h.Mean().Seconds() * float64(h.Count),
+		Min:               h.Min.Seconds(),
+		Max:               h.Max.Seconds(),
+	}
+	for _, n := range h.Buckets {
+		point.BucketCounts = append(point.BucketCounts, strconv.Itoa(n))
+	}
+	for _, bound := range core.IntervalBounds {
+		point.ExplicitBounds = append(point.ExplicitBounds, bound.Seconds())
+	}
+	return point
+}
+
+func (b *metricsBatch) histogram(name, unit, description string, points []otlpHistogramDataPoint) {
+	b.metrics = append(b.metrics, otlpMetric{
+		Name:        name,
+		Unit:        unit,
+		Description: description,
+		Histogram: &otlpHistogram{
+			AggregationTemporality: otlpTemporalityCumulative,
+			DataPoints:             points,
+		},
+	})
+}
+
+// Collect the current per-topic and pipeline metrics
+func collectMetrics(stats *StatsTracker, startTime time.Time) []otlpMetric {
+	now := time.Now()
+	b := &metricsBatch{start: otlpUnixNano(startTime), now: otlpUnixNano(now)}
+
+	var received, bytes, wireBytes, gaps, reordered, repeated, fragmented, lost, overBudget, rates, jitter []otlpNumberDataPoint
+	var intervals []otlpHistogramDataPoint
+	for _, topic := range stats.Topics() {
+		attr := otlpString("topic", topic.Topic)
+		received = append(received, b.intPoint(int64(topic.Total), attr))
+		bytes = append(bytes, b.intPoint(int64(topic.TotalBytes), attr))
+		wireBytes = append(wireBytes, b.intPoint(int64(topic.TotalWireBytes), attr))
+		gaps = append(gaps, b.intPoint(int64(topic.TotalGaps), attr))
+		reordered = append(reordered, b.intPoint(int64(topic.TotalReordered), attr))
+		repeated = append(repeated, b.intPoint(int64(topic.TotalRepeated), attr))
+		fragmented = append(fragmented, b.intPoint(int64(topic.TotalFragmented), attr))
+		lost = append(lost, b.intPoint(int64(topic.Lost), attr))
+		overBudget = append(overBudget, b.intPoint(int64(topic.TotalOverBudget), attr))
+		rates = append(rates, b.doublePoint(topic.Window(now, metricsExportInterval).Rate, attr))
+		if topic.Intervals.Count > 0 {
+			intervals = append(intervals, b.intervalPoint(topic.Intervals, attr))
+			jitter = append(jitter, b.doublePoint(topic.Intervals.StdDev().Seconds(), attr))
+		}
+	}
+	b.counter("cardinal.messages.received", "{message}", "Messages received per topic", received)
+	b.counter("cardinal.bytes.received", "By", "Payload bytes received per topic", bytes)
+	b.counter("cardinal.bytes.wire", "By", "Estimated on-wire bytes received per topic", wireBytes)
+	b.counter("cardinal.gaps", "{gap}", "Inter-arrival gaps detected per topic", gaps)
+	b.counter("cardinal.sequence.reordered", "{message}", "Samples older than one already seen from the same writer", reordered)
+	b.counter("cardinal.sequence.repeated", "{message}", "Samples repeating a writer's sequence number", repeated)
+	b.counter("cardinal.samples.fragmented", "{message}", "Samples estimated to be split into DATA_FRAG submessages per topic, at the default datagram limit", fragmented)
+	b.counter("cardinal.samples.lost", "{message}", "Samples the reader reported lost, incomplete fragmented ones included", lost)
+	b.counter("cardinal.latency.over_budget", "{message}", "Samples slower than their topic's latency budget", overBudget)
+	b.gauge("cardinal.topic.rate", "{message}/s", "Message rate per topic", rates)
+	b.histogram("cardinal.topic.interval", "s", "Time between consecutive messages per topic", intervals)
+	b.gauge("cardinal.topic.jitter", "s", "Standard deviation of the time between messages per topic", jitter)
+
+	var calls, busy []otlpNumberDataPoint
+	for _, s := range pipelineSnapshot() {
+		attr := otlpString("stage", s.name)
+		calls = append(calls, b.intPoint(s.count, attr))
+		busy = append(busy, b.doublePoint(s.total.Seconds(), attr))
+	}
+	b.counter("cardinal.pipeline.calls", "{call}", "Calls per pipeline stage", calls)
+	b.counter("cardinal.pipeline.time", "s", "Time spent per pipeline stage", busy)
+
+	b.gauge("cardinal.uptime", "s", "Time since Cardinal started",
+		[]otlpNumberDataPoint{b.doublePoint(now.Sub(startTime).Seconds())})
+
+	return b.metrics
+}
+
+func (e *otlpExporter) exportMetrics(metrics []otlpMetric) error {
+	return e.post("/v1/metrics", otlpMetricsRequest{
+		ResourceMetrics: []otlpResourceMetrics{{
+			Resource: newOTLPResource(),
+			ScopeMetrics: []otlpScopeMetrics{{
+				Scope:   otlpScope{Name: otlpServiceName},
+				Metrics: metrics,
+			}},
+		}},
+	})
+}
+
+// Push metrics to the OTLP endpoint periodically until ctx is done
+func startMetricsExport(ctx context.Context, exporter *otlpExporter, stats *StatsTracker, startTime time.Time) {
+	go runTask(ctx, "metrics-exporter", func(ctx context.Context) {
+		ticker := time.NewTicker(metricsExportInterval)
+		defer ticker.Stop()
+
+		for {
+			select {
+			case <-ctx.Done():
+				return
+			case <-ticker.C:
+				if err := exporter.exportMetrics(collectMetrics(stats, startTime)); err != nil {
+					log.Printf("Error exporting metrics: %v", err)
+				}
+			}
+		}
+	})
+}
@@ -5,25 +5,13 @@ package main
 
 import "fmt"
 
-// Mock implementation when FastDDS is not available
-type MockRealDDSPublisher struct {
-	*MockDDSPublisher
-}
-
-type MockRealDDSSubscriber struct {
-	*MockDDSSubscriber
-}
-
-func (p *MockRealDDSPublisher) Cleanup() {
-	// Nothing to cleanup in mock
-}
-
-func (s *MockRealDDSSubscriber) Cleanup() {
-	// Nothing to cleanup in mock
+// NewFastDDSTransport reports that Fast DDS is not available in this build
+func NewFastDDSTransport(domainID int, qos *QoSConfig) (Transport, error) {
+	// Always return an error to force fallback to mock DDS
+	return nil, fmt.Errorf("FastDDS not available - using mock DDS")
 }
 
-// NewRealDDSSystem creates a mock DDS system when FastDDS is not available
-func NewRealDDSSystem(domainID int, topic string) (*MockRealDDSPublisher, *MockRealDDSSubscriber, error) {
-	// Always return an error to force fallback to mock DDS
-	return nil, nil, fmt.Errorf("FastDDS not available - using mock DDS")
+// Version of the linked Fast DDS library; none in this build
+func fastDDSVersion() string {
+	return ""
 }
@@ -0,0 +1,384 @@
+package main
+
+import (
+	"fmt"
+	"slices"
+	"strings"
+
+	tea "github.com/charmbracelet/bubbletea"
+	"github.com/charmbracelet/lipgloss"
+)
+
+// The TUI is always in exactly one input mode, which decides what keys do.
+// Update hands every key to the mode's handler, so a key typed into the
+// filter never switches tabs and a new binding only has to be checked
+// against the mode it belongs to.
+type inputMode int
+
+const (
+	modeNormal  inputMode = iota
+	modeFilter            // typing the message filter after /
+	modeCommand           // typing a command after :
+	modeDetail            // showing one message from the table
+	modePublish           // the publish prompt, with its confirmation on protected profiles
+)
+
+// Settings changing what the views show, restored one change at a time by
+// undo. Cursor positions and collapsed groups are navigation, not settings.
+type viewSettings struct {
+	table       bool
+	sortColumn  sortColumn
+	descending  bool
+	groupMode   groupMode
+	showFields  bool
+	filter      string
+	leaderboard leaderboardState
+}
+
+// Most view changes undo remembers
+const maxUndo = 50
+
+func (m model) viewSettings() viewSettings {
+	return viewSettings{
+		table:       m.table.enabled,
+		sortColumn:  m.table.sortColumn,
+		descending:  m.table.descending,
+		groupMode:   m.table.groupMode,
+		showFields:  m.showFields,
+		filter:      m.filter,
+		leaderboard: m.leaderboard,
+	}
+}
+
+func (m *model) restoreView(v viewSettings) {
+	if v.groupMode != m.table.groupMode {
+		m.table.cursor = 0
+	}
+	m.table.enabled, m.table.sortColumn, m.table.descending, m.table.groupMode = v.table, v.sortColumn, v.descending, v.groupMode
+	m.showFields, m.filter, m.leaderboard = v.showFields, v.filter, v.leaderboard
+}
+
+// Restore the view settings before the last change
+func (m *model) undoView() {
+	if len(m.undo) == 0 {
+		m.status = "Nothing to undo"
+		return
+	}
+	m.restoreView(m.undo[len(m.undo)-1])
+	m.undo = m.undo[:len(m.undo)-1]
+	m.status = "Undone"
+}
+
+// Handle a key in the current mode, remembering the view settings before
+// any change it made
+func (m model) handleKey(msg tea.KeyMsg) (model, tea.Cmd) {
+	before, depth := m.viewSettings(), len(m.undo)
+	m.status = ""
+
+	var cmd tea.Cmd
+	switch m.mode {
+	case modeFilter:
+		m = m.handleFilterKey(msg)
+	case modeCommand:
+		m, cmd = m.handleCommandKey(msg)
+	case modeDetail:
+		m = m.handleDetailKey(msg)
+	case modePublish:
+		m.prompt, cmd = m.prompt.handleKey(msg, m.profile, m.publish)
+		if !m.prompt.active {
+			m.mode = modeNormal
+		}
+	default:
+		m, cmd = m.handleNormalKey(msg)
+	}
+
+	// Undo itself shrinks the stack and must not push onto it
+	if m.viewSettings() != before && len(m.undo) >= depth {
+		m.undo = append(m.undo, before)
+		if len(m.undo) > maxUndo {
+			m.undo = m.undo[1:]
+		}
+	}
+	m, search := m.searchChanged()
+	return m, tea.Batch(cmd, search)
+}
+
+func (m model) handleNormalKey(msg tea.KeyMsg) (model, tea.Cmd) {
+	switch msg.String() {
+	case "ctrl+c", "q":
+		return m, tea.Quit
+	case "1", "2", "3", "4", "5", "6", "7", "8", "9":
+		// Switch tabs with number keys
+		if tabIndex := int(msg.String()[0]) - '1'; tabIndex >= 0 && tabIndex < len(tabs) {
+			m.activeTab = tabIndex
+		}
+	case "tab":
+		// Cycle through tabs with Tab key
+		m.activeTab = (m.activeTab + 1) % len(tabs)
+	case "shift+tab":
+		// Cycle backwards through tabs with Shift+Tab
+		m.activeTab = (m.activeTab - 1 + len(tabs)) % len(tabs)
+	case "left", "h":
+		// Navigate left through tabs
+		m.activeTab = (m.activeTab - 1 + len(tabs)) % len(tabs)
+	case "right", "l":
+		// Navigate right through tabs
+		m.activeTab = (m.activeTab + 1) % len(tabs)
+	case ":":
+		m.mode, m.input = modeCommand, ""
+	case "u":
+		m.undoView()
+	case "F":
+		m.showFrames = !m.showFrames
+	case "/":
+		// Filter the messages tab
+		if tabs[m.activeTab].key == "messages" {
+			m.mode, m.input = modeFilter, m.filter
+		}
+	case "v":
+		// Toggle between the message stream and the table view
+		if tabs[m.activeTab].key == "messages" {
+			m.table.enabled = !m.table.enabled
+		}
+	case "f":
+		// Toggle the field tree of the newest structured payload
+		if tabs[m.activeTab].key == "messages" {
+			m.showFields = !m.showFields
+		}
+	case "p":
+		// Publish a message from the messages tab
+		if tabs[m.activeTab].key == "messages" {
+			m = m.openPublishPrompt()
+		}
+	default:
+		switch tabs[m.activeTab].key {
+		case "messages":
+			if !m.table.enabled {
+				break
+			}
+			rows := m.tableRows()
+			if msg.String() == "enter" && m.table.cursor < len(rows) && !rows[m.table.cursor].header {
+				m.mode, m.detail = modeDetail, rows[m.table.cursor].message
+				break
+			}
+			m.table = m.table.handleKey(msg.String(), rows)
+		case "topics":
+			return m.handleTopicsKey(msg.String())
+		case "dashboard":
+			m.leaderboard = m.leaderboard.handleKey(msg.String())
+		default:
+			if pane := m.activePane(); pane != nil {
+				pane.HandleKey(msg.String())
+			}
+		}
+	}
+	return m, nil
+}
+
+func (m model) openPublishPrompt() model {
+	if m.publish == nil {
+		m.prompt.status = "❌ Publishing is not available in this session"
+		return m
+	}
+	m.prompt = newPublishPrompt()
+	m.mode = modePublish
+	return m
+}
+
+// Edit a line of input with a key; false if the key is no edit
+func editLine(line string, msg tea.KeyMsg) (string, bool) {
+	switch msg.Type {
+	case tea.KeyBackspace:
+		if runes := []rune(line); len(runes) > 0 {
+			line = string(runes[:len(runes)-1])
+		}
+	case tea.KeySpace:
+		line += " "
+	case tea.KeyRunes:
+		line += string(msg.Runes)
+	default:
+		return line, false
+	}
+	return line, true
+}
+
+// Messages shown on the messages tab: those of topics neither muted nor
+// left out by a solo whose topic or payload contain the filter, ignoring
+// case. While the search for a changed filter runs, those matching the
+// previous one.
+func (m model) visibleMessages() []DDSMessage {
+	if m.query() == "" || m.search.query == "" {
+		return m.focus.apply(m.messages)
+	}
+	first := m.seen - uint64(len(m.messages))
+	var visible []DDSMessage
+	for _, pos := range m.search.matches {
+		if pos >= first && pos < m.seen {
+			visible = append(visible, m.messages[pos-first])
+		}
+	}
+	return m.focus.apply(visible)
+}
+
+func (m model) handleFilterKey(msg tea.KeyMsg) model {
+	switch msg.Type {
+	case tea.KeyEsc, tea.KeyCtrlC:
+		m.mode = modeNormal
+	case tea.KeyEnter:
+		m.mode, m.filter = modeNormal, strings.TrimSpace(m.input)
+		m.table.cursor = 0
+	default:
+		m.input, _ = editLine(m.input, msg)
+		m.table.cursor = 0
+	}
+	return m
+}
+
+// Commands typed after :, with what they do
+var tuiCommands = [][2]string{
+	{"sort time|topic|size|latency|content", "sort the table"},
+	{"reverse", "reverse the table's order"},
+	{"group none|topic|key", "group the table's rows"},
+	{"table, stream", "show messages as a table or a stream"},
+	{"fields", "toggle the field tree"},
+	{"filter TEXT, clear", "filter the messages, or show them all"},
+	{"publish", "open the publish prompt"},
+	{"undo", "restore the view before the last change"},
+	{"profile", "toggle the frame times overlay"},
+	{"quit", "quit"},
+}
+
+func (m model) handleCommandKey(msg tea.KeyMsg) (model, tea.Cmd) {
+	switch msg.Type {
+	case tea.KeyEsc, tea.KeyCtrlC:
+		m.mode = modeNormal
+	case tea.KeyEnter:
+		m.mode = modeNormal
+		return m.runCommand(m.input)
+	default:
+		m.input, _ = editLine(m.input, msg)
+	}
+	return m, nil
+}
+
+// Run a command typed after :
+func (m model) runCommand(line string) (model, tea.Cmd) {
+	name, arg, _ := strings.Cut(strings.TrimSpace(line), " ")
+	arg = strings.TrimSpace(arg)
+	switch name {
+	case "":
+	case "sort":
+		column := slices.IndexFunc(sortColumnNames[:], func(c string) bool { return strings.EqualFold(c, arg) })
+		if column < 0 {
+			m.status = fmt.Sprintf("Unknown sort column %q", arg)
+			break
+		}
+		m.table.sortColumn = sortColumn(column)
+	case "reverse":
+		m.table.descending = !m.table.descending
+	case "group":
+		mode := slices.IndexFunc(groupModeNames[:], func(g string) bool { return strings.EqualFold(g, arg) })
+		if mode < 0 {
+			m.status = fmt.Sprintf("Unknown grouping %q", arg)
+			break
+		}
+		m.table.groupMode, m.table.cursor = groupMode(mode), 0
+	case "table", "stream":
+		m.table.enabled = name == "table"
+	case "fields":
+		m.showFields = !m.showFields
+	case "profile":
+		m.showFrames = !m.showFrames
+	case "filter":
+		m.filter, m.table.cursor = arg, 0
+	case "clear":
+		m.filter = ""
+	case "publish":
+		m = m.openPublishPrompt()
+	case "undo":
+		m.undoView()
+	case "quit", "q":
+		return m, tea.Quit
+	default:
+		m.status = fmt.Sprintf("Unknown command %q", name)
+	}
+	return m, nil
+}
+
+func (m model) handleDetailKey(msg tea.KeyMsg) model {
+	switch msg.String() {
+	case "esc", "enter", "q", "backspace":
+		m.mode = modeNormal
+	}
+	return m
+}
+
+// Render the message opened from the table
+func (m model) renderDetail(primaryColor, textColor, dimColor lipgloss.Color) string {
+	headerStyle := lipgloss.NewStyle().Foreground(primaryColor).Bold(true).MarginBottom(1)
+	labelStyle := lipgloss.NewStyle().Foreground(dimColor).Width(12)
+	valueStyle := lipgloss.NewStyle().Foreground(textColor)
+	hintStyle := lipgloss.NewStyle().Foreground(dimColor).Italic(true)
+
+	msg := m.detail
+	row := func(label, value string) string {
+		return labelStyle.Render(label) + valueStyle.Render(value)
+	}
+	lines := []string{
+		headerStyle.Render("🔎 Message on " + topicLabel(msg)),
+		row("Sent", m.times.Format("messages", msg.Timestamp, "ms")),
+		row("Received", m.times.Format("messages", msg.ReceivedAt, "ms")),
+		row("Latency", msg.Latency().String()),
+		row("Size", formatBytes(float64(msg.Size()))),
+	}
+	if msg.Writer != "" {
+		lines = append(lines, row("Writer", fmt.Sprintf("%s (sequence %d)", msg.Writer, msg.Sequence)))
+	}
+	if msg.Key != "" {
+		lines = append(lines, row("Key", msg.Key+" "+msg.Instance))
+	}
+	if msg.Hash != "" {
+		lines = append(lines, row("Hash", msg.Hash))
+	}
+	var flags []string
+	for flag, set := range map[string]bool{"own": msg.Own, "duplicate": msg.Duplicate, "reordered": msg.Reordered, "repeated": msg.Repeated, "burst": msg.Burst, "over budget": msg.OverBudget} {
+		if set {
+			flags = append(flags, flag)
+		}
+	}
+	if len(flags) > 0 {
+		slices.Sort(flags)
+		lines = append(lines, row("Flags", strings.Join(flags, ", ")))
+	}
+
+	lines = append(lines, "", valueStyle.Render(m.formats.Render(msg)))
+	if fields, ok := fieldTree(m.formats.decoders.Decode(msg).Content); ok {
+		if len(fields) > maxFieldTreeLines {
+			fields = append(fields[:maxFieldTreeLines], fmt.Sprintf("… %d more fields", len(fields)-maxFieldTreeLines))
+		}
+		lines = append(lines, "", valueStyle.Render(strings.Join(fields, "\n")))
+	}
+	lines = append(lines, "", hintStyle.Render("Esc or Enter to go back"))
+
+	return lipgloss.NewStyle().
+		Border(lipgloss.RoundedBorder()).
+		BorderForeground(primaryColor).
+		Padding(1, 2).
+		Render(lipgloss.JoinVertical(lipgloss.Left, lines...))
+}
+
+// Render the line being typed in filter and command mode, or the outcome of
+// the last command; empty when there is neither
+func (m model) renderInputLine(primaryColor, textColor, dimColor lipgloss.Color) string {
+	inputStyle := lipgloss.NewStyle().Foreground(textColor)
+	switch m.mode {
+	case modeFilter:
+		return lipgloss.NewStyle().Foreground(primaryColor).Bold(true).Render("/") + inputStyle.Render(m.input+"█")
+	case modeCommand:
+		return lipgloss.NewStyle().Foreground(primaryColor).Bold(true).Render(":") + inputStyle.Render(m.input+"█")
+	}
+	if m.status != "" {
+		return lipgloss.NewStyle().Foreground(dimColor).Italic(true).Render(m.status)
+	}
+	return ""
+}
@@ -0,0 +1,159 @@
+package main
+
+import (
+	"slices"
+	"testing"
+	"time"
+
+	tea "github.com/charmbracelet/bubbletea"
+)
+
+// A model on the messages tab holding the given messages
+func newModesModel(messages ...DDSMessage) model {
+	return model{
+		store:     newMessageStore(),
+		messages:  messages,
+		seen:      uint64(len(messages)),
+		table:     newTableState(),
+		rows:      &rowCache{},
+		activeTab: slices.IndexFunc(tabs, func(t Tab) bool { return t.key == "messages" }),
+	}
+}
+
+// Press keys in turn, named like tea.KeyMsg.String() names them
+func pressKeys(m model, keys ...string) model {
+	for _, key := range keys {
+		msg := tea.KeyMsg{Type: tea.KeyRunes, Runes: []rune(key)}
+		switch key {
+		case "enter":
+			msg = tea.KeyMsg{Type: tea.KeyEnter}
+		case "esc":
+			msg = tea.KeyMsg{Type: tea.KeyEsc}
+		case " ":
+			msg = tea.KeyMsg{Type: tea.KeySpace}
+		}
+		m, _ = m.handleKey(msg)
+	}
+	return m
+}
+
+// Type a line of text one key at a time
+func typeText(m model, text string) model {
+	for _, r := range text {
+		m = pressKeys(m, string(r))
+	}
+	return m
+}
+
+func TestFilterMode(t *testing.T) {
+	m := pressKeys(newModesModel(), "/")
+	if m.mode != modeFilter {
+		t.Fatalf("mode after / = %d, want filter", m.mode)
+	}
+
+	// Keys bound in normal mode are typed into the filter
+	tab := m.activeTab
+	m = typeText(m, "hl2 q")
+	if m.activeTab != tab || m.mode != modeFilter || m.input != "hl2 q" {
+		t.Fatalf("after typing: tab %d, mode %d, input %q", m.activeTab, m.mode, m.input)
+	}
+	m = pressKeys(m, "enter")
+	if m.mode != modeNormal || m.filter != "hl2 q" {
+		t.Errorf("after enter: mode %d, filter %q", m.mode, m.filter)
+	}
+
+	// Esc leaves the filter as it was
+	m = typeText(pressKeys(m, "/"), "x")
+	m = pressKeys(m, "esc")
+	if m.mode != modeNormal || m.filter != "hl2 q" {
+		t.Errorf("after esc: mode %d, filter %q", m.mode, m.filter)
+	}
+}
+
+func TestCommandMode(t *testing.T) {
+	m := pressKeys(newModesModel(), ":")
+	if m.mode != modeCommand {
+		t.Fatalf("mode after : = %d, want command", m.mode)
+	}
+	m = pressKeys(typeText(m, "sort size"), "enter")
+	if m.mode != modeNormal || m.table.sortColumn != sortBySize {
+		t.Errorf("after :sort size: mode %d, sort column %d", m.mode, m.table.sortColumn)
+	}
+
+	m = pressKeys(typeText(pressKeys(m, ":"), "bogus"), "enter")
+	if m.mode != modeNormal || m.status != `Unknown command "bogus"` {
+		t.Errorf("after :bogus: mode %d, status %q", m.mode, m.status)
+	}
+	m = pressKeys(typeText(pressKeys(m, ":"), "table"), "esc")
+	if m.mode != modeNormal || m.table.enabled {
+		t.Errorf("esc ran the command: mode %d, table %v", m.mode, m.table.enabled)
+	}
+}
+
+func TestDetailMode(t *testing.T) {
+	msg := DDSMessage{Topic: "/imu", Content: "{}", ReceivedAt: time.Now()}
+	m := pressKeys(newModesModel(msg), "v", "enter")
+	if m.mode != modeDetail || m.detail.Topic != "/imu" {
+		t.Fatalf("after enter on a row: mode %d, detail %q", m.mode, m.detail.Topic)
+	}
+	tab := m.activeTab
+	m = pressKeys(m, "3")
+	if m.mode != modeDetail || m.activeTab != tab {
+		t.Errorf("a tab key left the detail: mode %d, tab %d", m.mode, m.activeTab)
+	}
+	m = pressKeys(m, "esc")
+	if m.mode != modeNormal || !m.table.enabled {
+		t.Errorf("after esc: mode %d, table %v", m.mode, m.table.enabled)
+	}
+}
+
+func TestPublishPromptMode(t *testing.T) {
+	m := pressKeys(newModesModel(), "p")
+	if m.mode != modeNormal {
+		t.Errorf("publish prompt opened without a publisher: mode %d", m.mode)
+	}
+
+	m = newModesModel()
+	m.publish = func(topic, content string) error { return nil }
+	m = pressKeys(m, "p")
+	if m.mode != modePublish || !m.prompt.active {
+		t.Fatalf("after p: mode %d, prompt active %v", m.mode, m.prompt.active)
+	}
+	tab := m.activeTab
+	m = typeText(m, "1")
+	if m.mode != modePublish || m.activeTab != tab {
+		t.Errorf("a tab key left the prompt: mode %d, tab %d", m.mode, m.activeTab)
+	}
+	m = pressKeys(m, "esc")
+	if m.mode != modeNormal || m.prompt.active {
+		t.Errorf("after esc: mode %d, prompt active %v", m.mode, m.prompt.active)
+	}
+}
+
+// Undo restores the view one change at a time and never pushes itself
+func TestUndoView(t *testing.T) {
+	m := newModesModel()
+	m = pressKeys(typeText(pressKeys(m, ":"), "sort size"), "enter")
+	m = pressKeys(typeText(pressKeys(m, ":"), "group topic"), "enter")
+	m = pressKeys(typeText(pressKeys(m, "/"), "imu"), "enter")
+	if len(m.undo) != 3 {
+		t.Fatalf("%d undo entries after three changes, want 3", len(m.undo))
+	}
+
+	m = pressKeys(m, "u")
+	if m.filter != "" || m.table.groupMode != groupByTopic || len(m.undo) != 2 {
+		t.Errorf("first undo: filter %q, group %d, %d entries left", m.filter, m.table.groupMode, len(m.undo))
+	}
+	m = pressKeys(m, "u")
+	if m.table.groupMode != groupNone || m.table.sortColumn != sortBySize || len(m.undo) != 1 {
+		t.Errorf("second undo: group %d, sort column %d, %d entries left", m.table.groupMode, m.table.sortColumn, len(m.undo))
+	}
+	m = pressKeys(typeText(pressKeys(m, ":"), "undo"), "enter")
+	if m.table.sortColumn != sortByTime || len(m.undo) != 0 {
+		t.Errorf(":undo: sort column %d, %d entries left", m.table.sortColumn, len(m.undo))
+	}
+	m = pressKeys(m, "u")
+	if m.status != "Nothing to undo" || len(m.undo) != 0 {
+		t.Errorf("undo of nothing: status %q, %d entries", m.status, len(m.undo))
+	}
+}
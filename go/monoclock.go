@@ -0,0 +1,74 @@
+package main
+
+import (
+	"log"
+	"sync"
+	"time"
+)
+
+// Wall-clock latency is only as good as the clocks of both hosts: an NTP
+// step on either side shows up as a latency spike, or as negative latency.
+// Every publisher therefore sends its monotonic clock along with the
+// timestamp, and with --monotonic-latency the receiver measures latency on
+// monotonic clocks instead. The two clocks have an unknown offset, so the
+// first sample of each writer serves as a handshake: its wall-clock latency
+// fixes the offset, and every later sample is measured as that baseline
+// plus the change in monotonic transit time. Clock steps after the
+// handshake no longer move the numbers.
+
+// Reference point of this process's monotonic clock
+var processStart = time.Now()
+
+// Nanoseconds on this process's monotonic clock, sent with every published
+// message
+func monotonicNow() int64 {
+	return int64(time.Since(processStart))
+}
+
+// Offset between a writer's monotonic clock and ours
+type clockOffset struct {
+	offset time.Duration // our clock minus the writer's, less the baseline latency
+	last   int64         // newest sender clock seen, to notice restarts
+}
+
+// Measures latency on monotonic clocks, falling back to wall clocks for
+// writers that send none. A nil LatencyClock always uses wall clocks.
+type LatencyClock struct {
+	mu      sync.Mutex
+	writers map[string]*clockOffset
+	warned  map[string]bool // topics already warned about falling back
+}
+
+func NewLatencyClock() *LatencyClock {
+	return &LatencyClock{writers: map[string]*clockOffset{}, warned: map[string]bool{}}
+}
+
+// Latency of a received message
+func (c *LatencyClock) Latency(msg DDSMessage) time.Duration {
+	wall := msg.ReceivedAt.Sub(msg.Timestamp)
+	if c == nil {
+		return wall
+	}
+
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	if msg.SenderClock == 0 || msg.Writer == "" {
+		if !c.warned[msg.Topic] {
+			c.warned[msg.Topic] = true
+			log.Printf("No monotonic clock from writers of %s, measuring its latency on wall clocks", msg.Topic)
+		}
+		return wall
+	}
+
+	// ReceivedAt carries a monotonic reading as long as it comes straight
+	// from time.Now; messages loaded from captures lose it
+	received := msg.ReceivedAt.Sub(processStart)
+	writer, ok := c.writers[msg.Writer]
+	if !ok || msg.SenderClock < writer.last {
+		// Handshake, again after the writer restarted
+		writer = &clockOffset{offset: received - time.Duration(msg.SenderClock) - max(wall, 0)}
+		c.writers[msg.Writer] = writer
+	}
+	writer.last = msg.SenderClock
+	return received - time.Duration(msg.SenderClock) - writer.offset
+}
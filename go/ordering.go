@@ -0,0 +1,101 @@
+package main
+
+import (
+	"sync"
+	"time"
+)
+
+// Each DataWriter numbers its samples, so per writer the sequence numbers a
+// reader sees must only grow. With RELIABLE and several writers the samples
+// of different writers still interleave freely; this checks every writer
+// on its own. Skipped numbers are left to gap detection.
+
+// How a sample's sequence number relates to the last one from its writer
+type sequenceOrder int
+
+const (
+	sequenceInOrder   sequenceOrder = iota
+	sequenceReordered               // lower than one already seen, and new
+	sequenceRepeated                // a number already seen, e.g. a retransmit
+)
+
+// Sequence numbers remembered below a writer's highest one, so a
+// retransmit of an older sample counts as repeated rather than reordered
+const sequenceWindow = 64
+
+// Writers silent for this long are forgotten
+const orderWriterIdle = time.Minute
+
+// What was seen of one writer's sequence numbers
+type writerSequences struct {
+	highest int64
+	seen    uint64    // bit i set: highest-1-i was received
+	last    time.Time // arrival of its latest sample
+}
+
+// Tracks the recent sequence numbers per topic and writer. Safe for
+// concurrent use.
+type OrderChecker struct {
+	mu      sync.Mutex
+	writers map[string]*writerSequences // topic + writer
+	pruned  time.Time
+}
+
+func NewOrderChecker() *OrderChecker {
+	return &OrderChecker{writers: map[string]*writerSequences{}, pruned: time.Now()}
+}
+
+// Record a message and classify its sequence number. Messages without a
+// known writer or sequence number are always in order. Below the window of
+// remembered numbers a sample cannot be told from a retransmit and counts
+// as reordered.
+func (c *OrderChecker) Check(msg DDSMessage) sequenceOrder {
+	if c == nil || msg.Writer == "" || msg.Sequence == 0 {
+		return sequenceInOrder
+	}
+
+	c.mu.Lock()
+	defer c.mu.Unlock()
+
+	now := time.Now()
+	c.prune(now)
+	key := msg.Topic + "\x00" + msg.Writer
+	w, ok := c.writers[key]
+	if !ok {
+		c.writers[key] = &writerSequences{highest: msg.Sequence, last: now}
+		return sequenceInOrder
+	}
+	w.last = now
+
+	behind := w.highest - msg.Sequence
+	switch {
+	case behind < 0:
+		// The old highest and what was seen below it move down the window
+		ahead := uint64(-behind)
+		w.seen = w.seen<<ahead | 1<<(ahead-1)
+		w.highest = msg.Sequence
+		return sequenceInOrder
+	case behind == 0:
+		return sequenceRepeated
+	case behind <= sequenceWindow:
+		bit := uint64(1) << (behind - 1)
+		if w.seen&bit != 0 {
+			return sequenceRepeated
+		}
+		w.seen |= bit
+	}
+	return sequenceReordered
+}
+
+// Forget the writers that went silent, at most once per idle period
+func (c *OrderChecker) prune(now time.Time) {
+	if now.Sub(c.pruned) < orderWriterIdle {
+		return
+	}
+	c.pruned = now
+	for key, w := range c.writers {
+		if now.Sub(w.last) > orderWriterIdle {
+			delete(c.writers, key)
+		}
+	}
+}
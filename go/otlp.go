@@ -0,0 +1,93 @@
+package main
+
+import (
+	"bytes"
+	"encoding/json"
+	"fmt"
+	"net/http"
+	"strconv"
+	"strings"
+	"time"
+)
+
+// Minimal OTLP/HTTP exporter using the JSON protobuf encoding, so no
+// OpenTelemetry SDK is needed to ship data to a collector
+
+// Service name reported in the OTLP resource
+const otlpServiceName = "cardinal"
+
+// Timeout for a single OTLP export request
+const otlpTimeout = 5 * time.Second
+
+type otlpKeyValue struct {
+	Key   string       `json:"key"`
+	Value otlpAnyValue `json:"value"`
+}
+
+type otlpAnyValue struct {
+	StringValue *string  `json:"stringValue,omitempty"`
+	IntValue    *string  `json:"intValue,omitempty"`
+	DoubleValue *float64 `json:"doubleValue,omitempty"`
+	BoolValue   *bool    `json:"boolValue,omitempty"`
+}
+
+type otlpResource struct {
+	Attributes []otlpKeyValue `json:"attributes"`
+}
+
+type otlpScope struct {
+	Name    string `json:"name"`
+	Version string `json:"version,omitempty"`
+}
+
+func otlpString(key, value string) otlpKeyValue {
+	return otlpKeyValue{Key: key, Value: otlpAnyValue{StringValue: &value}}
+}
+
+// OTLP JSON encodes 64-bit integers as decimal strings
+func otlpInt(key string, value int64) otlpKeyValue {
+	s := strconv.FormatInt(value, 10)
+	return otlpKeyValue{Key: key, Value: otlpAnyValue{IntValue: &s}}
+}
+
+func otlpUnixNano(t time.Time) string {
+	return strconv.FormatInt(t.UnixNano(), 10)
+}
+
+func newOTLPResource() otlpResource {
+	return otlpResource{Attributes: []otlpKeyValue{
+		otlpString("service.name", otlpServiceName),
+	}}
+}
+
+// Exports OTLP payloads to a collector's HTTP endpoint
+type otlpExporter struct {
+	endpoint string
+	client   *http.Client
+}
+
+func newOTLPExporter(endpoint string) *otlpExporter {
+	return &otlpExporter{
+		endpoint: strings.TrimRight(endpoint, "/"),
+		client:   &http.Client{Timeout: otlpTimeout},
+	}
+}
+
+// POST a JSON payload to the given signal path (e.g. /v1/traces)
+func (e *otlpExporter) post(path string, payload any) error {
+	body, err := json.Marshal(payload)
+	if err != nil {
+		return err
+	}
+
+	resp, err := e.client.Post(e.endpoint+path, "application/json", bytes.NewReader(body))
+	if err != nil {
+		return err
+	}
+	defer resp.Body.Close()
+
+	if resp.StatusCode/100 != 2 {
+		return fmt.Errorf("otlp export to %s failed: %s", path, resp.Status)
+	}
+	return nil
+}
@@ -0,0 +1,64 @@
+package main
+
+import (
+	"fmt"
+
+	"github.com/charmbracelet/lipgloss"
+)
+
+// Custom panes add tabs to the TUI for what only one deployment cares
+// about, e.g. a battery widget for a particular robot. A pane lives in its
+// own file of this package and registers itself from init, so Cardinal's own
+// files stay untouched:
+//
+//	func init() {
+//		RegisterPane(func() Pane { return &batteryPane{} })
+//	}
+//
+// Panes are driven from the TUI goroutine only, so they need no locking.
+
+// A custom tab of the TUI
+type Pane interface {
+	Title() string         // tab name, e.g. "🔋 Battery"
+	Update(msg DDSMessage) // called with every received message
+	HandleKey(key string)  // keys pressed while its tab is active, other than Cardinal's own
+	View(width int) string // content of its tab
+}
+
+// Creators of the registered panes, one pane per TUI
+var paneFactories []func() Pane
+
+// Add a pane as a tab after the built-in ones; call from init
+func RegisterPane(newPane func() Pane) {
+	paneFactories = append(paneFactories, newPane)
+	pane := newPane()
+	tabs = append(tabs, Tab{name: pane.Title(), key: fmt.Sprintf("pane%d", len(paneFactories)-1)})
+}
+
+// A fresh instance of every registered pane
+func newPanes() []Pane {
+	panes := make([]Pane, len(paneFactories))
+	for i, newPane := range paneFactories {
+		panes[i] = newPane()
+	}
+	return panes
+}
+
+// The pane of the active tab, or nil on a built-in tab
+func (m model) activePane() Pane {
+	var i int
+	if _, err := fmt.Sscanf(tabs[m.activeTab].key, "pane%d", &i); err != nil || i >= len(m.panes) {
+		return nil
+	}
+	return m.panes[i]
+}
+
+// Render the active pane in a card
+func (m model) renderPane(pane Pane, primaryColor lipgloss.Color) string {
+	return lipgloss.NewStyle().
+		Border(lipgloss.RoundedBorder()).
+		BorderForeground(primaryColor).
+		Padding(1, 2).
+		MarginBottom(1).
+		Render(pane.View(m.width - 8))
+}
@@ -0,0 +1,599 @@
+package main
+
+import (
+	"bufio"
+	"bytes"
+	"encoding/binary"
+	"encoding/json"
+	"fmt"
+	"io"
+	"math"
+	"os"
+	"path/filepath"
+	"sort"
+	"strconv"
+	"strings"
+	"time"
+)
+
+// Captures and live streams can be exported as Parquet files, one row per
+// message, for analysis in pandas or polars. Besides the topic, timestamps,
+// raw payload and the hash, writer and sequence number of the message, every
+// scalar field of a JSON payload becomes a column of
+// its own (nested fields as pose.x); arrays are kept as JSON text, and a
+// field seen with different types becomes a string column.
+//
+// The writer covers the small part of the format this needs: flat schemas,
+// uncompressed PLAIN pages, one page per column chunk and definition levels
+// for the optional field columns.
+
+// Rows per row group, which bounds the size of a single page
+const parquetRowGroupRows = 65536
+
+// Parquet physical types, converted types, encodings and repetitions
+const (
+	parquetBoolean   = 0
+	parquetInt64     = 2
+	parquetDouble    = 5
+	parquetByteArray = 6
+
+	parquetUTF8            = 0
+	parquetTimestampMicros = 10
+
+	parquetPlain = 0
+	parquetRLE   = 3
+
+	parquetRequired = 0
+	parquetOptional = 1
+)
+
+// Value types of the exported columns
+type parquetKind int
+
+const (
+	kindBool parquetKind = iota
+	kindDouble
+	kindString
+	kindTimestamp
+	kindInt64
+)
+
+// One column and its values, nil where a row has none
+type parquetColumn struct {
+	name     string
+	kind     parquetKind
+	required bool
+	values   []any
+}
+
+// Rows of exported messages held in memory until written
+type parquetTable struct {
+	columns []*parquetColumn
+	byName  map[string]*parquetColumn
+	rows    int
+}
+
+// Columns every row has, before the payload fields
+var messageColumns = []parquetColumn{
+	{name: "topic", kind: kindString, required: true},
+	{name: "received_at", kind: kindTimestamp, required: true},
+	{name: "timestamp", kind: kindTimestamp},
+	{name: "payload", kind: kindString, required: true},
+	{name: "hash", kind: kindString},
+	{name: "writer", kind: kindString},
+	{name: "sequence", kind: kindInt64},
+}
+
+func newParquetTable() *parquetTable {
+	t := &parquetTable{byName: map[string]*parquetColumn{}}
+	for _, column := range messageColumns {
+		t.addColumn(column.name, column.kind, column.required)
+	}
+	return t
+}
+
+func (t *parquetTable) addColumn(name string, kind parquetKind, required bool) *parquetColumn {
+	column := &parquetColumn{name: name, kind: kind, required: required, values: make([]any, t.rows)}
+	t.columns = append(t.columns, column)
+	t.byName[name] = column
+	return column
+}
+
+// Set a value of the row being added, creating or widening its column
+func (t *parquetTable) set(name string, kind parquetKind, value any) {
+	column, ok := t.byName[name]
+	if !ok {
+		column = t.addColumn(name, kind, false)
+	} else if column.kind != kind && !column.required {
+		column.kind = kindString
+	}
+	if len(column.values) > t.rows {
+		return // the same name twice in one row
+	}
+	column.values = append(column.values, value)
+}
+
+// Add one message as a row
+func (t *parquetTable) AddMessage(msg DDSMessage) {
+	t.set("topic", kindString, msg.Topic)
+	t.set("received_at", kindTimestamp, msg.ReceivedAt)
+	if !msg.Timestamp.IsZero() {
+		t.set("timestamp", kindTimestamp, msg.Timestamp)
+	}
+	t.set("payload", kindString, msg.Content)
+	if msg.Hash != "" {
+		t.set("hash", kindString, msg.Hash)
+	}
+	if msg.Writer != "" {
+		t.set("writer", kindString, msg.Writer)
+	}
+	if msg.Sequence != 0 {
+		t.set("sequence", kindInt64, msg.Sequence)
+	}
+
+	var payload map[string]any
+	if json.Unmarshal([]byte(msg.Content), &payload) == nil {
+		t.addFields("", payload)
+	}
+
+	t.rows++
+	for _, column := range t.columns {
+		for len(column.values) < t.rows {
+			column.values = append(column.values, nil)
+		}
+	}
+}
+
+// Add the scalar fields of a JSON object as columns, nested ones as a.b
+func (t *parquetTable) addFields(prefix string, object map[string]any) {
+	for key, value := range object {
+		name := prefix + key
+		for _, column := range messageColumns {
+			if name == column.name {
+				name = "payload." + name
+			}
+		}
+		switch v := value.(type) {
+		case map[string]any:
+			t.addFields(name+".", v)
+		case []any:
+			encoded, _ := json.Marshal(v)
+			t.set(name, kindString, string(encoded))
+		case float64:
+			t.set(name, kindDouble, v)
+		case bool:
+			t.set(name, kindBool, v)
+		case string:
+			t.set(name, kindString, v)
+		}
+	}
+}
+
+// Write the table as a Parquet file
+func (t *parquetTable) Write(w io.Writer) error {
+	buffered := bufio.NewWriter(w)
+	out := &countingWriter{w: buffered}
+	out.Write([]byte("PAR1"))
+
+	// Field columns in name order after the fixed ones
+	columns := append([]*parquetColumn(nil), t.columns[:len(messageColumns)]...)
+	fields := append([]*parquetColumn(nil), t.columns[len(messageColumns):]...)
+	sort.Slice(fields, func(i, j int) bool {
+		return fields[i].name < fields[j].name
+	})
+	columns = append(columns, fields...)
+
+	meta := newThriftWriter()
+	meta.i32(1, 1) // version
+	meta.list(2, thriftStruct, len(columns)+1)
+	meta.element()
+	meta.binary(4, "schema")
+	meta.i32(5, int32(len(columns)))
+	meta.end()
+	for _, column := range columns {
+		physical, converted := column.types()
+		repetition := int32(parquetOptional)
+		if column.required {
+			repetition = parquetRequired
+		}
+		meta.element()
+		meta.i32(1, physical)
+		meta.i32(3, repetition)
+		meta.binary(4, column.name)
+		if converted >= 0 {
+			meta.i32(6, converted)
+		}
+		meta.end()
+	}
+	meta.i64(3, int64(t.rows))
+
+	groups := (t.rows + parquetRowGroupRows - 1) / parquetRowGroupRows
+	meta.list(4, thriftStruct, groups)
+	for start := 0; start < t.rows; start += parquetRowGroupRows {
+		rows := min(parquetRowGroupRows, t.rows-start)
+		meta.element()
+		meta.list(1, thriftStruct, len(columns))
+		var groupBytes int64
+		for _, column := range columns {
+			offset := out.n
+			page := column.page(start, rows)
+			if _, err := out.Write(page); err != nil {
+				return err
+			}
+			groupBytes += int64(len(page))
+
+			physical, _ := column.types()
+			meta.element()
+			meta.i64(2, offset)
+			meta.begin(3)
+			meta.i32(1, physical)
+			meta.i32List(2, parquetPlain, parquetRLE)
+			meta.stringList(3, column.name)
+			meta.i32(4, 0) // uncompressed
+			meta.i64(5, int64(rows))
+			meta.i64(6, int64(len(page)))
+			meta.i64(7, int64(len(page)))
+			meta.i64(9, offset)
+			meta.end()
+			meta.end()
+		}
+		meta.i64(2, groupBytes)
+		meta.i64(3, int64(rows))
+		meta.end()
+	}
+	meta.binary(6, "cardinal")
+	meta.end()
+
+	out.Write(meta.buf.Bytes())
+	var footer [4]byte
+	binary.LittleEndian.PutUint32(footer[:], uint32(meta.buf.Len()))
+	out.Write(footer[:])
+	out.Write([]byte("PAR1"))
+	if out.err != nil {
+		return out.err
+	}
+	return buffered.Flush()
+}
+
+// Physical and converted type of a column, -1 when it has none
+func (c *parquetColumn) types() (int32, int32) {
+	switch c.kind {
+	case kindBool:
+		return parquetBoolean, -1
+	case kindDouble:
+		return parquetDouble, -1
+	case kindTimestamp:
+		return parquetInt64, parquetTimestampMicros
+	case kindInt64:
+		return parquetInt64, -1
+	}
+	return parquetByteArray, parquetUTF8
+}
+
+// Encode the rows [start, start+rows) of the column as one data page,
+// header included
+func (c *parquetColumn) page(start, rows int) []byte {
+	values := c.values[start : start+rows]
+	var data bytes.Buffer
+
+	// Definition levels: one bit-packed run of 0 (missing) or 1 (present),
+	// behind its byte length
+	if !c.required {
+		levels := make([]byte, (rows+7)/8)
+		for i, v := range values {
+			if v != nil {
+				levels[i/8] |= 1 << (i % 8)
+			}
+		}
+		var run bytes.Buffer
+		run.Write(binary.AppendUvarint(nil, uint64(len(levels))<<1|1))
+		run.Write(levels)
+		data.Write(binary.LittleEndian.AppendUint32(nil, uint32(run.Len())))
+		data.Write(run.Bytes())
+	}
+
+	// PLAIN values of the rows that have one
+	var bits []byte
+	present := 0
+	for _, v := range values {
+		if v == nil {
+			continue
+		}
+		switch c.kind {
+		case kindBool:
+			if present%8 == 0 {
+				bits = append(bits, 0)
+			}
+			if v.(bool) {
+				bits[present/8] |= 1 << (present % 8)
+			}
+		case kindDouble:
+			data.Write(binary.LittleEndian.AppendUint64(nil, math.Float64bits(v.(float64))))
+		case kindTimestamp:
+			data.Write(binary.LittleEndian.AppendUint64(nil, uint64(v.(time.Time).UnixMicro())))
+		case kindInt64:
+			data.Write(binary.LittleEndian.AppendUint64(nil, uint64(v.(int64))))
+		case kindString:
+			s := parquetString(v)
+			data.Write(binary.LittleEndian.AppendUint32(nil, uint32(len(s))))
+			data.WriteString(s)
+		}
+		present++
+	}
+	data.Write(bits)
+
+	header := newThriftWriter()
+	header.i32(1, 0) // data page
+	header.i32(2, int32(data.Len()))
+	header.i32(3, int32(data.Len()))
+	header.begin(5)
+	header.i32(1, int32(rows))
+	header.i32(2, parquetPlain)
+	header.i32(3, parquetRLE)
+	header.i32(4, parquetRLE)
+	header.end()
+	header.end()
+	return append(header.buf.Bytes(), data.Bytes()...)
+}
+
+// Text of a value in a string column, which may have been widened
+func parquetString(v any) string {
+	switch v := v.(type) {
+	case string:
+		return v
+	case float64:
+		return strconv.FormatFloat(v, 'g', -1, 64)
+	case bool:
+		return strconv.FormatBool(v)
+	case int64:
+		return strconv.FormatInt(v, 10)
+	}
+	return fmt.Sprint(v)
+}
+
+// Counts the bytes written, for the column chunk offsets
+type countingWriter struct {
+	w   io.Writer
+	n   int64
+	err error
+}
+
+func (c *countingWriter) Write(p []byte) (int, error) {
+	if c.err != nil {
+		return 0, c.err
+	}
+	n, err := c.w.Write(p)
+	c.n += int64(n)
+	c.err = err
+	return n, err
+}
+
+// Thrift compact protocol types used by the Parquet metadata
+const (
+	thriftI32    = 5
+	thriftI64    = 6
+	thriftBinary = 8
+	thriftList   = 9
+	thriftStruct = 12
+)
+
+// Encodes Thrift structs in the compact protocol
+type thriftWriter struct {
+	buf  bytes.Buffer
+	last []int16 // previous field id in each open struct
+}
+
+func newThriftWriter() *thriftWriter {
+	return &thriftWriter{last: []int16{0}}
+}
+
+func (w *thriftWriter) varint(v int64) {
+	w.buf.Write(binary.AppendUvarint(nil, uint64(v<<1^v>>63)))
+}
+
+func (w *thriftWriter) field(id int16, typ byte) {
+	top := len(w.last) - 1
+	if delta := id - w.last[top]; delta > 0 && delta <= 15 {
+		w.buf.WriteByte(byte(delta)<<4 | typ)
+	} else {
+		w.buf.WriteByte(typ)
+		w.varint(int64(id))
+	}
+	w.last[top] = id
+}
+
+func (w *thriftWriter) i32(id int16, v int32) {
+	w.field(id, thriftI32)
+	w.varint(int64(v))
+}
+
+func (w *thriftWriter) i64(id int16, v int64) {
+	w.field(id, thriftI64)
+	w.varint(v)
+}
+
+func (w *thriftWriter) binary(id int16, s string) {
+	w.field(id, thriftBinary)
+	w.buf.Write(binary.AppendUvarint(nil, uint64(len(s))))
+	w.buf.WriteString(s)
+}
+
+// Start a list field of n elements
+func (w *thriftWriter) list(id int16, elem byte, n int) {
+	w.field(id, thriftList)
+	if n < 15 {
+		w.buf.WriteByte(byte(n)<<4 | elem)
+	} else {
+		w.buf.WriteByte(0xf0 | elem)
+		w.buf.Write(binary.AppendUvarint(nil, uint64(n)))
+	}
+}
+
+func (w *thriftWriter) i32List(id int16, values ...int32) {
+	w.list(id, thriftI32, len(values))
+	for _, v := range values {
+		w.varint(int64(v))
+	}
+}
+
+func (w *thriftWriter) stringList(id int16, values ...string) {
+	w.list(id, thriftBinary, len(values))
+	for _, s := range values {
+		w.buf.Write(binary.AppendUvarint(nil, uint64(len(s))))
+		w.buf.WriteString(s)
+	}
+}
+
+// Start a struct field; end closes it
+func (w *thriftWriter) begin(id int16) {
+	w.field(id, thriftStruct)
+	w.last = append(w.last, 0)
+}
+
+// Start a struct element of a list; end closes it
+func (w *thriftWriter) element() {
+	w.last = append(w.last, 0)
+}
+
+// Close the innermost struct, or the top-level one
+func (w *thriftWriter) end() {
+	w.buf.WriteByte(0)
+	w.last = w.last[:len(w.last)-1]
+}
+
+// Write a table to path, through a temporary file so readers never see a
+// partial one
+func writeParquetFile(path string, table *parquetTable) error {
+	tmp := path + ".tmp"
+	file, err := os.Create(tmp)
+	if err != nil {
+		return err
+	}
+	if err := table.Write(file); err != nil {
+		file.Close()
+		os.Remove(tmp)
+		return err
+	}
+	if err := file.Close(); err != nil {
+		os.Remove(tmp)
+		return err
+	}
+	return os.Rename(tmp, path)
+}
+
+// Run `cardinal export`: convert a capture into a Parquet file and return
+// the exit code
+func runExport(opts Options) int {
+	settings, err := loadSettings(opts)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal export:", err)
+		return exitUsage
+	}
+	capture, err := OpenCapture(opts.Args[0])
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal export:", err)
+		return exitFailure
+	}
+	defer capture.Close()
+
+	table := newParquetTable()
+	undecryptable := 0
+	for {
+		msg, err := capture.Next()
+		if err == io.EOF {
+			break
+		}
+		if err != nil {
+			fmt.Fprintln(os.Stderr, "cardinal export:", err)
+			return exitFailure
+		}
+		if opts.Topic != "" && msg.Topic != opts.Topic {
+			continue
+		}
+		// Export plaintext where the key allows it, sealed payloads otherwise,
+		// scrubbed like recordings are
+		if opened, err := settings.Cipher.Open(msg); err == nil {
+			msg = opened
+		} else {
+			undecryptable++
+		}
+		table.AddMessage(settings.Redactor.Redact(msg))
+	}
+	if table.rows == 0 {
+		fmt.Fprintln(os.Stderr, "cardinal export: no messages to export")
+		return exitNoData
+	}
+
+	if err := writeParquetFile(opts.Output, table); err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal export:", err)
+		return exitFailure
+	}
+
+	result := map[string]any{"output": opts.Output, "rows": table.rows, "columns": len(table.columns), "undecryptable": undecryptable}
+	printResult(opts, result, func() {
+		fmt.Printf("✅ Exported %d rows with %d columns into %s\n", table.rows, len(table.columns), opts.Output)
+		if undecryptable > 0 {
+			fmt.Printf("⚠️  %d payloads could not be decrypted and were exported sealed\n", undecryptable)
+		}
+	})
+	return exitOK
+}
+
+// The Parquet sink collects messages into files of a set number of rows:
+//
+//	[sinks.analysis]
+//	type = "parquet"
+//	dir = "/data/parquet"
+//	rows = 100000
+type parquetSink struct {
+	name  string
+	dir   string
+	rows  int
+	table *parquetTable
+}
+
+func newParquetSink(spec sinkSpec) (sinkBackend, error) {
+	dir := spec.settings["dir"]
+	if dir == "" {
+		return nil, fmt.Errorf("needs a dir")
+	}
+	rows := 100000
+	if value, ok := spec.settings["rows"]; ok {
+		n, err := strconv.Atoi(value)
+		if err != nil || n <= 0 {
+			return nil, fmt.Errorf("rows must be a positive integer")
+		}
+		rows = n
+	}
+	if err := os.MkdirAll(dir, 0o755); err != nil {
+		return nil, err
+	}
+	return &parquetSink{name: spec.name, dir: dir, rows: rows, table: newParquetTable()}, nil
+}
+
+func (s *parquetSink) WriteBatch(batch []DDSMessage) error {
+	for _, msg := range batch {
+		s.table.AddMessage(msg)
+		if s.table.rows >= s.rows {
+			if err := s.flush(); err != nil {
+				return err
+			}
+		}
+	}
+	return nil
+}
+
+// Write the collected rows as the next file
+func (s *parquetSink) flush() error {
+	if s.table.rows == 0 {
+		return nil
+	}
+	name := fmt.Sprintf("%s-%s.parquet", s.name, strings.ReplaceAll(time.Now().UTC().Format("20060102T150405.000000"), ".", ""))
+	err := writeParquetFile(filepath.Join(s.dir, name), s.table)
+	s.table = newParquetTable()
+	return err
+}
+
+func (s *parquetSink) Close() error {
+	return s.flush()
+}
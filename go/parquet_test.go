@@ -0,0 +1,197 @@
+package main
+
+import (
+	"bytes"
+	"encoding/binary"
+	"fmt"
+	"math"
+	"testing"
+	"time"
+)
+
+// Writes a table and reads it back through the footer and the data pages,
+// with a small Thrift compact reader independent of the writer
+func TestParquetRoundTrip(t *testing.T) {
+	received := time.Date(2026, 3, 1, 12, 0, 0, 0, time.UTC)
+	table := newParquetTable()
+	table.AddMessage(DDSMessage{
+		Topic:      "/pose",
+		Content:    `{"x": 1.5, "ok": true, "name": "a"}`,
+		ReceivedAt: received,
+		Hash:       "00ff",
+		Writer:     "01.0f.aa",
+		Sequence:   1 << 60,
+	})
+	table.AddMessage(DDSMessage{
+		Topic:      "/pose",
+		Content:    `{"x": -2, "name": 7}`,
+		ReceivedAt: received.Add(time.Millisecond),
+	})
+
+	var buf bytes.Buffer
+	if err := table.Write(&buf); err != nil {
+		t.Fatal(err)
+	}
+	file := buf.Bytes()
+	if !bytes.HasPrefix(file, []byte("PAR1")) || !bytes.HasSuffix(file, []byte("PAR1")) {
+		t.Fatal("missing PAR1 magic")
+	}
+	footerLen := int(binary.LittleEndian.Uint32(file[len(file)-8:]))
+	footer := file[len(file)-8-footerLen : len(file)-8]
+	meta, rest := readThriftStruct(t, footer)
+	if len(rest) != 0 {
+		t.Fatalf("%d bytes after the file metadata", len(rest))
+	}
+
+	if rows := meta[3].(int64); rows != 2 {
+		t.Fatalf("num_rows = %d, want 2", rows)
+	}
+	schema := meta[2].([]any)
+	var names []string
+	for _, element := range schema[1:] {
+		names = append(names, element.(map[int16]any)[4].(string))
+	}
+	want := []string{"topic", "received_at", "timestamp", "payload", "hash", "writer", "sequence", "name", "ok", "x"}
+	if fmt.Sprint(names) != fmt.Sprint(want) {
+		t.Fatalf("columns = %v, want %v", names, want)
+	}
+
+	// Values of every column of the single row group
+	groups := meta[4].([]any)
+	if len(groups) != 1 {
+		t.Fatalf("%d row groups, want 1", len(groups))
+	}
+	values := map[string][]any{}
+	for i, chunk := range groups[0].(map[int16]any)[1].([]any) {
+		column := chunk.(map[int16]any)[3].(map[int16]any)
+		offset := column[9].(int64)
+		element := schema[i+1].(map[int16]any)
+		required := element[3].(int64) == parquetRequired
+		values[names[i]] = readParquetPage(t, file[offset:], element[1].(int64), required)
+	}
+
+	check := func(name string, want ...any) {
+		t.Helper()
+		if fmt.Sprint(values[name]) != fmt.Sprint(want) {
+			t.Errorf("%s = %v, want %v", name, values[name], want)
+		}
+	}
+	check("topic", "/pose", "/pose")
+	check("received_at", received.UnixMicro(), received.Add(time.Millisecond).UnixMicro())
+	check("timestamp", nil, nil)
+	check("hash", "00ff", nil)
+	check("writer", "01.0f.aa", nil)
+	check("sequence", int64(1<<60), nil)
+	check("x", 1.5, -2.0)
+	check("ok", true, nil)
+	check("name", "a", "7") // widened to a string column
+}
+
+// Decode the PLAIN values of a data page, nil for the rows without one
+func readParquetPage(t *testing.T, data []byte, physical int64, required bool) []any {
+	t.Helper()
+	header, data := readThriftStruct(t, data)
+	page := header[5].(map[int16]any)
+	rows := int(page[1].(int64))
+	data = data[:header[3].(int64)]
+
+	present := make([]bool, rows)
+	if required {
+		for i := range present {
+			present[i] = true
+		}
+	} else {
+		n := binary.LittleEndian.Uint32(data)
+		levels := data[4 : 4+n]
+		data = data[4+n:]
+		run, size := binary.Uvarint(levels)
+		if run&1 != 1 {
+			t.Fatal("definition levels are not bit-packed")
+		}
+		bits := levels[size:]
+		for i := range present {
+			present[i] = bits[i/8]&(1<<(i%8)) != 0
+		}
+	}
+
+	values := make([]any, rows)
+	bit := 0
+	for i := range values {
+		if !present[i] {
+			continue
+		}
+		switch physical {
+		case parquetBoolean:
+			values[i] = data[bit/8]&(1<<(bit%8)) != 0
+			bit++
+		case parquetInt64:
+			values[i] = int64(binary.LittleEndian.Uint64(data))
+			data = data[8:]
+		case parquetDouble:
+			values[i] = math.Float64frombits(binary.LittleEndian.Uint64(data))
+			data = data[8:]
+		case parquetByteArray:
+			n := binary.LittleEndian.Uint32(data)
+			values[i] = string(data[4 : 4+n])
+			data = data[4+n:]
+		default:
+			t.Fatalf("unexpected physical type %d", physical)
+		}
+	}
+	return values
+}
+
+// Read a Thrift compact struct into its fields by id, returning the bytes
+// after it
+func readThriftStruct(t *testing.T, data []byte) (map[int16]any, []byte) {
+	t.Helper()
+	fields := map[int16]any{}
+	var id int16
+	for {
+		if len(data) == 0 {
+			t.Fatal("truncated struct")
+		}
+		header := data[0]
+		data = data[1:]
+		if header == 0 {
+			return fields, data
+		}
+		if delta := int16(header >> 4); delta != 0 {
+			id += delta
+		} else {
+			v, n := binary.Varint(data)
+			id, data = int16(v), data[n:]
+		}
+		fields[id], data = readThriftValue(t, header&0x0f, data)
+	}
+}
+
+func readThriftValue(t *testing.T, typ byte, data []byte) (any, []byte) {
+	t.Helper()
+	switch typ {
+	case thriftI32, thriftI64:
+		v, n := binary.Varint(data)
+		return v, data[n:]
+	case thriftBinary:
+		n, size := binary.Uvarint(data)
+		data = data[size:]
+		return string(data[:n]), data[n:]
+	case thriftList:
+		header := data[0]
+		data = data[1:]
+		count := int(header >> 4)
+		if count == 15 {
+			n, size := binary.Uvarint(data)
+			count, data = int(n), data[size:]
+		}
+		list := make([]any, count)
+		for i := range list {
+			list[i], data = readThriftValue(t, header&0x0f, data)
+		}
+		return list, data
+	case thriftStruct:
+		return readThriftStruct(t, data)
+	}
+	t.Fatalf("unexpected Thrift type %d", typ)
+	return nil, nil
+}
@@ -0,0 +1,85 @@
+package main
+
+import (
+	"fmt"
+
+	"github.com/charmbracelet/lipgloss"
+)
+
+// The Participants tab lists the other DDS participants of the domain as
+// discovery finds and loses them, with the host and process they announce.
+// Participants of Cardinal and other Fast DDS applications announcing their
+// physical data show where they run; others show their GUID and name only.
+
+// A participant of another process, as discovery reported it
+type RemoteParticipant struct {
+	GUID    string `json:"guid"`
+	Name    string `json:"name,omitempty"`
+	Host    string `json:"host,omitempty"`    // empty when not announced
+	Process string `json:"process,omitempty"` // process id, empty when not announced
+	Domain  string `json:"domain,omitempty"`  // set when Cardinal joined several
+}
+
+// The participant as logs name it
+func (p RemoteParticipant) label() string {
+	label := p.GUID
+	if p.Name != "" {
+		label = p.Name + " (" + p.GUID + ")"
+	}
+	if p.Host != "" {
+		label += " on " + p.Host
+	}
+	return label
+}
+
+// Render the participants tab
+func (m model) renderParticipants(primaryColor, textColor, dimColor lipgloss.Color) string {
+	cardStyle := lipgloss.NewStyle().
+		Border(lipgloss.RoundedBorder()).
+		BorderForeground(primaryColor).
+		Padding(1, 2).
+		MarginBottom(1)
+	headerStyle := lipgloss.NewStyle().
+		Foreground(primaryColor).
+		Bold(true).
+		MarginBottom(1)
+	dimStyle := lipgloss.NewStyle().Foreground(dimColor)
+
+	lines := []string{headerStyle.Render(fmt.Sprintf("🛰️ Remote Participants (%d)", len(m.participants)))}
+	switch {
+	case m.remoteParticipants == nil:
+		lines = append(lines, dimStyle.Render("Participant discovery needs the Fast DDS transport"))
+	case len(m.participants) == 0:
+		lines = append(lines, dimStyle.Render("No other participants discovered yet"))
+	default:
+		columnStyle := lipgloss.NewStyle().Foreground(dimColor).Bold(true)
+		lines = append(lines, lipgloss.JoinHorizontal(lipgloss.Left,
+			columnStyle.Width(36).Render("GUID"),
+			columnStyle.Width(24).Render("Name"),
+			columnStyle.Width(24).Render("Host"),
+			columnStyle.Render("Process"),
+		))
+		textStyle := lipgloss.NewStyle().Foreground(textColor)
+		for _, p := range m.participants {
+			name := p.Name
+			if p.Domain != "" {
+				name = "d" + p.Domain + " " + name
+			}
+			lines = append(lines, lipgloss.JoinHorizontal(lipgloss.Left,
+				textStyle.Width(36).Render(truncate(p.GUID, 34)),
+				textStyle.Width(24).Render(truncate(orDash(name), 22)),
+				textStyle.Width(24).Render(truncate(orDash(p.Host), 22)),
+				textStyle.Render(orDash(p.Process)),
+			))
+		}
+	}
+	return cardStyle.Render(lipgloss.JoinVertical(lipgloss.Left, lines...))
+}
+
+// A value for a table cell, "-" when unknown
+func orDash(s string) string {
+	if s == "" {
+		return "-"
+	}
+	return s
+}
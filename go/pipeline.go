@@ -0,0 +1,132 @@
+package main
+
+import (
+	"fmt"
+	"strings"
+	"sync/atomic"
+	"time"
+
+	"github.com/charmbracelet/lipgloss"
+)
+
+// Stages of the message pipeline that are timed
+type pipelineStage int
+
+const (
+	stagePublish pipelineStage = iota
+	stageReceive
+	stageDecode
+	stageIngest
+	stageRender
+	stageCount
+)
+
+var pipelineStageNames = [...]string{"Publish (FFI)", "Receive (FFI)", "Decode", "Ingest", "Render"}
+
+// Accumulated timings for one pipeline stage
+type stageTimer struct {
+	count      atomic.Int64
+	totalNanos atomic.Int64
+	maxNanos   atomic.Int64
+}
+
+// Instrumented timers for every pipeline stage, shared by all goroutines
+var pipeline [stageCount]stageTimer
+
+// Start timing a stage; call the returned function when the stage is done
+func timeStage(stage pipelineStage) func() {
+	start := time.Now()
+	return func() {
+		pipeline[stage].observe(time.Since(start))
+	}
+}
+
+func (s *stageTimer) observe(d time.Duration) {
+	s.count.Add(1)
+	s.totalNanos.Add(int64(d))
+	for {
+		current := s.maxNanos.Load()
+		if int64(d) <= current || s.maxNanos.CompareAndSwap(current, int64(d)) {
+			return
+		}
+	}
+}
+
+// Snapshot of a stage's timings
+type stageSnapshot struct {
+	name  string
+	count int64
+	total time.Duration
+	max   time.Duration
+}
+
+func (s stageSnapshot) average() time.Duration {
+	if s.count == 0 {
+		return 0
+	}
+	return s.total / time.Duration(s.count)
+}
+
+// Upper bound on messages per second if this stage were the only cost
+func (s stageSnapshot) ceiling() float64 {
+	avg := s.average()
+	if avg == 0 {
+		return 0
+	}
+	return float64(time.Second) / float64(avg)
+}
+
+func pipelineSnapshot() []stageSnapshot {
+	snapshots := make([]stageSnapshot, stageCount)
+	for i := range pipeline {
+		snapshots[i] = stageSnapshot{
+			name:  pipelineStageNames[i],
+			count: pipeline[i].count.Load(),
+			total: time.Duration(pipeline[i].totalNanos.Load()),
+			max:   time.Duration(pipeline[i].maxNanos.Load()),
+		}
+	}
+	return snapshots
+}
+
+// Render the pipeline breakdown card
+func renderPipeline(headerStyle lipgloss.Style, textColor, dimColor, accentColor lipgloss.Color) string {
+	rowStyle := lipgloss.NewStyle().Foreground(textColor)
+	slowStyle := lipgloss.NewStyle().Foreground(accentColor).Bold(true)
+	dimStyle := lipgloss.NewStyle().Foreground(dimColor).Italic(true)
+
+	snapshots := pipelineSnapshot()
+
+	// The stage with the highest average cost limits throughput
+	slowest := -1
+	var slowestAvg time.Duration
+	for i, s := range snapshots {
+		if s.count > 0 && s.average() > slowestAvg {
+			slowest, slowestAvg = i, s.average()
+		}
+	}
+
+	var b strings.Builder
+	b.WriteString(headerStyle.Render("🧮 Pipeline Breakdown"))
+	b.WriteString("\n")
+	b.WriteString(dimStyle.Render(fmt.Sprintf("%-14s %9s %11s %11s %13s", "Stage", "Calls", "Avg", "Max", "Ceiling")))
+	b.WriteString("\n")
+
+	for i, s := range snapshots {
+		ceiling := "-"
+		if s.count > 0 {
+			ceiling = fmt.Sprintf("%.0f/s", s.ceiling())
+		}
+		line := fmt.Sprintf("%-14s %9d %11s %11s %13s",
+			s.name, s.count, s.average().Round(time.Microsecond), s.max.Round(time.Microsecond), ceiling)
+		if i == slowest {
+			b.WriteString(slowStyle.Render(line + " ◀"))
+		} else {
+			b.WriteString(rowStyle.Render(line))
+		}
+		b.WriteString("\n")
+	}
+	b.WriteString(dimStyle.Render("Ceiling: max msg/s if the stage were the only cost"))
+
+	return b.String()
+}
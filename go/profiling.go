@@ -0,0 +1,29 @@
+package main
+
+import (
+	"context"
+	"log"
+	"net/http"
+	_ "net/http/pprof"
+	"runtime/pprof"
+	"runtime/trace"
+)
+
+// Serve the net/http/pprof endpoints. Goroutine profiles group goroutines
+// by their task label, and /debug/pprof/trace captures execution traces
+// showing each named task for `go tool trace`.
+func startProfiling(addr string) {
+	go func() {
+		log.Printf("pprof listening on http://%s/debug/pprof/", addr)
+		if err := http.ListenAndServe(addr, nil); err != nil {
+			log.Printf("pprof server stopped: %v", err)
+		}
+	}()
+}
+
+// Run fn as a named task, visible in goroutine profiles and execution traces
+func runTask(ctx context.Context, name string, fn func(ctx context.Context)) {
+	ctx, task := trace.NewTask(ctx, name)
+	defer task.End()
+	pprof.Do(ctx, pprof.Labels("task", name), fn)
+}
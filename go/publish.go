@@ -0,0 +1,246 @@
+package main
+
+import (
+	"bufio"
+	"errors"
+	"fmt"
+	"io"
+	"os"
+	"strings"
+	"time"
+
+	tea "github.com/charmbracelet/bubbletea"
+	"github.com/charmbracelet/lipgloss"
+)
+
+// Publishes one message from the TUI
+type publishFunc func(topic, content string) error
+
+// Returned when a publish on a protected profile was not confirmed
+var errNotConfirmed = errors.New("publish not confirmed")
+
+// Ask for a token (what describes it, e.g. the topic name) to be typed
+// before publishing on a protected profile, so test data is not injected
+// into a live system by accident
+func confirmPublish(profile Profile, what, token string, in io.Reader, out io.Writer) error {
+	if !profile.Protected {
+		return nil
+	}
+
+	fmt.Fprintf(out, "⚠️  Profile %q is protected. Type %s (%s) to publish: ", profile.Name, what, token)
+	line, err := bufio.NewReader(in).ReadString('\n')
+	if err != nil && line == "" {
+		return errNotConfirmed
+	}
+	if strings.TrimSpace(line) != token {
+		return errNotConfirmed
+	}
+	return nil
+}
+
+// Confirm a command publishing on a protected profile. --confirm answers
+// the prompt non-interactively, for scripts.
+func confirmCommand(opts Options, profile Profile, what, token string, in io.Reader) error {
+	if opts.Confirm != "" {
+		if opts.Confirm != token {
+			return errNotConfirmed
+		}
+		return nil
+	}
+	return confirmPublish(profile, what, token, in, os.Stderr)
+}
+
+// Run `cardinal pub`: publish one message, or end an instance of a keyed
+// topic, and return the exit code
+func runPub(opts Options) int {
+	topic := opts.Args[0]
+
+	settings, err := loadSettings(opts)
+	profile := settings.Profile
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal pub:", err)
+		return exitUsage
+	}
+
+	if err := confirmCommand(opts, profile, "the topic name", topic, os.Stdin); err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal pub:", err)
+		return exitFailure
+	}
+
+	transport, err := NewFastDDSTransport(profile.Domain, settings.QoS)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal pub:", err)
+		return exitFailure
+	}
+	pub, err := withPayloadCipher(transport, settings.Cipher).NewPublisher(topic)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal pub:", err)
+		return exitFailure
+	}
+	defer pub.Cleanup()
+
+	// Without a matched reader the message would go nowhere
+	if opts.WaitMatched > 0 {
+		if code := waitMatched(pub, opts.WaitMatched); code != exitOK {
+			return code
+		}
+	}
+
+	if opts.Dispose || opts.Unregister {
+		return endPubInstance(opts, pub, topic)
+	}
+
+	msg := DDSMessage{Topic: topic, Content: opts.Args[1], Key: opts.Key, Timestamp: time.Now()}
+	if err := pub.Publish(msg); err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal pub:", err)
+		return exitFailure
+	}
+	qos, known := activeQoS(pub)
+	result := map[string]any{"topic": topic, "published": true}
+	if known {
+		result["publish_mode"] = qos.PublishMode
+		result["heartbeat_period_seconds"] = qos.Heartbeat.Seconds()
+		result["nack_response_delay_seconds"] = qos.NackDelay.Seconds()
+	}
+	printResult(opts, result, func() {
+		if known {
+			fmt.Printf("✅ Published to %s (%s)\n", topic, qos.writerSummary())
+		} else {
+			fmt.Printf("✅ Published to %s\n", topic)
+		}
+	})
+	return exitOK
+}
+
+// Dispose or unregister the instance of --key and return the exit code
+func endPubInstance(opts Options, pub DDSPublisher, topic string) int {
+	if err := endInstance(pub, opts.Key, opts.Dispose); err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal pub:", err)
+		return exitFailure
+	}
+	state := "unregistered"
+	if opts.Dispose {
+		state = "disposed"
+	}
+	printResult(opts, map[string]any{"topic": topic, "key": opts.Key, "instance": state}, func() {
+		fmt.Printf("✅ Instance %q of %s %s\n", opts.Key, topic, state)
+	})
+	return exitOK
+}
+
+// Block until the publisher has matched a reader or the timeout expires,
+// returning the exit code to stop with or exitOK
+func waitMatched(pub DDSPublisher, timeout time.Duration) int {
+	reporter, ok := baseEndpoint(pub).(matchReporter)
+	if !ok {
+		fmt.Fprintln(os.Stderr, "cardinal pub: --wait-matched needs the Fast DDS transport")
+		return exitFailure
+	}
+
+	deadline := time.Now().Add(timeout)
+	for reporter.MatchedReaders() == 0 {
+		if time.Now().After(deadline) {
+			if incompatibleQoS(pub) > 0 {
+				fmt.Fprintln(os.Stderr, "cardinal pub: readers found, but with incompatible QoS")
+				return exitQoSMismatch
+			}
+			fmt.Fprintf(os.Stderr, "cardinal pub: no reader matched within %v\n", timeout)
+			return exitTimeout
+		}
+		time.Sleep(50 * time.Millisecond)
+	}
+	return exitOK
+}
+
+// Steps of the interactive publish prompt
+type promptStage int
+
+const (
+	promptTopic promptStage = iota
+	promptContent
+	promptConfirm
+)
+
+// Interactive publish prompt on the messages tab
+type publishPrompt struct {
+	active  bool
+	stage   promptStage
+	input   string
+	topic   string
+	content string
+	status  string
+}
+
+// Result of an interactive publish
+type publishResultMsg struct {
+	topic string
+	err   error
+}
+
+// Open the prompt, starting with the topic
+func newPublishPrompt() publishPrompt {
+	return publishPrompt{active: true, stage: promptTopic, input: defaultTopic}
+}
+
+// Handle a key while the prompt is open. Publishing happens in the
+// returned command so a slow DataWriter creation does not block the UI.
+func (p publishPrompt) handleKey(msg tea.KeyMsg, profile Profile, publish publishFunc) (publishPrompt, tea.Cmd) {
+	switch msg.Type {
+	case tea.KeyEsc, tea.KeyCtrlC:
+		return publishPrompt{status: "Publish cancelled"}, nil
+	case tea.KeyEnter:
+	default:
+		p.input, _ = editLine(p.input, msg)
+		return p, nil
+	}
+
+	switch p.stage {
+	case promptTopic:
+		if p.input == "" {
+			return p, nil
+		}
+		p.topic, p.input, p.stage = p.input, "", promptContent
+		return p, nil
+	case promptContent:
+		p.content, p.input = p.input, ""
+		if profile.Protected {
+			p.stage = promptConfirm
+			return p, nil
+		}
+	case promptConfirm:
+		if p.input != p.topic {
+			return publishPrompt{status: "❌ " + errNotConfirmed.Error()}, nil
+		}
+	}
+
+	topic, content := p.topic, p.content
+	return publishPrompt{status: "Publishing to " + topic + "..."}, func() tea.Msg {
+		return publishResultMsg{topic: topic, err: publish(topic, content)}
+	}
+}
+
+// Render the prompt, or the outcome of the last publish
+func (p publishPrompt) render(primaryColor, textColor, dimColor lipgloss.Color, profile Profile) string {
+	labelStyle := lipgloss.NewStyle().Foreground(primaryColor).Bold(true)
+	inputStyle := lipgloss.NewStyle().Foreground(textColor)
+	hintStyle := lipgloss.NewStyle().Foreground(dimColor).Italic(true)
+
+	if !p.active {
+		return hintStyle.Render(p.status)
+	}
+
+	var label string
+	switch p.stage {
+	case promptTopic:
+		label = "Topic:"
+	case promptContent:
+		label = "Message for " + p.topic + ":"
+	case promptConfirm:
+		label = fmt.Sprintf("⚠️  Profile %q is protected. Type %s to confirm:", profile.Name, p.topic)
+	}
+	return lipgloss.JoinVertical(lipgloss.Left,
+		labelStyle.Render("📤 Publish"),
+		label+" "+inputStyle.Render(p.input+"█"),
+		hintStyle.Render("Enter to continue • Esc to cancel"),
+	)
+}
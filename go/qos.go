@@ -0,0 +1,265 @@
+package main
+
+import (
+	"fmt"
+	"path"
+	"strconv"
+	"strings"
+	"time"
+)
+
+// QoS of the endpoints Cardinal creates. The [qos] table sets it for every
+// topic, [qos."<topic pattern>"] tables for the topics matching the
+// pattern, an exact topic name over patterns:
+//
+//	[qos]
+//	reliability = "reliable"  # or best_effort
+//	durability = "transient_local"  # or volatile
+//	history = "keep_last"   # or keep_all
+//	history_depth = 10
+//	max_samples = 1000      # samples an endpoint may hold in memory
+//	publish_mode = "async"  # sync or async
+//	flow_limit = "256KiB"   # bytes per flow_period through a flow controller
+//	flow_period = "100ms"
+//	heartbeat_period = "100ms"  # reliable writers
+//	nack_response_delay = "1ms"
+//	latency_budget = "20ms"     # flags slower samples
+//	deadline = "100ms"          # longest expected gap between samples
+//	liveliness = "automatic"    # or manual_by_participant, manual_by_topic
+//	liveliness_lease = "2s"     # silence after which a writer counts as dead
+//	partitions = ["robot1"]     # partitions to publish and subscribe in
+//	keyed = true                # samples carry an instance key
+//	dynamic = true              # decode the type the writers announce
+//
+//	[qos."/camera/*"]
+//	flow_limit = "64KiB"
+//
+// Only the Fast DDS transport applies these settings.
+
+// QoS of the endpoints on one topic. The zero value keeps the Fast DDS
+// defaults.
+type QoS struct {
+	Reliability   string        // "reliable", "best_effort", or "" for reliable writers and best-effort readers
+	Durability    string        // "volatile", "transient_local", or "" for volatile
+	History       string        // "keep_last", "keep_all", or "" for keep_last
+	HistoryDepth  int           // samples kept with keep_last, 0 for 1
+	MaxSamples    int           // samples an endpoint may hold, 0 for no limit
+	PublishMode   string        // "sync", "async", or "" for sync unless there is a flow limit
+	FlowLimit     int64         // bytes a writer may send per FlowPeriod, 0 for no limit
+	FlowPeriod    time.Duration // 0 for 100ms
+	Heartbeat     time.Duration // reliable writers' heartbeat period
+	NackDelay     time.Duration // delay before a reliable writer answers a NACK
+	LatencyBudget time.Duration // end-to-end latency samples should stay within
+	Deadline      time.Duration // longest time between two samples of a topic
+	Liveliness    string        // "automatic", "manual_by_participant", "manual_by_topic", or "" for automatic
+	Lease         time.Duration // liveliness lease duration, 0 for infinite
+	Partitions    []string      // partitions of the endpoints, none for the default partition
+	Keyed         bool          // samples carry an instance key
+	Dynamic       bool          // readers decode samples, as JSON, with the type the writers announce
+}
+
+// Apply the settings of a [qos] table
+func (q *QoS) apply(table map[string]string) error {
+	for key, value := range table {
+		var err error
+		switch key {
+		case "reliability":
+			if value != "reliable" && value != "best_effort" {
+				err = fmt.Errorf("must be reliable or best_effort")
+			}
+			q.Reliability = value
+		case "durability":
+			if value != "volatile" && value != "transient_local" {
+				err = fmt.Errorf("must be volatile or transient_local")
+			}
+			q.Durability = value
+		case "history":
+			if value != "keep_last" && value != "keep_all" {
+				err = fmt.Errorf("must be keep_last or keep_all")
+			}
+			q.History = value
+		case "history_depth":
+			q.HistoryDepth, err = parsePositiveInt(value)
+		case "max_samples":
+			q.MaxSamples, err = parsePositiveInt(value)
+		case "publish_mode":
+			if value != "sync" && value != "async" {
+				err = fmt.Errorf("must be sync or async")
+			}
+			q.PublishMode = value
+		case "flow_limit":
+			q.FlowLimit, err = parseByteSize(value)
+		case "heartbeat_period":
+			q.Heartbeat, err = parsePositiveDuration(value)
+		case "nack_response_delay":
+			q.NackDelay, err = parsePositiveDuration(value)
+		case "latency_budget":
+			q.LatencyBudget, err = parsePositiveDuration(value)
+		case "deadline":
+			q.Deadline, err = parsePositiveDuration(value)
+		case "liveliness":
+			if value != "automatic" && value != "manual_by_participant" && value != "manual_by_topic" {
+				err = fmt.Errorf("must be automatic, manual_by_participant or manual_by_topic")
+			}
+			q.Liveliness = value
+		case "liveliness_lease":
+			q.Lease, err = parsePositiveDuration(value)
+		case "partitions":
+			q.Partitions, err = parseStringList(value)
+		case "keyed":
+			q.Keyed, err = strconv.ParseBool(value)
+		case "dynamic":
+			q.Dynamic, err = strconv.ParseBool(value)
+		case "flow_period":
+			q.FlowPeriod, err = time.ParseDuration(value)
+			if err == nil && (q.FlowPeriod < time.Millisecond || q.FlowPeriod%time.Millisecond != 0) {
+				err = fmt.Errorf("must be a whole number of milliseconds")
+			}
+		default:
+			err = fmt.Errorf("unknown key")
+		}
+		if err != nil {
+			return fmt.Errorf("%s: %w", key, err)
+		}
+	}
+	return nil
+}
+
+// Check settings that only work together
+func (q QoS) validate() error {
+	if q.FlowLimit > 0 && q.PublishMode == "sync" {
+		return fmt.Errorf("flow_limit needs publish_mode = \"async\"")
+	}
+	if q.HistoryDepth > 0 && q.History == "keep_all" {
+		return fmt.Errorf("history_depth needs history = \"keep_last\"")
+	}
+	if q.MaxSamples > 0 && q.HistoryDepth > q.MaxSamples {
+		return fmt.Errorf("history_depth must not exceed max_samples")
+	}
+	return nil
+}
+
+// Describe the writer settings, e.g. "async, heartbeat 3s, NACK delay 5ms"
+func (q QoS) writerSummary() string {
+	parts := []string{q.PublishMode}
+	if q.Heartbeat > 0 {
+		parts = append(parts, "heartbeat "+q.Heartbeat.String())
+	}
+	if q.NackDelay > 0 {
+		parts = append(parts, "NACK delay "+q.NackDelay.String())
+	}
+	return strings.Join(parts, ", ")
+}
+
+func parsePositiveDuration(value string) (time.Duration, error) {
+	d, err := time.ParseDuration(value)
+	if err == nil && d <= 0 {
+		err = fmt.Errorf("must be positive")
+	}
+	return d, err
+}
+
+func parsePositiveInt(value string) (int, error) {
+	n, err := strconv.Atoi(value)
+	if err == nil && n <= 0 {
+		err = fmt.Errorf("must be positive")
+	}
+	return n, err
+}
+
+// Parse a size like 1500, 64KiB or 2MB
+func parseByteSize(value string) (int64, error) {
+	units := []struct {
+		suffix string
+		bytes  int64
+	}{
+		{"KiB", 1 << 10}, {"MiB", 1 << 20}, {"GiB", 1 << 30},
+		{"KB", 1e3}, {"MB", 1e6}, {"GB", 1e9}, {"B", 1},
+	}
+	number, scale := strings.TrimSpace(value), int64(1)
+	for _, unit := range units {
+		if trimmed, ok := strings.CutSuffix(number, unit.suffix); ok {
+			number, scale = strings.TrimSpace(trimmed), unit.bytes
+			break
+		}
+	}
+	n, err := strconv.ParseFloat(number, 64)
+	if err != nil || n < 0 {
+		return 0, fmt.Errorf("invalid size %q", value)
+	}
+	return int64(n * float64(scale)), nil
+}
+
+// The [qos] tables
+type QoSConfig struct {
+	defaults QoS
+	topics   map[string]map[string]string // settings by topic pattern
+}
+
+// Read the [qos] and [qos."<topic pattern>"] tables
+func (c Config) QoS() (*QoSConfig, error) {
+	q := &QoSConfig{topics: map[string]map[string]string{}}
+	if err := q.defaults.apply(c["qos"]); err != nil {
+		return nil, fmt.Errorf("qos: %w", err)
+	}
+	if err := q.defaults.validate(); err != nil {
+		return nil, fmt.Errorf("qos: %w", err)
+	}
+	for table, settings := range c {
+		pattern, ok := strings.CutPrefix(table, "qos.")
+		if !ok {
+			continue
+		}
+		if unquoted, err := strconv.Unquote(pattern); err == nil {
+			pattern = unquoted
+		}
+		if _, err := path.Match(pattern, ""); err != nil {
+			return nil, fmt.Errorf("qos: topic pattern %q: %w", pattern, err)
+		}
+		var check QoS
+		if err := check.apply(settings); err != nil {
+			return nil, fmt.Errorf("qos %s: %w", pattern, err)
+		}
+		q.topics[pattern] = settings
+	}
+	for pattern := range q.topics {
+		if err := q.For(pattern).validate(); err != nil {
+			return nil, fmt.Errorf("qos %s: %w", pattern, err)
+		}
+	}
+	return q, nil
+}
+
+// Whether any topic has a latency budget
+func (q *QoSConfig) hasLatencyBudget() bool {
+	if q == nil {
+		return false
+	}
+	if q.defaults.LatencyBudget > 0 {
+		return true
+	}
+	for _, settings := range q.topics {
+		if settings["latency_budget"] != "" {
+			return true
+		}
+	}
+	return false
+}
+
+// QoS of a topic: the defaults, then every matching pattern in order, then
+// the topic's own table. A nil QoSConfig gives the zero QoS.
+func (q *QoSConfig) For(topic string) QoS {
+	if q == nil {
+		return QoS{}
+	}
+	qos := q.defaults
+	for _, pattern := range sortedKeys(q.topics) {
+		if ok, _ := path.Match(pattern, topic); ok && pattern != topic {
+			qos.apply(q.topics[pattern])
+		}
+	}
+	if settings, ok := q.topics[topic]; ok {
+		qos.apply(settings)
+	}
+	return qos
+}
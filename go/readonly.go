@@ -0,0 +1,19 @@
+package main
+
+import (
+	"errors"
+	"fmt"
+)
+
+// Returned by every publishing path when Cardinal runs with --read-only
+var errReadOnly = errors.New("publishing is disabled in read-only mode")
+
+// Wraps a transport so that no DataWriter can ever be created, making it
+// safe to attach Cardinal to production domains
+type readOnlyTransport struct {
+	Transport
+}
+
+func (t readOnlyTransport) NewPublisher(topic string) (DDSPublisher, error) {
+	return nil, fmt.Errorf("%s: %w", topic, errReadOnly)
+}
@@ -0,0 +1,69 @@
+package main
+
+import (
+	"context"
+	"sync"
+	"testing"
+	"time"
+)
+
+// A mock transport that can also read topics of other types, and watch
+// discovery
+type capableTransport struct {
+	*MockTransport
+	dynamic []string
+}
+
+func (t *capableTransport) NewDynamicSubscriber(topic string) (DDSSubscriber, error) {
+	t.dynamic = append(t.dynamic, topic)
+	return t.NewSubscriber(topic)
+}
+
+func (t *capableTransport) WatchDiscovery(discoveryListener) (func(), error) {
+	return func() {}, nil
+}
+
+// The read-only guard blocks writers but keeps the reading capabilities of
+// the transport under it, the payload cipher included
+func TestReadOnlyTransportKeepsReadingCapabilities(t *testing.T) {
+	base := &capableTransport{MockTransport: NewMockTransport()}
+	transport := readOnlyTransport{encryptedTransport{base, nil}}
+
+	if _, err := transport.NewPublisher("/cmd"); err == nil {
+		t.Error("read-only transport created a publisher")
+	}
+	if _, ok := baseTransport(transport).(discoveryWatcher); !ok {
+		t.Error("discovery watching is lost under the read-only guard")
+	}
+	if _, ok := baseTransport(transport).(dynamicSubscriber); !ok {
+		t.Error("dynamic subscribing is lost under the read-only guard")
+	}
+}
+
+// Subscribing to a discovered topic of a foreign type goes through the
+// dynamic subscriber, also in read-only mode
+func TestSubscribeDiscoveredReadOnly(t *testing.T) {
+	base := &capableTransport{MockTransport: NewMockTransport()}
+	ctx, cancel := context.WithCancel(context.Background())
+	var wg sync.WaitGroup
+	session := NewSession(ctx, &wg, readOnlyTransport{base}, NewStatsTracker(), time.Now(), func(DDSMessage) {})
+	defer func() {
+		cancel()
+		wg.Wait()
+		session.Close()
+	}()
+
+	session.mu.Lock()
+	session.endpoints["01.0f"] = RemoteEndpoint{GUID: "01.0f", Topic: "/scan", Type: "sensor_msgs::msg::dds_::LaserScan_", Writer: true}
+	session.mu.Unlock()
+
+	if err := session.SubscribeDiscovered("/scan"); err != nil {
+		t.Fatal(err)
+	}
+	if len(base.dynamic) != 1 || base.dynamic[0] != "/scan" {
+		t.Errorf("dynamic subscribers = %v, want [/scan]", base.dynamic)
+	}
+	if topics := session.Topics(); len(topics) != 1 || topics[0] != "/scan" {
+		t.Errorf("topics = %v, want [/scan]", topics)
+	}
+}
@@ -0,0 +1,220 @@
+package main
+
+import (
+	"context"
+	"fmt"
+	"log"
+	"path/filepath"
+	"strings"
+	"sync"
+	"time"
+)
+
+// Status of an active recording
+type RecordingStatus struct {
+	Path      string    `json:"path"`
+	Started   time.Time `json:"started"`
+	Messages  int       `json:"messages"`
+	Bytes     int64     `json:"bytes"`
+	Decimated int       `json:"decimated,omitempty"` // samples left out by [record.decimate]
+	Chunk     string    `json:"chunk,omitempty"`     // file being written when chunked
+	Chunks    int       `json:"chunks,omitempty"`
+}
+
+// When a chunked recording moves on to its next file; the zero value
+// records into a single file. The [record] table sets it:
+//
+//	[record]
+//	chunk_size = "256MiB"
+//	chunk_duration = "10m"
+type chunkPolicy struct {
+	size     int64         // bytes per file, 0 for no limit
+	duration time.Duration // time per file, 0 for no limit
+}
+
+// Read the chunk settings of the [record] table
+func (c Config) ChunkPolicy() (chunkPolicy, error) {
+	var policy chunkPolicy
+	var err error
+	if value, ok := c["record"]["chunk_size"]; ok {
+		if policy.size, err = parseByteSize(value); err != nil || policy.size <= 0 {
+			return policy, fmt.Errorf("record: chunk_size must be a positive size")
+		}
+	}
+	if value, ok := c["record"]["chunk_duration"]; ok {
+		if policy.duration, err = parsePositiveDuration(value); err != nil {
+			return policy, fmt.Errorf("record: chunk_duration: %w", err)
+		}
+	}
+	return policy, nil
+}
+
+func (p chunkPolicy) enabled() bool {
+	return p.size > 0 || p.duration > 0
+}
+
+// How recordings are written
+type recorderOptions struct {
+	decimation decimationRules
+	metadata   map[string]string // session metadata written to every file
+	chunks     chunkPolicy
+	finished   func(path string) // called with every finished file
+}
+
+// How often a recording is flushed to disk, so `cardinal view --follow` sees
+// it while it is written
+const recorderFlushInterval = time.Second
+
+// Path of a numbered chunk: run.mcap becomes run-0001.mcap
+func chunkPath(path string, n int) string {
+	ext := filepath.Ext(path)
+	return fmt.Sprintf("%s-%04d%s", strings.TrimSuffix(path, ext), n, ext)
+}
+
+// Records received messages to a capture file, or a numbered series of them
+// when chunked. Safe for concurrent use by the session's subscriber
+// goroutines.
+type Recorder struct {
+	path      string
+	started   time.Time
+	opts      recorderOptions
+	decimator *Decimator
+
+	mu         sync.Mutex
+	capture    *CaptureWriter
+	chunk      int    // number of the open chunk, 0 when not chunked
+	chunkPath  string // file being written
+	chunkStart time.Time
+	messages   int           // in finished chunks
+	bytes      int64         // in finished chunks
+	stop       chan struct{} // ends the flushing, nil once closed
+}
+
+func NewRecorder(path string, opts recorderOptions) (*Recorder, error) {
+	r := &Recorder{path: path, started: time.Now(), opts: opts, decimator: NewDecimator(opts.decimation)}
+	if err := r.openChunk(); err != nil {
+		return nil, err
+	}
+	stop := make(chan struct{})
+	r.stop = stop
+	go runTask(context.Background(), "recorder-flush", func(context.Context) {
+		r.flush(stop)
+	})
+	return r, nil
+}
+
+// Flush the open capture file every recorderFlushInterval until stopped
+func (r *Recorder) flush(stop <-chan struct{}) {
+	ticker := time.NewTicker(recorderFlushInterval)
+	defer ticker.Stop()
+
+	for {
+		select {
+		case <-stop:
+			return
+		case <-ticker.C:
+			r.mu.Lock()
+			if r.capture != nil {
+				if err := r.capture.Flush(); err != nil {
+					log.Printf("Error flushing recording to %s: %v", r.chunkPath, err)
+				}
+			}
+			r.mu.Unlock()
+		}
+	}
+}
+
+// Create the next capture file
+func (r *Recorder) openChunk() error {
+	path := r.path
+	if r.opts.chunks.enabled() {
+		r.chunk++
+		path = chunkPath(r.path, r.chunk)
+	}
+
+	capture, err := CreateCapture(path)
+	if err != nil {
+		return fmt.Errorf("failed to create capture %s: %w", path, err)
+	}
+	if r.opts.metadata != nil {
+		if err := capture.WriteMetadata(captureSessionMetadata, r.opts.metadata); err != nil {
+			capture.Close()
+			return fmt.Errorf("failed to write capture %s: %w", path, err)
+		}
+	}
+	r.capture, r.chunkPath, r.chunkStart = capture, path, time.Now()
+	return nil
+}
+
+// Finish the open capture file and hand it on
+func (r *Recorder) finishChunk() error {
+	r.messages += r.capture.messages
+	r.bytes += r.capture.bytes
+	err := r.capture.Close()
+	r.capture = nil
+	if err == nil && r.opts.finished != nil {
+		r.opts.finished(r.chunkPath)
+	}
+	return err
+}
+
+// Whether the open chunk is complete
+func (r *Recorder) chunkDone() bool {
+	chunks := r.opts.chunks
+	return (chunks.size > 0 && r.capture.bytes >= chunks.size) ||
+		(chunks.duration > 0 && time.Since(r.chunkStart) >= chunks.duration)
+}
+
+// Write a message to the capture, unless decimation leaves it out
+func (r *Recorder) Write(msg DDSMessage) error {
+	if !r.decimator.Keep(msg) {
+		return nil
+	}
+
+	r.mu.Lock()
+	defer r.mu.Unlock()
+
+	if r.capture == nil {
+		return fmt.Errorf("recording to %s already finished", r.path)
+	}
+	if r.opts.chunks.enabled() && r.chunkDone() {
+		if err := r.finishChunk(); err != nil {
+			return err
+		}
+		if err := r.openChunk(); err != nil {
+			return err
+		}
+	}
+	return r.capture.Write(msg)
+}
+
+func (r *Recorder) Status() RecordingStatus {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+
+	status := RecordingStatus{Path: r.path, Started: r.started, Messages: r.messages, Bytes: r.bytes, Decimated: r.decimator.Dropped()}
+	if r.capture != nil {
+		status.Messages += r.capture.messages
+		status.Bytes += r.capture.bytes
+	}
+	if r.chunk > 0 {
+		status.Chunk = r.chunkPath
+		status.Chunks = r.chunk
+	}
+	return status
+}
+
+// Finish the capture file
+func (r *Recorder) Close() error {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+
+	if r.stop != nil {
+		close(r.stop)
+		r.stop = nil
+	}
+	if r.capture == nil {
+		return nil
+	}
+	return r.finishChunk()
+}
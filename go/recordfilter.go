@@ -0,0 +1,125 @@
+package main
+
+import (
+	"fmt"
+	"path"
+	"sync"
+)
+
+// Captures can be limited to the relevant topics with glob rules in the
+// [record] table, and changed on a running daemon with `cardinal ctl
+// record-include`, `record-exclude` and `record-clear`:
+//
+//	[record]
+//	include = ["/cmd/*"]
+//	exclude = ["/camera/*"]
+//
+// A topic is recorded when it matches an include rule, or there are none,
+// and matches no exclude rule.
+
+// Topic rules of the recorder
+type RecordFilterStatus struct {
+	Include []string `json:"include,omitempty"`
+	Exclude []string `json:"exclude,omitempty"`
+}
+
+// Decides which topics are recorded. Safe for concurrent use; a nil
+// RecordFilter records every topic.
+type RecordFilter struct {
+	mu    sync.Mutex
+	rules RecordFilterStatus
+}
+
+// Read the [record] table
+func (c Config) RecordFilter() (*RecordFilter, error) {
+	f := &RecordFilter{}
+	for key, value := range c["record"] {
+		add := f.Include
+		switch key {
+		case "include":
+		case "exclude":
+			add = f.Exclude
+		case "chunk_size", "chunk_duration":
+			continue // see ChunkPolicy
+		default:
+			return nil, fmt.Errorf("record: %s: unknown key", key)
+		}
+		patterns, err := parseStringList(value)
+		if err != nil {
+			return nil, fmt.Errorf("record: %s: %w", key, err)
+		}
+		for _, pattern := range patterns {
+			if err := add(pattern); err != nil {
+				return nil, fmt.Errorf("record: %s: %w", key, err)
+			}
+		}
+	}
+	return f, nil
+}
+
+// Record only topics matching a pattern, and those of other include rules
+func (f *RecordFilter) Include(pattern string) error {
+	if _, err := path.Match(pattern, ""); err != nil {
+		return fmt.Errorf("topic pattern %q: %w", pattern, err)
+	}
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	f.rules.Include = append(f.rules.Include, pattern)
+	return nil
+}
+
+// Leave topics matching a pattern out of recordings
+func (f *RecordFilter) Exclude(pattern string) error {
+	if _, err := path.Match(pattern, ""); err != nil {
+		return fmt.Errorf("topic pattern %q: %w", pattern, err)
+	}
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	f.rules.Exclude = append(f.rules.Exclude, pattern)
+	return nil
+}
+
+// Drop every rule, recording all topics again
+func (f *RecordFilter) Clear() {
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	f.rules = RecordFilterStatus{}
+}
+
+// Whether to record a topic
+func (f *RecordFilter) Allows(topic string) bool {
+	if f == nil {
+		return true
+	}
+
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	for _, pattern := range f.rules.Exclude {
+		if ok, _ := path.Match(pattern, topic); ok {
+			return false
+		}
+	}
+	if len(f.rules.Include) == 0 {
+		return true
+	}
+	for _, pattern := range f.rules.Include {
+		if ok, _ := path.Match(pattern, topic); ok {
+			return true
+		}
+	}
+	return false
+}
+
+// Copies of the rules
+func (f *RecordFilter) Status() RecordFilterStatus {
+	if f == nil {
+		return RecordFilterStatus{}
+	}
+
+	f.mu.Lock()
+	defer f.mu.Unlock()
+	return RecordFilterStatus{
+		Include: append([]string(nil), f.rules.Include...),
+		Exclude: append([]string(nil), f.rules.Exclude...),
+	}
+}
@@ -0,0 +1,162 @@
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+	"io"
+	"regexp"
+	"slices"
+	"strings"
+)
+
+// Applies redaction rules to payloads before they are written to
+// recordings, so captures can be shared without leaking serials,
+// coordinates or user data. Rules come from the [redaction] config table:
+//
+//	[redaction]
+//	fields = ["gps.lat", "gps.lon", "user.email"]   # JSON payloads
+//	patterns = ["SN-[0-9]+"]                         # any payload
+//	replacement = "[REDACTED]"
+type Redactor struct {
+	fields      [][]string
+	patterns    []*regexp.Regexp
+	replacement string
+}
+
+const defaultRedaction = "[REDACTED]"
+
+// Build the redactor from the [redaction] table, or nil when the config has
+// none
+func (c Config) Redactor() (*Redactor, error) {
+	table, ok := c["redaction"]
+	if !ok {
+		return nil, nil
+	}
+
+	r := &Redactor{replacement: defaultRedaction}
+	if replacement, ok := table["replacement"]; ok {
+		r.replacement = replacement
+	}
+
+	if raw, ok := table["fields"]; ok {
+		fields, err := parseStringList(raw)
+		if err != nil {
+			return nil, fmt.Errorf("redaction: fields: %w", err)
+		}
+		for _, field := range fields {
+			r.fields = append(r.fields, strings.Split(field, "."))
+		}
+	}
+
+	if raw, ok := table["patterns"]; ok {
+		patterns, err := parseStringList(raw)
+		if err != nil {
+			return nil, fmt.Errorf("redaction: patterns: %w", err)
+		}
+		for _, pattern := range patterns {
+			re, err := regexp.Compile(pattern)
+			if err != nil {
+				return nil, fmt.Errorf("redaction: pattern %q: %w", pattern, err)
+			}
+			r.patterns = append(r.patterns, re)
+		}
+	}
+	return r, nil
+}
+
+// Redact a message's payload. Field paths apply when the payload is a JSON
+// object, patterns to every payload. A nil redactor changes nothing.
+func (r *Redactor) Redact(msg DDSMessage) DDSMessage {
+	if r == nil {
+		return msg
+	}
+
+	if len(r.fields) > 0 {
+		msg.Content = r.redactFields(msg.Content)
+	}
+
+	for _, re := range r.patterns {
+		msg.Content = re.ReplaceAllLiteralString(msg.Content, r.replacement)
+	}
+	return msg
+}
+
+// Replace the values at the field paths of a JSON object payload. Only
+// those values are rewritten: key order, numbers and spacing elsewhere stay
+// byte for byte as they were. Other payloads are returned unchanged.
+func (r *Redactor) redactFields(payload string) string {
+	dec := json.NewDecoder(strings.NewReader(payload))
+	dec.UseNumber()
+	if tok, err := dec.Token(); err != nil || tok != json.Delim('{') {
+		return payload
+	}
+	var spans [][2]int64
+	if err := r.scanObject(dec, payload, nil, &spans); err != nil {
+		return payload
+	}
+	if _, err := dec.Token(); err != io.EOF {
+		return payload // more than one JSON value
+	}
+
+	replacement, _ := json.Marshal(r.replacement)
+	var b strings.Builder
+	last := int64(0)
+	for _, span := range spans {
+		b.WriteString(payload[last:span[0]])
+		b.Write(replacement)
+		last = span[1]
+	}
+	b.WriteString(payload[last:])
+	return b.String()
+}
+
+// Walk the members of an object whose opening brace was read, at path
+// prefix, noting where the values to redact start and end in the payload
+func (r *Redactor) scanObject(dec *json.Decoder, payload string, prefix []string, spans *[][2]int64) error {
+	for dec.More() {
+		tok, err := dec.Token()
+		if err != nil {
+			return err
+		}
+		key, _ := tok.(string)
+		path := append(slices.Clip(prefix), key)
+
+		// The value starts after the colon following the key
+		start := dec.InputOffset()
+		for start < int64(len(payload)) && strings.IndexByte(": \t\r\n", payload[start]) >= 0 {
+			start++
+		}
+		switch {
+		case slices.ContainsFunc(r.fields, func(field []string) bool { return slices.Equal(field, path) }):
+			var value json.RawMessage
+			if err := dec.Decode(&value); err != nil {
+				return err
+			}
+			*spans = append(*spans, [2]int64{start, dec.InputOffset()})
+		case r.leadsTo(path) && start < int64(len(payload)) && payload[start] == '{':
+			if _, err := dec.Token(); err != nil {
+				return err
+			}
+			if err := r.scanObject(dec, payload, path, spans); err != nil {
+				return err
+			}
+		default:
+			var value json.RawMessage
+			if err := dec.Decode(&value); err != nil {
+				return err
+			}
+		}
+	}
+	_, err := dec.Token() // the closing brace
+	return err
+}
+
+// Whether a field path continues below path
+func (r *Redactor) leadsTo(path []string) bool {
+	for _, field := range r.fields {
+		if len(field) > len(path) && slices.Equal(field[:len(path)], path) {
+			return true
+		}
+	}
+	return false
+}
@@ -0,0 +1,357 @@
+package main
+
+import (
+	"bufio"
+	"context"
+	"encoding/json"
+	"fmt"
+	"io"
+	"os"
+	"os/signal"
+	"strconv"
+	"strings"
+	"sync"
+	"sync/atomic"
+	"syscall"
+	"time"
+)
+
+// How often a replay checks its clock while waiting for the next message
+const replayPollInterval = time.Millisecond
+
+// Most messages replay publishes in one batch
+const maxReplayBatch = 256
+
+// Messages to replay, in order. Each message's ReceivedAt is its original
+// arrival time, which sets when it is published.
+type replaySource interface {
+	Next() (DDSMessage, error)
+}
+
+// Position in source time during a replay, negative until it starts
+type replayClock interface {
+	Now() time.Duration
+}
+
+// Replay clock advancing with wall time at an adjustable rate
+type playbackClock struct {
+	mu       sync.Mutex
+	origin   time.Time     // wall time when position was last rebased
+	position time.Duration // source time at origin
+	rate     float64
+	paused   bool
+}
+
+func newPlaybackClock(rate float64) *playbackClock {
+	return &playbackClock{origin: time.Now(), rate: rate}
+}
+
+func (c *playbackClock) Now() time.Duration {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	return c.now()
+}
+
+func (c *playbackClock) now() time.Duration {
+	if c.paused {
+		return c.position
+	}
+	return c.position + time.Duration(float64(time.Since(c.origin))*c.rate)
+}
+
+// Restart the wall time reference from the current position, so rate
+// changes only affect the future
+func (c *playbackClock) rebase() {
+	c.position = c.now()
+	c.origin = time.Now()
+}
+
+func (c *playbackClock) SetPaused(paused bool) {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	c.rebase()
+	c.paused = paused
+}
+
+func (c *playbackClock) SetRate(rate float64) {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	c.rebase()
+	c.rate = rate
+}
+
+// Simulation time on a clock topic, as in ROS builtin_interfaces/Time
+type simClockMessage struct {
+	Sec     int64 `json:"sec"`
+	Nanosec int64 `json:"nanosec"`
+}
+
+func newSimClockMessage(t time.Duration) simClockMessage {
+	return simClockMessage{Sec: int64(t / time.Second), Nanosec: int64(t % time.Second)}
+}
+
+func (m simClockMessage) Duration() time.Duration {
+	return time.Duration(m.Sec)*time.Second + time.Duration(m.Nanosec)
+}
+
+// Replay clock driven by a simulator's clock topic. The replay starts at
+// the first clock message and follows it from then on, including pauses
+// and non-realtime rates.
+type externalClock struct {
+	first  atomic.Int64
+	latest atomic.Int64
+}
+
+func newExternalClock() *externalClock {
+	c := &externalClock{}
+	c.first.Store(-1)
+	return c
+}
+
+func (c *externalClock) Now() time.Duration {
+	first := c.first.Load()
+	if first < 0 {
+		return -1
+	}
+	return time.Duration(c.latest.Load() - first)
+}
+
+// Follow clock messages until the subscription ends
+func (c *externalClock) follow(sub DDSSubscriber) {
+	for msg := range sub.Subscribe() {
+		var clock simClockMessage
+		if err := json.Unmarshal([]byte(msg.Content), &clock); err != nil {
+			continue
+		}
+		t := int64(clock.Duration())
+		c.first.CompareAndSwap(-1, t)
+		c.latest.Store(t)
+	}
+}
+
+// Publish the messages of a source as the clock reaches their original
+// offsets. Consecutive messages on one topic that are already due go out in
+// one batch. Returns the number of messages published.
+func replay(ctx context.Context, source replaySource, clock replayClock, started func(time.Time), publish func([]DDSMessage) error) (int, error) {
+	var start time.Time
+	var batch []DDSMessage
+	count := 0
+	flush := func() error {
+		if len(batch) == 0 {
+			return nil
+		}
+		if err := publish(batch); err != nil {
+			return err
+		}
+		count += len(batch)
+		batch = batch[:0]
+		return nil
+	}
+
+	for {
+		msg, err := source.Next()
+		if err == io.EOF {
+			err = flush()
+			return count, err
+		}
+		if err != nil {
+			return count, err
+		}
+		if start.IsZero() {
+			start = msg.ReceivedAt
+			started(start)
+		}
+
+		offset := msg.ReceivedAt.Sub(start)
+		if clock.Now() < offset || len(batch) == maxReplayBatch || (len(batch) > 0 && batch[0].Topic != msg.Topic) {
+			if err := flush(); err != nil {
+				return count, err
+			}
+		}
+		for clock.Now() < offset {
+			select {
+			case <-ctx.Done():
+				return count, ctx.Err()
+			case <-time.After(replayPollInterval):
+			}
+		}
+
+		msg.Timestamp = time.Now()
+		batch = append(batch, msg)
+	}
+}
+
+// Publish the replay position on the clock topic as simulation time, the
+// source's start time plus the position, so pauses and rate changes reach
+// the simulator
+func publishSimClock(ctx context.Context, pub DDSPublisher, clock replayClock, start *atomic.Int64, hz float64) {
+	ticker := time.NewTicker(time.Duration(float64(time.Second) / hz))
+	defer ticker.Stop()
+
+	for {
+		select {
+		case <-ctx.Done():
+			return
+		case <-ticker.C:
+		}
+		base := start.Load()
+		if base == 0 {
+			continue
+		}
+		content, _ := json.Marshal(newSimClockMessage(time.Duration(base) + clock.Now()))
+		pub.Publish(DDSMessage{Content: string(content), Timestamp: time.Now()})
+	}
+}
+
+// Apply playback commands read from stdin: pause, resume, rate <x>, and
+// +/- to double or halve the rate
+func controlPlayback(in *bufio.Reader, clock *playbackClock, rate float64) {
+	for {
+		line, err := in.ReadString('\n')
+		if err != nil {
+			return
+		}
+		fields := strings.Fields(line)
+		if len(fields) == 0 {
+			continue
+		}
+
+		switch fields[0] {
+		case "pause", "p":
+			clock.SetPaused(true)
+			fmt.Printf("⏸  Paused at %.3fs\n", clock.Now().Seconds())
+			continue
+		case "resume", "r":
+			clock.SetPaused(false)
+			fmt.Printf("▶  Resumed at %.3fs\n", clock.Now().Seconds())
+			continue
+		case "+":
+			rate *= 2
+		case "-":
+			rate /= 2
+		case "rate":
+			if len(fields) != 2 {
+				fmt.Println("usage: rate <x>")
+				continue
+			}
+			r, err := strconv.ParseFloat(fields[1], 64)
+			if err != nil || r <= 0 {
+				fmt.Println("rate must be a positive number")
+				continue
+			}
+			rate = r
+		default:
+			fmt.Println("commands: pause, resume, rate <x>, +, -")
+			continue
+		}
+		clock.SetRate(rate)
+		fmt.Printf("⏩ Rate %gx\n", rate)
+	}
+}
+
+// Run `cardinal replay`: publish a capture's messages with their original
+// timing, or the rows of a CSV file, and return the exit code
+func runReplay(opts Options) int {
+	settings, err := loadSettings(opts)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal replay:", err)
+		return exitUsage
+	}
+
+	stdin := bufio.NewReader(os.Stdin)
+	if err := confirmCommand(opts, settings.Profile, "the profile name", settings.Profile.Name, stdin); err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal replay:", err)
+		return exitFailure
+	}
+
+	var source replaySource
+	if opts.CSV != "" {
+		rows, err := openCSVSource(opts.CSV, opts.Topic, opts.Map, opts.TimeColumn, opts.Hz)
+		if err != nil {
+			fmt.Fprintln(os.Stderr, "cardinal replay:", err)
+			return exitUsage
+		}
+		defer rows.Close()
+		source = rows
+	} else {
+		capture, err := OpenCapture(opts.Args[0])
+		if err != nil {
+			fmt.Fprintln(os.Stderr, "cardinal replay:", err)
+			return exitUsage
+		}
+		defer capture.Close()
+		source = capture
+	}
+
+	transport, err := NewFastDDSTransport(settings.Profile.Domain, settings.QoS)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal replay:", err)
+		return exitFailure
+	}
+	// Recorded payloads are replayed as they were captured, so encrypted
+	// topics stay sealed without another pass through the cipher; CSV rows
+	// are plain and get sealed like any other publisher's
+	if opts.CSV != "" {
+		transport = withPayloadCipher(transport, settings.Cipher)
+	}
+
+	ctx, cancel := signal.NotifyContext(context.Background(), os.Interrupt, syscall.SIGTERM)
+	defer cancel()
+
+	var clock replayClock
+	if opts.ExternalClock != "" {
+		sub, err := transport.NewSubscriber(opts.ExternalClock)
+		if err != nil {
+			fmt.Fprintln(os.Stderr, "cardinal replay:", err)
+			return exitFailure
+		}
+		defer sub.Cleanup()
+		external := newExternalClock()
+		go external.follow(sub)
+		clock = external
+		fmt.Printf("⏱  Following the simulation clock on %s\n", opts.ExternalClock)
+	} else {
+		playback := newPlaybackClock(opts.Rate)
+		go controlPlayback(stdin, playback, opts.Rate)
+		clock = playback
+		fmt.Printf("▶  Replaying at %gx (commands: pause, resume, rate <x>, +, -)\n", opts.Rate)
+	}
+
+	var start atomic.Int64
+	if opts.ClockTopic != "" {
+		pub, err := transport.NewPublisher(opts.ClockTopic)
+		if err != nil {
+			fmt.Fprintln(os.Stderr, "cardinal replay:", err)
+			return exitFailure
+		}
+		defer pub.Cleanup()
+		go publishSimClock(ctx, pub, clock, &start, opts.ClockHz)
+	}
+
+	publishers := map[string]DDSPublisher{}
+	defer func() {
+		for _, pub := range publishers {
+			pub.Cleanup()
+		}
+	}()
+	publish := func(batch []DDSMessage) error {
+		topic := batch[0].Topic
+		pub, ok := publishers[topic]
+		if !ok {
+			var err error
+			if pub, err = transport.NewPublisher(topic); err != nil {
+				return fmt.Errorf("failed to publish to %s: %w", topic, err)
+			}
+			publishers[topic] = pub
+		}
+		return publishBatch(pub, batch)
+	}
+
+	count, err := replay(ctx, source, clock, func(t time.Time) { start.Store(t.UnixNano()) }, publish)
+	if err != nil && err != context.Canceled {
+		fmt.Fprintln(os.Stderr, "cardinal replay:", err)
+		return exitFailure
+	}
+	fmt.Printf("✅ Replayed %d messages on %d topics\n", count, len(publishers))
+	return exitOK
+}
@@ -0,0 +1,105 @@
+package main
+
+import (
+	"encoding/json"
+	"encoding/xml"
+	"fmt"
+	"os"
+	"time"
+)
+
+// Assertion and scenario runs can write their results as JUnit XML and as
+// JSON files, so CI dashboards pick them up without parsing the console.
+
+// One checked item of a run: a rule or a scenario step
+type testCase struct {
+	Name     string
+	Class    string // e.g. the topic
+	Duration time.Duration
+	Failure  string // empty when passed
+}
+
+type junitFailure struct {
+	Message string `xml:"message,attr"`
+	Text    string `xml:",chardata"`
+}
+
+type junitCase struct {
+	Name      string        `xml:"name,attr"`
+	Classname string        `xml:"classname,attr"`
+	Time      string        `xml:"time,attr"`
+	Failure   *junitFailure `xml:"failure,omitempty"`
+}
+
+type junitSuite struct {
+	Name     string      `xml:"name,attr"`
+	Tests    int         `xml:"tests,attr"`
+	Failures int         `xml:"failures,attr"`
+	Time     string      `xml:"time,attr"`
+	Cases    []junitCase `xml:"testcase"`
+}
+
+type junitSuites struct {
+	XMLName xml.Name     `xml:"testsuites"`
+	Suites  []junitSuite `xml:"testsuite"`
+}
+
+func junitSeconds(d time.Duration) string {
+	return fmt.Sprintf("%.3f", d.Seconds())
+}
+
+// Write a run as a JUnit XML file with a single test suite
+func writeJUnitReport(path, suite string, duration time.Duration, cases []testCase) error {
+	s := junitSuite{Name: suite, Tests: len(cases), Time: junitSeconds(duration)}
+	for _, c := range cases {
+		jc := junitCase{Name: c.Name, Classname: c.Class, Time: junitSeconds(c.Duration)}
+		if c.Failure != "" {
+			s.Failures++
+			jc.Failure = &junitFailure{Message: c.Failure, Text: c.Failure}
+		}
+		s.Cases = append(s.Cases, jc)
+	}
+
+	out, err := xml.MarshalIndent(junitSuites{Suites: []junitSuite{s}}, "", "  ")
+	if err != nil {
+		return err
+	}
+	return os.WriteFile(path, append([]byte(xml.Header), append(out, '\n')...), 0o644)
+}
+
+// Print a command's result as selected by --quiet and --json, otherwise
+// through text
+func printResult(opts Options, result any, text func()) {
+	switch {
+	case opts.Quiet:
+	case opts.JSON:
+		out, _ := json.MarshalIndent(result, "", "  ")
+		fmt.Println(string(out))
+	default:
+		text()
+	}
+}
+
+// Write a report value as an indented JSON file
+func writeJSONReport(path string, report any) error {
+	out, err := json.MarshalIndent(report, "", "  ")
+	if err != nil {
+		return err
+	}
+	return os.WriteFile(path, append(out, '\n'), 0o644)
+}
+
+// Write the report files requested on the command line
+func writeReports(opts Options, suite string, duration time.Duration, cases []testCase, report any) error {
+	if opts.JUnitPath != "" {
+		if err := writeJUnitReport(opts.JUnitPath, suite, duration, cases); err != nil {
+			return fmt.Errorf("failed to write JUnit report: %w", err)
+		}
+	}
+	if opts.JSONReport != "" {
+		if err := writeJSONReport(opts.JSONReport, report); err != nil {
+			return fmt.Errorf("failed to write JSON report: %w", err)
+		}
+	}
+	return nil
+}
@@ -0,0 +1,437 @@
+package main
+
+import (
+	"bufio"
+	"encoding/binary"
+	"encoding/hex"
+	"fmt"
+	"io"
+	"math"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"sort"
+	"strconv"
+	"strings"
+	"time"
+
+	"cardinal/core"
+)
+
+// ROS 2 bags are directories of sqlite3 (.db3) or MCAP (.mcap) files. The
+// importer converts their messages into a Cardinal capture so they can be
+// replayed onto raw DDS. Payloads are CDR-serialized ROS messages; the
+// std_msgs types with a single data field are converted to text, everything
+// else is kept as the raw CDR payload on a channel tagged with its ROS type.
+
+// A message read from a bag, before conversion
+type bagMessage struct {
+	topic       string
+	rosType     string
+	logTime     int64
+	publishTime int64
+	data        []byte
+}
+
+// Storage files of a bag, in order: a directory's .db3 and .mcap files or a
+// single file
+func bagFiles(path string) ([]string, error) {
+	info, err := os.Stat(path)
+	if err != nil {
+		return nil, err
+	}
+	if !info.IsDir() {
+		return []string{path}, nil
+	}
+
+	var files []string
+	for _, pattern := range []string{"*.db3", "*.mcap"} {
+		matches, err := filepath.Glob(filepath.Join(path, pattern))
+		if err != nil {
+			return nil, err
+		}
+		files = append(files, matches...)
+	}
+	if len(files) == 0 {
+		return nil, fmt.Errorf("%s contains no .db3 or .mcap files", path)
+	}
+	sort.Strings(files)
+	return files, nil
+}
+
+// Read the messages of a sqlite3 bag file in timestamp order, through the
+// sqlite3 command line tool
+func readSqliteBag(path string, each func(bagMessage) error) error {
+	query := "SELECT t.name, t.type, m.timestamp, hex(m.data) FROM messages m JOIN topics t ON m.topic_id = t.id ORDER BY m.timestamp"
+	cmd := exec.Command("sqlite3", "-readonly", "-batch", "-noheader", "-separator", "\t", path, query)
+	cmd.Stderr = os.Stderr
+	stdout, err := cmd.StdoutPipe()
+	if err != nil {
+		return err
+	}
+	if err := cmd.Start(); err != nil {
+		return fmt.Errorf("reading %s needs the sqlite3 command: %w", path, err)
+	}
+
+	scanner := bufio.NewScanner(stdout)
+	scanner.Buffer(make([]byte, 64*1024), maxCaptureRecord)
+	for scanner.Scan() {
+		fields := strings.Split(scanner.Text(), "\t")
+		if len(fields) != 4 {
+			// A tab in a topic or type name; the row cannot be split
+			fmt.Fprintf(os.Stderr, "cardinal import: %s: skipping a row that is not topic, type, timestamp and data\n", path)
+			continue
+		}
+		timestamp, err := strconv.ParseInt(fields[2], 10, 64)
+		if err != nil {
+			return fmt.Errorf("%s: invalid timestamp %q", path, fields[2])
+		}
+		data, err := hex.DecodeString(fields[3])
+		if err != nil {
+			return fmt.Errorf("%s: invalid message data: %w", path, err)
+		}
+		msg := bagMessage{topic: fields[0], rosType: fields[1], logTime: timestamp, publishTime: timestamp, data: data}
+		if err := each(msg); err != nil {
+			cmd.Process.Kill()
+			cmd.Wait()
+			return err
+		}
+	}
+	if err := scanner.Err(); err != nil {
+		cmd.Process.Kill()
+		cmd.Wait()
+		return err
+	}
+	if err := cmd.Wait(); err != nil {
+		return fmt.Errorf("sqlite3 failed on %s: %w", path, err)
+	}
+	return nil
+}
+
+// Read the messages of an MCAP bag file in log time order. rosbag2 writes
+// chunked files; only uncompressed chunks can be read, since the standard
+// library has no zstd or lz4.
+func readMcapBag(path string, each func(bagMessage) error) error {
+	file, err := os.Open(path)
+	if err != nil {
+		return err
+	}
+	defer file.Close()
+
+	r := bufio.NewReader(file)
+	magic := make([]byte, len(captureMagic))
+	if _, err := io.ReadFull(r, magic); err != nil || string(magic) != captureMagic {
+		return fmt.Errorf("%s is not an MCAP file", path)
+	}
+
+	schemas := map[uint16]string{}
+	channels := map[uint16]bagMessage{}
+
+	// Messages are held back only until nothing earlier can follow. Chunks
+	// come in log time order but may overlap their neighbours, and messages
+	// outside chunks come in log time order.
+	var pending []bagMessage
+	inChunk := false
+	release := func(until int64) error {
+		sort.SliceStable(pending, func(i, j int) bool {
+			return pending[i].logTime < pending[j].logTime
+		})
+		n := 0
+		for n < len(pending) && pending[n].logTime < until {
+			if err := each(pending[n]); err != nil {
+				return err
+			}
+			n++
+		}
+		pending = append(pending[:0], pending[n:]...)
+		return nil
+	}
+
+	var parse func(records []byte) error
+	handle := func(opcode byte, record []byte) error {
+		rec := core.Fields{Data: record}
+		switch opcode {
+		case mcapOpSchema:
+			id := rec.Uint16()
+			name := rec.String()
+			if rec.Err == nil {
+				schemas[id] = name
+			}
+		case mcapOpChannel:
+			id := rec.Uint16()
+			schema := rec.Uint16()
+			topic := rec.String()
+			if rec.Err == nil {
+				channels[id] = bagMessage{topic: topic, rosType: schemas[schema]}
+			}
+		case mcapOpMessage:
+			id := rec.Uint16()
+			rec.Uint32() // sequence
+			logTime := rec.Uint64()
+			publishTime := rec.Uint64()
+			if rec.Err != nil {
+				return fmt.Errorf("%s: invalid message record: %w", path, rec.Err)
+			}
+			msg, ok := channels[id]
+			if !ok {
+				return fmt.Errorf("%s: message on unknown channel %d", path, id)
+			}
+			msg.logTime, msg.publishTime = int64(logTime), int64(publishTime)
+			msg.data = rec.Data
+			pending = append(pending, msg)
+			if !inChunk {
+				return release(msg.logTime + 1)
+			}
+		case mcapOpChunk:
+			startTime := rec.Uint64()
+			rec.Uint64() // message end time
+			rec.Uint64() // uncompressed size
+			rec.Uint32() // uncompressed CRC
+			compression := rec.String()
+			records := rec.Take(int(rec.Uint64()))
+			if rec.Err != nil {
+				return fmt.Errorf("%s: invalid chunk: %w", path, rec.Err)
+			}
+			if compression != "" {
+				return fmt.Errorf("%s: %s-compressed chunks are not supported; convert the bag without compression first", path, compression)
+			}
+			if err := release(int64(startTime)); err != nil {
+				return err
+			}
+			inChunk = true
+			defer func() { inChunk = false }()
+			return parse(records)
+		}
+		return nil
+	}
+	parse = func(records []byte) error {
+		for len(records) > 0 {
+			if len(records) < 9 {
+				return fmt.Errorf("%s: truncated chunk", path)
+			}
+			length := binary.LittleEndian.Uint64(records[1:9])
+			if length > uint64(len(records)-9) {
+				return fmt.Errorf("%s: truncated chunk", path)
+			}
+			if err := handle(records[0], records[9:9+length]); err != nil {
+				return err
+			}
+			records = records[9+length:]
+		}
+		return nil
+	}
+
+	for done := false; !done; {
+		var prefix [9]byte
+		if _, err := io.ReadFull(r, prefix[:]); err != nil {
+			if err == io.EOF {
+				break
+			}
+			return fmt.Errorf("%s: truncated file: %w", path, err)
+		}
+		length := binary.LittleEndian.Uint64(prefix[1:])
+		if length > maxCaptureRecord {
+			return fmt.Errorf("%s: corrupt %d byte record", path, length)
+		}
+		record := make([]byte, length)
+		if _, err := io.ReadFull(r, record); err != nil {
+			return fmt.Errorf("%s: truncated file: %w", path, err)
+		}
+		if prefix[0] == mcapOpDataEnd {
+			done = true
+			continue
+		}
+		if err := handle(prefix[0], record); err != nil {
+			return err
+		}
+	}
+
+	return release(math.MaxInt64)
+}
+
+// Reads CDR-serialized ROS 2 messages, aligned relative to the data after
+// the encapsulation header
+type cdrReader struct {
+	data  []byte
+	pos   int
+	order binary.ByteOrder
+	err   error
+}
+
+func newCDRReader(data []byte) (*cdrReader, error) {
+	if len(data) < 4 {
+		return nil, fmt.Errorf("missing CDR header")
+	}
+	r := &cdrReader{data: data[4:], order: binary.BigEndian}
+	if data[1]&1 == 1 {
+		r.order = binary.LittleEndian
+	}
+	return r, nil
+}
+
+func (r *cdrReader) take(size, align int) []byte {
+	if rem := r.pos % align; rem != 0 {
+		r.pos += align - rem
+	}
+	if r.err != nil || r.pos+size > len(r.data) {
+		r.err = fmt.Errorf("truncated CDR data")
+		return make([]byte, 8)
+	}
+	b := r.data[r.pos : r.pos+size]
+	r.pos += size
+	return b
+}
+
+func (r *cdrReader) uint32() uint32 {
+	return r.order.Uint32(r.take(4, 4))
+}
+
+func (r *cdrReader) uint64() uint64 {
+	return r.order.Uint64(r.take(8, 8))
+}
+
+func (r *cdrReader) string() string {
+	n := int(r.uint32())
+	b := r.take(n, 1)
+	return strings.TrimRight(string(b), "\x00")
+}
+
+// Text conversions of std_msgs types with a single data field
+var rosTextTypes = map[string]func(r *cdrReader) string{
+	"std_msgs/msg/String":  func(r *cdrReader) string { return r.string() },
+	"std_msgs/msg/Bool":    func(r *cdrReader) string { return strconv.FormatBool(r.take(1, 1)[0] != 0) },
+	"std_msgs/msg/Int32":   func(r *cdrReader) string { return strconv.FormatInt(int64(int32(r.uint32())), 10) },
+	"std_msgs/msg/Int64":   func(r *cdrReader) string { return strconv.FormatInt(int64(r.uint64()), 10) },
+	"std_msgs/msg/UInt32":  func(r *cdrReader) string { return strconv.FormatUint(uint64(r.uint32()), 10) },
+	"std_msgs/msg/UInt64":  func(r *cdrReader) string { return strconv.FormatUint(r.uint64(), 10) },
+	"std_msgs/msg/Float32": func(r *cdrReader) string { return formatFloat(float64(math.Float32frombits(r.uint32())), 32) },
+	"std_msgs/msg/Float64": func(r *cdrReader) string { return formatFloat(math.Float64frombits(r.uint64()), 64) },
+}
+
+func formatFloat(v float64, bits int) string {
+	return strconv.FormatFloat(v, 'g', -1, bits)
+}
+
+// Convert a bag message's payload to Cardinal's text payload. Types without
+// a text conversion keep their raw CDR payload, reported by raw.
+func convertBagPayload(msg bagMessage) (content string, raw bool, err error) {
+	convert, ok := rosTextTypes[msg.rosType]
+	if !ok {
+		return string(msg.data), true, nil
+	}
+	r, err := newCDRReader(msg.data)
+	if err != nil {
+		return "", false, err
+	}
+	content = convert(r)
+	return content, false, r.err
+}
+
+// Parse --map entries of the form from=to
+func parseTopicMap(entries []string) (map[string]string, error) {
+	topics := map[string]string{}
+	for _, entry := range entries {
+		from, to, ok := strings.Cut(entry, "=")
+		if !ok || from == "" || to == "" {
+			return nil, fmt.Errorf("invalid topic mapping %q, expected from=to", entry)
+		}
+		topics[from] = to
+	}
+	return topics, nil
+}
+
+// Run `cardinal import`: convert a ROS 2 bag into a capture and return the
+// exit code
+func runImport(opts Options) int {
+	settings, err := loadSettings(opts)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal import:", err)
+		return exitUsage
+	}
+	topics, err := parseTopicMap(opts.Map)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal import:", err)
+		return exitUsage
+	}
+	files, err := bagFiles(opts.Args[0])
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal import:", err)
+		return exitUsage
+	}
+
+	capture, err := CreateCapture(opts.Output)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal import:", err)
+		return exitFailure
+	}
+
+	imported := 0
+	raw := map[string]map[string]int{}     // by topic, then ROS type
+	skipped := map[string]map[string]int{} // by topic, then reason
+	write := func(msg bagMessage) error {
+		content, isRaw, err := convertBagPayload(msg)
+		if err != nil {
+			if skipped[msg.topic] == nil {
+				skipped[msg.topic] = map[string]int{}
+			}
+			skipped[msg.topic][err.Error()]++
+			return nil
+		}
+		topic := msg.topic
+		if mapped, ok := topics[topic]; ok {
+			topic = mapped
+		}
+		if isRaw {
+			if raw[msg.topic] == nil {
+				raw[msg.topic] = map[string]int{}
+				capture.SetROSType(topic, msg.rosType)
+			}
+			raw[msg.topic][msg.rosType]++
+		}
+		// Imported captures are stored like recorded ones
+		out := DDSMessage{
+			Topic:      topic,
+			Content:    content,
+			Timestamp:  time.Unix(0, msg.publishTime),
+			ReceivedAt: time.Unix(0, msg.logTime),
+		}
+		imported++
+		return capture.Write(settings.Cipher.Seal(settings.Redactor.Redact(out)))
+	}
+
+	for _, file := range files {
+		read := readMcapBag
+		if strings.HasSuffix(file, ".db3") {
+			read = readSqliteBag
+		}
+		if err = read(file, write); err != nil {
+			break
+		}
+	}
+	if err == nil {
+		err = capture.WriteMetadata("import", map[string]string{"source": opts.Args[0], "format": "rosbag2"})
+	}
+	if closeErr := capture.Close(); err == nil {
+		err = closeErr
+	}
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal import:", err)
+		return exitFailure
+	}
+
+	result := map[string]any{"output": opts.Output, "imported": imported, "raw": raw, "skipped": skipped}
+	printResult(opts, result, func() {
+		fmt.Printf("✅ Imported %d messages into %s\n", imported, opts.Output)
+		for _, topic := range sortedKeys(raw) {
+			for _, rosType := range sortedKeys(raw[topic]) {
+				fmt.Printf("📦 Kept %d on %s as raw %s CDR\n", raw[topic][rosType], topic, rosType)
+			}
+		}
+		for _, topic := range sortedKeys(skipped) {
+			for _, reason := range sortedKeys(skipped[topic]) {
+				fmt.Printf("⚠️  Skipped %d on %s: %s\n", skipped[topic][reason], topic, reason)
+			}
+		}
+	})
+	return exitOK
+}
@@ -0,0 +1,472 @@
+package main
+
+import (
+	"bufio"
+	"context"
+	"fmt"
+	"io"
+	"os"
+	"os/signal"
+	"sort"
+	"strconv"
+	"strings"
+	"sync"
+	"syscall"
+	"time"
+)
+
+// Scenarios are YAML files of timed steps, run against the live domain:
+//
+//	name: startup handshake
+//	steps:
+//	  - at: 0s
+//	    publish: /cmd
+//	    content: '{"mode":"start"}'
+//	  - at: 0s
+//	    expect: /status
+//	    match: ready
+//	    within: 2s
+//	  - at: 10s
+//	    publish: /cmd
+//	    content: '{"mode":"run"}'
+//	    hz: 20
+//
+// A publish step with hz keeps publishing until the scenario ends or a later
+// step publishes on the same topic. Only this subset of YAML is accepted.
+
+// One timed step of a scenario
+type scenarioStep struct {
+	line    int
+	at      time.Duration
+	publish string // topic to publish on
+	content string
+	hz      float64 // repeat rate, 0 to publish once
+	expect  string  // topic a message is expected on
+	match   string  // substring the expected payload must contain
+	within  time.Duration
+}
+
+func (s scenarioStep) String() string {
+	if s.publish != "" {
+		if s.hz > 0 {
+			return fmt.Sprintf("publish %s at %g Hz", s.publish, s.hz)
+		}
+		return "publish " + s.publish
+	}
+	if s.match != "" {
+		return fmt.Sprintf("expect %s containing %q within %v", s.expect, s.match, s.within)
+	}
+	return fmt.Sprintf("expect %s within %v", s.expect, s.within)
+}
+
+// A parsed scenario file
+type Scenario struct {
+	Name     string
+	Duration time.Duration // minimum run time, so streams run this long
+	Steps    []scenarioStep
+}
+
+// Value of a YAML scalar: quoted strings are unquoted, plain ones lose a
+// trailing comment
+func yamlScalar(value string) (string, error) {
+	switch {
+	case strings.HasPrefix(value, "'"):
+		// '' is an escaped quote inside single quotes
+		var b strings.Builder
+		for i := 1; i < len(value); i++ {
+			if value[i] == '\'' {
+				if i+1 < len(value) && value[i+1] == '\'' {
+					b.WriteByte('\'')
+					i++
+					continue
+				}
+				return b.String(), nil
+			}
+			b.WriteByte(value[i])
+		}
+		return "", fmt.Errorf("unterminated string")
+	case strings.HasPrefix(value, `"`):
+		quoted, err := strconv.QuotedPrefix(value)
+		if err != nil {
+			return "", fmt.Errorf("invalid string")
+		}
+		return strconv.Unquote(quoted)
+	}
+	if i := strings.Index(value, " #"); i >= 0 {
+		value = value[:i]
+	}
+	return strings.TrimSpace(value), nil
+}
+
+// Parse a scenario file
+func parseScenario(r io.Reader) (Scenario, error) {
+	var scenario Scenario
+	var step *scenarioStep
+	inSteps := false
+
+	scanner := bufio.NewScanner(r)
+	for lineNo := 1; scanner.Scan(); lineNo++ {
+		raw := scanner.Text()
+		line := strings.TrimSpace(raw)
+		if line == "" || strings.HasPrefix(line, "#") {
+			continue
+		}
+		indented := raw[0] == ' ' || raw[0] == '\t'
+
+		if strings.HasPrefix(line, "- ") {
+			if !inSteps {
+				return scenario, fmt.Errorf("line %d: list item outside steps", lineNo)
+			}
+			scenario.Steps = append(scenario.Steps, scenarioStep{line: lineNo, within: time.Second})
+			step = &scenario.Steps[len(scenario.Steps)-1]
+			line = strings.TrimSpace(line[2:])
+		} else if !indented {
+			step, inSteps = nil, false
+		}
+
+		key, value, ok := strings.Cut(line, ":")
+		if !ok {
+			return scenario, fmt.Errorf("line %d: expected key: value", lineNo)
+		}
+		key = strings.TrimSpace(key)
+		value, err := yamlScalar(strings.TrimSpace(value))
+		if err != nil {
+			return scenario, fmt.Errorf("line %d: %v", lineNo, err)
+		}
+
+		if step == nil {
+			switch key {
+			case "name":
+				scenario.Name = value
+			case "duration":
+				if scenario.Duration, err = time.ParseDuration(value); err != nil {
+					return scenario, fmt.Errorf("line %d: invalid duration %q", lineNo, value)
+				}
+			case "steps":
+				inSteps = true
+			default:
+				return scenario, fmt.Errorf("line %d: unknown key %q", lineNo, key)
+			}
+			continue
+		}
+
+		switch key {
+		case "at":
+			step.at, err = time.ParseDuration(value)
+		case "within":
+			step.within, err = time.ParseDuration(value)
+		case "hz":
+			step.hz, err = strconv.ParseFloat(value, 64)
+		case "publish":
+			step.publish = value
+		case "content":
+			step.content = value
+		case "expect":
+			step.expect = value
+		case "match":
+			step.match = value
+		default:
+			return scenario, fmt.Errorf("line %d: unknown step key %q", lineNo, key)
+		}
+		if err != nil {
+			return scenario, fmt.Errorf("line %d: invalid %s %q", lineNo, key, value)
+		}
+	}
+	if err := scanner.Err(); err != nil {
+		return scenario, err
+	}
+
+	for _, step := range scenario.Steps {
+		if (step.publish == "") == (step.expect == "") {
+			return scenario, fmt.Errorf("line %d: a step needs exactly one of publish or expect", step.line)
+		}
+		if step.hz < 0 || step.at < 0 || step.within <= 0 {
+			return scenario, fmt.Errorf("line %d: at, within and hz must not be negative", step.line)
+		}
+		if !(step.hz <= maxRateHz) {
+			return scenario, fmt.Errorf("line %d: hz must be at most %g", step.line, maxRateHz)
+		}
+	}
+	sort.SliceStable(scenario.Steps, func(i, j int) bool {
+		return scenario.Steps[i].at < scenario.Steps[j].at
+	})
+	return scenario, nil
+}
+
+// Messages received on the expected topics, with their arrival times
+type trafficLog struct {
+	mu       sync.Mutex
+	messages map[string][]DDSMessage
+}
+
+func (l *trafficLog) follow(topic string, sub DDSSubscriber) {
+	for msg := range sub.Subscribe() {
+		msg.ReceivedAt = time.Now()
+		l.mu.Lock()
+		l.messages[topic] = append(l.messages[topic], msg)
+		l.mu.Unlock()
+	}
+}
+
+// Whether a message on topic containing match arrived at or after since
+func (l *trafficLog) seen(topic, match string, since time.Time) bool {
+	l.mu.Lock()
+	defer l.mu.Unlock()
+	for _, msg := range l.messages[topic] {
+		if !msg.ReceivedAt.Before(since) && strings.Contains(msg.Content, match) {
+			return true
+		}
+	}
+	return false
+}
+
+// Outcome of one step
+type stepResult struct {
+	step   scenarioStep
+	err    error
+	finish time.Duration // when the step passed or failed, from the start
+}
+
+// A step's outcome in the JSON report
+type stepReport struct {
+	At     string `json:"at"`
+	Step   string `json:"step"`
+	Line   int    `json:"line"`
+	Passed bool   `json:"passed"`
+	Finish string `json:"finish"`
+	Error  string `json:"error,omitempty"`
+}
+
+// The JSON report of a scenario run
+type scenarioReport struct {
+	Name     string       `json:"name"`
+	Passed   bool         `json:"passed"`
+	Duration string       `json:"duration"`
+	Steps    []stepReport `json:"steps"`
+}
+
+// Publish content on pub at hz until ctx is done
+func streamScenario(ctx context.Context, pub DDSPublisher, step scenarioStep, errs chan<- error) {
+	ticker := time.NewTicker(time.Duration(float64(time.Second) / step.hz))
+	defer ticker.Stop()
+	for {
+		msg := DDSMessage{Topic: step.publish, Content: step.content, Timestamp: time.Now()}
+		if err := pub.Publish(msg); err != nil {
+			errs <- err
+			return
+		}
+		select {
+		case <-ctx.Done():
+			errs <- nil
+			return
+		case <-ticker.C:
+		}
+	}
+}
+
+// Run the steps of a scenario on a transport, returning one result per step
+func runScenario(ctx context.Context, scenario Scenario, transport Transport) []stepResult {
+	log := &trafficLog{messages: map[string][]DDSMessage{}}
+	publishers := map[string]DDSPublisher{}
+	subscribed := map[string]bool{}
+	results := make([]stepResult, len(scenario.Steps))
+	for i, step := range scenario.Steps {
+		results[i].step = step
+	}
+
+	// Subscribe and create publishers up front, so discovery has happened
+	// by the time the first step runs
+	for i, step := range scenario.Steps {
+		var err error
+		switch {
+		case step.expect != "" && !subscribed[step.expect]:
+			var sub DDSSubscriber
+			if sub, err = transport.NewSubscriber(step.expect); err == nil {
+				defer sub.Cleanup()
+				go log.follow(step.expect, sub)
+				subscribed[step.expect] = true
+			}
+		case step.publish != "" && publishers[step.publish] == nil:
+			var pub DDSPublisher
+			if pub, err = transport.NewPublisher(step.publish); err == nil {
+				defer pub.Cleanup()
+				publishers[step.publish] = pub
+			}
+		}
+		if err != nil {
+			results[i].err = err
+			return results
+		}
+	}
+
+	var wg sync.WaitGroup
+	streams := map[string]context.CancelFunc{}
+	streamErrs := map[int]chan error{}
+	defer func() {
+		for _, stop := range streams {
+			stop()
+		}
+		for i, errs := range streamErrs {
+			results[i].err = <-errs
+		}
+	}()
+
+	start := time.Now()
+	end := scenario.Duration
+	for i, step := range scenario.Steps {
+		select {
+		case <-ctx.Done():
+			for j := i; j < len(results); j++ {
+				results[j].err = ctx.Err()
+			}
+			wg.Wait()
+			return results
+		case <-time.After(time.Until(start.Add(step.at))):
+		}
+
+		if step.publish != "" {
+			if stop, ok := streams[step.publish]; ok {
+				stop()
+				delete(streams, step.publish)
+			}
+			pub := publishers[step.publish]
+			if step.hz == 0 {
+				results[i].err = pub.Publish(DDSMessage{Topic: step.publish, Content: step.content, Timestamp: time.Now()})
+				results[i].finish = time.Since(start)
+				continue
+			}
+			streamCtx, stop := context.WithCancel(ctx)
+			streams[step.publish] = stop
+			streamErrs[i] = make(chan error, 1)
+			go streamScenario(streamCtx, pub, step, streamErrs[i])
+			results[i].finish = time.Since(start)
+			continue
+		}
+
+		end = max(end, step.at+step.within)
+		wg.Add(1)
+		go func(result *stepResult) {
+			defer wg.Done()
+			since := start.Add(step.at)
+			deadline := time.NewTimer(time.Until(since.Add(step.within)))
+			defer deadline.Stop()
+			poll := time.NewTicker(10 * time.Millisecond)
+			defer poll.Stop()
+			for {
+				if log.seen(step.expect, step.match, since) {
+					result.finish = time.Since(start)
+					return
+				}
+				select {
+				case <-ctx.Done():
+					result.err = ctx.Err()
+					return
+				case <-deadline.C:
+					result.err = fmt.Errorf("no matching message")
+					result.finish = time.Since(start)
+					return
+				case <-poll.C:
+				}
+			}
+		}(&results[i])
+	}
+
+	wg.Wait()
+	select {
+	case <-ctx.Done():
+	case <-time.After(time.Until(start.Add(end))):
+	}
+	return results
+}
+
+// Run `cardinal scenario run`: execute a scenario file and report each
+// step, returning 1 when any failed
+func runScenarioCommand(opts Options) int {
+	settings, err := loadSettings(opts)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal scenario:", err)
+		return exitUsage
+	}
+
+	file, err := os.Open(opts.Args[1])
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal scenario:", err)
+		return exitUsage
+	}
+	scenario, err := parseScenario(file)
+	file.Close()
+	if err != nil {
+		fmt.Fprintf(os.Stderr, "cardinal scenario: %s: %v\n", opts.Args[1], err)
+		return exitUsage
+	}
+
+	if err := confirmCommand(opts, settings.Profile, "the profile name", settings.Profile.Name, os.Stdin); err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal scenario:", err)
+		return exitFailure
+	}
+
+	transport, err := NewFastDDSTransport(settings.Profile.Domain, settings.QoS)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal scenario:", err)
+		return exitFailure
+	}
+	transport = withPayloadCipher(transport, settings.Cipher)
+
+	ctx, cancel := signal.NotifyContext(context.Background(), os.Interrupt, syscall.SIGTERM)
+	defer cancel()
+
+	name := scenario.Name
+	if name == "" {
+		name = opts.Args[1]
+	}
+	if !opts.Quiet && !opts.JSON {
+		fmt.Printf("🎬 %s (%d steps)\n", name, len(scenario.Steps))
+	}
+
+	start := time.Now()
+	results := runScenario(ctx, scenario, transport)
+	duration := time.Since(start)
+
+	failed := 0
+	report := scenarioReport{Name: name, Passed: true, Duration: duration.String()}
+	var cases []testCase
+	for i, result := range results {
+		step := stepReport{At: result.step.at.String(), Step: result.step.String(), Line: result.step.line, Passed: true, Finish: result.finish.String()}
+		tc := testCase{Name: fmt.Sprintf("%02d %s", i+1, result.step), Class: name, Duration: max(result.finish-result.step.at, 0)}
+		if result.err != nil {
+			failed++
+			step.Passed, step.Error = false, result.err.Error()
+			report.Passed = false
+			tc.Failure = fmt.Sprintf("line %d: %v", result.step.line, result.err)
+		}
+		report.Steps = append(report.Steps, step)
+		cases = append(cases, tc)
+	}
+	if err := writeReports(opts, name, duration, cases, report); err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal scenario:", err)
+		return exitFailure
+	}
+
+	printResult(opts, report, func() {
+		for _, result := range results {
+			status, detail := "✅", ""
+			if result.err != nil {
+				status, detail = "❌", ": "+result.err.Error()
+			}
+			fmt.Printf("%s %8s  %s%s (at %s)\n", status, formatOffset(result.step.at), result.step, detail, formatOffset(result.finish))
+		}
+		if failed > 0 {
+			fmt.Printf("\n%d of %d steps failed\n", failed, len(scenario.Steps))
+		} else {
+			fmt.Printf("\nAll %d steps passed\n", len(scenario.Steps))
+		}
+	})
+
+	switch {
+	case ctx.Err() != nil:
+		return exitFailure
+	case failed > 0:
+		return exitAssertFailed
+	}
+	return exitOK
+}
@@ -0,0 +1,105 @@
+package main
+
+import (
+	"strings"
+
+	tea "github.com/charmbracelet/bubbletea"
+)
+
+// The messages tab's filter applies as it is typed. Every change of it
+// searches the store's lower-cased copies of the messages in the
+// background, so a long history never holds up typing or a frame; until the
+// matches arrive the tab shows those of the previous filter. Messages
+// arriving afterwards are searched as they come in, and matches leave with
+// their message when the store drops it.
+
+// Matches of the messages tab's filter
+type searchState struct {
+	wanted  string   // filter last searched for
+	query   string   // filter the matches are for
+	seen    uint64   // messages of the store the matches cover
+	matches []uint64 // positions in the store, counted from its first message ever
+	version int      // bumped whenever the matches change
+}
+
+// Matches of a search in the background
+type searchResultMsg struct {
+	query   string
+	seen    uint64
+	matches []uint64
+}
+
+// Text of a message that filters search: its topic and payload in lower
+// case, kept apart so a filter never matches across both
+func searchText(msg DDSMessage) string {
+	return strings.ToLower(msg.Topic) + "\x00" + strings.ToLower(msg.Content)
+}
+
+// Positions of the snapshot's messages, from position from on, containing
+// the query
+func searchSnapshot(snap *storeSnapshot, query string, from uint64) []uint64 {
+	query = strings.ToLower(query)
+	first := snap.first()
+	var matches []uint64
+	for i := max(from, first) - first; i < uint64(len(snap.Lowered)); i++ {
+		if strings.Contains(snap.Lowered[i], query) {
+			matches = append(matches, first+i)
+		}
+	}
+	return matches
+}
+
+// The filter the messages tab applies: the one being typed, or the last one
+// entered
+func (m model) query() string {
+	if m.mode == modeFilter {
+		return strings.TrimSpace(m.input)
+	}
+	return m.filter
+}
+
+// Search the store in the background if the filter changed since the last
+// search
+func (m model) searchChanged() (model, tea.Cmd) {
+	query := m.query()
+	if query == m.search.wanted {
+		return m, nil
+	}
+	if query == "" {
+		// Nothing to keep up to date
+		m.search = searchState{version: m.search.version + 1}
+		return m, nil
+	}
+	m.search.wanted = query
+	snap := m.store.Snapshot()
+	return m, func() tea.Msg {
+		return searchResultMsg{query: query, seen: snap.Total, matches: searchSnapshot(snap, query, 0)}
+	}
+}
+
+// Take in the matches of a search, unless the filter changed again since it
+// started
+func (s searchState) found(result searchResultMsg) searchState {
+	if result.query != s.wanted {
+		return s
+	}
+	s.query, s.seen, s.matches = result.query, result.seen, result.matches
+	s.version++
+	return s
+}
+
+// Search the messages the store took in since the matches were found, and
+// forget those it dropped
+func (s searchState) caughtUp(snap *storeSnapshot) searchState {
+	if s.query == "" || snap.Total <= s.seen {
+		return s
+	}
+	dropped := 0
+	for dropped < len(s.matches) && s.matches[dropped] < snap.first() {
+		dropped++
+	}
+	s.matches = append(s.matches[dropped:], searchSnapshot(snap, s.query, s.seen)...)
+	s.seen = snap.Total
+	s.version++
+	return s
+}
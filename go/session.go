@@ -0,0 +1,813 @@
+package main
+
+import (
+	"context"
+	"fmt"
+	"log"
+	"sort"
+	"strconv"
+	"sync"
+	"time"
+)
+
+// A running set of subscriptions on one transport. Every received message
+// is recorded in the statistics and then published on the session's event
+// bus, where the history, the active recording, the sinks and the session's
+// message handler (the TUI or the log) pick it up.
+type Session struct {
+	ctx        context.Context
+	wg         *sync.WaitGroup
+	transport  Transport
+	stats      *StatsTracker
+	startTime  time.Time
+	events     *EventBus
+	cipher     *payloadCipher     // seals recorded payloads on encrypted topics
+	redactor   *Redactor          // scrubs recorded payloads
+	duplicates *DuplicateDetector // flags payloads repeated by another writer
+	ordering   *OrderChecker      // flags samples out of their writer's sequence
+	bursts     *BurstDetector     // flags and logs message bursts
+	sinks      []*Sink            // external stores receiving every message
+	clock      *LatencyClock      // measures latency, on wall clocks when nil
+	budgets    *BudgetChecker     // flags latencies beyond the topics' budgets
+	ignore     *IgnoreList        // writers whose samples are dropped
+	hideOwn    bool               // drop samples of own writers instead of marking them
+	recFilter  *RecordFilter      // topics left out of recordings
+	recOpts    recorderOptions    // how recordings are written
+	uploader   *Uploader          // uploads finished recordings
+	statsOnly  bool               // keep statistics only, dropping every payload
+
+	mu            sync.Mutex
+	subscriptions map[string]DDSSubscriber
+	publishers    map[string]DDSPublisher
+	own           map[string]bool              // GUIDs of the writers of this instance
+	liveliness    map[string]map[string]bool   // whether matched writers are alive, by topic and GUID
+	participants  map[string]RemoteParticipant // other participants of the domain, by GUID
+	endpoints     map[string]RemoteEndpoint    // their writers and readers, by GUID
+	recorder      *Recorder
+	history       []DDSMessage
+	listeners     map[chan DDSMessage]struct{}
+}
+
+// Messages buffered for each attached client before it starts losing them
+const listenerBuffer = 1024
+
+// Status of a subscribed topic
+type TopicStatus struct {
+	Topic      string  `json:"topic"`
+	Messages   int     `json:"messages"`
+	Bytes      int     `json:"bytes"`
+	Rate       float64 `json:"rate"`
+	Duplicates int     `json:"duplicates"`
+	Reordered  int     `json:"reordered"`
+	Repeated   int     `json:"repeated"`
+	Jitter     float64 `json:"jitter_seconds"`
+	Fragmented int     `json:"fragmented"`       // samples estimated to be split into DATA_FRAG submessages
+	Fragments  int     `json:"fragments"`        // estimated DATA_FRAG submessages received
+	OverMTU    int     `json:"over_mtu"`         // samples estimated to be fragmented by IP
+	Lost       int     `json:"samples_lost"`     // samples the reader reported lost
+	OverBudget int     `json:"over_budget"`      // samples slower than the latency budget
+	Deadlines  int     `json:"deadlines_missed"` // deadline periods the reader received nothing in
+}
+
+// Status of a writer the session publishes with. The QoS fields are empty
+// when the transport cannot tell.
+type WriterStatus struct {
+	Topic       string  `json:"topic"`
+	PublishMode string  `json:"publish_mode,omitempty"` // sync or async
+	Heartbeat   float64 `json:"heartbeat_period_seconds,omitempty"`
+	NackDelay   float64 `json:"nack_response_delay_seconds,omitempty"`
+	Deadlines   int     `json:"deadlines_missed"` // deadline periods the writer wrote nothing in
+}
+
+// Liveliness of a writer a subscriber matched
+type RemoteWriter struct {
+	Topic string `json:"topic"`
+	GUID  string `json:"guid"`
+	Alive bool   `json:"alive"`
+}
+
+// Status of the whole session
+type SessionStatus struct {
+	Transport string              `json:"transport"`
+	Uptime    float64             `json:"uptime_seconds"`
+	Topics    []TopicStatus       `json:"topics"`
+	Writers   []WriterStatus      `json:"writers,omitempty"`
+	Remote    []RemoteWriter      `json:"remote_writers,omitempty"`
+	Peers     []RemoteParticipant `json:"participants,omitempty"`
+	Ignored   []IgnoredStatus     `json:"ignored,omitempty"`
+	Record    RecordFilterStatus  `json:"record_filter"` // topics recordings are limited to
+	Recording *RecordingStatus    `json:"recording,omitempty"`
+	Uploads   *UploadStatus       `json:"uploads,omitempty"`
+	Viewers   int                 `json:"viewers"`
+	StatsOnly bool                `json:"stats_only,omitempty"`
+}
+
+// Window used for the rates reported in the session status
+const statusRateWindow = 10 * time.Second
+
+// Create a session handing received messages to handler, after its own
+// history and recording
+func NewSession(ctx context.Context, wg *sync.WaitGroup, transport Transport, stats *StatsTracker, startTime time.Time, handler func(DDSMessage)) *Session {
+	s := &Session{
+		ctx:           ctx,
+		wg:            wg,
+		transport:     transport,
+		stats:         stats,
+		startTime:     startTime,
+		events:        &EventBus{},
+		subscriptions: map[string]DDSSubscriber{},
+		publishers:    map[string]DDSPublisher{},
+		own:           map[string]bool{},
+		participants:  map[string]RemoteParticipant{},
+		endpoints:     map[string]RemoteEndpoint{},
+		ignore:        NewIgnoreList(),
+		recFilter:     &RecordFilter{},
+		listeners:     map[chan DDSMessage]struct{}{},
+	}
+	s.events.OnMessage(s.store)
+	s.events.OnMessage(handler)
+	return s
+}
+
+// Log an alert and publish it
+func (s *Session) alert(alert AlertFired) {
+	log.Print(alert.Text)
+	s.events.Publish(alert)
+}
+
+// Subscribe to a topic on the session's transport
+func (s *Session) Subscribe(topic string) error {
+	return s.subscribe(topic, s.transport.NewSubscriber)
+}
+
+// Subscribe to a topic with a subscriber open creates. The reader is
+// created outside the lock, so two calls for one topic may both get this
+// far; the check is repeated under the lock and the loser's reader dropped.
+func (s *Session) subscribe(topic string, open func(topic string) (DDSSubscriber, error)) error {
+	s.mu.Lock()
+	_, exists := s.subscriptions[topic]
+	s.mu.Unlock()
+	if exists {
+		return fmt.Errorf("already subscribed to %s", topic)
+	}
+
+	sub, err := open(topic)
+	if err != nil {
+		return fmt.Errorf("failed to subscribe to %s: %w", topic, err)
+	}
+	if !s.addSubscriber(topic, sub) {
+		sub.Cleanup()
+		return fmt.Errorf("already subscribed to %s", topic)
+	}
+	return nil
+}
+
+// Publish a message, creating the topic's publisher on first use. Only the
+// lookup holds the lock, so a slow write does not stall the receive path.
+func (s *Session) Publish(topic, content string) error {
+	pub, err := s.publisher(topic)
+	if err != nil {
+		return err
+	}
+	return pub.Publish(DDSMessage{Topic: topic, Content: content, Timestamp: time.Now()})
+}
+
+// The session's publisher of a topic, created on first use
+func (s *Session) publisher(topic string) (DDSPublisher, error) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+
+	if pub, ok := s.publishers[topic]; ok {
+		return pub, nil
+	}
+	pub, err := s.transport.NewPublisher(topic)
+	if err != nil {
+		return nil, fmt.Errorf("failed to publish to %s: %w", topic, err)
+	}
+	s.publishers[topic] = pub
+	if guid := writerGUID(pub); guid != "" {
+		s.own[guid] = true
+	}
+	onIncompatibleQoS(pub, func(policy string) {
+		s.incompatibleQoS(topic, "reader", policy)
+	})
+	return pub, nil
+}
+
+// Mark the samples of a publisher created outside the session as its own
+func (s *Session) AddOwnPublisher(pub DDSPublisher) {
+	if guid := writerGUID(pub); guid != "" {
+		s.mu.Lock()
+		s.own[guid] = true
+		s.mu.Unlock()
+	}
+}
+
+// Whether a writer belongs to this instance
+func (s *Session) isOwn(writer string) bool {
+	if writer == "" {
+		return false
+	}
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	return s.own[writer]
+}
+
+// Start receiving from an already created subscriber
+func (s *Session) AddSubscriber(topic string, sub DDSSubscriber) {
+	if !s.addSubscriber(topic, sub) {
+		log.Printf("Already subscribed to %s", topic)
+		sub.Cleanup()
+	}
+}
+
+// Start receiving from sub unless the topic already has a subscriber
+func (s *Session) addSubscriber(topic string, sub DDSSubscriber) bool {
+	s.mu.Lock()
+	if _, exists := s.subscriptions[topic]; exists {
+		s.mu.Unlock()
+		return false
+	}
+	s.subscriptions[topic] = sub
+	s.mu.Unlock()
+
+	onWriterMatched(sub, func(writer string, matched bool) {
+		s.writerMatched(topic, writer, matched)
+	})
+	onIncompatibleQoS(sub, func(policy string) {
+		s.incompatibleQoS(topic, "writer", policy)
+	})
+	s.wg.Add(1)
+	go runTask(s.ctx, "subscriber", func(ctx context.Context) {
+		s.receive(ctx, topic, sub)
+	})
+	return true
+}
+
+// Log a writer matching or leaving a subscription and publish it. Runs on a
+// transport thread, possibly while Close holds mu, so it must not lock it.
+func (s *Session) writerMatched(topic, writer string, matched bool) {
+	change := "matched"
+	if !matched {
+		change = "unmatched"
+	}
+	log.Printf("Writer %s %s on %s", writer, change, topic)
+	s.events.Publish(EndpointMatched{Topic: topic, Writer: writer, Matched: matched})
+}
+
+// Warn of a remote writer or reader that cannot match because of the QoS
+// policy named. Runs on a transport thread, possibly while Publish holds mu,
+// so it must not lock it.
+func (s *Session) incompatibleQoS(topic, remote, policy string) {
+	s.alert(AlertFired{
+		Kind:  "incompatible_qos",
+		Topic: topic,
+		Text:  fmt.Sprintf("A %s on %s does not match: its %s QoS is incompatible", remote, topic, policy),
+		Vars:  map[string]string{"POLICY": policy, "ENDPOINT": remote},
+	})
+}
+
+// Subscriber Thread for one topic
+func (s *Session) receive(ctx context.Context, topic string, sub DDSSubscriber) {
+	defer s.wg.Done()
+
+	msgChan := sub.Subscribe()
+
+	for {
+		select {
+		case <-ctx.Done():
+			log.Printf("Subscriber %s: Shutting down...", topic)
+			return
+		case msg, ok := <-msgChan:
+			if !ok {
+				return
+			}
+			s.ingest(msg)
+		}
+	}
+}
+
+// Check a received message, count it and publish it
+func (s *Session) ingest(msg DDSMessage) {
+	if s.ignore.Drop(msg.Writer) {
+		return
+	}
+	if msg.Own = s.isOwn(msg.Writer); msg.Own && s.hideOwn {
+		return
+	}
+	if msg.ReceivedAt.IsZero() {
+		msg.ReceivedAt = time.Now()
+	}
+	if msg.Hash == "" && !s.statsOnly {
+		msg.Hash = payloadHash(msg.Content)
+	}
+	msg.SetLatency(s.clock.Latency(msg))
+	budget, over, alert := s.budgets.Check(msg)
+	msg.OverBudget = over
+	if alert {
+		latency := msg.Latency().Round(time.Microsecond)
+		s.alert(AlertFired{
+			Kind:  "latency_budget",
+			Topic: msg.Topic,
+			Text:  fmt.Sprintf("Latency on %s exceeded its %s budget: %s", msg.Topic, budget, latency),
+			Vars:  map[string]string{"LATENCY": latency.String(), "BUDGET": budget.String()},
+		})
+	}
+	if s.duplicates.Check(msg) {
+		msg.Duplicate = true
+	}
+	switch s.ordering.Check(msg) {
+	case sequenceReordered:
+		msg.Reordered = true
+	case sequenceRepeated:
+		msg.Repeated = true
+	}
+	inBurst, ended := s.bursts.Check(msg)
+	msg.Burst = inBurst
+	for _, burst := range ended {
+		s.alert(AlertFired{
+			Kind:  "burst",
+			Topic: burst.Topic,
+			Text:  fmt.Sprintf("Burst on %s: %s", burst.Topic, burst),
+			Vars:  map[string]string{"MESSAGES": strconv.Itoa(burst.Messages), "DURATION": burst.Duration().String()},
+		})
+	}
+
+	// Own traffic is shown but kept out of the statistics
+	if !msg.Own {
+		s.stats.Record(statsSample(msg))
+	}
+	if s.statsOnly {
+		return
+	}
+	s.events.Publish(MessageReceived{Message: msg})
+}
+
+// Keep a received message in the history for attaching clients and write
+// it to the active recording and the sinks
+func (s *Session) store(msg DDSMessage) {
+	span := startSpan("ingest", msg.Trace)
+	span.SetInt("messages", 1)
+	done := timeStage(stageIngest)
+
+	s.mu.Lock()
+	recorder := s.recorder
+	s.history = append(s.history, msg)
+	if len(s.history) > maxMessageHistory {
+		s.history = s.history[1:]
+	}
+	for listener := range s.listeners {
+		select {
+		case listener <- msg:
+		default:
+		}
+	}
+	s.mu.Unlock()
+	if !s.recFilter.Allows(msg.Topic) {
+		recorder = nil
+	}
+	if recorder != nil || len(s.sinks) > 0 {
+		stored := s.cipher.Seal(s.redactor.Redact(msg))
+		if recorder != nil {
+			if err := recorder.Write(stored); err != nil {
+				log.Printf("Error recording message: %v", err)
+			}
+		}
+		for _, sink := range s.sinks {
+			sink.Write(stored)
+		}
+	}
+
+	done()
+	span.End()
+}
+
+// Attach a client: returns the recent message history and a channel that
+// receives every message ingested from now on, until Detach
+func (s *Session) Attach() ([]DDSMessage, chan DDSMessage) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+
+	history := make([]DDSMessage, len(s.history))
+	copy(history, s.history)
+	listener := make(chan DDSMessage, listenerBuffer)
+	s.listeners[listener] = struct{}{}
+	return history, listener
+}
+
+// A message as attached clients get it: redacted like recordings, and on
+// the shared socket sealed on encrypted topics like recordings too
+func (s *Session) forViewer(msg DDSMessage, shared bool) DDSMessage {
+	msg = s.redactor.Redact(msg)
+	if shared {
+		msg = s.cipher.Seal(msg)
+	}
+	return msg
+}
+
+// Stop delivering messages to an attached client
+func (s *Session) Detach(listener chan DDSMessage) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+
+	delete(s.listeners, listener)
+}
+
+// Subscribed topics, sorted by name
+func (s *Session) Topics() []string {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+
+	topics := make([]string, 0, len(s.subscriptions))
+	for topic := range s.subscriptions {
+		topics = append(topics, topic)
+	}
+	sort.Strings(topics)
+	return topics
+}
+
+// Start recording received messages to a capture file
+func (s *Session) StartRecording(path string) (RecordingStatus, error) {
+	s.mu.Lock()
+	if s.statsOnly {
+		s.mu.Unlock()
+		return RecordingStatus{}, fmt.Errorf("the session keeps statistics only and records nothing")
+	}
+	if s.recorder != nil {
+		s.mu.Unlock()
+		return RecordingStatus{}, fmt.Errorf("already recording to %s", s.recorder.path)
+	}
+	recorder, err := NewRecorder(path, s.recOpts)
+	if err != nil {
+		s.mu.Unlock()
+		return RecordingStatus{}, err
+	}
+	s.recorder = recorder
+	status := recorder.Status()
+	s.mu.Unlock()
+
+	log.Printf("Recording to %s", path)
+	s.events.Publish(RecordingStateChanged{Recording: true, Status: status})
+	return status, nil
+}
+
+// Stop the active recording
+func (s *Session) StopRecording() (RecordingStatus, error) {
+	s.mu.Lock()
+	recorder := s.recorder
+	s.recorder = nil
+	s.mu.Unlock()
+
+	if recorder == nil {
+		return RecordingStatus{}, fmt.Errorf("not recording")
+	}
+	status := recorder.Status()
+	if err := recorder.Close(); err != nil {
+		return status, err
+	}
+	log.Printf("Recording to %s stopped after %d messages", status.Path, status.Messages)
+	s.events.Publish(RecordingStateChanged{Recording: false, Status: status})
+	return status, nil
+}
+
+// Drop the samples of a participant or writer from now on
+func (s *Session) Ignore(name, guid string) error {
+	if err := s.ignore.Add(name, guid); err != nil {
+		return err
+	}
+	log.Printf("Ignoring %s", guid)
+	return nil
+}
+
+// Take samples of an ignored participant or writer again
+func (s *Session) Unignore(name string) error {
+	if err := s.ignore.Remove(name); err != nil {
+		return err
+	}
+	log.Printf("No longer ignoring %s", name)
+	return nil
+}
+
+// Add an include or exclude rule for the topics recordings contain
+func (s *Session) FilterRecording(include bool, pattern string) error {
+	add, format := s.recFilter.Exclude, "Excluding %s from recordings"
+	if include {
+		add, format = s.recFilter.Include, "Including %s in recordings"
+	}
+	if err := add(pattern); err != nil {
+		return err
+	}
+	log.Printf(format, pattern)
+	return nil
+}
+
+// Record every topic again
+func (s *Session) ClearRecordingFilter() {
+	s.recFilter.Clear()
+	log.Printf("Recording every topic")
+}
+
+// Copy the lost sample counts and writer liveliness of the subscribers into
+// the session every second, since readers only report them when asked
+func (s *Session) trackReaders() {
+	s.wg.Add(1)
+	go runTask(s.ctx, "reader-tracker", func(ctx context.Context) {
+		defer s.wg.Done()
+		ticker := time.NewTicker(time.Second)
+		defer ticker.Stop()
+
+		for {
+			select {
+			case <-ctx.Done():
+				return
+			case <-ticker.C:
+				s.refreshLoss()
+				s.refreshLiveliness()
+			}
+		}
+	})
+}
+
+// Copy the lost sample counts of the subscribers into the statistics
+func (s *Session) refreshLoss() {
+	s.mu.Lock()
+	lost := map[string]int{}
+	for topic, sub := range s.subscriptions {
+		lost[topic] = samplesLost(sub)
+	}
+	s.mu.Unlock()
+	for topic, n := range lost {
+		s.stats.SetLost(topic, n)
+	}
+}
+
+// Deadlines missed by the readers and writers of the session, by topic
+func (s *Session) DeadlinesMissed() map[string]int {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+
+	missed := map[string]int{}
+	for topic, sub := range s.subscriptions {
+		if n := deadlinesMissed(sub); n > 0 {
+			missed[topic] += n
+		}
+	}
+	for topic, pub := range s.publishers {
+		if n := deadlinesMissed(pub); n > 0 {
+			missed[topic] += n
+		}
+	}
+	return missed
+}
+
+// Pick up the liveliness of the writers the subscribers matched, logging
+// writers that lose or regain it
+func (s *Session) refreshLiveliness() {
+	s.mu.Lock()
+	current := map[string]map[string]bool{}
+	for topic, sub := range s.subscriptions {
+		if writers := writerLiveliness(sub); len(writers) > 0 {
+			current[topic] = writers
+		}
+	}
+	previous := s.liveliness
+	s.liveliness = current
+	s.mu.Unlock()
+
+	for _, topic := range sortedKeys(current) {
+		for _, guid := range sortedKeys(current[topic]) {
+			alive := current[topic][guid]
+			was, known := previous[topic][guid]
+			switch {
+			case !alive && (!known || was):
+				s.alert(AlertFired{
+					Kind:  "liveliness_lost",
+					Topic: topic,
+					Text:  fmt.Sprintf("Writer %s on %s lost liveliness", guid, topic),
+					Vars:  map[string]string{"WRITER": guid},
+				})
+			case alive && known && !was:
+				log.Printf("Writer %s on %s is alive again", guid, topic)
+			}
+		}
+	}
+}
+
+// Liveliness of the writers the subscribers matched, by topic and GUID
+func (s *Session) RemoteWriters() []RemoteWriter {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+
+	var writers []RemoteWriter
+	for _, topic := range sortedKeys(s.liveliness) {
+		for _, guid := range sortedKeys(s.liveliness[topic]) {
+			writers = append(writers, RemoteWriter{Topic: topic, GUID: guid, Alive: s.liveliness[topic][guid]})
+		}
+	}
+	return writers
+}
+
+// Keep track of the other participants of the domain and their endpoints
+// until the session ends, if the transport reports them
+func (s *Session) watchDiscovery() {
+	watcher, ok := baseTransport(s.transport).(discoveryWatcher)
+	if !ok {
+		return
+	}
+	stop, err := watcher.WatchDiscovery(s)
+	if err != nil {
+		log.Printf("Not watching discovery: %v", err)
+		return
+	}
+	s.wg.Add(1)
+	go runTask(s.ctx, "discovery-watcher", func(ctx context.Context) {
+		defer s.wg.Done()
+		<-ctx.Done()
+		stop()
+	})
+}
+
+// Log a participant joining or leaving the domain. Runs on a transport
+// thread.
+func (s *Session) participantChanged(p RemoteParticipant, present bool) {
+	s.mu.Lock()
+	if present {
+		s.participants[p.GUID] = p
+	} else {
+		delete(s.participants, p.GUID)
+		// Its endpoints went with it, reported or not
+		for guid, e := range s.endpoints {
+			if e.Participant == p.GUID {
+				delete(s.endpoints, guid)
+			}
+		}
+	}
+	s.mu.Unlock()
+
+	if present {
+		log.Printf("Participant %s discovered", p.label())
+	} else {
+		log.Printf("Participant %s left", p.label())
+	}
+}
+
+// Keep track of an endpoint of another participant. Runs on a transport
+// thread.
+func (s *Session) endpointChanged(e RemoteEndpoint, present bool) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	if present {
+		s.endpoints[e.GUID] = e
+	} else {
+		delete(s.endpoints, e.GUID)
+	}
+}
+
+// The topics the other participants write or read, by name
+func (s *Session) DiscoveredTopics() []DiscoveredTopic {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	return discoveredTopics(s.endpoints, s.subscriptions)
+}
+
+// Subscribe to a topic found by discovery. A topic of another type than
+// Cardinal's own is read with the type its writers announce.
+func (s *Session) SubscribeDiscovered(topic string) error {
+	s.mu.Lock()
+	foreign := false
+	for _, e := range s.endpoints {
+		if e.Topic == topic && !cardinalTypes[e.Type] {
+			foreign = true
+			break
+		}
+	}
+	s.mu.Unlock()
+	if !foreign {
+		return s.Subscribe(topic)
+	}
+	d, ok := baseTransport(s.transport).(dynamicSubscriber)
+	if !ok {
+		return fmt.Errorf("%s transport cannot read topics of other types", s.transport.Name())
+	}
+	return s.subscribe(topic, d.NewDynamicSubscriber)
+}
+
+// The other participants of the domain, by GUID
+func (s *Session) Participants() []RemoteParticipant {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+
+	participants := make([]RemoteParticipant, 0, len(s.participants))
+	for _, guid := range sortedKeys(s.participants) {
+		participants = append(participants, s.participants[guid])
+	}
+	return participants
+}
+
+// Current status of topics and recording
+func (s *Session) Status() SessionStatus {
+	now := time.Now()
+	status := SessionStatus{
+		Transport: s.transport.Name(),
+		Uptime:    now.Sub(s.startTime).Seconds(),
+		StatsOnly: s.statsOnly,
+	}
+
+	seen := map[string]TopicStats{}
+	for _, stats := range s.stats.Topics() {
+		seen[stats.Topic] = stats
+	}
+	for _, topic := range s.Topics() {
+		ts := TopicStatus{Topic: topic}
+		if stats, ok := seen[topic]; ok {
+			ts.Messages = stats.Total
+			ts.Bytes = stats.TotalBytes
+			ts.Rate = stats.Window(now, statusRateWindow).Rate
+			ts.Duplicates = stats.TotalDuplicates
+			ts.Reordered = stats.TotalReordered
+			ts.Repeated = stats.TotalRepeated
+			ts.Jitter = stats.Intervals.StdDev().Seconds()
+			ts.Fragmented = stats.TotalFragmented
+			ts.Fragments = stats.TotalFragments
+			ts.OverMTU = stats.TotalOverMTU
+			ts.Lost = stats.Lost
+			ts.OverBudget = stats.TotalOverBudget
+		}
+		status.Topics = append(status.Topics, ts)
+	}
+	status.Remote = s.RemoteWriters()
+	status.Peers = s.Participants()
+
+	s.mu.Lock()
+	for _, topic := range sortedKeys(s.publishers) {
+		writer := WriterStatus{Topic: topic}
+		if qos, ok := activeQoS(s.publishers[topic]); ok {
+			writer.PublishMode = qos.PublishMode
+			writer.Heartbeat = qos.Heartbeat.Seconds()
+			writer.NackDelay = qos.NackDelay.Seconds()
+		}
+		writer.Deadlines = deadlinesMissed(s.publishers[topic])
+		status.Writers = append(status.Writers, writer)
+	}
+	for i := range status.Topics {
+		status.Topics[i].Deadlines = deadlinesMissed(s.subscriptions[status.Topics[i].Topic])
+	}
+	status.Ignored = s.ignore.Entries()
+	status.Record = s.recFilter.Status()
+	status.Uploads = s.uploader.Status()
+	if s.recorder != nil {
+		recording := s.recorder.Status()
+		status.Recording = &recording
+	}
+	status.Viewers = len(s.listeners)
+	s.mu.Unlock()
+
+	return status
+}
+
+// Release all subscribers and publishers and finish the active recording
+// and the sinks. Call after the session's goroutines have stopped.
+func (s *Session) Close() {
+	s.mu.Lock()
+	recording := s.recorder != nil
+	s.mu.Unlock()
+	if recording {
+		if _, err := s.StopRecording(); err != nil {
+			log.Printf("Error finishing recording: %v", err)
+		}
+	}
+	for _, sink := range s.sinks {
+		if err := sink.Close(); err != nil {
+			log.Printf("Error closing sink %s: %v", sink.spec.name, err)
+		}
+	}
+	s.sinks = nil
+
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	for topic, sub := range s.subscriptions {
+		sub.Cleanup()
+		delete(s.subscriptions, topic)
+	}
+	for topic, pub := range s.publishers {
+		pub.Cleanup()
+		delete(s.publishers, topic)
+	}
+}
+
+// Log a received message, used when running without the TUI
+func logMessage(msg DDSMessage) {
+	if msg.Own {
+		log.Printf("Received on %s: %s (own)", topicLabel(msg), payloadText(msg.Content))
+		return
+	}
+	if msg.Duplicate {
+		log.Printf("Received on %s: %s (duplicate payload from another writer, %s)", topicLabel(msg), payloadText(msg.Content), msg.Writer)
+		return
+	}
+	if msg.Reordered || msg.Repeated {
+		log.Printf("Received on %s: %s (sequence %d out of order for writer %s)", topicLabel(msg), payloadText(msg.Content), msg.Sequence, msg.Writer)
+		return
+	}
+	log.Printf("Received on %s: %s", topicLabel(msg), payloadText(msg.Content))
+}
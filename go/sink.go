@@ -0,0 +1,182 @@
+package main
+
+import (
+	"context"
+	"fmt"
+	"log"
+	"path"
+	"sort"
+	"strings"
+	"sync/atomic"
+	"time"
+)
+
+// Sinks forward received messages to external stores, next to the capture
+// recording. Each one is a [sinks.<name>] table in the config file with a
+// type, optional topic patterns, and settings of its type:
+//
+//	[sinks.lab]
+//	type = "sqlite"
+//	path = "/var/lib/cardinal/messages.db"
+//	topics = ["sensor/*"]
+//
+// Like recordings, sinks get payloads redacted and sealed. Writes are
+// queued and handed to the backend in batches off the receive path; when a
+// backend falls behind, messages are dropped rather than stalling the
+// subscribers.
+
+// Messages queued per sink before it starts dropping
+const sinkQueueSize = 8192
+
+// Largest batch handed to a backend, and the longest a message waits for one
+const (
+	sinkBatchSize     = 500
+	sinkFlushInterval = time.Second
+)
+
+// Configuration of one sink from its [sinks.<name>] table
+type sinkSpec struct {
+	name     string
+	kind     string
+	topics   []string // path.Match patterns; empty for every topic
+	settings map[string]string
+}
+
+// Where a sink writes its batches
+type sinkBackend interface {
+	WriteBatch(batch []DDSMessage) error
+	Close() error
+}
+
+// Backends by sink type
+var sinkBackends = map[string]func(spec sinkSpec) (sinkBackend, error){
+	"sqlite":  newSQLiteSink,
+	"parquet": newParquetSink,
+	"influx":  newInfluxSink,
+	"kafka":   newKafkaSink,
+}
+
+// Read the [sinks.<name>] tables, sorted by name
+func (c Config) SinkSpecs() ([]sinkSpec, error) {
+	var specs []sinkSpec
+	for table, settings := range c {
+		name, ok := strings.CutPrefix(table, "sinks.")
+		if !ok {
+			continue
+		}
+		spec := sinkSpec{name: name, kind: settings["type"], settings: settings}
+		if _, ok := sinkBackends[spec.kind]; !ok {
+			return nil, fmt.Errorf("sink %s: unknown type %q (%s)", name, spec.kind, strings.Join(sortedKeys(sinkBackends), ", "))
+		}
+		if settings["topics"] != "" {
+			topics, err := parseStringList(settings["topics"])
+			if err != nil {
+				return nil, fmt.Errorf("sink %s: topics: %w", name, err)
+			}
+			for _, pattern := range topics {
+				if _, err := path.Match(pattern, ""); err != nil {
+					return nil, fmt.Errorf("sink %s: topic pattern %q: %w", name, pattern, err)
+				}
+			}
+			spec.topics = topics
+		}
+		specs = append(specs, spec)
+	}
+	sort.Slice(specs, func(i, j int) bool {
+		return specs[i].name < specs[j].name
+	})
+	return specs, nil
+}
+
+// A running sink: a queue drained in batches into its backend
+type Sink struct {
+	spec    sinkSpec
+	backend sinkBackend
+	queue   chan DDSMessage
+	done    chan struct{}
+	dropped atomic.Int64
+}
+
+// Open the backend of a sink and start draining its queue
+func StartSink(spec sinkSpec) (*Sink, error) {
+	backend, err := sinkBackends[spec.kind](spec)
+	if err != nil {
+		return nil, fmt.Errorf("sink %s: %w", spec.name, err)
+	}
+	s := &Sink{spec: spec, backend: backend, queue: make(chan DDSMessage, sinkQueueSize), done: make(chan struct{})}
+	go runTask(context.Background(), "sink", func(context.Context) {
+		s.run()
+	})
+	return s, nil
+}
+
+// Whether the sink takes messages on topic
+func (s *Sink) matches(topic string) bool {
+	if len(s.spec.topics) == 0 {
+		return true
+	}
+	for _, pattern := range s.spec.topics {
+		if ok, _ := path.Match(pattern, topic); ok {
+			return true
+		}
+	}
+	return false
+}
+
+// Queue a message for the sink, dropping it when the queue is full
+func (s *Sink) Write(msg DDSMessage) {
+	if !s.matches(msg.Topic) {
+		return
+	}
+	select {
+	case s.queue <- msg:
+	default:
+		if s.dropped.Add(1) == 1 {
+			log.Printf("Sink %s is falling behind, dropping messages", s.spec.name)
+		}
+	}
+}
+
+func (s *Sink) run() {
+	defer close(s.done)
+	ticker := time.NewTicker(sinkFlushInterval)
+	defer ticker.Stop()
+
+	batch := make([]DDSMessage, 0, sinkBatchSize)
+	flush := func() {
+		if len(batch) == 0 {
+			return
+		}
+		if err := s.backend.WriteBatch(batch); err != nil {
+			log.Printf("Error writing to sink %s: %v", s.spec.name, err)
+		}
+		batch = batch[:0]
+	}
+
+	for {
+		select {
+		case msg, ok := <-s.queue:
+			if !ok {
+				flush()
+				return
+			}
+			batch = append(batch, msg)
+			if len(batch) == sinkBatchSize {
+				flush()
+			}
+		case <-ticker.C:
+			flush()
+		}
+	}
+}
+
+// Write what is still queued and close the backend. Call once no more
+// messages are written.
+func (s *Sink) Close() error {
+	close(s.queue)
+	<-s.done
+	if dropped := s.dropped.Load(); dropped > 0 {
+		log.Printf("Sink %s dropped %d messages", s.spec.name, dropped)
+	}
+	return s.backend.Close()
+}
@@ -0,0 +1,107 @@
+package main
+
+import (
+	"bufio"
+	"encoding/hex"
+	"encoding/json"
+	"fmt"
+	"io"
+	"os"
+	"os/exec"
+	"strconv"
+	"strings"
+)
+
+// The SQLite sink writes messages into a plain table, through the sqlite3
+// command line tool like the bag importer, so captures can be queried with
+// SQL afterwards:
+//
+//	SELECT ts, json_extract(json, '$.pose.x') FROM messages WHERE topic = 'odom';
+//
+// The database runs in WAL mode so it can be read while Cardinal writes,
+// and every batch is one transaction.
+
+const sqliteSchema = `PRAGMA journal_mode=WAL;
+PRAGMA synchronous=NORMAL;
+CREATE TABLE IF NOT EXISTS messages (
+  id INTEGER PRIMARY KEY,
+  topic TEXT NOT NULL,
+  ts INTEGER NOT NULL,
+  source_ts INTEGER,
+  seq INTEGER,
+  writer TEXT,
+  hash TEXT,
+  payload BLOB NOT NULL,
+  json TEXT
+);
+CREATE INDEX IF NOT EXISTS messages_topic_ts ON messages (topic, ts);
+`
+
+// A sqlite3 process fed SQL on its standard input
+type sqliteSink struct {
+	cmd   *exec.Cmd
+	stdin io.WriteCloser
+	out   *bufio.Writer
+}
+
+func newSQLiteSink(spec sinkSpec) (sinkBackend, error) {
+	path := spec.settings["path"]
+	if path == "" {
+		return nil, fmt.Errorf("needs a path")
+	}
+	cmd := exec.Command("sqlite3", "-batch", "-bail", path)
+	cmd.Stdout = io.Discard
+	cmd.Stderr = os.Stderr
+	stdin, err := cmd.StdinPipe()
+	if err != nil {
+		return nil, err
+	}
+	if err := cmd.Start(); err != nil {
+		return nil, fmt.Errorf("needs the sqlite3 command: %w", err)
+	}
+	s := &sqliteSink{cmd: cmd, stdin: stdin, out: bufio.NewWriter(stdin)}
+	if _, err := s.out.WriteString(sqliteSchema); err != nil {
+		s.Close()
+		return nil, err
+	}
+	return s, s.out.Flush()
+}
+
+// Quote a string as an SQL literal
+func sqlString(s string) string {
+	return "'" + strings.ReplaceAll(s, "'", "''") + "'"
+}
+
+// Insert a batch of messages in one transaction
+func (s *sqliteSink) WriteBatch(batch []DDSMessage) error {
+	s.out.WriteString("BEGIN;\n")
+	for _, msg := range batch {
+		sourceTS, seq, writer, hash, jsonText := "NULL", "NULL", "NULL", "NULL", "NULL"
+		if !msg.Timestamp.IsZero() {
+			sourceTS = strconv.FormatInt(msg.Timestamp.UnixNano(), 10)
+		}
+		if msg.Sequence != 0 {
+			seq = strconv.FormatInt(msg.Sequence, 10)
+		}
+		if msg.Writer != "" {
+			writer = sqlString(msg.Writer)
+		}
+		if msg.Hash != "" {
+			hash = sqlString(msg.Hash)
+		}
+		if json.Valid([]byte(msg.Content)) {
+			jsonText = sqlString(msg.Content)
+		}
+		fmt.Fprintf(s.out, "INSERT INTO messages (topic, ts, source_ts, seq, writer, hash, payload, json) VALUES (%s, %d, %s, %s, %s, %s, X'%s', %s);\n",
+			sqlString(msg.Topic), msg.ReceivedAt.UnixNano(), sourceTS, seq, writer, hash, hex.EncodeToString([]byte(msg.Content)), jsonText)
+	}
+	s.out.WriteString("COMMIT;\n")
+	return s.out.Flush()
+}
+
+// Finish the last transaction and wait for sqlite3 to exit
+func (s *sqliteSink) Close() error {
+	s.out.Flush()
+	s.stdin.Close()
+	return s.cmd.Wait()
+}
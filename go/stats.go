@@ -0,0 +1,28 @@
+package main
+
+import "cardinal/core"
+
+// The per-topic statistics live in package core, shared with the WASM
+// capture viewer
+type (
+	StatsTracker     = core.StatsTracker
+	TopicStats       = core.TopicStats
+	TopicWindowStats = core.TopicWindowStats
+)
+
+func NewStatsTracker() *StatsTracker {
+	return core.NewStatsTracker()
+}
+
+// What the statistics take of a message
+func statsSample(msg DDSMessage) core.Sample {
+	return core.Sample{
+		Topic:      msg.Topic,
+		Bytes:      msg.Size(),
+		ReceivedAt: msg.ReceivedAt,
+		Duplicate:  msg.Duplicate,
+		Reordered:  msg.Reordered,
+		Repeated:   msg.Repeated,
+		OverBudget: msg.OverBudget,
+	}
+}
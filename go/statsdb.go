@@ -0,0 +1,211 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+	"log"
+	"os"
+	"path/filepath"
+	"sort"
+	"sync"
+	"time"
+)
+
+// How often the cumulative statistics are written while running
+const statsSaveInterval = 30 * time.Second
+
+// Format version of the statistics file
+const statsDBVersion = 1
+
+// Cumulative totals of one topic over every session
+type storedTopic struct {
+	Messages   int               `json:"messages"`
+	Bytes      int               `json:"bytes"`
+	WireBytes  int               `json:"wire_bytes"`
+	Gaps       int               `json:"gaps"`
+	Duplicates int               `json:"duplicates"`
+	Reordered  int               `json:"reordered"`
+	Repeated   int               `json:"repeated"`
+	FirstSeen  time.Time         `json:"first_seen"`
+	LastSeen   time.Time         `json:"last_seen"`
+	Intervals  IntervalHistogram `json:"intervals"`
+}
+
+// Statistics kept across restarts in a small JSON file
+type statsDB struct {
+	Version  int                     `json:"version"`
+	Updated  time.Time               `json:"updated"`
+	Sessions int                     `json:"sessions"`
+	Uptime   float64                 `json:"uptime_seconds"`
+	Topics   map[string]*storedTopic `json:"topics"`
+}
+
+// Default path of the statistics file
+func defaultStatsDB() string {
+	dir, err := os.UserCacheDir()
+	if err != nil {
+		return "cardinal-stats.json"
+	}
+	return filepath.Join(dir, "cardinal", "stats.json")
+}
+
+// Load the statistics file at path; a missing file is an empty database
+func loadStatsDB(path string) (*statsDB, error) {
+	db := &statsDB{Version: statsDBVersion, Topics: map[string]*storedTopic{}}
+	data, err := os.ReadFile(path)
+	if os.IsNotExist(err) {
+		return db, nil
+	}
+	if err != nil {
+		return nil, err
+	}
+	if err := json.Unmarshal(data, db); err != nil {
+		return nil, fmt.Errorf("%s: %w", path, err)
+	}
+	if db.Version != statsDBVersion {
+		return nil, fmt.Errorf("%s: unsupported version %d", path, db.Version)
+	}
+	if db.Topics == nil {
+		db.Topics = map[string]*storedTopic{}
+	}
+	return db, nil
+}
+
+// The database with one more session's statistics added
+func (db *statsDB) merged(topics []TopicStats, uptime time.Duration, now time.Time) *statsDB {
+	result := &statsDB{
+		Version:  statsDBVersion,
+		Updated:  now,
+		Sessions: db.Sessions + 1,
+		Uptime:   db.Uptime + uptime.Seconds(),
+		Topics:   make(map[string]*storedTopic, len(db.Topics)),
+	}
+	for topic, stored := range db.Topics {
+		copied := *stored
+		result.Topics[topic] = &copied
+	}
+	for _, stats := range topics {
+		stored, ok := result.Topics[stats.Topic]
+		if !ok {
+			stored = &storedTopic{FirstSeen: now}
+			result.Topics[stats.Topic] = stored
+		}
+		stored.Messages += stats.Total
+		stored.Bytes += stats.TotalBytes
+		stored.WireBytes += stats.TotalWireBytes
+		stored.Gaps += stats.TotalGaps
+		stored.Duplicates += stats.TotalDuplicates
+		stored.Reordered += stats.TotalReordered
+		stored.Repeated += stats.TotalRepeated
+		if stats.LastSeen.After(stored.LastSeen) {
+			stored.LastSeen = stats.LastSeen
+		}
+		stored.Intervals.Merge(stats.Intervals)
+	}
+	return result
+}
+
+// Write the database atomically, so a crash never leaves half a file
+func (db *statsDB) save(path string) error {
+	if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+		return err
+	}
+	out, err := json.MarshalIndent(db, "", "  ")
+	if err != nil {
+		return err
+	}
+	tmp := path + ".tmp"
+	if err := os.WriteFile(tmp, append(out, '\n'), 0o644); err != nil {
+		return err
+	}
+	return os.Rename(tmp, path)
+}
+
+// Add this session's statistics to the database at path, saving every
+// statsSaveInterval and once more when the context is done
+func startStatsPersistence(ctx context.Context, wg *sync.WaitGroup, path string, stats *StatsTracker, startTime time.Time) error {
+	base, err := loadStatsDB(path)
+	if err != nil {
+		return err
+	}
+
+	save := func() {
+		now := time.Now()
+		if err := base.merged(stats.Topics(), now.Sub(startTime), now).save(path); err != nil {
+			log.Printf("Error saving statistics: %v", err)
+		}
+	}
+
+	wg.Add(1)
+	go runTask(ctx, "stats-persistence", func(ctx context.Context) {
+		defer wg.Done()
+		ticker := time.NewTicker(statsSaveInterval)
+		defer ticker.Stop()
+
+		for {
+			select {
+			case <-ctx.Done():
+				save()
+				return
+			case <-ticker.C:
+				save()
+			}
+		}
+	})
+	return nil
+}
+
+// One topic in the output of `cardinal stats show`
+type storedTopicReport struct {
+	Topic string `json:"topic"`
+	storedTopic
+	Jitter float64 `json:"jitter_seconds"`
+}
+
+// Run `cardinal stats show`: print the totals kept across restarts, and
+// return the exit code
+func runStats(opts Options) int {
+	if _, err := os.Stat(opts.StatsDB); os.IsNotExist(err) {
+		fmt.Fprintf(os.Stderr, "cardinal stats: no statistics at %s (run with --persist-stats)\n", opts.StatsDB)
+		return exitNoData
+	}
+	db, err := loadStatsDB(opts.StatsDB)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal stats:", err)
+		return exitFailure
+	}
+
+	topics := make([]string, 0, len(db.Topics))
+	for topic := range db.Topics {
+		topics = append(topics, topic)
+	}
+	sort.Strings(topics)
+
+	if opts.JSON {
+		reports := make([]storedTopicReport, len(topics))
+		for i, topic := range topics {
+			stored := db.Topics[topic]
+			reports[i] = storedTopicReport{Topic: topic, storedTopic: *stored, Jitter: stored.Intervals.StdDev().Seconds()}
+		}
+		printResult(opts, struct {
+			Updated  time.Time           `json:"updated"`
+			Sessions int                 `json:"sessions"`
+			Uptime   float64             `json:"uptime_seconds"`
+			Topics   []storedTopicReport `json:"topics"`
+		}{db.Updated, db.Sessions, db.Uptime, reports}, nil)
+		return exitOK
+	}
+
+	fmt.Printf("Sessions:  %d (uptime %s)\n", db.Sessions, formatDuration(time.Duration(db.Uptime*float64(time.Second))))
+	fmt.Printf("Updated:   %s\n", db.Updated.Format(time.RFC3339))
+	fmt.Println()
+	fmt.Printf("%-32s %10s %12s %8s %10s %10s %10s %10s  %s\n", "Topic", "Messages", "Bytes", "Gaps", "Duplicates", "Reordered", "Repeated", "Jitter", "Last seen")
+	for _, topic := range topics {
+		stored := db.Topics[topic]
+		fmt.Printf("%-32s %10d %12s %8d %10d %10d %10d %10s  %s\n", topic, stored.Messages,
+			formatBytes(float64(stored.Bytes)), stored.Gaps, stored.Duplicates, stored.Reordered, stored.Repeated,
+			formatInterval(stored.Intervals.StdDev().Round(time.Microsecond)), stored.LastSeen.Format(time.DateTime))
+	}
+	return exitOK
+}
@@ -0,0 +1,88 @@
+package main
+
+import (
+	"sync"
+	"sync/atomic"
+
+	tea "github.com/charmbracelet/bubbletea"
+)
+
+// Received messages on their way to the TUI. Ingest appends to the store
+// without waiting for the TUI, and the TUI takes an immutable snapshot when
+// told the store changed, so a slow frame never holds up a transport thread
+// and a frame never shows a half-applied update. Bursts coalesce into one
+// update of the TUI. Ingest also keeps the text filters search, so the TUI
+// never lower-cases the history itself.
+type messageStore struct {
+	mu       sync.Mutex // serializes appends; readers never take it
+	messages []DDSMessage
+	lowered  []string
+	total    uint64
+	snapshot atomic.Pointer[storeSnapshot]
+	updated  chan struct{}
+}
+
+// The store as of one moment; nothing in it changes afterwards
+type storeSnapshot struct {
+	Messages []DDSMessage // the most recent, oldest first
+	Lowered  []string     // the Messages' topics and payloads in lower case, as searchText has them
+	Total    uint64       // messages ever appended
+}
+
+// Tells the TUI the store changed
+type storeUpdatedMsg struct{}
+
+func newMessageStore() *messageStore {
+	s := &messageStore{updated: make(chan struct{}, 1)}
+	s.snapshot.Store(&storeSnapshot{})
+	return s
+}
+
+// Add a received message, keeping at most maxMessageHistory
+func (s *messageStore) Append(msg DDSMessage) {
+	s.mu.Lock()
+	// Appending only writes past the end of earlier snapshots, and dropping
+	// the oldest only reslices, so no snapshot sees its messages change
+	s.messages = append(s.messages, msg)
+	s.lowered = append(s.lowered, searchText(msg))
+	if len(s.messages) > maxMessageHistory {
+		s.messages, s.lowered = s.messages[1:], s.lowered[1:]
+	}
+	s.total++
+	n := len(s.messages)
+	s.snapshot.Store(&storeSnapshot{Messages: s.messages[:n:n], Lowered: s.lowered[:n:n], Total: s.total})
+	s.mu.Unlock()
+
+	select {
+	case s.updated <- struct{}{}:
+	default: // the TUI has not caught up with the last update yet
+	}
+}
+
+func (s *messageStore) Snapshot() *storeSnapshot {
+	return s.snapshot.Load()
+}
+
+// A command waiting for the next change of the store
+func (s *messageStore) Wait() tea.Cmd {
+	return func() tea.Msg {
+		<-s.updated
+		return storeUpdatedMsg{}
+	}
+}
+
+// The messages appended after the snapshot with total seen, as far as the
+// snapshot still holds them
+func (snap *storeSnapshot) Since(seen uint64) []DDSMessage {
+	n := snap.Total - seen
+	if n > uint64(len(snap.Messages)) {
+		n = uint64(len(snap.Messages))
+	}
+	return snap.Messages[len(snap.Messages)-int(n):]
+}
+
+// Position in the store, counted from its first message ever, of the
+// snapshot's oldest message
+func (snap *storeSnapshot) first() uint64 {
+	return snap.Total - uint64(len(snap.Messages))
+}
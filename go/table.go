@@ -0,0 +1,400 @@
+package main
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+	"time"
+
+	"github.com/charmbracelet/lipgloss"
+)
+
+// Columns the message table can be sorted by
+type sortColumn int
+
+const (
+	sortByTime sortColumn = iota
+	sortByTopic
+	sortBySize
+	sortByLatency
+	sortByContent
+	sortColumnCount
+)
+
+var sortColumnNames = [...]string{"Time", "Topic", "Size", "Latency", "Content"}
+
+// Ways the message table can group its rows
+type groupMode int
+
+const (
+	groupNone groupMode = iota
+	groupByTopic
+	groupByKey
+	groupModeCount
+)
+
+var groupModeNames = [...]string{"None", "Topic", "Key"}
+
+// Group of the messages of unkeyed topics when grouping by key
+const noKeyGroup = "(no key)"
+
+// Number of table rows rendered at once
+const tableVisibleRows = 15
+
+// View settings for the message table. Only the rows in view are rendered,
+// from offset on; anchor keeps the row at the cursor in place as messages
+// arrive and leave the history.
+type tableState struct {
+	enabled    bool
+	sortColumn sortColumn
+	descending bool
+	groupMode  groupMode
+	collapsed  map[string]bool
+	cursor     int
+	offset     int      // first row in view
+	anchor     tableRow // row at the cursor when it last moved
+}
+
+// A single table row: either a group header or a message
+type tableRow struct {
+	header  bool
+	group   string
+	count   int
+	message DDSMessage
+}
+
+// Whether two rows, possibly of rows built at different times, show the
+// same group header or message
+func (r tableRow) is(other tableRow) bool {
+	if r.header || other.header {
+		return r.header == other.header && r.group == other.group
+	}
+	a, b := r.message, other.message
+	if a.Writer != "" {
+		return a.Writer == b.Writer && a.Sequence == b.Sequence && a.Domain == b.Domain && a.Topic == b.Topic
+	}
+	return b.Writer == "" && a.Timestamp.Equal(b.Timestamp) && a.Domain == b.Domain && a.Topic == b.Topic
+}
+
+func newTableState() tableState {
+	return tableState{
+		sortColumn: sortByTime,
+		descending: true,
+		collapsed:  map[string]bool{},
+	}
+}
+
+// Handle a key press while the table view is active
+func (t tableState) handleKey(key string, rows []tableRow) tableState {
+	switch key {
+	case "s":
+		t.sortColumn = (t.sortColumn + 1) % sortColumnCount
+	case "r":
+		t.descending = !t.descending
+	case "g":
+		t.groupMode = (t.groupMode + 1) % groupModeCount
+		t.cursor = 0
+	case "up", "k":
+		t.cursor--
+	case "down", "j":
+		t.cursor++
+	case "pgup":
+		t.cursor -= tableVisibleRows
+	case "pgdown":
+		t.cursor += tableVisibleRows
+	case "home":
+		t.cursor = 0
+	case "end":
+		t.cursor = len(rows) - 1
+	case "enter", " ":
+		if t.cursor < len(rows) && rows[t.cursor].header {
+			group := rows[t.cursor].group
+			t.collapsed[group] = !t.collapsed[group]
+		}
+	}
+	t = t.scrolled(len(rows))
+	if t.cursor < len(rows) {
+		t.anchor = rows[t.cursor]
+	}
+	return t
+}
+
+// Keep the row at the cursor where it is on screen after the rows were
+// built again. A cursor on the first row stays there, following the newest
+// messages of a table sorted by descending time.
+func (t tableState) follow(rows []tableRow) tableState {
+	if t.cursor == 0 {
+		return t.scrolled(len(rows))
+	}
+	// Rows move by as many places as arrived or left since, so search
+	// outwards from the old position
+	for d := 0; d < len(rows); d++ {
+		for _, i := range [2]int{t.cursor + d, t.cursor - d} {
+			if i >= 0 && i < len(rows) && rows[i].is(t.anchor) {
+				t.offset += i - t.cursor
+				t.cursor = i
+				return t.scrolled(len(rows))
+			}
+		}
+	}
+	// The row left the history: keep the position instead
+	return t.scrolled(len(rows))
+}
+
+// Clamp the cursor to the rows and scroll it into view
+func (t tableState) scrolled(n int) tableState {
+	t.cursor = max(min(t.cursor, n-1), 0)
+	if t.cursor < t.offset {
+		t.offset = t.cursor
+	}
+	if t.cursor >= t.offset+tableVisibleRows {
+		t.offset = t.cursor - tableVisibleRows + 1
+	}
+	t.offset = max(min(t.offset, n-tableVisibleRows), 0)
+	return t
+}
+
+// Compare two messages by the selected sort column
+func (t tableState) less(a, b DDSMessage) bool {
+	switch t.sortColumn {
+	case sortByTopic:
+		if a.Topic != b.Topic {
+			return a.Topic < b.Topic
+		}
+	case sortBySize:
+		if a.Size() != b.Size() {
+			return a.Size() < b.Size()
+		}
+	case sortByLatency:
+		if a.Latency() != b.Latency() {
+			return a.Latency() < b.Latency()
+		}
+	case sortByContent:
+		if a.Content != b.Content {
+			return a.Content < b.Content
+		}
+	}
+	return a.Timestamp.Before(b.Timestamp)
+}
+
+// Group key of a message under the current grouping mode
+func (t tableState) groupKey(msg DDSMessage) string {
+	switch t.groupMode {
+	case groupByTopic:
+		return msg.Topic
+	case groupByKey:
+		if msg.Key == "" {
+			return noKeyGroup
+		}
+		return msg.Key
+	}
+	return ""
+}
+
+// Build the sorted (and optionally grouped) rows for the given messages
+func (t tableState) rows(messages []DDSMessage) []tableRow {
+	sorted := make([]DDSMessage, len(messages))
+	copy(sorted, messages)
+	sort.SliceStable(sorted, func(i, j int) bool {
+		if t.descending {
+			return t.less(sorted[j], sorted[i])
+		}
+		return t.less(sorted[i], sorted[j])
+	})
+
+	if t.groupMode == groupNone {
+		rows := make([]tableRow, len(sorted))
+		for i, msg := range sorted {
+			rows[i] = tableRow{message: msg}
+		}
+		return rows
+	}
+
+	// Groups are ordered by their first appearance in sort order, so the
+	// group holding the slowest/largest/latest sample comes first
+	var order []string
+	members := map[string][]DDSMessage{}
+	for _, msg := range sorted {
+		key := t.groupKey(msg)
+		if _, ok := members[key]; !ok {
+			order = append(order, key)
+		}
+		members[key] = append(members[key], msg)
+	}
+
+	var rows []tableRow
+	for _, key := range order {
+		rows = append(rows, tableRow{header: true, group: key, count: len(members[key])})
+		if t.collapsed[key] {
+			continue
+		}
+		for _, msg := range members[key] {
+			rows = append(rows, tableRow{group: key, message: msg})
+		}
+	}
+	return rows
+}
+
+// Render the message table
+func (m model) renderTable(primaryColor, textColor, dimColor lipgloss.Color) string {
+	headerStyle := lipgloss.NewStyle().
+		Foreground(primaryColor).
+		Bold(true)
+
+	groupStyle := lipgloss.NewStyle().
+		Foreground(primaryColor)
+
+	rowStyle := lipgloss.NewStyle().
+		Foreground(textColor)
+
+	cursorStyle := lipgloss.NewStyle().
+		Foreground(textColor).
+		Background(lipgloss.Color("#1A1A2E")).
+		Bold(true)
+
+	dimStyle := lipgloss.NewStyle().
+		Foreground(dimColor).
+		Italic(true)
+
+	rows := m.tableRows()
+	view := m.table.scrolled(len(rows))
+	cursor := view.cursor
+
+	// Column titles with the active sort direction
+	titles := make([]string, len(sortColumnNames))
+	for i, name := range sortColumnNames {
+		if sortColumn(i) == m.table.sortColumn {
+			if m.table.descending {
+				name += " ▼"
+			} else {
+				name += " ▲"
+			}
+		}
+		titles[i] = name
+	}
+
+	var b strings.Builder
+	b.WriteString(headerStyle.Render(fmt.Sprintf("  %-14s %-20s %8s %10s  %s  %s",
+		titles[sortByTime], titles[sortByTopic], titles[sortBySize], titles[sortByLatency], "Hash    ", titles[sortByContent])))
+	b.WriteString("\n")
+
+	if len(rows) == 0 {
+		b.WriteString(dimStyle.Render("  No messages yet"))
+		return b.String()
+	}
+
+	// Only the rows in view are rendered
+	start, end := view.offset, min(view.offset+tableVisibleRows, len(rows))
+	for i := start; i < end; i++ {
+		row := rows[i]
+		marker := "  "
+		if i == cursor {
+			marker = "› "
+		}
+
+		var line string
+		if row.header {
+			arrow := "▼"
+			if m.table.collapsed[row.group] {
+				arrow = "▶"
+			}
+			line = groupStyle.Render(fmt.Sprintf("%s%s %s (%d)", marker, arrow, row.group, row.count))
+		} else {
+			msg := row.message
+			duplicate := " "
+			if msg.Duplicate {
+				duplicate = "⧉"
+			}
+			if msg.Reordered || msg.Repeated {
+				duplicate = "↯"
+			}
+			if msg.Own {
+				duplicate = "↺"
+			}
+			latency := msg.Latency().Round(time.Microsecond).String()
+			if msg.OverBudget {
+				latency = "!" + latency
+			}
+			text := fmt.Sprintf("%s%-14s %-20s %8d %10s  %-8s%s %s",
+				marker,
+				m.times.Format("table", msg.Timestamp, "ms"),
+				truncate(topicLabel(msg), 20),
+				msg.Size(),
+				latency,
+				shortHash(msg.Hash),
+				duplicate,
+				truncate(m.formats.Render(msg), 40),
+			)
+			if i == cursor {
+				line = cursorStyle.Render(text)
+			} else {
+				line = rowStyle.Render(text)
+			}
+		}
+		b.WriteString(line + "\n")
+	}
+
+	b.WriteString(dimStyle.Render(fmt.Sprintf("  %d-%d of %d rows • sort: %s • group: %s",
+		start+1, end, len(rows), sortColumnNames[m.table.sortColumn], groupModeNames[m.table.groupMode])))
+
+	return b.String()
+}
+
+// The table's rows as last built, which the model's copies share. Frames and
+// key presses reuse them until the messages or the view settings change,
+// rather than sorting the whole history on every frame.
+type rowCache struct {
+	key   rowCacheKey
+	rows  []tableRow
+	built bool
+}
+
+// What the table's rows are built from
+type rowCacheKey struct {
+	seen       uint64
+	filter     string
+	search     int // version of the filter's matches
+	sortColumn sortColumn
+	descending bool
+	groupMode  groupMode
+	collapsed  string // the collapsed groups
+	focus      string // the muted and soloed topics
+}
+
+// Rows of the table for the messages shown, built again only when they or
+// the view settings changed since the last call
+func (m model) tableRows() []tableRow {
+	var collapsed []string
+	for group, c := range m.table.collapsed {
+		if c {
+			collapsed = append(collapsed, group)
+		}
+	}
+	sort.Strings(collapsed)
+	key := rowCacheKey{
+		seen:       m.seen,
+		filter:     m.query(),
+		search:     m.search.version,
+		sortColumn: m.table.sortColumn,
+		descending: m.table.descending,
+		groupMode:  m.table.groupMode,
+		collapsed:  strings.Join(collapsed, "\x00"),
+		focus:      m.focus.String(),
+	}
+	if !m.rows.built || m.rows.key != key {
+		m.rows.key, m.rows.rows, m.rows.built = key, m.table.rows(m.visibleMessages()), true
+	}
+	return m.rows.rows
+}
+
+// Truncate a string to at most n runes
+func truncate(s string, n int) string {
+	runes := []rune(s)
+	if len(runes) <= n {
+		return s
+	}
+	if n <= 1 {
+		return string(runes[:n])
+	}
+	return string(runes[:n-1]) + "…"
+}
@@ -0,0 +1,130 @@
+package main
+
+import (
+	"fmt"
+	"slices"
+	"strings"
+	"time"
+)
+
+// Timestamps are shown in local time by default, at a precision chosen per
+// view. The [time] table changes that for every view, and [time.<view>]
+// tables for a single one:
+//
+//	[time]
+//	zone = "UTC"          # local, UTC or a fixed offset like +05:30
+//	precision = "us"      # s, ms, us or ns
+//
+//	[time.table]
+//	date = true           # prefix the date
+var timeViews = []string{"messages", "table", "echo", "formats", "charts"}
+
+// Layouts by precision
+var timeLayouts = map[string]string{
+	"s":  "15:04:05",
+	"ms": "15:04:05.000",
+	"us": "15:04:05.000000",
+	"ns": "15:04:05.000000000",
+}
+
+// How one view renders timestamps. The zero value is the view's default.
+type timeFormat struct {
+	location  *time.Location // nil for local time
+	precision string         // "" for the view's own precision
+	date      bool
+}
+
+// Render a timestamp, at defaultPrecision unless one is configured
+func (f timeFormat) Format(t time.Time, defaultPrecision string) string {
+	if f.location != nil {
+		t = t.In(f.location)
+	}
+	precision := f.precision
+	if precision == "" {
+		precision = defaultPrecision
+	}
+	layout := timeLayouts[precision]
+	if f.date {
+		layout = "2006-01-02 " + layout
+	}
+	return t.Format(layout)
+}
+
+// Apply the settings of a [time] or [time.<view>] table
+func (f *timeFormat) apply(name string, table map[string]string) error {
+	if zone, ok := table["zone"]; ok {
+		location, err := parseZone(zone)
+		if err != nil {
+			return fmt.Errorf("%s: %w", name, err)
+		}
+		f.location = location
+	}
+	if precision, ok := table["precision"]; ok {
+		if _, ok := timeLayouts[precision]; !ok {
+			return fmt.Errorf("%s: unknown precision %q (s, ms, us, ns)", name, precision)
+		}
+		f.precision = precision
+	}
+	if date, ok := table["date"]; ok {
+		if date != "true" && date != "false" {
+			return fmt.Errorf("%s: date must be true or false", name)
+		}
+		f.date = date == "true"
+	}
+	return nil
+}
+
+// Parse a zone setting: local, UTC, a fixed offset (+05:30, -08:00) or an
+// IANA zone name where the system has the zone database
+func parseZone(zone string) (*time.Location, error) {
+	switch strings.ToLower(zone) {
+	case "local":
+		return time.Local, nil
+	case "utc", "z":
+		return time.UTC, nil
+	}
+	if strings.HasPrefix(zone, "+") || strings.HasPrefix(zone, "-") {
+		offset, err := time.Parse("-07:00", zone)
+		if err != nil {
+			return nil, fmt.Errorf("invalid offset %q, expected +HH:MM", zone)
+		}
+		_, seconds := offset.Zone()
+		return time.FixedZone(zone, seconds), nil
+	}
+	location, err := time.LoadLocation(zone)
+	if err != nil {
+		return nil, fmt.Errorf("unknown zone %q", zone)
+	}
+	return location, nil
+}
+
+// Timestamp formats by view
+type timeFormats map[string]timeFormat
+
+// Read the [time] and [time.<view>] tables
+func (c Config) TimeFormats() (timeFormats, error) {
+	for table := range c {
+		if view, ok := strings.CutPrefix(table, "time."); ok && !slices.Contains(timeViews, view) {
+			return nil, fmt.Errorf("time: unknown view %q (%s)", view, strings.Join(timeViews, ", "))
+		}
+	}
+
+	var global timeFormat
+	if err := global.apply("time", c["time"]); err != nil {
+		return nil, err
+	}
+	formats := timeFormats{}
+	for _, view := range timeViews {
+		format := global
+		if err := format.apply("time."+view, c["time."+view]); err != nil {
+			return nil, err
+		}
+		formats[view] = format
+	}
+	return formats, nil
+}
+
+// Render a timestamp in a view, at defaultPrecision unless one is configured
+func (f timeFormats) Format(view string, t time.Time, defaultPrecision string) string {
+	return f[view].Format(t, defaultPrecision)
+}
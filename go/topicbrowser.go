@@ -0,0 +1,265 @@
+package main
+
+import (
+	"fmt"
+	"slices"
+	"sort"
+	"strings"
+	"time"
+
+	tea "github.com/charmbracelet/bubbletea"
+	"github.com/charmbracelet/lipgloss"
+)
+
+// The Topics tab lists every topic the other participants of the domain
+// write or read, as their DataWriters and DataReaders appear on the
+// DCPSPublication and DCPSSubscription builtin topics. Enter subscribes to
+// the selected topic and opens the Messages tab filtered to it; topics of
+// other types than Cardinal's own are read with the type their writers
+// announce, as with dynamic = true. m and s mute and solo the selected topic
+// on the Messages tab, see topicFocus. Below the list, the selected topic's
+// writers and readers show the QoS they announce, to find why endpoints do
+// not match.
+
+// A DataWriter or DataReader of another participant
+type RemoteEndpoint struct {
+	GUID        string `json:"guid"`
+	Participant string `json:"participant"` // GUID of its participant
+	Topic       string `json:"topic"`
+	Type        string `json:"type"`
+	Writer      bool   `json:"writer"`           // false for readers
+	Domain      string `json:"domain,omitempty"` // set when Cardinal joined several
+	QoS         QoS    `json:"qos"`              // as announced; zero durations are infinite
+	Exclusive   bool   `json:"exclusive"`        // exclusive rather than shared ownership
+	Strength    int    `json:"strength"`         // ownership strength of writers
+}
+
+// Type names of Cardinal's own samples
+var cardinalTypes = map[string]bool{"SimpleMessage": true, "KeyedSimpleMessage": true}
+
+// A topic found by discovery
+type DiscoveredTopic struct {
+	Name       string
+	Types      []string // as the endpoints announce them, usually one
+	Writers    int
+	Readers    int
+	Subscribed bool
+	Endpoints  []RemoteEndpoint // writers first, by GUID
+}
+
+// Gather the endpoints into their topics, by name
+func discoveredTopics(endpoints map[string]RemoteEndpoint, subscriptions map[string]DDSSubscriber) []DiscoveredTopic {
+	byName := map[string]*DiscoveredTopic{}
+	for _, e := range endpoints {
+		topic, ok := byName[e.Topic]
+		if !ok {
+			topic = &DiscoveredTopic{Name: e.Topic}
+			_, topic.Subscribed = subscriptions[e.Topic]
+			byName[e.Topic] = topic
+		}
+		if e.Writer {
+			topic.Writers++
+		} else {
+			topic.Readers++
+		}
+		if !slices.Contains(topic.Types, e.Type) {
+			topic.Types = append(topic.Types, e.Type)
+		}
+		topic.Endpoints = append(topic.Endpoints, e)
+	}
+	topics := make([]DiscoveredTopic, 0, len(byName))
+	for _, name := range sortedKeys(byName) {
+		topic := byName[name]
+		sort.Strings(topic.Types)
+		sort.Slice(topic.Endpoints, func(i, j int) bool {
+			a, b := topic.Endpoints[i], topic.Endpoints[j]
+			if a.Writer != b.Writer {
+				return a.Writer
+			}
+			return a.GUID < b.GUID
+		})
+		topics = append(topics, *topic)
+	}
+	return topics
+}
+
+// Outcome of subscribing from the topic browser
+type subscribeResultMsg struct {
+	topic string
+	err   error
+}
+
+// Index of the tab with a key
+func tabIndex(key string) int {
+	for i, tab := range tabs {
+		if tab.key == key {
+			return i
+		}
+	}
+	return 0
+}
+
+// Handle a key on the topics tab: move the cursor, or subscribe to the
+// selected topic and show its messages
+func (m model) handleTopicsKey(key string) (model, tea.Cmd) {
+	switch key {
+	case "up", "k":
+		if m.topicCursor > 0 {
+			m.topicCursor--
+		}
+	case "down", "j":
+		if m.topicCursor < len(m.topics)-1 {
+			m.topicCursor++
+		}
+	case "m", "s":
+		if m.topicCursor >= len(m.topics) {
+			break
+		}
+		topic := m.topics[m.topicCursor].Name
+		if key == "m" {
+			m.focus = m.focus.toggleMute(topic)
+		} else {
+			m.focus = m.focus.toggleSolo(topic)
+		}
+		m.table.cursor = 0
+	case "c":
+		m.focus = topicFocus{}
+	case "enter":
+		if m.topicCursor >= len(m.topics) {
+			break
+		}
+		topic := m.topics[m.topicCursor]
+		m.activeTab, m.filter, m.table.cursor = tabIndex("messages"), topic.Name, 0
+		if topic.Subscribed {
+			break
+		}
+		m.status = "Subscribing to " + topic.Name + "…"
+		subscribe := m.subscribe
+		return m, func() tea.Msg {
+			// Reading another type waits for a writer to announce it
+			return subscribeResultMsg{topic: topic.Name, err: subscribe(topic.Name)}
+		}
+	}
+	return m, nil
+}
+
+// Render the topics tab
+func (m model) renderTopics(primaryColor, textColor, dimColor lipgloss.Color) string {
+	cardStyle := lipgloss.NewStyle().
+		Border(lipgloss.RoundedBorder()).
+		BorderForeground(primaryColor).
+		Padding(1, 2).
+		MarginBottom(1)
+	headerStyle := lipgloss.NewStyle().
+		Foreground(primaryColor).
+		Bold(true).
+		MarginBottom(1)
+	dimStyle := lipgloss.NewStyle().Foreground(dimColor)
+	rowStyle := lipgloss.NewStyle().Foreground(textColor)
+	cursorStyle := lipgloss.NewStyle().
+		Foreground(textColor).
+		Background(lipgloss.Color("#1A1A2E")).
+		Bold(true)
+
+	lines := []string{headerStyle.Render(fmt.Sprintf("🧭 Discovered Topics (%d)", len(m.topics)))}
+	switch {
+	case m.discoveredTopics == nil:
+		lines = append(lines, dimStyle.Render("Topic discovery needs the Fast DDS transport"))
+	case len(m.topics) == 0:
+		lines = append(lines, dimStyle.Render("No topics of other participants discovered yet"))
+	default:
+		lines = append(lines, dimStyle.Bold(true).Render(fmt.Sprintf("  %-36s %-28s %7s %7s  %s", "Topic", "Type", "Writers", "Readers", "View")))
+		for i, topic := range m.topics {
+			mark := " "
+			if topic.Subscribed {
+				mark = "✓"
+			}
+			view := ""
+			switch {
+			case m.focus.soloed[topic.Name]:
+				view = "solo"
+			case m.focus.muted[topic.Name]:
+				view = "muted"
+			case !m.focus.shows(topic.Name):
+				view = "hidden"
+			}
+			line := fmt.Sprintf("%s %-36s %-28s %7d %7d  %s", mark, truncate(topic.Name, 36),
+				truncate(strings.Join(topic.Types, ", "), 28), topic.Writers, topic.Readers, view)
+			if i == m.topicCursor {
+				lines = append(lines, cursorStyle.Render(line))
+			} else {
+				lines = append(lines, rowStyle.Render(line))
+			}
+		}
+		lines = append(lines, "", dimStyle.Render("↑/↓: select • Enter: subscribe and show its messages • m/s: mute/solo • c: show all"))
+	}
+	card := cardStyle.Render(lipgloss.JoinVertical(lipgloss.Left, lines...))
+	if m.topicCursor >= len(m.topics) {
+		return card
+	}
+	return lipgloss.JoinVertical(lipgloss.Left, card,
+		m.renderEndpoints(m.topics[m.topicCursor], primaryColor, textColor, dimColor))
+}
+
+// Render the inspector of a topic's endpoints and the QoS they announce
+func (m model) renderEndpoints(topic DiscoveredTopic, primaryColor, textColor, dimColor lipgloss.Color) string {
+	headerStyle := lipgloss.NewStyle().
+		Foreground(primaryColor).
+		Bold(true).
+		MarginBottom(1)
+	columnStyle := lipgloss.NewStyle().Foreground(dimColor).Bold(true)
+	textStyle := lipgloss.NewStyle().Foreground(textColor)
+
+	lines := []string{
+		headerStyle.Render("🔎 Endpoints of " + topic.Name),
+		lipgloss.JoinHorizontal(lipgloss.Left,
+			columnStyle.Width(8).Render("Kind"),
+			columnStyle.Width(36).Render("GUID"),
+			columnStyle.Width(13).Render("Reliability"),
+			columnStyle.Width(17).Render("Durability"),
+			columnStyle.Width(10).Render("Deadline"),
+			columnStyle.Width(28).Render("Liveliness"),
+			columnStyle.Width(16).Render("Ownership"),
+			columnStyle.Render("Partitions"),
+		),
+	}
+	for _, e := range topic.Endpoints {
+		kind := "reader"
+		if e.Writer {
+			kind = "writer"
+		}
+		if e.Domain != "" {
+			kind = "d" + e.Domain + " " + kind
+		}
+		ownership := "shared"
+		if e.Exclusive {
+			ownership = "exclusive"
+			if e.Writer {
+				ownership += fmt.Sprintf(" (%d)", e.Strength)
+			}
+		}
+		lines = append(lines, lipgloss.JoinHorizontal(lipgloss.Left,
+			textStyle.Width(8).Render(kind),
+			textStyle.Width(36).Render(truncate(e.GUID, 34)),
+			textStyle.Width(13).Render(orDash(e.QoS.Reliability)),
+			textStyle.Width(17).Render(orDash(e.QoS.Durability)),
+			textStyle.Width(10).Render(announcedDuration(e.QoS.Deadline)),
+			textStyle.Width(28).Render(orDash(e.QoS.Liveliness)+", "+announcedDuration(e.QoS.Lease)),
+			textStyle.Width(16).Render(ownership),
+			textStyle.Render(orDash(strings.Join(e.QoS.Partitions, ", "))),
+		))
+	}
+	return lipgloss.NewStyle().
+		Border(lipgloss.RoundedBorder()).
+		BorderForeground(primaryColor).
+		Padding(1, 2).
+		Render(lipgloss.JoinVertical(lipgloss.Left, lines...))
+}
+
+// A duration endpoints announce, of which 0 is infinite
+func announcedDuration(d time.Duration) string {
+	if d == 0 {
+		return "∞"
+	}
+	return formatInterval(d)
+}
@@ -0,0 +1,206 @@
+package main
+
+import (
+	"context"
+	"crypto/rand"
+	"encoding/hex"
+	"log"
+	"sync/atomic"
+	"time"
+
+	"cardinal/dds"
+)
+
+// Spans buffered before new ones are dropped
+const spanQueueSize = 4096
+
+// Spans sent per export request
+const spanBatchSize = 512
+
+// How often buffered spans are exported
+const spanExportInterval = 2 * time.Second
+
+// Identifies a span within a trace; messages of the DDS layer carry one
+type spanContext = dds.SpanContext
+
+// A timed pipeline operation. All methods are safe on a nil span, which is
+// what startSpan returns while tracing is disabled.
+type Span struct {
+	name     string
+	ctx      spanContext
+	parentID [8]byte
+	start    time.Time
+	end      time.Time
+	attrs    []otlpKeyValue
+}
+
+// Active span queue; nil while tracing is disabled
+var spanQueue atomic.Pointer[chan *Span]
+
+// Number of spans dropped because the queue was full
+var droppedSpans atomic.Int64
+
+// Start a span, as a child of parent when it is valid
+func startSpan(name string, parent spanContext) *Span {
+	return startSpanAt(name, parent, time.Now())
+}
+
+// Start a span that began at start, for operations only worth a span once
+// they turn out to have done something
+func startSpanAt(name string, parent spanContext, start time.Time) *Span {
+	if spanQueue.Load() == nil {
+		return nil
+	}
+
+	s := &Span{name: name, start: start}
+	if parent.Valid() {
+		s.ctx.TraceID = parent.TraceID
+		s.parentID = parent.SpanID
+	} else {
+		rand.Read(s.ctx.TraceID[:])
+	}
+	rand.Read(s.ctx.SpanID[:])
+	return s
+}
+
+// Context of the span, for starting children
+func (s *Span) Context() spanContext {
+	if s == nil {
+		return spanContext{}
+	}
+	return s.ctx
+}
+
+// Attach an integer field to the span
+func (s *Span) SetInt(key string, value int64) {
+	if s == nil {
+		return
+	}
+	s.attrs = append(s.attrs, otlpInt(key, value))
+}
+
+// Attach a string field to the span
+func (s *Span) SetString(key, value string) {
+	if s == nil {
+		return
+	}
+	s.attrs = append(s.attrs, otlpString(key, value))
+}
+
+// Finish the span and queue it for export
+func (s *Span) End() {
+	if s == nil {
+		return
+	}
+	s.end = time.Now()
+	s.attrs = append(s.attrs, otlpInt("duration_us", s.end.Sub(s.start).Microseconds()))
+
+	queue := spanQueue.Load()
+	if queue == nil {
+		return
+	}
+	select {
+	case *queue <- s:
+	default:
+		droppedSpans.Add(1)
+	}
+}
+
+type otlpSpan struct {
+	TraceID           string         `json:"traceId"`
+	SpanID            string         `json:"spanId"`
+	ParentSpanID      string         `json:"parentSpanId,omitempty"`
+	Name              string         `json:"name"`
+	Kind              int            `json:"kind"`
+	StartTimeUnixNano string         `json:"startTimeUnixNano"`
+	EndTimeUnixNano   string         `json:"endTimeUnixNano"`
+	Attributes        []otlpKeyValue `json:"attributes,omitempty"`
+}
+
+type otlpScopeSpans struct {
+	Scope otlpScope  `json:"scope"`
+	Spans []otlpSpan `json:"spans"`
+}
+
+type otlpResourceSpans struct {
+	Resource   otlpResource     `json:"resource"`
+	ScopeSpans []otlpScopeSpans `json:"scopeSpans"`
+}
+
+type otlpTracesRequest struct {
+	ResourceSpans []otlpResourceSpans `json:"resourceSpans"`
+}
+
+// OTLP span kind for internal operations
+const otlpSpanKindInternal = 1
+
+func (s *Span) toOTLP() otlpSpan {
+	span := otlpSpan{
+		TraceID:           hex.EncodeToString(s.ctx.TraceID[:]),
+		SpanID:            hex.EncodeToString(s.ctx.SpanID[:]),
+		Name:              s.name,
+		Kind:              otlpSpanKindInternal,
+		StartTimeUnixNano: otlpUnixNano(s.start),
+		EndTimeUnixNano:   otlpUnixNano(s.end),
+		Attributes:        s.attrs,
+	}
+	if s.parentID != [8]byte{} {
+		span.ParentSpanID = hex.EncodeToString(s.parentID[:])
+	}
+	return span
+}
+
+// Enable tracing and export spans to the OTLP endpoint until ctx is done
+func startTracing(ctx context.Context, exporter *otlpExporter) {
+	queue := make(chan *Span, spanQueueSize)
+	spanQueue.Store(&queue)
+
+	go runTask(ctx, "trace-exporter", func(ctx context.Context) {
+		ticker := time.NewTicker(spanExportInterval)
+		defer ticker.Stop()
+
+		var batch []*Span
+		flush := func() {
+			if len(batch) == 0 {
+				return
+			}
+			if err := exporter.exportSpans(batch); err != nil {
+				log.Printf("Error exporting spans: %v", err)
+			}
+			batch = batch[:0]
+		}
+
+		for {
+			select {
+			case <-ctx.Done():
+				spanQueue.Store(nil)
+				flush()
+				return
+			case s := <-queue:
+				batch = append(batch, s)
+				if len(batch) >= spanBatchSize {
+					flush()
+				}
+			case <-ticker.C:
+				flush()
+			}
+		}
+	})
+}
+
+func (e *otlpExporter) exportSpans(spans []*Span) error {
+	otlpSpans := make([]otlpSpan, len(spans))
+	for i, s := range spans {
+		otlpSpans[i] = s.toOTLP()
+	}
+
+	return e.post("/v1/traces", otlpTracesRequest{
+		ResourceSpans: []otlpResourceSpans{{
+			Resource: newOTLPResource(),
+			ScopeSpans: []otlpScopeSpans{{
+				Scope: otlpScope{Name: otlpServiceName},
+				Spans: otlpSpans,
+			}},
+		}},
+	})
+}
@@ -0,0 +1,105 @@
+package main
+
+import (
+	"context"
+	"time"
+
+	"cardinal/core"
+)
+
+// Typed endpoints carry Go values instead of strings, encoded as plain CDR.
+// Types generated by `cardinal gen --idl` bring their own CDR methods; any
+// other struct is encoded by reflection (see core.MarshalCDR), so panes and
+// tools can publish their own types without writing IDL.
+
+// Implemented by types with their own CDR encoding, like generated ones
+type cdrMarshaler interface {
+	MarshalCDR() []byte
+}
+
+type cdrUnmarshaler interface {
+	UnmarshalCDR(data []byte) error
+}
+
+// Encode a value with its own CDR methods if it has them
+func encodeTyped[T any](v *T) ([]byte, error) {
+	if m, ok := any(v).(cdrMarshaler); ok {
+		return m.MarshalCDR(), nil
+	}
+	return core.MarshalCDR(v)
+}
+
+// Decode a payload with the value's own CDR methods if it has them
+func decodeTyped[T any](data []byte) (T, error) {
+	var v T
+	if u, ok := any(&v).(cdrUnmarshaler); ok {
+		return v, u.UnmarshalCDR(data)
+	}
+	return v, core.UnmarshalCDR(data, &v)
+}
+
+// Publishes values of type T on one topic
+type TypedPublisher[T any] struct {
+	topic string
+	pub   DDSPublisher
+}
+
+func NewTypedPublisher[T any](transport Transport, topic string) (*TypedPublisher[T], error) {
+	pub, err := transport.NewPublisher(topic)
+	if err != nil {
+		return nil, err
+	}
+	return &TypedPublisher[T]{topic: topic, pub: pub}, nil
+}
+
+func (p *TypedPublisher[T]) Publish(v T) error {
+	payload, err := encodeTyped(&v)
+	if err != nil {
+		return err
+	}
+	return p.pub.Publish(DDSMessage{Topic: p.topic, Content: string(payload), Timestamp: time.Now()})
+}
+
+func (p *TypedPublisher[T]) Cleanup() {
+	p.pub.Cleanup()
+}
+
+// A value received on a typed topic, with the message it came in. Err is set
+// instead of Value when the payload does not decode as T.
+type TypedMessage[T any] struct {
+	Value   T
+	Message DDSMessage
+	Err     error
+}
+
+// Receives values of type T from one topic
+type TypedSubscriber[T any] struct {
+	sub DDSSubscriber
+}
+
+func NewTypedSubscriber[T any](transport Transport, topic string) (*TypedSubscriber[T], error) {
+	sub, err := transport.NewSubscriber(topic)
+	if err != nil {
+		return nil, err
+	}
+	return &TypedSubscriber[T]{sub: sub}, nil
+}
+
+// Start receiving; like DDSSubscriber.Subscribe, call it once. The channel
+// closes when the subscriber is cleaned up.
+func (s *TypedSubscriber[T]) Subscribe() <-chan TypedMessage[T] {
+	messages := s.sub.Subscribe()
+	values := make(chan TypedMessage[T], cap(messages))
+	go runTask(context.Background(), "typed-receive", func(context.Context) {
+		defer close(values)
+		for msg := range messages {
+			v, err := decodeTyped[T]([]byte(msg.Content))
+			values <- TypedMessage[T]{Value: v, Message: msg, Err: err}
+		}
+	})
+	return values
+}
+
+func (s *TypedSubscriber[T]) Cleanup() {
+	s.sub.Cleanup()
+}
@@ -0,0 +1,334 @@
+package main
+
+import (
+	"crypto/hmac"
+	"crypto/sha256"
+	"encoding/hex"
+	"fmt"
+	"log"
+	"net/http"
+	"net/url"
+	"os"
+	"path"
+	"path/filepath"
+	"strconv"
+	"strings"
+	"sync/atomic"
+	"time"
+)
+
+// Robots with small disks can offload long recordings: with an [upload]
+// table every finished capture file, each chunk when recordings are chunked,
+// is uploaded in the background and optionally deleted afterwards. Failed
+// uploads are retried with exponential backoff; files that still fail stay
+// on disk.
+//
+//	[upload]
+//	url = "s3://fleet-captures/robot-3"  # or https://host/captures/
+//	region = "eu-central-1"
+//	retries = 5
+//	delete = true
+//
+// S3 uploads are signed with the AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY
+// and optional AWS_SESSION_TOKEN of the environment; endpoint points them at
+// an S3-compatible store such as MinIO. Other URLs receive a PUT of each
+// file under the URL, with token sent as a bearer token.
+
+// Files queued for upload before new ones are left on disk
+const uploadQueueSize = 256
+
+// Time allowed for one upload request, and for the queue to drain on exit
+const (
+	uploadTimeout      = 10 * time.Minute
+	uploadDrainTimeout = 30 * time.Second
+)
+
+// Longest wait between two attempts
+const maxUploadBackoff = time.Minute
+
+// Where finished captures go, from the [upload] table
+type uploadSpec struct {
+	url      *url.URL
+	region   string
+	endpoint string // S3-compatible endpoint, "" for AWS
+	token    string
+	retries  int
+	remove   bool
+}
+
+// Read the [upload] table, or nil when the config has none
+func (c Config) UploadSpec() (*uploadSpec, error) {
+	table, ok := c["upload"]
+	if !ok {
+		return nil, nil
+	}
+
+	spec := &uploadSpec{region: table["region"], endpoint: table["endpoint"], token: table["token"], retries: 5}
+	var err error
+	if spec.url, err = url.Parse(table["url"]); err != nil || spec.url.Host == "" {
+		return nil, fmt.Errorf("upload: needs a url like s3://bucket/prefix or https://host/path")
+	}
+	switch spec.url.Scheme {
+	case "s3":
+		if spec.region == "" {
+			return nil, fmt.Errorf("upload: s3 needs a region")
+		}
+		if endpoint, err := url.Parse(spec.endpoint); spec.endpoint != "" && (err != nil || endpoint.Host == "") {
+			return nil, fmt.Errorf("upload: invalid endpoint %q", spec.endpoint)
+		}
+	case "http", "https":
+	default:
+		return nil, fmt.Errorf("upload: unsupported scheme %s", spec.url.Scheme)
+	}
+	if value, ok := table["retries"]; ok {
+		if spec.retries, err = strconv.Atoi(value); err != nil || spec.retries < 0 {
+			return nil, fmt.Errorf("upload: retries must be a number of at least 0")
+		}
+	}
+	if value, ok := table["delete"]; ok {
+		if spec.remove, err = strconv.ParseBool(value); err != nil {
+			return nil, fmt.Errorf("upload: delete must be true or false")
+		}
+	}
+	return spec, nil
+}
+
+// Upload progress
+type UploadStatus struct {
+	Pending  int `json:"pending"`
+	Uploaded int `json:"uploaded"`
+	Failed   int `json:"failed"`
+}
+
+// Uploads finished captures in the background. A nil Uploader uploads
+// nothing.
+type Uploader struct {
+	spec   *uploadSpec
+	client *http.Client
+	queue  chan string
+	done   chan struct{}
+
+	pending  atomic.Int64
+	uploaded atomic.Int64
+	failed   atomic.Int64
+}
+
+// Start uploading to the spec's destination, or return nil without a spec
+func StartUploader(spec *uploadSpec) *Uploader {
+	if spec == nil {
+		return nil
+	}
+	u := &Uploader{
+		spec:   spec,
+		client: &http.Client{Timeout: uploadTimeout},
+		queue:  make(chan string, uploadQueueSize),
+		done:   make(chan struct{}),
+	}
+	go u.run()
+	return u
+}
+
+// Queue a finished capture file for upload
+func (u *Uploader) Enqueue(file string) {
+	if u == nil {
+		return
+	}
+	u.pending.Add(1)
+	select {
+	case u.queue <- file:
+	default:
+		u.pending.Add(-1)
+		u.failed.Add(1)
+		log.Printf("Upload queue full, leaving %s on disk", file)
+	}
+}
+
+func (u *Uploader) run() {
+	defer close(u.done)
+	for file := range u.queue {
+		u.uploadWithRetries(file)
+		u.pending.Add(-1)
+	}
+}
+
+func (u *Uploader) uploadWithRetries(file string) {
+	backoff := time.Second
+	for attempt := 0; ; attempt++ {
+		err := u.upload(file)
+		if err == nil {
+			u.uploaded.Add(1)
+			log.Printf("Uploaded %s", file)
+			if u.spec.remove {
+				if err := os.Remove(file); err != nil {
+					log.Printf("Error deleting uploaded %s: %v", file, err)
+				}
+			}
+			return
+		}
+		if attempt == u.spec.retries {
+			u.failed.Add(1)
+			log.Printf("Giving up uploading %s, leaving it on disk: %v", file, err)
+			return
+		}
+		log.Printf("Error uploading %s, retrying in %s: %v", file, backoff, err)
+		time.Sleep(backoff)
+		backoff = min(2*backoff, maxUploadBackoff)
+	}
+}
+
+// Upload a file in one PUT request
+func (u *Uploader) upload(file string) error {
+	f, err := os.Open(file)
+	if err != nil {
+		return err
+	}
+	defer f.Close()
+	info, err := f.Stat()
+	if err != nil {
+		return err
+	}
+
+	target := u.objectURL(filepath.Base(file))
+	req, err := http.NewRequest(http.MethodPut, target.String(), f)
+	if err != nil {
+		return err
+	}
+	req.ContentLength = info.Size()
+	req.Header.Set("Content-Type", "application/octet-stream")
+	if u.spec.url.Scheme == "s3" {
+		if err := signS3Request(req, u.spec.region, time.Now()); err != nil {
+			return err
+		}
+	} else if u.spec.token != "" {
+		req.Header.Set("Authorization", "Bearer "+u.spec.token)
+	}
+
+	resp, err := u.client.Do(req)
+	if err != nil {
+		return err
+	}
+	defer resp.Body.Close()
+	if resp.StatusCode/100 != 2 {
+		return fmt.Errorf("upload failed: %s", resp.Status)
+	}
+	return nil
+}
+
+// URL a file is uploaded to
+func (u *Uploader) objectURL(name string) *url.URL {
+	if u.spec.url.Scheme != "s3" {
+		target := *u.spec.url
+		target.Path = path.Join("/", target.Path, name)
+		return &target
+	}
+
+	bucket, key := u.spec.url.Host, path.Join(strings.TrimPrefix(u.spec.url.Path, "/"), name)
+	if u.spec.endpoint != "" {
+		// S3-compatible stores take the bucket in the path
+		target, _ := url.Parse(u.spec.endpoint)
+		target.Path = path.Join("/", target.Path, bucket, key)
+		return target
+	}
+	return &url.URL{Scheme: "https", Host: bucket + ".s3." + u.spec.region + ".amazonaws.com", Path: "/" + key}
+}
+
+// Wait for queued uploads to finish, at most uploadDrainTimeout
+func (u *Uploader) Close() {
+	if u == nil {
+		return
+	}
+	close(u.queue)
+	select {
+	case <-u.done:
+	case <-time.After(uploadDrainTimeout):
+		log.Printf("Stopped waiting for %d uploads, their files stay on disk", u.pending.Load())
+	}
+}
+
+func (u *Uploader) Status() *UploadStatus {
+	if u == nil {
+		return nil
+	}
+	return &UploadStatus{
+		Pending:  int(u.pending.Load()),
+		Uploaded: int(u.uploaded.Load()),
+		Failed:   int(u.failed.Load()),
+	}
+}
+
+// Sign a request with AWS Signature Version 4, leaving the payload unsigned
+// so files are streamed rather than hashed first
+func signS3Request(req *http.Request, region string, now time.Time) error {
+	accessKey, secretKey := os.Getenv("AWS_ACCESS_KEY_ID"), os.Getenv("AWS_SECRET_ACCESS_KEY")
+	if accessKey == "" || secretKey == "" {
+		return fmt.Errorf("s3 upload needs AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY")
+	}
+
+	amzDate := now.UTC().Format("20060102T150405Z")
+	date := amzDate[:8]
+	req.Header.Set("X-Amz-Date", amzDate)
+	req.Header.Set("X-Amz-Content-Sha256", "UNSIGNED-PAYLOAD")
+	if token := os.Getenv("AWS_SESSION_TOKEN"); token != "" {
+		req.Header.Set("X-Amz-Security-Token", token)
+	}
+
+	headers := []string{"host", "x-amz-content-sha256", "x-amz-date"}
+	if req.Header.Get("X-Amz-Security-Token") != "" {
+		headers = append(headers, "x-amz-security-token")
+	}
+	var canonicalHeaders strings.Builder
+	for _, name := range headers {
+		value := req.Header.Get(name)
+		if name == "host" {
+			value = req.URL.Host
+		}
+		canonicalHeaders.WriteString(name + ":" + strings.TrimSpace(value) + "\n")
+	}
+	signedHeaders := strings.Join(headers, ";")
+
+	// Send the path encoded exactly as signed
+	req.URL.RawPath = awsEscapePath(req.URL.Path)
+	canonicalRequest := strings.Join([]string{
+		req.Method,
+		req.URL.RawPath,
+		"", // no query
+		canonicalHeaders.String(),
+		signedHeaders,
+		"UNSIGNED-PAYLOAD",
+	}, "\n")
+	scope := date + "/" + region + "/s3/aws4_request"
+	hashed := sha256.Sum256([]byte(canonicalRequest))
+	stringToSign := "AWS4-HMAC-SHA256\n" + amzDate + "\n" + scope + "\n" + hex.EncodeToString(hashed[:])
+
+	key := []byte("AWS4" + secretKey)
+	for _, part := range []string{date, region, "s3", "aws4_request"} {
+		key = hmacSHA256(key, part)
+	}
+	signature := hex.EncodeToString(hmacSHA256(key, stringToSign))
+	req.Header.Set("Authorization", fmt.Sprintf("AWS4-HMAC-SHA256 Credential=%s/%s, SignedHeaders=%s, Signature=%s",
+		accessKey, scope, signedHeaders, signature))
+	return nil
+}
+
+func hmacSHA256(key []byte, data string) []byte {
+	mac := hmac.New(sha256.New, key)
+	mac.Write([]byte(data))
+	return mac.Sum(nil)
+}
+
+// Percent-encode a path as SigV4 expects: everything but unreserved
+// characters and the slashes between segments
+func awsEscapePath(p string) string {
+	var b strings.Builder
+	for i := 0; i < len(p); i++ {
+		c := p[i]
+		switch {
+		case 'A' <= c && c <= 'Z', 'a' <= c && c <= 'z', '0' <= c && c <= '9',
+			c == '-', c == '_', c == '.', c == '~', c == '/':
+			b.WriteByte(c)
+		default:
+			fmt.Fprintf(&b, "%%%02X", c)
+		}
+	}
+	return b.String()
+}
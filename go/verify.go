@@ -0,0 +1,188 @@
+package main
+
+import (
+	"crypto/sha256"
+	"fmt"
+	"io"
+	"os"
+	"time"
+)
+
+// Messages of one topic in a capture, reduced to what verification compares
+type topicTrace struct {
+	hashes  [][sha256.Size]byte
+	offsets []time.Duration // log time relative to the first message in the capture
+}
+
+// Read a capture into per-topic traces, opening encrypted payloads so
+// that re-sealed recordings of the same data compare equal
+func readTraces(path string, cipher *payloadCipher) (map[string]*topicTrace, error) {
+	capture, err := OpenCapture(path)
+	if err != nil {
+		return nil, err
+	}
+	defer capture.Close()
+
+	traces := map[string]*topicTrace{}
+	var start time.Time
+	for {
+		msg, err := capture.Next()
+		if err == io.EOF {
+			return traces, nil
+		}
+		if err != nil {
+			return nil, fmt.Errorf("%s: %w", path, err)
+		}
+		if start.IsZero() {
+			start = msg.ReceivedAt
+		}
+		if opened, err := cipher.Open(msg); err == nil {
+			msg = opened
+		}
+
+		trace, ok := traces[msg.Topic]
+		if !ok {
+			trace = &topicTrace{}
+			traces[msg.Topic] = trace
+		}
+		trace.hashes = append(trace.hashes, sha256.Sum256([]byte(msg.Content)))
+		trace.offsets = append(trace.offsets, msg.ReceivedAt.Sub(start))
+	}
+}
+
+// Differences found on one topic
+type topicDiff struct {
+	topic        string
+	expected     int
+	actual       int
+	payloadDiffs int
+	firstDiff    int // index of the first differing payload, -1 if none
+	timingDiffs  int
+	maxSkew      time.Duration
+}
+
+func (d topicDiff) ok() bool {
+	return d.expected == d.actual && d.payloadDiffs == 0 && d.timingDiffs == 0
+}
+
+// Compare two traces of the same topic message by message. Timing is only
+// checked when tolerance is positive.
+func compareTraces(topic string, expected, actual *topicTrace, tolerance time.Duration) topicDiff {
+	diff := topicDiff{topic: topic, firstDiff: -1}
+	if expected == nil {
+		expected = &topicTrace{}
+	}
+	if actual == nil {
+		actual = &topicTrace{}
+	}
+	diff.expected, diff.actual = len(expected.hashes), len(actual.hashes)
+
+	for i := 0; i < min(diff.expected, diff.actual); i++ {
+		if expected.hashes[i] != actual.hashes[i] {
+			diff.payloadDiffs++
+			if diff.firstDiff < 0 {
+				diff.firstDiff = i
+			}
+		}
+		if tolerance > 0 {
+			skew := actual.offsets[i] - expected.offsets[i]
+			if skew < 0 {
+				skew = -skew
+			}
+			diff.maxSkew = max(diff.maxSkew, skew)
+			if skew > tolerance {
+				diff.timingDiffs++
+			}
+		}
+	}
+	return diff
+}
+
+// Run `cardinal verify`: compare a capture against a golden one topic by
+// topic and return the exit code (1 when they differ)
+func runVerify(opts Options) int {
+	settings, err := loadSettings(opts)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal verify:", err)
+		return exitUsage
+	}
+
+	expected, err := readTraces(opts.Expected, settings.Cipher)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal verify:", err)
+		return exitUsage
+	}
+	actual, err := readTraces(opts.Actual, settings.Cipher)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal verify:", err)
+		return exitUsage
+	}
+
+	topics := map[string]bool{}
+	for topic := range expected {
+		topics[topic] = true
+	}
+	for topic := range actual {
+		topics[topic] = true
+	}
+
+	var diffs []topicDiff
+	failed := 0
+	for _, topic := range sortedKeys(topics) {
+		diff := compareTraces(topic, expected[topic], actual[topic], opts.TimeTolerance)
+		if !diff.ok() {
+			failed++
+		}
+		diffs = append(diffs, diff)
+	}
+
+	printResult(opts, verifyResult(diffs), func() {
+		fmt.Printf("%-32s %10s %10s  %s\n", "Topic", "Expected", "Actual", "Result")
+		for _, diff := range diffs {
+			result := "✅ match"
+			if !diff.ok() {
+				result = "❌"
+				if diff.expected != diff.actual {
+					result += fmt.Sprintf(" count differs by %+d;", diff.actual-diff.expected)
+				}
+				if diff.payloadDiffs > 0 {
+					result += fmt.Sprintf(" %d payloads differ (first at #%d);", diff.payloadDiffs, diff.firstDiff+1)
+				}
+				if diff.timingDiffs > 0 {
+					result += fmt.Sprintf(" %d messages outside ±%v (max skew %v);", diff.timingDiffs, opts.TimeTolerance, diff.maxSkew)
+				}
+				result = result[:len(result)-1]
+			}
+			fmt.Printf("%-32s %10d %10d  %s\n", diff.topic, diff.expected, diff.actual, result)
+		}
+		if failed > 0 {
+			fmt.Printf("\n%d of %d topics differ\n", failed, len(topics))
+		} else {
+			fmt.Printf("\nAll %d topics match\n", len(topics))
+		}
+	})
+
+	if failed > 0 {
+		return exitAssertFailed
+	}
+	return exitOK
+}
+
+// Per-topic differences as printed with --json
+func verifyResult(diffs []topicDiff) map[string]any {
+	topics := []map[string]any{}
+	passed := true
+	for _, diff := range diffs {
+		passed = passed && diff.ok()
+		topics = append(topics, map[string]any{
+			"topic":         diff.topic,
+			"passed":        diff.ok(),
+			"expected":      diff.expected,
+			"actual":        diff.actual,
+			"payload_diffs": diff.payloadDiffs,
+			"timing_diffs":  diff.timingDiffs,
+			"max_skew":      diff.maxSkew.String(),
+		})
+	}
+	return map[string]any{"passed": passed, "topics": topics}
+}
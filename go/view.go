@@ -0,0 +1,150 @@
+package main
+
+import (
+	"context"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+	"regexp"
+	"strconv"
+	"time"
+)
+
+// Numbered chunk of a recording, as written by chunkPath
+var chunkPattern = regexp.MustCompile(`^(.*)-(\d{4,})(\.[^./]*)?$`)
+
+// The chunk written after a chunk; false when path is not a chunk
+func nextChunk(path string) (string, bool) {
+	match := chunkPattern.FindStringSubmatch(path)
+	if match == nil {
+		return "", false
+	}
+	n, err := strconv.Atoi(match[2])
+	if err != nil {
+		return "", false
+	}
+	return chunkPath(match[1]+match[3], n+1), true
+}
+
+// Where to start following a recording: the path itself, or for the path of
+// a chunked recording its newest chunk
+func latestChunk(path string) string {
+	if _, err := os.Stat(path); err == nil {
+		return path
+	}
+	latest := path
+	for n := 1; ; n++ {
+		if _, err := os.Stat(chunkPath(path, n)); err != nil {
+			return latest
+		}
+		latest = chunkPath(path, n)
+	}
+}
+
+// Send the messages of a capture until its end and, when following, go on
+// with the chunks written after it
+func streamCapture(ctx context.Context, capture *CaptureReader, path string, follow bool, cipher *payloadCipher, send func(DDSMessage)) error {
+	for {
+		err := sendCapture(capture, cipher, send)
+		capture.Close()
+		if err != nil {
+			return fmt.Errorf("%s: %w", path, err)
+		}
+		next, ok := nextChunk(path)
+		if !follow || !ok {
+			return nil
+		}
+		path = next
+		if capture, err = FollowCapture(ctx, path); err != nil {
+			return fmt.Errorf("%s: %w", path, err)
+		}
+	}
+}
+
+func sendCapture(capture *CaptureReader, cipher *payloadCipher, send func(DDSMessage)) error {
+	for {
+		msg, err := capture.Next()
+		if err == io.EOF {
+			return nil
+		}
+		if err != nil {
+			return err
+		}
+		if opened, err := cipher.Open(msg); err == nil {
+			msg = opened
+		}
+		send(msg)
+	}
+}
+
+// Run `cardinal view`: show the messages of a capture in the TUI. With
+// --follow the capture may still be written by another process, a daemon
+// recording included; new messages appear as they are flushed, and chunked
+// recordings are followed from chunk to chunk. Returns the exit code.
+func runView(opts Options) int {
+	settings, err := loadSettings(opts)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal view:", err)
+		return exitUsage
+	}
+
+	ctx, cancel := context.WithCancel(context.Background())
+	defer cancel()
+
+	path := opts.Args[0]
+	label := "Capture " + filepath.Base(path)
+	var capture *CaptureReader
+	if opts.Follow {
+		path = latestChunk(path)
+		label = "Following " + filepath.Base(path)
+	} else if capture, err = OpenCapture(path); err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal view:", err)
+		return exitUsage
+	}
+
+	stats := NewStatsTracker()
+	bursts := NewBurstDetector()
+	program, store := newProgram(stats, time.Now(), programConfig{
+		sessionLabel: label,
+		bursts:       bursts,
+		formats:      settings.Formats,
+		times:        settings.Times,
+	})
+
+	streamErr := make(chan error, 1)
+	go runTask(ctx, "view-stream", func(ctx context.Context) {
+		var err error
+		if capture == nil {
+			capture, err = FollowCapture(ctx, path)
+		}
+		if err == nil {
+			err = streamCapture(ctx, capture, path, opts.Follow, settings.Cipher, func(msg DDSMessage) {
+				stats.Record(statsSample(msg))
+				bursts.Check(msg)
+				store.Append(msg)
+			})
+		}
+		// Quitting ends following, which is no error
+		if err != nil && ctx.Err() == nil {
+			streamErr <- err
+		}
+	})
+
+	_, err = program.Run()
+	cancel()
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "cardinal view:", err)
+		return exitFailure
+	}
+
+	// A damaged capture still shows the messages before the damage, and is
+	// reported once the TUI is closed
+	select {
+	case err := <-streamErr:
+		fmt.Fprintln(os.Stderr, "cardinal view:", err)
+		return exitFailure
+	default:
+	}
+	return exitOK
+}
@@ -0,0 +1,95 @@
+//go:build js && wasm
+
+// The capture viewer core for browsers: `just go-wasm` builds it into
+// cardinal.wasm, which defines one JavaScript function,
+//
+//	cardinalReadCapture(bytes: Uint8Array) -> {messages, topics, metadata}
+//
+// decoding an MCAP capture and computing its per-topic statistics with the
+// same package core the CLI uses. Failures throw an Error.
+package main
+
+import (
+	"bytes"
+	"io"
+	"syscall/js"
+	"time"
+
+	"cardinal/core"
+)
+
+// Window the rates of the topic statistics are computed over, ending at
+// the last message
+const rateWindow = 10 * time.Second
+
+func main() {
+	js.Global().Set("cardinalReadCapture", js.FuncOf(readCapture))
+	select {} // keep the function callable
+}
+
+func readCapture(_ js.Value, args []js.Value) (result any) {
+	defer func() {
+		if r := recover(); r != nil {
+			result = js.Global().Get("Error").New(r)
+		}
+	}()
+	if len(args) != 1 {
+		panic("cardinalReadCapture takes the bytes of a capture")
+	}
+	data := make([]byte, args[0].Get("length").Int())
+	js.CopyBytesToGo(data, args[0])
+
+	decoder, err := core.NewDecoder(bytes.NewReader(data))
+	if err != nil {
+		panic(err.Error())
+	}
+	stats := core.NewStatsTracker()
+	var messages []any
+	var last time.Time
+	for {
+		record, err := decoder.Next()
+		if err == io.EOF {
+			break
+		}
+		if err != nil {
+			panic(err.Error())
+		}
+		stats.Record(core.Sample{Topic: record.Topic, Bytes: len(record.Data), ReceivedAt: record.LogTime})
+		payload := js.Global().Get("Uint8Array").New(len(record.Data))
+		js.CopyBytesToJS(payload, record.Data)
+		messages = append(messages, map[string]any{
+			"topic":          record.Topic,
+			"payload":        payload,
+			"publishTimeNs":  record.PublishTime.UnixNano(),
+			"receivedTimeNs": record.LogTime.UnixNano(),
+		})
+		last = record.LogTime
+	}
+
+	var topics []any
+	for _, topic := range stats.Topics() {
+		window := topic.Window(last, rateWindow)
+		topics = append(topics, map[string]any{
+			"topic":          topic.Topic,
+			"messages":       topic.Total,
+			"bytes":          topic.TotalBytes,
+			"wireBytes":      topic.TotalWireBytes,
+			"gaps":           topic.TotalGaps,
+			"largestSample":  topic.LargestSample,
+			"meanPeriodSecs": topic.Intervals.Mean().Seconds(),
+			"jitterSecs":     topic.Intervals.StdDev().Seconds(),
+			"rate":           window.Rate,
+			"bandwidth":      window.Bandwidth,
+		})
+	}
+
+	metadata := map[string]any{}
+	for name, entries := range decoder.Metadata() {
+		values := map[string]any{}
+		for key, value := range entries {
+			values[key] = value
+		}
+		metadata[name] = values
+	}
+	return map[string]any{"messages": messages, "topics": topics, "metadata": metadata}
+}
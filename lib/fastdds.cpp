@@ -1,48 +1,98 @@
 #include "fastdds.h"
+#include <fastdds/config.hpp>
+#include <fastdds/dds/common/InstanceHandle.hpp>
 #include <fastdds/dds/domain/DomainParticipantFactory.hpp>
 #include <fastdds/dds/domain/DomainParticipant.hpp>
+#include <fastdds/dds/domain/DomainParticipantListener.hpp>
 #include <fastdds/dds/publisher/Publisher.hpp>
 #include <fastdds/dds/publisher/DataWriter.hpp>
+#include <fastdds/dds/publisher/DataWriterListener.hpp>
 #include <fastdds/dds/subscriber/Subscriber.hpp>
 #include <fastdds/dds/subscriber/DataReader.hpp>
+#include <fastdds/dds/subscriber/DataReaderListener.hpp>
 #include <fastdds/dds/topic/Topic.hpp>
 #include <fastdds/dds/topic/TypeSupport.hpp>
+#include <fastdds/dds/xtypes/dynamic_types/DynamicData.hpp>
+#include <fastdds/dds/xtypes/dynamic_types/DynamicDataFactory.hpp>
+#include <fastdds/dds/xtypes/dynamic_types/DynamicPubSubType.hpp>
+#include <fastdds/dds/xtypes/dynamic_types/DynamicTypeBuilderFactory.hpp>
+#include <fastdds/dds/xtypes/type_representation/ITypeObjectRegistry.hpp>
+#include <fastdds/dds/xtypes/utils.hpp>
 #include <fastdds/dds/subscriber/SampleInfo.hpp>
+#include <fastdds/rtps/common/InstanceHandle.hpp>
 #include <fastdds/rtps/common/SerializedPayload.hpp>
+#include <fastdds/rtps/flowcontrol/FlowControllerDescriptor.hpp>
+#include <fastdds/utils/md5.hpp>
+#include <chrono>
+#include <condition_variable>
+#include <cstdint>
+#include <sstream>
 #include <string>
 #include <iostream>
+#include <map>
 #include <memory>
+#include <mutex>
+#include <set>
+#include <vector>
 #include <cstring>
+#include <unistd.h>
 
 using namespace eprosima::fastdds::dds;
 using namespace eprosima::fastdds::rtps;
 
+// Older senders put whole seconds on the wire. Below this a timestamp is
+// taken to be in seconds: as nanoseconds it would lie in 1970.
+static const int64_t kSecondTimestampLimit = 100000000000LL;
+
+// Fast DDS physical data properties, which participants announce to tell
+// where they run
+static const char* kHostProperty = "fastdds.physical_data.host";
+static const char* kProcessProperty = "fastdds.physical_data.process";
+
+// Name of the host this process runs on
+static std::string local_hostname() {
+    char name[256] = {};
+    gethostname(name, sizeof(name) - 1);
+    return name;
+}
+
 // Simple message class for Fast DDS
 class SimpleMessageData {
 public:
     std::string message;
-    int64_t timestamp;
+    int64_t timestamp;        // ns since the Unix epoch
+    int64_t sender_clock = 0; // monotonic, optional on the wire
+    std::string key;          // instance key, on the wire of keyed topics only
+
+    // When set, the payload is serialized from this caller-owned buffer
+    // instead of message, saving the copy into a std::string
+    const char* borrowed = nullptr;
+    uint32_t borrowed_len = 0;
+
+    const char* payload_data() const { return borrowed ? borrowed : message.data(); }
+    uint32_t payload_size() const { return borrowed ? borrowed_len : static_cast<uint32_t>(message.length()); }
 
     SimpleMessageData() = default;
-    SimpleMessageData(const std::string& msg, int64_t ts) : message(msg), timestamp(ts) {}
+    SimpleMessageData(const std::string& msg, int64_t ts, int64_t clock) : message(msg), timestamp(ts), sender_clock(clock) {}
 };
 
-// Simplified TypeSupport for Fast DDS
+// Simplified TypeSupport for Fast DDS. The keyed variant is a type of its
+// own, which appends the instance key to the sample.
 class SimpleMessageTypeSupport : public TopicDataType {
 public:
-    SimpleMessageTypeSupport() {
-        set_name("SimpleMessage");
-        max_serialized_type_size = 300; // 256 + some overhead
-        is_compute_key_provided = false;
+    explicit SimpleMessageTypeSupport(bool keyed = false) : keyed(keyed) {
+        set_name(keyed ? "KeyedSimpleMessage" : "SimpleMessage");
+        max_serialized_type_size = 300; // initial buffer size; larger samples grow their buffers
+        is_compute_key_provided = keyed;
     }
 
     bool serialize(const void* data, SerializedPayload_t& payload, DataRepresentationId_t representation) override {
         const SimpleMessageData* msg_data = static_cast<const SimpleMessageData*>(data);
         
-        // Simple serialization: message length + message + timestamp
-        uint32_t msg_len = static_cast<uint32_t>(msg_data->message.length());
+        // Simple serialization: message length + message + timestamp + sender clock
+        uint32_t msg_len = msg_data->payload_size();
         
-        payload.reserve(sizeof(uint32_t) + msg_len + sizeof(int64_t));
+        payload.reserve(calculate_serialized_size(data, representation));
         payload.pos = 0;
         
         // Serialize message length
@@ -50,13 +100,26 @@ public:
         payload.pos += sizeof(uint32_t);
         
         // Serialize message
-        memcpy(payload.data + payload.pos, msg_data->message.c_str(), msg_len);
+        memcpy(payload.data + payload.pos, msg_data->payload_data(), msg_len);
         payload.pos += msg_len;
         
         // Serialize timestamp
         memcpy(payload.data + payload.pos, &msg_data->timestamp, sizeof(int64_t));
         payload.pos += sizeof(int64_t);
         
+        // Serialize sender clock
+        memcpy(payload.data + payload.pos, &msg_data->sender_clock, sizeof(int64_t));
+        payload.pos += sizeof(int64_t);
+        
+        // Serialize instance key: length + key
+        if (keyed) {
+            uint32_t key_len = static_cast<uint32_t>(msg_data->key.size());
+            memcpy(payload.data + payload.pos, &key_len, sizeof(uint32_t));
+            payload.pos += sizeof(uint32_t);
+            memcpy(payload.data + payload.pos, msg_data->key.data(), key_len);
+            payload.pos += key_len;
+        }
+        
         payload.length = payload.pos;
         return true;
     }
@@ -67,27 +130,60 @@ public:
         payload.pos = 0;
         
         // Deserialize message length
+        if (payload.length < sizeof(uint32_t)) {
+            return false;
+        }
         uint32_t msg_len;
         memcpy(&msg_len, payload.data + payload.pos, sizeof(uint32_t));
         payload.pos += sizeof(uint32_t);
         
-        // Deserialize message
-        char buffer[256];
-        memcpy(buffer, payload.data + payload.pos, std::min(msg_len, 255u));
-        buffer[std::min(msg_len, 255u)] = '\0';
-        msg_data->message = std::string(buffer);
+        // Deserialize message, whatever its size and content
+        if (payload.length - payload.pos < static_cast<uint64_t>(msg_len) + sizeof(int64_t)) {
+            return false;
+        }
+        msg_data->message.assign(reinterpret_cast<const char*>(payload.data + payload.pos), msg_len);
         payload.pos += msg_len;
         
         // Deserialize timestamp
         memcpy(&msg_data->timestamp, payload.data + payload.pos, sizeof(int64_t));
         payload.pos += sizeof(int64_t);
+        if (msg_data->timestamp > 0 && msg_data->timestamp < kSecondTimestampLimit) {
+            msg_data->timestamp *= 1000000000LL; // older senders send whole seconds
+        }
+        
+        // Deserialize sender clock; older senders end before it
+        msg_data->sender_clock = 0;
+        if (payload.length >= payload.pos + sizeof(int64_t)) {
+            memcpy(&msg_data->sender_clock, payload.data + payload.pos, sizeof(int64_t));
+            payload.pos += sizeof(int64_t);
+        }
+        
+        // Deserialize instance key
+        msg_data->key.clear();
+        if (keyed) {
+            uint32_t key_len;
+            if (payload.length < payload.pos + sizeof(uint32_t)) {
+                return false;
+            }
+            memcpy(&key_len, payload.data + payload.pos, sizeof(uint32_t));
+            payload.pos += sizeof(uint32_t);
+            if (payload.length - payload.pos < key_len) {
+                return false;
+            }
+            msg_data->key.assign(reinterpret_cast<const char*>(payload.data + payload.pos), key_len);
+            payload.pos += key_len;
+        }
         
         return true;
     }
 
     uint32_t calculate_serialized_size(const void* data, DataRepresentationId_t representation) override {
         const SimpleMessageData* msg_data = static_cast<const SimpleMessageData*>(data);
-        return sizeof(uint32_t) + msg_data->message.length() + sizeof(int64_t);
+        uint32_t size = sizeof(uint32_t) + msg_data->payload_size() + 2 * sizeof(int64_t);
+        if (keyed) {
+            size += sizeof(uint32_t) + static_cast<uint32_t>(msg_data->key.size());
+        }
+        return size;
     }
 
     void* create_data() override {
@@ -99,21 +195,350 @@ public:
     }
 
     bool compute_key(SerializedPayload_t& payload, InstanceHandle_t& handle, bool force_md5) override {
-        return true;
+        if (!keyed) {
+            return true;
+        }
+        SimpleMessageData msg_data;
+        return deserialize(payload, &msg_data) && compute_key(&msg_data, handle, force_md5);
     }
 
+    // The instance of a sample is the MD5 of its key
     bool compute_key(const void* data, InstanceHandle_t& handle, bool force_md5) override {
+        if (!keyed) {
+            return true;
+        }
+        const std::string& key = static_cast<const SimpleMessageData*>(data)->key;
+        eprosima::fastdds::MD5 md5;
+        md5.init();
+        md5.update(key.data(), static_cast<unsigned int>(key.size()));
+        md5.finalize();
+        for (size_t i = 0; i < 16; ++i) {
+            handle.value[i] = md5.digest[i];
+        }
         return true;
     }
+
+private:
+    bool keyed;
 };
 
 // Publisher wrapper
+// Name of a QoS policy an endpoint may be incompatible in, as Cardinal's QoS
+// settings call it
+static const char* qos_policy_name(QosPolicyId_t policy) {
+    switch (policy) {
+    case DURABILITY_QOS_POLICY_ID:
+        return "durability";
+    case PRESENTATION_QOS_POLICY_ID:
+        return "presentation";
+    case DEADLINE_QOS_POLICY_ID:
+        return "deadline";
+    case LATENCYBUDGET_QOS_POLICY_ID:
+        return "latency_budget";
+    case OWNERSHIP_QOS_POLICY_ID:
+        return "ownership";
+    case LIVELINESS_QOS_POLICY_ID:
+        return "liveliness";
+    case RELIABILITY_QOS_POLICY_ID:
+        return "reliability";
+    case DESTINATIONORDER_QOS_POLICY_ID:
+        return "destination_order";
+    case DATAREPRESENTATION_QOS_POLICY_ID:
+        return "data_representation";
+    case TYPECONSISTENCYENFORCEMENT_QOS_POLICY_ID:
+        return "type_consistency";
+    default:
+        return "unknown";
+    }
+}
+
+// Writer listener passing events to the callbacks set on the publisher
+class WriterListener : public DataWriterListener {
+public:
+    void on_offered_incompatible_qos(DataWriter*, const OfferedIncompatibleQosStatus& status) override {
+        std::lock_guard<std::mutex> lock(callbacks_mutex);
+        if (callbacks.incompatible_qos) {
+            callbacks.incompatible_qos(callbacks.context, qos_policy_name(status.last_policy_id));
+        }
+    }
+
+    // Held while a callback runs, so removing them waits for running ones
+    std::mutex callbacks_mutex;
+    SimpleWriterCallbacks callbacks{};
+};
+
 struct SimplePublisherWrapper {
     DomainParticipant* participant;
     Publisher* publisher;
     Topic* topic;
     DataWriter* writer;
     TypeSupport type_support;
+    bool keyed = false;
+    WriterListener listener;
+};
+
+// Keeps the liveliness of the writers a reader matched, as Fast DDS reports
+// its changes, and passes data and match events on to the callbacks
+class ReaderListener : public DataReaderListener {
+public:
+    void on_data_available(DataReader*) override {
+        std::lock_guard<std::mutex> lock(callbacks_mutex);
+        if (callbacks.data_available) {
+            callbacks.data_available(callbacks.context);
+        }
+    }
+
+    void on_subscription_matched(DataReader*, const SubscriptionMatchedStatus& status) override {
+        if (status.current_count_change == 0) {
+            return;
+        }
+        GUID_t writer;
+        iHandle2GUID(writer, status.last_publication_handle);
+        unsigned char guid[16];
+        memcpy(guid, writer.guidPrefix.value, 12);
+        memcpy(guid + 12, writer.entityId.value, 4);
+
+        std::lock_guard<std::mutex> lock(callbacks_mutex);
+        if (callbacks.writer_matched) {
+            callbacks.writer_matched(callbacks.context, guid, status.current_count_change > 0 ? 1 : 0);
+        }
+    }
+
+    void on_requested_incompatible_qos(DataReader*, const RequestedIncompatibleQosStatus& status) override {
+        std::lock_guard<std::mutex> lock(callbacks_mutex);
+        if (callbacks.incompatible_qos) {
+            callbacks.incompatible_qos(callbacks.context, qos_policy_name(status.last_policy_id));
+        }
+    }
+
+    void on_liveliness_changed(DataReader*, const LivelinessChangedStatus& status) override {
+        GUID_t writer;
+        iHandle2GUID(writer, status.last_publication_handle);
+
+        std::lock_guard<std::mutex> lock(mutex);
+        if (status.alive_count_change > 0) {
+            writers[writer] = true;
+        } else if (status.not_alive_count_change > 0) {
+            writers[writer] = false;
+        } else {
+            writers.erase(writer); // unmatched
+        }
+    }
+
+    std::mutex mutex;
+    std::map<GUID_t, bool> writers;
+
+    // Held while a callback runs, so removing them waits for running ones
+    std::mutex callbacks_mutex;
+    SimpleReaderCallbacks callbacks{};
+};
+
+// Waits for a writer of a topic to be discovered, keeping the type it
+// announced
+class TypeDiscoveryListener : public DomainParticipantListener {
+public:
+    explicit TypeDiscoveryListener(std::string topic) : topic_name(std::move(topic)) {}
+
+    void on_data_writer_discovery(DomainParticipant*, WriterDiscoveryStatus reason,
+                                  const PublicationBuiltinTopicData& info, bool& should_be_ignored) override {
+        should_be_ignored = false;
+        if (reason != WriterDiscoveryStatus::DISCOVERED_WRITER || info.topic_name.to_string() != topic_name ||
+            !info.type_information.assigned()) {
+            return;
+        }
+
+        std::lock_guard<std::mutex> lock(mutex);
+        if (!found) {
+            type_name = info.type_name.to_string();
+            type_information = info.type_information.type_information;
+            found = true;
+            discovered.notify_all();
+        }
+    }
+
+    // Wait up to timeout_ms for the type; false when none was announced
+    bool wait(int timeout_ms) {
+        std::unique_lock<std::mutex> lock(mutex);
+        return discovered.wait_for(lock, std::chrono::milliseconds(timeout_ms), [this] { return found; });
+    }
+
+    const std::string topic_name;
+    std::mutex mutex;
+    std::condition_variable discovered;
+    bool found = false;
+    std::string type_name;
+    xtypes::TypeInformation type_information;
+};
+
+// Copy a GUID into its C form, prefix then entity id
+static void copy_guid(unsigned char out[16], const GUID_t& guid) {
+    memcpy(out, guid.guidPrefix.value, 12);
+    memcpy(out + 12, guid.entityId.value, 4);
+}
+
+// Reports the participants and endpoints discovery finds and loses to the
+// callbacks, leaving out those of this process
+class DiscoveryWatchListener : public DomainParticipantListener {
+public:
+    void on_participant_discovery(DomainParticipant*, ParticipantDiscoveryStatus reason,
+                                  const ParticipantBuiltinTopicData& info, bool& should_be_ignored) override {
+        should_be_ignored = false;
+        int discovered;
+        if (reason == ParticipantDiscoveryStatus::DISCOVERED_PARTICIPANT) {
+            discovered = 1;
+        } else if (reason == ParticipantDiscoveryStatus::REMOVED_PARTICIPANT ||
+                   reason == ParticipantDiscoveryStatus::DROPPED_PARTICIPANT) {
+            discovered = 0;
+        } else {
+            return;
+        }
+
+        std::string hostname, process;
+        for (const auto& property : info.properties) {
+            if (property.first() == kHostProperty) {
+                hostname = property.second();
+            } else if (property.first() == kProcessProperty) {
+                process = property.second();
+            }
+        }
+        std::string name = info.participant_name.to_string();
+
+        SimpleParticipantInfo participant{};
+        copy_guid(participant.guid, info.guid);
+        participant.name = name.c_str();
+        participant.hostname = hostname.c_str();
+        participant.process = process.c_str();
+        participant.discovered = discovered;
+
+        std::lock_guard<std::mutex> lock(mutex);
+        if (hostname == local_host && process == local_process) {
+            // One of this process's own, whose endpoints are left out too
+            if (discovered) {
+                own.insert(info.guid.guidPrefix);
+            } else {
+                own.erase(info.guid.guidPrefix);
+            }
+            return;
+        }
+        if (callbacks.participant) {
+            callbacks.participant(callbacks.context, &participant);
+        }
+    }
+
+    void on_data_writer_discovery(DomainParticipant*, WriterDiscoveryStatus reason,
+                                  const PublicationBuiltinTopicData& info, bool& should_be_ignored) override {
+        should_be_ignored = false;
+        int discovered;
+        if (reason == WriterDiscoveryStatus::DISCOVERED_WRITER ||
+            reason == WriterDiscoveryStatus::CHANGED_QOS_WRITER) {
+            discovered = 1;
+        } else if (reason == WriterDiscoveryStatus::REMOVED_WRITER) {
+            discovered = 0;
+        } else {
+            return;
+        }
+        SimpleEndpointInfo endpoint{};
+        endpoint.writer = 1;
+        endpoint.ownership_strength = static_cast<int>(info.ownership_strength.value);
+        report_endpoint(endpoint, info, discovered);
+    }
+
+    void on_data_reader_discovery(DomainParticipant*, ReaderDiscoveryStatus reason,
+                                  const SubscriptionBuiltinTopicData& info, bool& should_be_ignored) override {
+        should_be_ignored = false;
+        int discovered;
+        if (reason == ReaderDiscoveryStatus::DISCOVERED_READER ||
+            reason == ReaderDiscoveryStatus::CHANGED_QOS_READER) {
+            discovered = 1;
+        } else if (reason == ReaderDiscoveryStatus::REMOVED_READER) {
+            discovered = 0;
+        } else {
+            return;
+        }
+        SimpleEndpointInfo endpoint{};
+        report_endpoint(endpoint, info, discovered);
+    }
+
+    const std::string local_host = local_hostname();
+    const std::string local_process = std::to_string(getpid());
+
+    // Held while a callback runs, so removing them waits for running ones
+    std::mutex mutex;
+    SimpleDiscoveryCallbacks callbacks{};
+    std::set<GuidPrefix_t> own; // prefixes of this process's participants
+
+private:
+    // Fill in what writers and readers announce alike, from their
+    // PublicationBuiltinTopicData or SubscriptionBuiltinTopicData, and report
+    // the endpoint
+    template <typename BuiltinTopicData>
+    void report_endpoint(SimpleEndpointInfo& endpoint, const BuiltinTopicData& info, int discovered) {
+        std::string topic = info.topic_name.to_string();
+        std::string type = info.type_name.to_string();
+        std::vector<std::string> names = info.partition.names();
+        std::vector<const char*> partitions;
+        for (const auto& name : names) {
+            partitions.push_back(name.c_str());
+        }
+
+        copy_guid(endpoint.guid, info.guid);
+        copy_guid(endpoint.participant_guid, info.participant_guid);
+        endpoint.topic_name = topic.c_str();
+        endpoint.type_name = type.c_str();
+        endpoint.discovered = discovered;
+        // Entity kinds 0x02 and 0x07 are the writers and readers with a key
+        endpoint.keyed = info.guid.entityId.value[3] == 0x02 || info.guid.entityId.value[3] == 0x07;
+        endpoint.reliability = info.reliability.kind == RELIABLE_RELIABILITY_QOS ? SIMPLE_RELIABILITY_RELIABLE
+                                                                                 : SIMPLE_RELIABILITY_BEST_EFFORT;
+        switch (info.durability.kind) {
+        case TRANSIENT_LOCAL_DURABILITY_QOS:
+            endpoint.durability = SIMPLE_DURABILITY_TRANSIENT_LOCAL;
+            break;
+        case TRANSIENT_DURABILITY_QOS:
+            endpoint.durability = SIMPLE_DURABILITY_TRANSIENT;
+            break;
+        case PERSISTENT_DURABILITY_QOS:
+            endpoint.durability = SIMPLE_DURABILITY_PERSISTENT;
+            break;
+        default:
+            endpoint.durability = SIMPLE_DURABILITY_VOLATILE;
+        }
+        endpoint.deadline_period_ns = announced_nanoseconds(info.deadline.period);
+        endpoint.latency_budget_ns = announced_nanoseconds(info.latency_budget.duration);
+        switch (info.liveliness.kind) {
+        case MANUAL_BY_PARTICIPANT_LIVELINESS_QOS:
+            endpoint.liveliness.kind = SIMPLE_LIVELINESS_MANUAL_BY_PARTICIPANT;
+            break;
+        case MANUAL_BY_TOPIC_LIVELINESS_QOS:
+            endpoint.liveliness.kind = SIMPLE_LIVELINESS_MANUAL_BY_TOPIC;
+            break;
+        default:
+            endpoint.liveliness.kind = SIMPLE_LIVELINESS_AUTOMATIC;
+        }
+        endpoint.liveliness.lease_duration_ns = announced_nanoseconds(info.liveliness.lease_duration);
+        endpoint.exclusive = info.ownership.kind == EXCLUSIVE_OWNERSHIP_QOS;
+        endpoint.partitions = partitions.data();
+        endpoint.partition_count = static_cast<int>(partitions.size());
+
+        std::lock_guard<std::mutex> lock(mutex);
+        if (own.count(info.guid.guidPrefix) == 0 && callbacks.endpoint) {
+            callbacks.endpoint(callbacks.context, &endpoint);
+        }
+    }
+
+    // An announced duration in nanoseconds, 0 when infinite
+    static long long announced_nanoseconds(const Duration_t& duration) {
+        if (duration == c_TimeInfinite) {
+            return 0;
+        }
+        return static_cast<long long>(duration.seconds) * 1000000000LL + duration.nanosec;
+    }
+};
+
+// Discovery watcher wrapper
+struct SimpleDiscoveryWatcherWrapper {
+    DomainParticipant* participant = nullptr;
+    DiscoveryWatchListener listener;
 };
 
 // Subscriber wrapper
@@ -123,15 +548,127 @@ struct SimpleSubscriberWrapper {
     Topic* topic;
     DataReader* reader;
     TypeSupport type_support;
+    ReaderListener listener;
+    SimpleMessageData last; // sample last received, which SimpleMessage::data points into
+    bool keyed = false;
+    std::map<InstanceHandle_t, std::string> instance_keys; // of keyed samples, for those without data
+    std::unique_ptr<TypeDiscoveryListener> discovery; // of dynamic subscribers, outliving their participant
+    DynamicType::_ref_type dynamic_type;              // discovered type of dynamic subscribers
 };
 
 extern "C" {
 
+// Conversions between nanoseconds and DDS durations
+static Duration_t to_duration(long long ns) {
+    return Duration_t(static_cast<int32_t>(ns / 1000000000LL), static_cast<uint32_t>(ns % 1000000000LL));
+}
+
+static long long to_nanoseconds(const Duration_t& duration) {
+    return static_cast<long long>(duration.seconds) * 1000000000LL + duration.nanosec;
+}
+
+// Set a reliability policy from SIMPLE_RELIABILITY_*, keeping the default
+// for SIMPLE_RELIABILITY_DEFAULT
+static void apply_reliability(ReliabilityQosPolicy& policy, int reliability) {
+    if (reliability == SIMPLE_RELIABILITY_BEST_EFFORT) {
+        policy.kind = BEST_EFFORT_RELIABILITY_QOS;
+    } else if (reliability == SIMPLE_RELIABILITY_RELIABLE) {
+        policy.kind = RELIABLE_RELIABILITY_QOS;
+    }
+}
+
+// Set a durability policy from SIMPLE_DURABILITY_*, keeping the default for
+// SIMPLE_DURABILITY_DEFAULT
+static void apply_durability(DurabilityQosPolicy& policy, int durability) {
+    if (durability == SIMPLE_DURABILITY_VOLATILE) {
+        policy.kind = VOLATILE_DURABILITY_QOS;
+    } else if (durability == SIMPLE_DURABILITY_TRANSIENT_LOCAL) {
+        policy.kind = TRANSIENT_LOCAL_DURABILITY_QOS;
+    }
+}
+
+// Set the history and resource limits policies. Topics are keyless, so the
+// one instance may hold all of max_samples.
+static void apply_history(HistoryQosPolicy& history, ResourceLimitsQosPolicy& limits, const SimpleHistoryQos& qos) {
+    if (qos.kind == SIMPLE_HISTORY_KEEP_LAST) {
+        history.kind = KEEP_LAST_HISTORY_QOS;
+    } else if (qos.kind == SIMPLE_HISTORY_KEEP_ALL) {
+        history.kind = KEEP_ALL_HISTORY_QOS;
+    }
+    if (qos.depth > 0) {
+        history.depth = qos.depth;
+    }
+    if (qos.max_samples > 0) {
+        limits.max_samples = qos.max_samples;
+        limits.max_samples_per_instance = qos.max_samples;
+        limits.allocated_samples = std::min<int32_t>(limits.allocated_samples, qos.max_samples);
+    }
+}
+
+// Set a liveliness policy, keeping the defaults for zero fields
+static void apply_liveliness(LivelinessQosPolicy& policy, const SimpleLivelinessQos& qos) {
+    if (qos.kind == SIMPLE_LIVELINESS_AUTOMATIC) {
+        policy.kind = AUTOMATIC_LIVELINESS_QOS;
+    } else if (qos.kind == SIMPLE_LIVELINESS_MANUAL_BY_PARTICIPANT) {
+        policy.kind = MANUAL_BY_PARTICIPANT_LIVELINESS_QOS;
+    } else if (qos.kind == SIMPLE_LIVELINESS_MANUAL_BY_TOPIC) {
+        policy.kind = MANUAL_BY_TOPIC_LIVELINESS_QOS;
+    }
+    if (qos.lease_duration_ns > 0) {
+        policy.lease_duration = to_duration(qos.lease_duration_ns);
+        // Writers announce themselves well within the lease
+        policy.announcement_period = to_duration(qos.lease_duration_ns / 3);
+    }
+}
+
+// Add partition names to a partition policy; none keeps the default
+// partition
+static void apply_partitions(PartitionQosPolicy& policy, const char* const* partitions, int count) {
+    for (int i = 0; partitions && i < count; i++) {
+        policy.push_back(partitions[i]);
+    }
+}
+
+// Participant QoS announcing the host and process the participant runs in
+static DomainParticipantQos announced_participant_qos() {
+    DomainParticipantQos qos = PARTICIPANT_QOS_DEFAULT;
+    qos.properties().properties().emplace_back(kHostProperty, local_hostname(), true);
+    qos.properties().properties().emplace_back(kProcessProperty, std::to_string(getpid()), true);
+    return qos;
+}
+
+// Name of the flow controller of a publisher's participant
+static const char* kFlowControllerName = "cardinal_flow";
+
+const char* simple_fastdds_version(void) {
+    return FASTDDS_VERSION_STR;
+}
+
 SimpleDDSPublisher create_simple_publisher(const char* topic_name) {
+    return create_simple_publisher_qos(0, topic_name, nullptr);
+}
+
+SimpleDDSPublisher create_simple_publisher_qos(int domain_id, const char* topic_name, const SimpleWriterQos* qos) {
+    SimpleWriterQos defaults = {};
+    if (!qos) {
+        qos = &defaults;
+    }
+
     try {
+        // Flow controllers belong to the participant; each publisher has its own
+        DomainParticipantQos participant_qos = announced_participant_qos();
+        if (qos->flow_max_bytes > 0) {
+            auto flow = std::make_shared<FlowControllerDescriptor>();
+            flow->name = kFlowControllerName;
+            flow->scheduler = FlowControllerSchedulerPolicy::FIFO;
+            flow->max_bytes_per_period = static_cast<int32_t>(std::min<long long>(qos->flow_max_bytes, INT32_MAX));
+            flow->period_ms = qos->flow_period_ms > 0 ? qos->flow_period_ms : 100;
+            participant_qos.flow_controllers().push_back(flow);
+        }
+
         // Create participant
         DomainParticipant* participant = DomainParticipantFactory::get_instance()->create_participant(
-            0, PARTICIPANT_QOS_DEFAULT);
+            domain_id, participant_qos);
         if (!participant) {
             std::cerr << "Failed to create participant" << std::endl;
             return nullptr;
@@ -140,7 +677,8 @@ SimpleDDSPublisher create_simple_publisher(const char* topic_name) {
         // Create wrapper
         SimplePublisherWrapper* wrapper = new SimplePublisherWrapper();
         wrapper->participant = participant;
-        wrapper->type_support = TypeSupport(new SimpleMessageTypeSupport());
+        wrapper->type_support = TypeSupport(new SimpleMessageTypeSupport(qos->keyed != 0));
+        wrapper->keyed = qos->keyed != 0;
 
         // Register type
         if (wrapper->type_support.register_type(participant) != RETCODE_OK) {
@@ -159,15 +697,43 @@ SimpleDDSPublisher create_simple_publisher(const char* topic_name) {
         }
 
         // Create publisher
-        wrapper->publisher = participant->create_publisher(PUBLISHER_QOS_DEFAULT);
+        PublisherQos publisher_qos = PUBLISHER_QOS_DEFAULT;
+        apply_partitions(publisher_qos.partition(), qos->partitions, qos->partition_count);
+        wrapper->publisher = participant->create_publisher(publisher_qos);
         if (!wrapper->publisher) {
             std::cerr << "Failed to create publisher" << std::endl;
             delete wrapper;
             return nullptr;
         }
 
-        // Create writer
-        wrapper->writer = wrapper->publisher->create_datawriter(wrapper->topic, DATAWRITER_QOS_DEFAULT);
+        // Create writer; flow controllers only apply to asynchronous writers
+        DataWriterQos writer_qos = DATAWRITER_QOS_DEFAULT;
+        if (qos->publish_mode == SIMPLE_PUBLISH_SYNC) {
+            writer_qos.publish_mode().kind = SYNCHRONOUS_PUBLISH_MODE;
+        } else if (qos->publish_mode == SIMPLE_PUBLISH_ASYNC || qos->flow_max_bytes > 0) {
+            writer_qos.publish_mode().kind = ASYNCHRONOUS_PUBLISH_MODE;
+        }
+        if (qos->flow_max_bytes > 0) {
+            writer_qos.publish_mode().flow_controller_name = kFlowControllerName;
+        }
+        if (qos->heartbeat_period_ns > 0) {
+            writer_qos.reliable_writer_qos().times.heartbeat_period = to_duration(qos->heartbeat_period_ns);
+        }
+        if (qos->nack_response_delay_ns > 0) {
+            writer_qos.reliable_writer_qos().times.nack_response_delay = to_duration(qos->nack_response_delay_ns);
+        }
+        if (qos->latency_budget_ns > 0) {
+            writer_qos.latency_budget().duration = to_duration(qos->latency_budget_ns);
+        }
+        apply_reliability(writer_qos.reliability(), qos->reliability);
+        apply_durability(writer_qos.durability(), qos->durability);
+        apply_history(writer_qos.history(), writer_qos.resource_limits(), qos->history);
+        if (qos->deadline_period_ns > 0) {
+            writer_qos.deadline().period = to_duration(qos->deadline_period_ns);
+        }
+        apply_liveliness(writer_qos.liveliness(), qos->liveliness);
+        wrapper->writer = wrapper->publisher->create_datawriter(wrapper->topic, writer_qos, &wrapper->listener,
+                                                                StatusMask::offered_incompatible_qos());
         if (!wrapper->writer) {
             std::cerr << "Failed to create writer" << std::endl;
             delete wrapper;
@@ -181,14 +747,15 @@ SimpleDDSPublisher create_simple_publisher(const char* topic_name) {
     }
 }
 
-int publish_simple_message(SimpleDDSPublisher pub, const char* message, long timestamp) {
+int publish_simple_message(SimpleDDSPublisher pub, const char* data, int length, long long timestamp,
+                           long long sender_clock) {
     SimplePublisherWrapper* wrapper = static_cast<SimplePublisherWrapper*>(pub);
-    if (!wrapper || !wrapper->writer) {
+    if (!wrapper || !wrapper->writer || length < 0) {
         return -1;
     }
 
     try {
-        SimpleMessageData msg_data(std::string(message), timestamp);
+        SimpleMessageData msg_data(std::string(data, length), timestamp, sender_clock);
         return wrapper->writer->write(&msg_data) == RETCODE_OK ? 0 : -1;
     } catch (const std::exception& e) {
         std::cerr << "Exception in publish_simple_message: " << e.what() << std::endl;
@@ -196,6 +763,108 @@ int publish_simple_message(SimpleDDSPublisher pub, const char* message, long tim
     }
 }
 
+// Fast DDS loans samples only for plain types, and SimpleMessage carries a
+// variable-length payload, so large payloads are instead serialized straight
+// from the caller's buffer: one copy into the writer's history, where
+// publish_simple_message makes three.
+int publish_simple_borrowed(SimpleDDSPublisher pub, const char* data, int length, long long timestamp,
+                            long long sender_clock) {
+    SimplePublisherWrapper* wrapper = static_cast<SimplePublisherWrapper*>(pub);
+    if (!wrapper || !wrapper->writer || length < 0) {
+        return -1;
+    }
+
+    try {
+        SimpleMessageData msg_data;
+        msg_data.borrowed = data;
+        msg_data.borrowed_len = static_cast<uint32_t>(length);
+        msg_data.timestamp = timestamp;
+        msg_data.sender_clock = sender_clock;
+        return wrapper->writer->write(&msg_data) == RETCODE_OK ? 0 : -1;
+    } catch (const std::exception& e) {
+        std::cerr << "Exception in publish_simple_borrowed: " << e.what() << std::endl;
+        return -1;
+    }
+}
+
+int publish_simple_batch(SimpleDDSPublisher pub, const char* const* messages, const int* lengths,
+                         const long long* timestamps, const long long* sender_clocks, int count) {
+    SimplePublisherWrapper* wrapper = static_cast<SimplePublisherWrapper*>(pub);
+    if (!wrapper || !wrapper->writer) {
+        return -1;
+    }
+
+    int written = 0;
+    try {
+        SimpleMessageData msg_data;
+        for (; written < count; ++written) {
+            msg_data.message.assign(messages[written], lengths[written]);
+            msg_data.timestamp = timestamps[written];
+            msg_data.sender_clock = sender_clocks[written];
+            if (wrapper->writer->write(&msg_data) != RETCODE_OK) {
+                break;
+            }
+        }
+    } catch (const std::exception& e) {
+        std::cerr << "Exception in publish_simple_batch: " << e.what() << std::endl;
+    }
+    return written;
+}
+
+int publish_simple_keyed(SimpleDDSPublisher pub, const char* key, int key_length, const char* data, int length,
+                         long long timestamp, long long sender_clock) {
+    SimplePublisherWrapper* wrapper = static_cast<SimplePublisherWrapper*>(pub);
+    if (!wrapper || !wrapper->writer || !wrapper->keyed || key_length < 0 || length < 0) {
+        return -1;
+    }
+
+    try {
+        SimpleMessageData msg_data(std::string(data, length), timestamp, sender_clock);
+        msg_data.key.assign(key, key_length);
+        return wrapper->writer->write(&msg_data) == RETCODE_OK ? 0 : -1;
+    } catch (const std::exception& e) {
+        std::cerr << "Exception in publish_simple_keyed: " << e.what() << std::endl;
+        return -1;
+    }
+}
+
+// Dispose or unregister the instance of a key
+static int end_instance(SimpleDDSPublisher pub, const char* key, int key_length, bool dispose) {
+    SimplePublisherWrapper* wrapper = static_cast<SimplePublisherWrapper*>(pub);
+    if (!wrapper || !wrapper->writer || !wrapper->keyed || key_length < 0) {
+        return -1;
+    }
+
+    try {
+        SimpleMessageData msg_data;
+        msg_data.key.assign(key, key_length);
+        ReturnCode_t result = dispose ? wrapper->writer->dispose(&msg_data, HANDLE_NIL)
+                                      : wrapper->writer->unregister_instance(&msg_data, HANDLE_NIL);
+        return result == RETCODE_OK ? 0 : -1;
+    } catch (const std::exception& e) {
+        std::cerr << "Exception ending instance: " << e.what() << std::endl;
+        return -1;
+    }
+}
+
+int dispose_simple_instance(SimpleDDSPublisher pub, const char* key, int key_length) {
+    return end_instance(pub, key, key_length, true);
+}
+
+int unregister_simple_instance(SimpleDDSPublisher pub, const char* key, int key_length) {
+    return end_instance(pub, key, key_length, false);
+}
+
+void simple_publisher_set_callbacks(SimpleDDSPublisher pub, const SimpleWriterCallbacks* callbacks) {
+    SimplePublisherWrapper* wrapper = static_cast<SimplePublisherWrapper*>(pub);
+    if (!wrapper) {
+        return;
+    }
+
+    std::lock_guard<std::mutex> lock(wrapper->listener.callbacks_mutex);
+    wrapper->listener.callbacks = callbacks ? *callbacks : SimpleWriterCallbacks{};
+}
+
 void destroy_simple_publisher(SimpleDDSPublisher pub) {
     SimplePublisherWrapper* wrapper = static_cast<SimplePublisherWrapper*>(pub);
     if (wrapper) {
@@ -207,11 +876,146 @@ void destroy_simple_publisher(SimpleDDSPublisher pub) {
     }
 }
 
+int simple_publisher_incompatible_qos(SimpleDDSPublisher pub) {
+    SimplePublisherWrapper* wrapper = static_cast<SimplePublisherWrapper*>(pub);
+    if (!wrapper || !wrapper->writer) {
+        return 0;
+    }
+
+    OfferedIncompatibleQosStatus status;
+    if (wrapper->writer->get_offered_incompatible_qos_status(status) != RETCODE_OK) {
+        return 0;
+    }
+    return status.total_count;
+}
+
+int simple_publisher_matched_readers(SimpleDDSPublisher pub) {
+    SimplePublisherWrapper* wrapper = static_cast<SimplePublisherWrapper*>(pub);
+    if (!wrapper || !wrapper->writer) {
+        return 0;
+    }
+
+    PublicationMatchedStatus status;
+    if (wrapper->writer->get_publication_matched_status(status) != RETCODE_OK) {
+        return 0;
+    }
+    return status.current_count;
+}
+
+int simple_publisher_publish_mode(SimpleDDSPublisher pub) {
+    SimplePublisherWrapper* wrapper = static_cast<SimplePublisherWrapper*>(pub);
+    if (!wrapper || !wrapper->writer) {
+        return SIMPLE_PUBLISH_DEFAULT;
+    }
+
+    return wrapper->writer->get_qos().publish_mode().kind == ASYNCHRONOUS_PUBLISH_MODE
+        ? SIMPLE_PUBLISH_ASYNC : SIMPLE_PUBLISH_SYNC;
+}
+
+void simple_publisher_reliable_times(SimpleDDSPublisher pub, long long* heartbeat_period_ns,
+                                     long long* nack_response_delay_ns) {
+    *heartbeat_period_ns = 0;
+    *nack_response_delay_ns = 0;
+    SimplePublisherWrapper* wrapper = static_cast<SimplePublisherWrapper*>(pub);
+    if (!wrapper || !wrapper->writer) {
+        return;
+    }
+
+    const auto& times = wrapper->writer->get_qos().reliable_writer_qos().times;
+    *heartbeat_period_ns = to_nanoseconds(times.heartbeat_period);
+    *nack_response_delay_ns = to_nanoseconds(times.nack_response_delay);
+}
+
+void simple_publisher_guid(SimpleDDSPublisher pub, unsigned char guid[16]) {
+    memset(guid, 0, 16);
+    SimplePublisherWrapper* wrapper = static_cast<SimplePublisherWrapper*>(pub);
+    if (!wrapper || !wrapper->writer) {
+        return;
+    }
+
+    const GUID_t& writer = wrapper->writer->guid();
+    memcpy(guid, writer.guidPrefix.value, 12);
+    memcpy(guid + 12, writer.entityId.value, 4);
+}
+
+int simple_publisher_deadlines_missed(SimpleDDSPublisher pub) {
+    SimplePublisherWrapper* wrapper = static_cast<SimplePublisherWrapper*>(pub);
+    if (!wrapper || !wrapper->writer) {
+        return 0;
+    }
+
+    OfferedDeadlineMissedStatus status;
+    if (wrapper->writer->get_offered_deadline_missed_status(status) != RETCODE_OK) {
+        return 0;
+    }
+    return status.total_count;
+}
+
+// Register the wrapper's type and create its topic, subscriber and reader
+static bool create_reader(SimpleSubscriberWrapper* wrapper, const char* topic_name, const SimpleReaderQos* qos) {
+    DomainParticipant* participant = wrapper->participant;
+
+    // Register type
+    if (wrapper->type_support.register_type(participant) != RETCODE_OK) {
+        std::cerr << "Failed to register type" << std::endl;
+        return false;
+    }
+
+    // Create topic
+    wrapper->topic = participant->create_topic(
+        topic_name, wrapper->type_support.get_type_name(), TOPIC_QOS_DEFAULT);
+    if (!wrapper->topic) {
+        std::cerr << "Failed to create topic" << std::endl;
+        return false;
+    }
+
+    // Create subscriber
+    SubscriberQos subscriber_qos = SUBSCRIBER_QOS_DEFAULT;
+    apply_partitions(subscriber_qos.partition(), qos->partitions, qos->partition_count);
+    wrapper->subscriber = participant->create_subscriber(subscriber_qos);
+    if (!wrapper->subscriber) {
+        std::cerr << "Failed to create subscriber" << std::endl;
+        return false;
+    }
+
+    // Create reader
+    DataReaderQos reader_qos = DATAREADER_QOS_DEFAULT;
+    if (qos->latency_budget_ns > 0) {
+        reader_qos.latency_budget().duration = to_duration(qos->latency_budget_ns);
+    }
+    apply_reliability(reader_qos.reliability(), qos->reliability);
+    apply_durability(reader_qos.durability(), qos->durability);
+    apply_history(reader_qos.history(), reader_qos.resource_limits(), qos->history);
+    if (qos->deadline_period_ns > 0) {
+        reader_qos.deadline().period = to_duration(qos->deadline_period_ns);
+    }
+    apply_liveliness(reader_qos.liveliness(), qos->liveliness);
+    wrapper->reader = wrapper->subscriber->create_datareader(
+        wrapper->topic, reader_qos, &wrapper->listener,
+        StatusMask::liveliness_changed() << StatusMask::data_available() << StatusMask::subscription_matched()
+            << StatusMask::requested_incompatible_qos());
+    if (!wrapper->reader) {
+        std::cerr << "Failed to create reader" << std::endl;
+        return false;
+    }
+
+    return true;
+}
+
 SimpleDDSSubscriber create_simple_subscriber(const char* topic_name) {
+    return create_simple_subscriber_qos(0, topic_name, nullptr);
+}
+
+SimpleDDSSubscriber create_simple_subscriber_qos(int domain_id, const char* topic_name, const SimpleReaderQos* qos) {
+    SimpleReaderQos defaults = {};
+    if (!qos) {
+        qos = &defaults;
+    }
+
     try {
         // Create participant
         DomainParticipant* participant = DomainParticipantFactory::get_instance()->create_participant(
-            0, PARTICIPANT_QOS_DEFAULT);
+            domain_id, announced_participant_qos());
         if (!participant) {
             std::cerr << "Failed to create participant" << std::endl;
             return nullptr;
@@ -220,47 +1024,123 @@ SimpleDDSSubscriber create_simple_subscriber(const char* topic_name) {
         // Create wrapper
         SimpleSubscriberWrapper* wrapper = new SimpleSubscriberWrapper();
         wrapper->participant = participant;
-        wrapper->type_support = TypeSupport(new SimpleMessageTypeSupport());
-
-        // Register type
-        if (wrapper->type_support.register_type(participant) != RETCODE_OK) {
-            std::cerr << "Failed to register type" << std::endl;
+        wrapper->type_support = TypeSupport(new SimpleMessageTypeSupport(qos->keyed != 0));
+        wrapper->keyed = qos->keyed != 0;
+        if (!create_reader(wrapper, topic_name, qos)) {
             delete wrapper;
             return nullptr;
         }
 
-        // Create topic
-        wrapper->topic = participant->create_topic(
-            topic_name, wrapper->type_support.get_type_name(), TOPIC_QOS_DEFAULT);
-        if (!wrapper->topic) {
-            std::cerr << "Failed to create topic" << std::endl;
+        return wrapper;
+    } catch (const std::exception& e) {
+        std::cerr << "Exception in create_simple_subscriber: " << e.what() << std::endl;
+        return nullptr;
+    }
+}
+
+SimpleDDSSubscriber create_dynamic_subscriber(int domain_id, const char* topic_name, const SimpleReaderQos* qos,
+                                              int timeout_ms) {
+    SimpleReaderQos defaults = {};
+    if (!qos) {
+        qos = &defaults;
+    }
+
+    try {
+        // Create the participant with a listener for the writers' types
+        SimpleSubscriberWrapper* wrapper = new SimpleSubscriberWrapper();
+        wrapper->discovery = std::make_unique<TypeDiscoveryListener>(topic_name);
+        wrapper->participant = DomainParticipantFactory::get_instance()->create_participant(
+            domain_id, announced_participant_qos(), wrapper->discovery.get(), StatusMask::none());
+        if (!wrapper->participant) {
+            std::cerr << "Failed to create participant" << std::endl;
             delete wrapper;
             return nullptr;
         }
 
-        // Create subscriber
-        wrapper->subscriber = participant->create_subscriber(SUBSCRIBER_QOS_DEFAULT);
-        if (!wrapper->subscriber) {
-            std::cerr << "Failed to create subscriber" << std::endl;
-            delete wrapper;
+        if (!wrapper->discovery->wait(timeout_ms)) {
+            std::cerr << "No writer of " << topic_name << " announced its type" << std::endl;
+            destroy_simple_subscriber(wrapper);
             return nullptr;
         }
 
-        // Create reader
-        wrapper->reader = wrapper->subscriber->create_datareader(wrapper->topic, DATAREADER_QOS_DEFAULT);
-        if (!wrapper->reader) {
-            std::cerr << "Failed to create reader" << std::endl;
-            delete wrapper;
+        // Build the announced type from the type object Fast DDS looked up
+        xtypes::TypeObject type_object;
+        const xtypes::TypeIdentifier& type_id =
+            wrapper->discovery->type_information.complete().typeid_with_size().type_id();
+        if (DomainParticipantFactory::get_instance()->type_object_registry().get_type_object(type_id, type_object) !=
+            RETCODE_OK) {
+            std::cerr << "Failed to get the type object of " << wrapper->discovery->type_name << std::endl;
+            destroy_simple_subscriber(wrapper);
+            return nullptr;
+        }
+        wrapper->dynamic_type =
+            DynamicTypeBuilderFactory::get_instance()->create_type_w_type_object(type_object)->build();
+        if (!wrapper->dynamic_type) {
+            std::cerr << "Failed to build type " << wrapper->discovery->type_name << std::endl;
+            destroy_simple_subscriber(wrapper);
+            return nullptr;
+        }
+        wrapper->type_support = TypeSupport(new DynamicPubSubType(wrapper->dynamic_type));
+        if (!create_reader(wrapper, topic_name, qos)) {
+            destroy_simple_subscriber(wrapper);
             return nullptr;
         }
 
         return wrapper;
     } catch (const std::exception& e) {
-        std::cerr << "Exception in create_simple_subscriber: " << e.what() << std::endl;
+        std::cerr << "Exception in create_dynamic_subscriber: " << e.what() << std::endl;
         return nullptr;
     }
 }
 
+const char* simple_subscriber_type_name(SimpleDDSSubscriber sub) {
+    SimpleSubscriberWrapper* wrapper = static_cast<SimpleSubscriberWrapper*>(sub);
+    if (!wrapper || !wrapper->discovery) {
+        return nullptr;
+    }
+    return wrapper->discovery->type_name.c_str();
+}
+
+// Take the next sample of a dynamic subscriber, as JSON
+static int receive_dynamic_message(SimpleSubscriberWrapper* wrapper, SimpleMessage* msg) {
+    SampleInfo info;
+    SimpleMessageData& msg_data = wrapper->last;
+    DynamicData::_ref_type data = DynamicDataFactory::get_instance()->create_data(wrapper->dynamic_type);
+    if (wrapper->reader->take_next_sample(&data, &info) != RETCODE_OK) {
+        return -1;
+    }
+
+    msg_data.message.clear();
+    if (info.valid_data) {
+        std::ostringstream json;
+        if (json_serialize(data, DynamicDataJsonFormat::EPROSIMA, json) != RETCODE_OK) {
+            std::cerr << "Failed to convert a sample of " << wrapper->discovery->type_name << " to JSON" << std::endl;
+        }
+        msg_data.message = json.str();
+    }
+    msg_data.timestamp = info.source_timestamp.to_ns();
+    DynamicDataFactory::get_instance()->delete_data(data);
+
+    msg->data = msg_data.message.data();
+    msg->length = static_cast<int>(msg_data.message.size());
+    msg->key = nullptr;
+    msg->key_length = 0;
+    msg->instance_state = SIMPLE_INSTANCE_ALIVE;
+    if (info.instance_state == NOT_ALIVE_DISPOSED_INSTANCE_STATE) {
+        msg->instance_state = SIMPLE_INSTANCE_DISPOSED;
+    } else if (info.instance_state == NOT_ALIVE_NO_WRITERS_INSTANCE_STATE) {
+        msg->instance_state = SIMPLE_INSTANCE_UNREGISTERED;
+    }
+    msg->timestamp = msg_data.timestamp;
+    msg->sender_clock = 0;
+
+    const GUID_t& writer = info.sample_identity.writer_guid();
+    memcpy(msg->writer_guid, writer.guidPrefix.value, 12);
+    memcpy(msg->writer_guid + 12, writer.entityId.value, 4);
+    msg->sequence = info.sample_identity.sequence_number().to64long();
+    return 0;
+}
+
 int receive_simple_message(SimpleDDSSubscriber sub, SimpleMessage* msg) {
     SimpleSubscriberWrapper* wrapper = static_cast<SimpleSubscriberWrapper*>(sub);
     if (!wrapper || !wrapper->reader) {
@@ -268,13 +1148,42 @@ int receive_simple_message(SimpleDDSSubscriber sub, SimpleMessage* msg) {
     }
 
     try {
+        if (wrapper->dynamic_type) {
+            return receive_dynamic_message(wrapper, msg);
+        }
+
         SampleInfo info;
-        SimpleMessageData msg_data;
+        SimpleMessageData& msg_data = wrapper->last;
         
         if (wrapper->reader->read_next_sample(&msg_data, &info) == RETCODE_OK) {
-            strncpy(msg->message, msg_data.message.c_str(), 255);
-            msg->message[255] = '\0';
+            msg->instance_state = SIMPLE_INSTANCE_ALIVE;
+            if (!info.valid_data) {
+                // A disposed or unregistered instance, known by its handle only
+                msg_data.message.clear();
+                msg_data.timestamp = info.source_timestamp.to_ns();
+                msg_data.sender_clock = 0;
+                msg_data.key = wrapper->instance_keys[info.instance_handle];
+                if (info.instance_state == NOT_ALIVE_DISPOSED_INSTANCE_STATE) {
+                    msg->instance_state = SIMPLE_INSTANCE_DISPOSED;
+                } else {
+                    msg->instance_state = SIMPLE_INSTANCE_UNREGISTERED;
+                    wrapper->instance_keys.erase(info.instance_handle);
+                }
+            } else if (wrapper->keyed) {
+                wrapper->instance_keys[info.instance_handle] = msg_data.key;
+            }
+
+            msg->data = msg_data.message.data();
+            msg->length = static_cast<int>(msg_data.message.size());
+            msg->key = wrapper->keyed ? msg_data.key.data() : nullptr;
+            msg->key_length = static_cast<int>(msg_data.key.size());
             msg->timestamp = msg_data.timestamp;
+            msg->sender_clock = msg_data.sender_clock;
+
+            const GUID_t& writer = info.sample_identity.writer_guid();
+            memcpy(msg->writer_guid, writer.guidPrefix.value, 12);
+            memcpy(msg->writer_guid + 12, writer.entityId.value, 4);
+            msg->sequence = info.sample_identity.sequence_number().to64long();
             return 0;
         }
         return -1; // No data available
@@ -284,6 +1193,16 @@ int receive_simple_message(SimpleDDSSubscriber sub, SimpleMessage* msg) {
     }
 }
 
+void simple_subscriber_set_callbacks(SimpleDDSSubscriber sub, const SimpleReaderCallbacks* callbacks) {
+    SimpleSubscriberWrapper* wrapper = static_cast<SimpleSubscriberWrapper*>(sub);
+    if (!wrapper) {
+        return;
+    }
+
+    std::lock_guard<std::mutex> lock(wrapper->listener.callbacks_mutex);
+    wrapper->listener.callbacks = callbacks ? *callbacks : SimpleReaderCallbacks{};
+}
+
 void destroy_simple_subscriber(SimpleDDSSubscriber sub) {
     SimpleSubscriberWrapper* wrapper = static_cast<SimpleSubscriberWrapper*>(sub);
     if (wrapper) {
@@ -295,4 +1214,93 @@ void destroy_simple_subscriber(SimpleDDSSubscriber sub) {
     }
 }
 
+int simple_subscriber_incompatible_qos(SimpleDDSSubscriber sub) {
+    SimpleSubscriberWrapper* wrapper = static_cast<SimpleSubscriberWrapper*>(sub);
+    if (!wrapper || !wrapper->reader) {
+        return 0;
+    }
+
+    RequestedIncompatibleQosStatus status;
+    if (wrapper->reader->get_requested_incompatible_qos_status(status) != RETCODE_OK) {
+        return 0;
+    }
+    return status.total_count;
+}
+
+int simple_subscriber_samples_lost(SimpleDDSSubscriber sub) {
+    SimpleSubscriberWrapper* wrapper = static_cast<SimpleSubscriberWrapper*>(sub);
+    if (!wrapper || !wrapper->reader) {
+        return 0;
+    }
+
+    SampleLostStatus status;
+    if (wrapper->reader->get_sample_lost_status(status) != RETCODE_OK) {
+        return 0;
+    }
+    return status.total_count;
+}
+
+int simple_subscriber_deadlines_missed(SimpleDDSSubscriber sub) {
+    SimpleSubscriberWrapper* wrapper = static_cast<SimpleSubscriberWrapper*>(sub);
+    if (!wrapper || !wrapper->reader) {
+        return 0;
+    }
+
+    RequestedDeadlineMissedStatus status;
+    if (wrapper->reader->get_requested_deadline_missed_status(status) != RETCODE_OK) {
+        return 0;
+    }
+    return status.total_count;
+}
+
+int simple_subscriber_writer_liveliness(SimpleDDSSubscriber sub, SimpleWriterLiveliness* writers, int max) {
+    SimpleSubscriberWrapper* wrapper = static_cast<SimpleSubscriberWrapper*>(sub);
+    if (!wrapper || !wrapper->reader) {
+        return 0;
+    }
+
+    std::lock_guard<std::mutex> lock(wrapper->listener.mutex);
+    int i = 0;
+    for (const auto& [writer, alive] : wrapper->listener.writers) {
+        if (i == max) {
+            break;
+        }
+        memcpy(writers[i].guid, writer.guidPrefix.value, 12);
+        memcpy(writers[i].guid + 12, writer.entityId.value, 4);
+        writers[i].alive = alive ? 1 : 0;
+        i++;
+    }
+    return static_cast<int>(wrapper->listener.writers.size());
+}
+
+SimpleDDSDiscoveryWatcher create_discovery_watcher(int domain_id, const SimpleDiscoveryCallbacks* callbacks) {
+    try {
+        SimpleDiscoveryWatcherWrapper* wrapper = new SimpleDiscoveryWatcherWrapper();
+        wrapper->listener.callbacks = *callbacks;
+        wrapper->participant = DomainParticipantFactory::get_instance()->create_participant(
+            domain_id, announced_participant_qos(), &wrapper->listener, StatusMask::none());
+        if (!wrapper->participant) {
+            std::cerr << "Failed to create participant" << std::endl;
+            delete wrapper;
+            return nullptr;
+        }
+        return wrapper;
+    } catch (const std::exception& e) {
+        std::cerr << "Exception in create_discovery_watcher: " << e.what() << std::endl;
+        return nullptr;
+    }
+}
+
+void destroy_discovery_watcher(SimpleDDSDiscoveryWatcher watcher) {
+    SimpleDiscoveryWatcherWrapper* wrapper = static_cast<SimpleDiscoveryWatcherWrapper*>(watcher);
+    if (wrapper) {
+        {
+            std::lock_guard<std::mutex> lock(wrapper->listener.mutex);
+            wrapper->listener.callbacks = SimpleDiscoveryCallbacks{};
+        }
+        DomainParticipantFactory::get_instance()->delete_participant(wrapper->participant);
+        delete wrapper;
+    }
+}
+
 }
\ No newline at end of file
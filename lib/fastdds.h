@@ -1,29 +1,224 @@
 #ifndef FASTDDS_SIMPLE_H
 #define FASTDDS_SIMPLE_H
 
+#include <stdint.h>
+
 #ifdef __cplusplus
 extern "C" {
 #endif
 
 // Simple message structure
 typedef struct {
-    char message[256];
-    long timestamp;
+    const char* data;              // payload, valid until the next receive on the subscriber or its destruction
+    int length;                    // payload bytes; payloads may hold NULs and have any size
+    long long timestamp;           // publication time in ns since the Unix epoch
+    unsigned char writer_guid[16]; // GUID of the DataWriter that sent the sample (prefix + entity id)
+    long long sequence;            // the writer's sequence number of the sample
+    long long sender_clock;        // sender's monotonic clock in ns, 0 if it sent none
+    const char* key;               // instance key on keyed topics, valid like data; NULL on unkeyed ones
+    int key_length;
+    int instance_state;            // SIMPLE_INSTANCE_*; disposed and unregistered instances come without payload
 } SimpleMessage;
 
+// Instance states of a keyed sample
+#define SIMPLE_INSTANCE_ALIVE 0
+#define SIMPLE_INSTANCE_DISPOSED 1     // a writer disposed the instance
+#define SIMPLE_INSTANCE_UNREGISTERED 2 // no writer keeps the instance any more
+
+// Publish modes of a writer
+#define SIMPLE_PUBLISH_DEFAULT 0 // synchronous unless a flow controller needs asynchronous
+#define SIMPLE_PUBLISH_SYNC 1
+#define SIMPLE_PUBLISH_ASYNC 2
+
+// Reliability kinds
+#define SIMPLE_RELIABILITY_DEFAULT 0 // reliable writers, best-effort readers
+#define SIMPLE_RELIABILITY_BEST_EFFORT 1
+#define SIMPLE_RELIABILITY_RELIABLE 2
+
+// Durability kinds
+#define SIMPLE_DURABILITY_DEFAULT 0 // volatile
+#define SIMPLE_DURABILITY_VOLATILE 1
+#define SIMPLE_DURABILITY_TRANSIENT_LOCAL 2 // writers keep their history for late-joining readers
+#define SIMPLE_DURABILITY_TRANSIENT 3       // only announced by other participants
+#define SIMPLE_DURABILITY_PERSISTENT 4      // only announced by other participants
+
+// History kinds
+#define SIMPLE_HISTORY_DEFAULT 0 // keep last
+#define SIMPLE_HISTORY_KEEP_LAST 1
+#define SIMPLE_HISTORY_KEEP_ALL 2
+
+// Liveliness kinds
+#define SIMPLE_LIVELINESS_DEFAULT 0 // automatic
+#define SIMPLE_LIVELINESS_AUTOMATIC 1
+#define SIMPLE_LIVELINESS_MANUAL_BY_PARTICIPANT 2
+#define SIMPLE_LIVELINESS_MANUAL_BY_TOPIC 3 // writers assert liveliness by writing
+
+// History and resource limits of an endpoint; zero fields keep the Fast DDS
+// defaults
+typedef struct {
+    int kind;        // SIMPLE_HISTORY_*
+    int depth;       // samples kept with SIMPLE_HISTORY_KEEP_LAST
+    int max_samples; // samples the endpoint may hold in memory
+} SimpleHistoryQos;
+
+// Liveliness of an endpoint; zero fields keep the Fast DDS defaults
+typedef struct {
+    int kind;                    // SIMPLE_LIVELINESS_*
+    long long lease_duration_ns; // time a writer may stay silent before it counts as not alive
+} SimpleLivelinessQos;
+
+// Liveliness of a remote writer as a reader last saw it
+typedef struct {
+    unsigned char guid[16]; // GUID of the DataWriter (prefix + entity id)
+    int alive;              // 0 once the writer let its lease expire
+} SimpleWriterLiveliness;
+
+// Reader events, called on Fast DDS threads as they happen with the context
+// they were set with. They must not call back into their subscriber.
+typedef struct {
+    void (*data_available)(uintptr_t context); // unread samples arrived
+    void (*writer_matched)(uintptr_t context, const unsigned char guid[16],
+                           int matched); // a writer matched, or unmatched when 0
+    void (*incompatible_qos)(uintptr_t context,
+                             const char* policy); // a writer did not match because of the policy, e.g. "reliability"
+    uintptr_t context;
+} SimpleReaderCallbacks;
+
+// Writer events, called on Fast DDS threads as they happen with the context
+// they were set with. They must not call back into their publisher.
+typedef struct {
+    void (*incompatible_qos)(uintptr_t context,
+                             const char* policy); // a reader did not match because of the policy, e.g. "durability"
+    uintptr_t context;
+} SimpleWriterCallbacks;
+
+// A remote participant as discovery reported it. The strings are only valid
+// during the callback.
+typedef struct {
+    unsigned char guid[16]; // GUID of the participant (prefix + entity id)
+    const char* name;       // participant name, "" if it has none
+    const char* hostname;   // host it runs on, "" if it does not announce it
+    const char* process;    // its process id, "" if it does not announce it
+    int discovered;         // 0 once the participant was removed or dropped
+} SimpleParticipantInfo;
+
+// A remote DataWriter or DataReader as discovery reported it, from the
+// DCPSPublication and DCPSSubscription builtin topics, with the QoS it
+// announced. The strings are only valid during the callback.
+typedef struct {
+    unsigned char guid[16];             // GUID of the endpoint (prefix + entity id)
+    unsigned char participant_guid[16]; // GUID of its participant
+    const char* topic_name;
+    const char* type_name;
+    int writer;                         // 1 for DataWriters, 0 for DataReaders
+    int discovered;                     // 0 once the endpoint was removed
+    int keyed;                          // its samples carry an instance key
+    int reliability;                    // SIMPLE_RELIABILITY_BEST_EFFORT or SIMPLE_RELIABILITY_RELIABLE
+    int durability;                     // SIMPLE_DURABILITY_*, never SIMPLE_DURABILITY_DEFAULT
+    long long deadline_period_ns;       // 0 for no deadline
+    long long latency_budget_ns;
+    SimpleLivelinessQos liveliness;     // a lease duration of 0 is infinite
+    int exclusive;                      // 1 for exclusive ownership, 0 for shared
+    int ownership_strength;             // of exclusive writers; 0 for readers
+    const char* const* partitions;      // partition names, none for the default partition
+    int partition_count;
+} SimpleEndpointInfo;
+
+// Discovery events, called on Fast DDS threads as they happen with the
+// context they were set with. Changes of a known endpoint's QoS are reported
+// as discovering it again.
+typedef struct {
+    void (*participant)(uintptr_t context, const SimpleParticipantInfo* info);
+    void (*endpoint)(uintptr_t context, const SimpleEndpointInfo* info);
+    uintptr_t context;
+} SimpleDiscoveryCallbacks;
+
+// Writer QoS; zero fields keep the Fast DDS defaults
+typedef struct {
+    long long flow_max_bytes;         // bytes a flow controller lets through per period, 0 for no flow controller
+    int flow_period_ms;               // flow controller period, 0 for 100ms
+    int publish_mode;                 // SIMPLE_PUBLISH_*
+    long long heartbeat_period_ns;    // reliable writers' heartbeat period
+    long long nack_response_delay_ns; // delay before answering a NACK with repairs
+    long long latency_budget_ns;      // offered latency budget
+    int reliability;                  // SIMPLE_RELIABILITY_*
+    int durability;                   // SIMPLE_DURABILITY_*
+    SimpleHistoryQos history;
+    long long deadline_period_ns;     // offered longest time between two samples
+    SimpleLivelinessQos liveliness;
+    const char* const* partitions;    // partition names, NULL for the default partition
+    int partition_count;
+    int keyed;                        // samples carry an instance key; only matches keyed readers
+} SimpleWriterQos;
+
+// Reader QoS; zero fields keep the Fast DDS defaults
+typedef struct {
+    long long latency_budget_ns;   // requested latency budget, matched against the writers' offers
+    int reliability;               // SIMPLE_RELIABILITY_*; a reliable reader only matches reliable writers
+    int durability;                // SIMPLE_DURABILITY_*; a transient local reader only matches such writers
+    SimpleHistoryQos history;
+    long long deadline_period_ns;  // requested longest time between two samples, matched against the writers' offers
+    SimpleLivelinessQos liveliness;
+    const char* const* partitions; // partition names, NULL for the default partition; names may hold wildcards
+    int partition_count;
+    int keyed;                     // samples carry an instance key; only matches keyed writers
+} SimpleReaderQos;
+
+// Version of the linked Fast DDS library, e.g. "3.2.1"
+const char* simple_fastdds_version(void);
+
 // Opaque handles for C interface
 typedef void* SimpleDDSPublisher;
 typedef void* SimpleDDSSubscriber;
+typedef void* SimpleDDSDiscoveryWatcher;
 
 // Publisher functions
 SimpleDDSPublisher create_simple_publisher(const char* topic_name);
-int publish_simple_message(SimpleDDSPublisher pub, const char* message, long timestamp);
+SimpleDDSPublisher create_simple_publisher_qos(int domain_id, const char* topic_name, const SimpleWriterQos* qos);
+int publish_simple_message(SimpleDDSPublisher pub, const char* data, int length, long long timestamp,
+                           long long sender_clock);
+int publish_simple_borrowed(SimpleDDSPublisher pub, const char* data, int length, long long timestamp,
+                            long long sender_clock); // data is only read during the call, need not end in NUL
+int publish_simple_batch(SimpleDDSPublisher pub, const char* const* messages, const int* lengths,
+                         const long long* timestamps, const long long* sender_clocks,
+                         int count); // samples written, stops at the first failure
+int publish_simple_keyed(SimpleDDSPublisher pub, const char* key, int key_length, const char* data, int length,
+                         long long timestamp, long long sender_clock); // keyed writers only
+int dispose_simple_instance(SimpleDDSPublisher pub, const char* key, int key_length);
+int unregister_simple_instance(SimpleDDSPublisher pub, const char* key, int key_length);
+void simple_publisher_set_callbacks(SimpleDDSPublisher pub,
+                                    const SimpleWriterCallbacks* callbacks); // NULL removes them once none runs
 void destroy_simple_publisher(SimpleDDSPublisher pub);
+int simple_publisher_incompatible_qos(SimpleDDSPublisher pub); // readers not matched because of QoS
+int simple_publisher_matched_readers(SimpleDDSPublisher pub);
+int simple_publisher_publish_mode(SimpleDDSPublisher pub); // SIMPLE_PUBLISH_SYNC or SIMPLE_PUBLISH_ASYNC
+void simple_publisher_reliable_times(SimpleDDSPublisher pub, long long* heartbeat_period_ns,
+                                     long long* nack_response_delay_ns); // active values
+void simple_publisher_guid(SimpleDDSPublisher pub, unsigned char guid[16]); // the DataWriter's, zeros if unknown
+int simple_publisher_deadlines_missed(SimpleDDSPublisher pub); // periods the writer wrote nothing in
 
 // Subscriber functions
 SimpleDDSSubscriber create_simple_subscriber(const char* topic_name);
+SimpleDDSSubscriber create_simple_subscriber_qos(int domain_id, const char* topic_name, const SimpleReaderQos* qos);
+// A subscriber of any topic, with the type its writers announce through XTypes; waits up to timeout_ms for one to be
+// discovered. Samples are received as JSON, with keyed samples not split into key and payload.
+SimpleDDSSubscriber create_dynamic_subscriber(int domain_id, const char* topic_name, const SimpleReaderQos* qos,
+                                              int timeout_ms);
+const char* simple_subscriber_type_name(SimpleDDSSubscriber sub); // discovered type of dynamic subscribers, else NULL
 int receive_simple_message(SimpleDDSSubscriber sub, SimpleMessage* msg);
+void simple_subscriber_set_callbacks(SimpleDDSSubscriber sub,
+                                     const SimpleReaderCallbacks* callbacks); // NULL removes them once none runs
 void destroy_simple_subscriber(SimpleDDSSubscriber sub);
+int simple_subscriber_incompatible_qos(SimpleDDSSubscriber sub); // writers not matched because of QoS
+int simple_subscriber_samples_lost(SimpleDDSSubscriber sub);      // samples lost, incomplete fragmented ones included
+int simple_subscriber_deadlines_missed(SimpleDDSSubscriber sub);  // periods the reader received nothing in
+int simple_subscriber_writer_liveliness(SimpleDDSSubscriber sub, SimpleWriterLiveliness* writers,
+                                        int max); // matched writers, of which at most max are filled in
+
+// Discovery: a participant of its own reporting the other participants of a domain and their endpoints, those of
+// this process excepted, as they come and go. Every participant Cardinal creates announces its host and process.
+SimpleDDSDiscoveryWatcher create_discovery_watcher(int domain_id, const SimpleDiscoveryCallbacks* callbacks);
+void destroy_discovery_watcher(SimpleDDSDiscoveryWatcher watcher); // waits for running callbacks
 
 #ifdef __cplusplus
 }
@@ -0,0 +1,222 @@
+"""Python bindings for Cardinal, over the libcardinal.so that `just go-capi`
+builds.
+
+Test scripts drive the same headless session the daemon runs: publish and
+subscribe through the transport of the config, record captures, read
+captures back and look at the statistics.
+
+    import cardinal
+
+    with cardinal.Session() as session:
+        sub = session.subscribe("sensor/imu")
+        session.publisher("sensor/cmd").publish(b"calibrate")
+        for msg in sub.messages(timeout=5):
+            print(msg.topic, msg.payload)
+        print(session.stats()["topics"])
+
+    for msg in cardinal.Capture("run.mcap"):
+        print(msg.timestamp_ns, msg.topic, msg.payload)
+
+The library is found through CARDINAL_LIBRARY, or the usual search path.
+"""
+
+import ctypes
+import json
+import os
+import queue
+import threading
+import time
+from dataclasses import dataclass
+
+__all__ = ["CardinalError", "Message", "Session", "Publisher", "Subscriber", "Capture"]
+
+
+class CardinalError(Exception):
+    """A failed call, with the reason libcardinal gave"""
+
+
+@dataclass(frozen=True)
+class Message:
+    topic: str
+    payload: bytes
+    timestamp_ns: int
+
+
+class _CMessage(ctypes.Structure):
+    _fields_ = [
+        ("topic", ctypes.c_void_p),
+        ("payload", ctypes.c_void_p),
+        ("length", ctypes.c_int),
+        ("timestamp_ns", ctypes.c_longlong),
+    ]
+
+
+_MessageCallback = ctypes.CFUNCTYPE(
+    None, ctypes.c_size_t, ctypes.c_char_p, ctypes.POINTER(ctypes.c_char), ctypes.c_int, ctypes.c_longlong
+)
+
+_lib = None
+
+
+def _library():
+    global _lib
+    if _lib is None:
+        lib = ctypes.CDLL(os.environ.get("CARDINAL_LIBRARY", "libcardinal.so"))
+        lib.cardinal_open.argtypes = [ctypes.c_char_p]
+        lib.cardinal_publish.argtypes = [ctypes.c_char_p, ctypes.c_char_p, ctypes.c_int]
+        lib.cardinal_subscribe.argtypes = [ctypes.c_char_p]
+        lib.cardinal_on_message.argtypes = [_MessageCallback, ctypes.c_size_t]
+        lib.cardinal_on_message.restype = None
+        lib.cardinal_start_recording.argtypes = [ctypes.c_char_p]
+        lib.cardinal_status_json.restype = ctypes.c_void_p
+        lib.cardinal_close.restype = None
+        lib.cardinal_last_error.restype = ctypes.c_char_p
+        lib.cardinal_free.argtypes = [ctypes.c_void_p]
+        lib.cardinal_free.restype = None
+        lib.cardinal_capture_open.argtypes = [ctypes.c_char_p]
+        lib.cardinal_capture_open.restype = ctypes.c_size_t
+        lib.cardinal_capture_next.argtypes = [ctypes.c_size_t, ctypes.POINTER(_CMessage)]
+        lib.cardinal_capture_close.argtypes = [ctypes.c_size_t]
+        lib.cardinal_capture_close.restype = None
+        _lib = lib
+    return _lib
+
+
+def _check(result):
+    if result == -1:
+        raise CardinalError(_library().cardinal_last_error().decode())
+    return result
+
+
+class Session:
+    """The one headless session of the process, configured like the daemon
+    by the config file at path, or the default config"""
+
+    def __init__(self, config=None):
+        self._lib = _library()
+        _check(self._lib.cardinal_open(config.encode() if config else None))
+        self._subscribers = {}
+        self._lock = threading.Lock()
+        # Kept referenced so the callback outlives the call that set it
+        self._callback = _MessageCallback(self._deliver)
+        self._lib.cardinal_on_message(self._callback, 0)
+
+    def __enter__(self):
+        return self
+
+    def __exit__(self, *exc):
+        self.close()
+
+    def _deliver(self, _context, topic, payload, length, timestamp_ns):
+        msg = Message(topic.decode(), ctypes.string_at(payload, length), timestamp_ns)
+        with self._lock:
+            subscriber = self._subscribers.get(msg.topic)
+        if subscriber is not None:
+            subscriber._queue.put(msg)
+
+    def publisher(self, topic):
+        return Publisher(self, topic)
+
+    def subscribe(self, topic):
+        """Subscribe to a topic; its messages queue up on the Subscriber"""
+        subscriber = Subscriber(topic)
+        with self._lock:
+            self._subscribers[topic] = subscriber
+        try:
+            _check(self._lib.cardinal_subscribe(topic.encode()))
+        except CardinalError:
+            with self._lock:
+                del self._subscribers[topic]
+            raise
+        return subscriber
+
+    def start_recording(self, path):
+        _check(self._lib.cardinal_start_recording(path.encode()))
+
+    def stop_recording(self):
+        _check(self._lib.cardinal_stop_recording())
+
+    def stats(self):
+        """Session status with per-topic statistics, as `cardinal ctl status`
+        receives it"""
+        status = self._lib.cardinal_status_json()
+        if not status:
+            _check(-1)
+        try:
+            return json.loads(ctypes.string_at(status))
+        finally:
+            self._lib.cardinal_free(status)
+
+    def close(self):
+        self._lib.cardinal_close()
+
+
+class Publisher:
+    """Publishes to a topic of a session"""
+
+    def __init__(self, session, topic):
+        self._session = session
+        self.topic = topic
+
+    def publish(self, payload):
+        if isinstance(payload, str):
+            payload = payload.encode()
+        _check(self._session._lib.cardinal_publish(self.topic.encode(), payload, len(payload)))
+
+
+class Subscriber:
+    """Messages received on a topic of a session"""
+
+    def __init__(self, topic):
+        self.topic = topic
+        self._queue = queue.Queue()
+
+    def receive(self, timeout=None):
+        """The next message, or None when none arrives within timeout"""
+        try:
+            return self._queue.get(timeout=timeout)
+        except queue.Empty:
+            return None
+
+    def messages(self, timeout=None):
+        """Iterate over the messages arriving within timeout seconds"""
+        deadline = None if timeout is None else time.monotonic() + timeout
+        while True:
+            remaining = None if deadline is None else deadline - time.monotonic()
+            if remaining is not None and remaining <= 0:
+                return
+            msg = self.receive(remaining)
+            if msg is None:
+                return
+            yield msg
+
+
+class Capture:
+    """Reads the messages of a capture file, no session needed"""
+
+    def __init__(self, path):
+        self._lib = _library()
+        self._handle = self._lib.cardinal_capture_open(path.encode())
+        if not self._handle:
+            _check(-1)
+
+    def __enter__(self):
+        return self
+
+    def __exit__(self, *exc):
+        self.close()
+
+    def __iter__(self):
+        msg = _CMessage()
+        while self._handle and _check(self._lib.cardinal_capture_next(self._handle, ctypes.byref(msg))) == 1:
+            try:
+                payload = ctypes.string_at(msg.payload, msg.length) if msg.length else b""
+                yield Message(ctypes.string_at(msg.topic).decode(), payload, msg.timestamp_ns)
+            finally:
+                self._lib.cardinal_free(msg.topic)
+                self._lib.cardinal_free(msg.payload)
+
+    def close(self):
+        if self._handle:
+            self._lib.cardinal_capture_close(self._handle)
+            self._handle = 0